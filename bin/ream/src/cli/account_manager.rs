@@ -1,9 +1,19 @@
-use clap::Parser;
+use std::{path::PathBuf, sync::Arc};
+
+use alloy_primitives::Address;
+use clap::{Parser, ValueEnum, error::ErrorKind};
+use ream_consensus_misc::constants::beacon::{
+    COMPOUNDING_WITHDRAWAL_PREFIX, EFFECTIVE_BALANCE_INCREMENT, ETH1_ADDRESS_WITHDRAWAL_PREFIX,
+    MAX_EFFECTIVE_BALANCE_ELECTRA, MIN_ACTIVATION_BALANCE,
+};
+use ream_network_spec::{cli::beacon_network_parser, networks::BeaconNetworkSpec};
+
+use crate::cli::constants::DEFAULT_NETWORK;
 
 const DEFAULT_ACTIVATION_EPOCH: u32 = 0;
 const DEFAULT_NUM_ACTIVE_EPOCHS: u32 = 1 << 18;
 
-#[derive(Debug, Parser, Default)]
+#[derive(Debug, Parser)]
 pub struct AccountManagerConfig {
     /// Account lifetime in 2 ** lifetime slots
     #[arg(short, long, default_value_t = 18, value_parser = clap::value_parser!(u32).range(18..))]
@@ -29,7 +39,104 @@ pub struct AccountManagerConfig {
     #[arg(long, default_value_t = DEFAULT_NUM_ACTIVE_EPOCHS)]
     pub num_active_epochs: u32,
 
-    /// Path for keystore directory (relative to data-dir if not absolute)
+    /// Path for keystore directory (relative to data-dir if not absolute). Defaults to keystores,
+    /// or validator_keys with --beacon
     #[arg(long)]
     pub keystore_path: Option<String>,
+
+    /// File holding the password to encrypt the keystores with. Prompted for if not set
+    #[arg(long)]
+    pub password_file: Option<PathBuf>,
+
+    /// Generate BLS keystores and deposit data for beacon chain validators instead of lean keys
+    #[arg(long, requires = "withdrawal_address")]
+    pub beacon: bool,
+
+    /// Beacon network the deposits are signed for: mainnet, sepolia, hoodi, dev or a path to a
+    /// YAML config file
+    #[arg(long, requires = "beacon", default_value = DEFAULT_NETWORK, value_parser = beacon_network_parser)]
+    pub network: Arc<BeaconNetworkSpec>,
+
+    /// Number of beacon validator keys to generate
+    #[arg(long, requires = "beacon", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub num_validators: u32,
+
+    /// Index of the first beacon validator key, in the EIP-2334 path m/12381/3600/<index>/0/0
+    #[arg(long, requires = "beacon", default_value_t = 0)]
+    pub start_index: u32,
+
+    /// Execution address the withdrawal credentials of the beacon validators point to
+    #[arg(long, requires = "beacon")]
+    pub withdrawal_address: Option<Address>,
+
+    /// Type of the withdrawal credentials: 0x01 for regular or 0x02 for compounding validators
+    #[arg(long, requires = "beacon", value_enum, default_value_t = WithdrawalCredentialsType::Eth1Address)]
+    pub withdrawal_credentials_type: WithdrawalCredentialsType,
+
+    /// Amount of each deposit in Gwei
+    #[arg(long, requires = "beacon", default_value_t = MIN_ACTIVATION_BALANCE)]
+    pub amount: u64,
+
+    /// Key derivation function of the beacon keystores
+    #[arg(long, requires = "beacon", value_enum, default_value_t = KeystoreKdf::Scrypt)]
+    pub kdf: KeystoreKdf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WithdrawalCredentialsType {
+    #[value(name = "0x01")]
+    Eth1Address,
+    #[value(name = "0x02")]
+    Compounding,
+}
+
+impl WithdrawalCredentialsType {
+    pub fn prefix(&self) -> &'static [u8] {
+        match self {
+            WithdrawalCredentialsType::Eth1Address => ETH1_ADDRESS_WITHDRAWAL_PREFIX,
+            WithdrawalCredentialsType::Compounding => COMPOUNDING_WITHDRAWAL_PREFIX,
+        }
+    }
+
+    /// The largest effective balance of a validator with these withdrawal credentials.
+    pub fn max_effective_balance(&self) -> u64 {
+        match self {
+            WithdrawalCredentialsType::Eth1Address => MIN_ACTIVATION_BALANCE,
+            WithdrawalCredentialsType::Compounding => MAX_EFFECTIVE_BALANCE_ELECTRA,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum KeystoreKdf {
+    Scrypt,
+    Pbkdf2,
+}
+
+impl AccountManagerConfig {
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        if !self.beacon {
+            return Ok(());
+        }
+
+        let max_amount = self.withdrawal_credentials_type.max_effective_balance();
+        if self.amount < EFFECTIVE_BALANCE_INCREMENT || self.amount > max_amount {
+            return Err(clap::Error::raw(
+                ErrorKind::ValueValidation,
+                format!(
+                    "--amount is {} Gwei, but must be between {EFFECTIVE_BALANCE_INCREMENT} and {max_amount} Gwei",
+                    self.amount
+                ),
+            ));
+        }
+
+        if self.start_index.checked_add(self.num_validators).is_none() {
+            return Err(clap::Error::raw(
+                ErrorKind::ValueValidation,
+                "--start-index plus --num-validators overflows the key index",
+            ));
+        }
+
+        Ok(())
+    }
 }
@@ -5,12 +5,16 @@ use ream_consensus_misc::checkpoint::Checkpoint;
 use ream_network_manager::config::ManagerConfig;
 use ream_network_spec::{cli::beacon_network_parser, networks::BeaconNetworkSpec};
 use ream_p2p::bootnodes::Bootnodes;
+use ream_req_resp::{Chain, rate_limiter::ProtocolQuota};
 use url::Url;
 
-use crate::cli::constants::{
-    DEFAULT_DISABLE_DISCOVERY, DEFAULT_DISCOVERY_PORT, DEFAULT_HTTP_ADDRESS,
-    DEFAULT_HTTP_ALLOW_ORIGIN, DEFAULT_HTTP_PORT, DEFAULT_NETWORK, DEFAULT_SOCKET_ADDRESS,
-    DEFAULT_SOCKET_PORT,
+use crate::cli::{
+    constants::{
+        DEFAULT_DISABLE_DISCOVERY, DEFAULT_DISCOVERY_PORT, DEFAULT_HTTP_ADDRESS,
+        DEFAULT_HTTP_ALLOW_ORIGIN, DEFAULT_HTTP_PORT, DEFAULT_NETWORK, DEFAULT_SOCKET_ADDRESS,
+        DEFAULT_SOCKET_PORT,
+    },
+    inbound_rate_limit_parser,
 };
 #[derive(Debug, Parser)]
 pub struct BeaconNodeConfig {
@@ -43,6 +47,14 @@ pub struct BeaconNodeConfig {
     #[arg(long, help = "Disable Discv5", default_value_t = DEFAULT_DISABLE_DISCOVERY)]
     pub disable_discovery: bool,
 
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = |value: &str| inbound_rate_limit_parser(Chain::Beacon, value),
+        help = "Per-peer quotas of inbound req/resp requests as comma-delimited <protocol>:<max tokens>/<seconds>, e.g. beacon_blocks_by_range:512/10. A request takes one token per requested item. Replaces the defaults of the given protocols"
+    )]
+    pub inbound_rate_limits: Vec<ProtocolQuota>,
+
     #[arg(
         default_value = "default",
         long,
@@ -50,6 +62,13 @@ pub struct BeaconNodeConfig {
     )]
     pub bootnodes: Bootnodes,
 
+    #[arg(
+        long,
+        help = "The path to the hex encoded secp256k1 libp2p key. Defaults to the key saved in the data directory, which is created on first start",
+        alias = "node-key"
+    )]
+    pub private_key_path: Option<PathBuf>,
+
     #[arg(long, help = "Trusted RPC URL to initiate Checkpoint Sync.")]
     pub checkpoint_sync_url: Option<Url>,
 
@@ -112,12 +131,14 @@ impl From<BeaconNodeConfig> for ManagerConfig {
             discovery_port: config.discovery_port,
             disable_discovery: config.disable_discovery,
             bootnodes: config.bootnodes,
+            private_key_path: config.private_key_path,
             checkpoint_sync_url: config.checkpoint_sync_url,
             execution_endpoint: config.execution_endpoint,
             execution_jwt_secret: config.execution_jwt_secret,
             enable_builder: config.enable_builder,
             mev_relay_url: config.mev_relay_url,
             blob_retention_epochs: config.blob_retention_epochs,
+            inbound_rate_limits: config.inbound_rate_limits,
         }
     }
 }
@@ -1,5 +1,6 @@
 use std::net::{IpAddr, Ipv4Addr};
 
+pub const DEFAULT_ARCHIVE_SNAPSHOT_INTERVAL: u64 = 64;
 pub const DEFAULT_BEACON_API_ENDPOINT: &str = "http://localhost:5052";
 pub const DEFAULT_DEVNET: &str = "1";
 pub const DEFAULT_DISABLE_DISCOVERY: bool = false;
@@ -7,7 +8,9 @@ pub const DEFAULT_DISCOVERY_PORT: u16 = 9000;
 pub const DEFAULT_HTTP_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
 pub const DEFAULT_HTTP_ALLOW_ORIGIN: bool = false;
 pub const DEFAULT_HTTP_PORT: u16 = 5052;
+pub const DEFAULT_KEY_LIFETIME_WARNING_EPOCHS: u64 = 151_200;
 pub const DEFAULT_KEY_MANAGER_HTTP_PORT: u16 = 8008;
+pub const DEFAULT_LEAN_DISCOVERY_PORT: u16 = 9001;
 pub const DEFAULT_METRICS_ENABLED: bool = false;
 pub const DEFAULT_METRICS_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
 pub const DEFAULT_METRICS_PORT: u16 = 8080;
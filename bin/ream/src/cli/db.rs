@@ -0,0 +1,141 @@
+use std::{path::PathBuf, sync::Arc};
+
+use alloy_primitives::B256;
+use clap::{Parser, Subcommand, ValueEnum};
+use ream_network_spec::{
+    cli::{beacon_network_parser, lean_network_parser},
+    networks::{BeaconNetworkSpec, LeanNetworkSpec},
+};
+
+use crate::cli::constants::DEFAULT_NETWORK;
+
+#[derive(Debug, Parser)]
+pub struct DbConfig {
+    #[arg(
+        long,
+        value_enum,
+        help = "The chain the database belongs to. Defaults to the chain recorded in the database"
+    )]
+    pub chain: Option<DbChain>,
+
+    #[arg(
+        long,
+        help = "Choose mainnet, sepolia, hoodi, dev or provide a path to a YAML config file. Only used for beacon databases",
+        default_value = DEFAULT_NETWORK,
+        value_parser = beacon_network_parser
+    )]
+    pub network: Arc<BeaconNetworkSpec>,
+
+    #[arg(
+        long,
+        help = "Provide a path to a YAML config file, or use 'ephemery' for the Ephemery network. Required by the lean commands which write the database",
+        value_parser = lean_network_parser
+    )]
+    pub lean_network: Option<LeanNetworkSpec>,
+
+    #[command(subcommand)]
+    pub command: DbCommand,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DbChain {
+    Beacon,
+    Lean,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InspectObject {
+    Block,
+    State,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InspectFormat {
+    Json,
+    Ssz,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DbCommand {
+    /// Print the checkpoints, the slot range and the size of every table
+    Info,
+
+    /// Dump a block or a state by block root or slot
+    Inspect {
+        #[arg(value_enum, help = "Whether to dump the block or its post-state")]
+        object: InspectObject,
+
+        #[arg(
+            long,
+            conflicts_with = "slot",
+            required_unless_present = "slot",
+            help = "Root of the block"
+        )]
+        root: Option<B256>,
+
+        #[arg(long, help = "Slot of the block on the canonical chain")]
+        slot: Option<u64>,
+
+        #[arg(long, value_enum, default_value_t = InspectFormat::Json, help = "Output format")]
+        format: InspectFormat,
+
+        #[arg(
+            long,
+            help = "Path to write the output to. Printed to stdout if not set, with SSZ hex encoded"
+        )]
+        output_path: Option<PathBuf>,
+    },
+
+    /// Delete the blocks of abandoned forks or old states
+    Prune {
+        #[arg(
+            long,
+            help = "Delete the blocks which are neither ancestors nor descendants of the finalized block"
+        )]
+        non_canonical_blocks: bool,
+
+        #[arg(
+            long,
+            help = "Delete the states before this slot, which can no longer be regenerated afterwards"
+        )]
+        states_before_slot: Option<u64>,
+    },
+
+    /// Export the finalized chain, as era files for the beacon chain or as a single SSZ stream
+    /// for the lean chain
+    Export {
+        #[arg(
+            long,
+            help = "Directory to write the era files to, which only ream can import, or file to write the lean chain to"
+        )]
+        output_path: PathBuf,
+    },
+
+    /// Import a chain written by `export`, verifying its block and state roots
+    Import {
+        #[arg(
+            long,
+            help = "Directory to read the era files from, or file to read the lean chain from"
+        )]
+        input_path: PathBuf,
+    },
+
+    /// Compact the database file, returning the space of deleted entries to the file system
+    Compact,
+
+    /// Check parent links, state roots, index tables and blob and column files
+    Verify,
+}
+
+impl DbCommand {
+    /// Whether the command only reads the database, which is then neither migrated nor written.
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            DbCommand::Info
+                | DbCommand::Inspect { .. }
+                | DbCommand::Export { .. }
+                | DbCommand::Verify
+        )
+    }
+}
@@ -3,7 +3,7 @@ use std::{
     path::PathBuf,
 };
 
-use anyhow::{anyhow, bail};
+use anyhow::{anyhow, bail, ensure};
 use ream_keystore::keystore::EncryptedKeystore;
 use unicode_normalization::UnicodeNormalization;
 
@@ -42,6 +42,15 @@ pub fn load_password_from_config(
     }
 }
 
+/// Ask for a new keystore password on the terminal, twice to rule out typos.
+pub fn prompt_new_password() -> anyhow::Result<String> {
+    let password = rpassword::prompt_password("Enter a password to encrypt the keystores: ")?;
+    ensure!(!password.is_empty(), "Password must not be empty");
+    let confirmation = rpassword::prompt_password("Repeat the password: ")?;
+    ensure!(password == confirmation, "Passwords do not match");
+    Ok(password)
+}
+
 pub fn process_password(password: String) -> String {
     password
         .nfkd()
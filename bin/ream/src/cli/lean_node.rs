@@ -1,14 +1,23 @@
 use std::{net::IpAddr, path::PathBuf};
 
 use clap::{Parser, error::ErrorKind};
+use ream_checkpoint_sync_lean::checkpoint::get_checkpoint_sync_sources;
+use ream_consensus_lean::checkpoint::Checkpoint;
+use ream_discv5::subnet::ATTESTATION_SUBNET_COUNT;
 use ream_fork_choice_lean::store::BlockProductionStrategy;
 use ream_network_spec::{cli::lean_network_parser, networks::LeanNetworkSpec};
 use ream_p2p::bootnodes::Bootnodes;
+use ream_req_resp::{Chain, rate_limiter::ProtocolQuota};
 use url::Url;
 
-use crate::cli::constants::{
-    DEFAULT_HTTP_ADDRESS, DEFAULT_HTTP_ALLOW_ORIGIN, DEFAULT_HTTP_PORT, DEFAULT_METRICS_ADDRESS,
-    DEFAULT_METRICS_ENABLED, DEFAULT_METRICS_PORT, DEFAULT_SOCKET_ADDRESS, DEFAULT_SOCKET_PORT,
+use crate::cli::{
+    constants::{
+        DEFAULT_ARCHIVE_SNAPSHOT_INTERVAL, DEFAULT_DISABLE_DISCOVERY, DEFAULT_HTTP_ADDRESS,
+        DEFAULT_HTTP_ALLOW_ORIGIN, DEFAULT_HTTP_PORT, DEFAULT_KEY_LIFETIME_WARNING_EPOCHS,
+        DEFAULT_LEAN_DISCOVERY_PORT, DEFAULT_METRICS_ADDRESS, DEFAULT_METRICS_ENABLED,
+        DEFAULT_METRICS_PORT, DEFAULT_SOCKET_ADDRESS, DEFAULT_SOCKET_PORT,
+    },
+    inbound_rate_limit_parser,
 };
 
 #[derive(Debug, Parser, Clone)]
@@ -27,8 +36,27 @@ pub struct LeanNodeConfig {
     )]
     pub bootnodes: Bootnodes,
 
-    #[arg(long, help = "HTTP URL of a remote node to sync checkpoint state from")]
-    pub checkpoint_sync_url: Option<Url>,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "HTTP URLs of remote nodes to sync checkpoint state from (comma-separated or repeated)"
+    )]
+    pub checkpoint_sync_url: Vec<Url>,
+
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "checkpoint_sync_url",
+        help = "Number of checkpoint sync URLs which must agree on the finalized block. Defaults to a majority of them"
+    )]
+    pub checkpoint_sync_quorum: Option<u64>,
+
+    #[arg(
+        long,
+        requires = "checkpoint_sync_url",
+        help = "Weak subjectivity checkpoint in format <0xblock_root>:<slot>, which the checkpoint sync anchor must contain"
+    )]
+    pub weak_subjectivity_checkpoint: Option<Checkpoint>,
 
     #[arg(long, help = "The path to the validator registry")]
     pub validator_registry_path: PathBuf,
@@ -40,6 +68,29 @@ pub struct LeanNodeConfig {
     )]
     pub node_id: String,
 
+    #[arg(
+        long,
+        requires = "password_source",
+        help = "Directory of encrypted keystores written by account_manager. The private keys of the validator registry are taken from these instead of its key files"
+    )]
+    pub keystore_path: Option<PathBuf>,
+
+    #[arg(
+        long,
+        group = "password_source",
+        requires = "keystore_path",
+        help = "The plaintext password file to use for keystores"
+    )]
+    pub password_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        group = "password_source",
+        requires = "keystore_path",
+        help = "The password to use for keystores. It's recommended to use password-file over this in order to prevent your keystore password from appearing in the shell history"
+    )]
+    pub password: Option<String>,
+
     #[arg(
         long,
         help = "The path to the hex encoded secp256k1 libp2p key",
@@ -53,6 +104,24 @@ pub struct LeanNodeConfig {
     #[arg(long, help = "Set P2P socket port (QUIC)", default_value_t = DEFAULT_SOCKET_PORT)]
     pub socket_port: u16,
 
+    #[arg(
+        long,
+        help = "Discovery 5 listening port (UDP). Must differ from the QUIC socket port",
+        default_value_t = DEFAULT_LEAN_DISCOVERY_PORT
+    )]
+    pub discovery_port: u16,
+
+    #[arg(long, help = "Disable Discv5", default_value_t = DEFAULT_DISABLE_DISCOVERY)]
+    pub disable_discovery: bool,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = |value: &str| inbound_rate_limit_parser(Chain::Lean, value),
+        help = "Per-peer quotas of inbound req/resp requests as comma-delimited <protocol>:<max tokens>/<seconds>, e.g. blocks_by_range:512/10. A request takes one token per requested block. Replaces the defaults of the given protocols"
+    )]
+    pub inbound_rate_limits: Vec<ProtocolQuota>,
+
     #[arg(long, help = "Set HTTP address", default_value_t = DEFAULT_HTTP_ADDRESS)]
     pub http_address: IpAddr,
 
@@ -101,6 +170,35 @@ pub struct LeanNodeConfig {
         help = "Attestation selection strategy for block production: round-based or tiered."
     )]
     pub block_production: BlockProductionStrategy,
+
+    #[arg(
+        long,
+        help = "Path to an EIP-3076 style slashing protection interchange file to import before the validators start"
+    )]
+    pub slashing_protection_import: Option<PathBuf>,
+
+    #[arg(
+        long,
+        default_value_t = DEFAULT_KEY_LIFETIME_WARNING_EPOCHS,
+        help = "Warn at startup about validator keys with fewer remaining epochs than this"
+    )]
+    pub key_lifetime_warning_epochs: u64,
+
+    #[arg(
+        long,
+        help = "Keep finalized states at a fixed slot interval instead of pruning them, so historical states can be regenerated",
+        default_value_t = false
+    )]
+    pub archive: bool,
+
+    #[arg(
+        long,
+        default_value_t = DEFAULT_ARCHIVE_SNAPSHOT_INTERVAL,
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "archive",
+        help = "Number of slots between the finalized states kept in archive mode. Requires --archive."
+    )]
+    pub archive_snapshot_interval: u64,
 }
 
 impl LeanNodeConfig {
@@ -117,6 +215,41 @@ impl LeanNodeConfig {
             }
         }
 
+        if !self.disable_discovery && self.discovery_port == self.socket_port {
+            return Err(clap::Error::raw(
+                ErrorKind::ValueValidation,
+                format!(
+                    "--discovery-port and --socket-port are both {}, but discv5 and QUIC can't share a UDP port",
+                    self.socket_port
+                ),
+            ));
+        }
+
+        // The ENR advertises subscribed subnets in the attnets bitfield, which has a fixed length
+        if !self.disable_discovery
+            && self.attestation_committee_count > ATTESTATION_SUBNET_COUNT as u64
+        {
+            return Err(clap::Error::raw(
+                ErrorKind::ValueValidation,
+                format!(
+                    "--attestation-committee-count is {}, but discovery can only advertise {ATTESTATION_SUBNET_COUNT} attestation subnets. Lower it or pass --disable-discovery",
+                    self.attestation_committee_count
+                ),
+            ));
+        }
+
+        if let Some(quorum) = self.checkpoint_sync_quorum {
+            let source_count = get_checkpoint_sync_sources(self.checkpoint_sync_url.clone()).len();
+            if quorum as usize > source_count {
+                return Err(clap::Error::raw(
+                    ErrorKind::ValueValidation,
+                    format!(
+                        "--checkpoint-sync-quorum is {quorum}, but only {source_count} distinct checkpoint sync URLs were given"
+                    ),
+                ));
+            }
+        }
+
         Ok(())
     }
 }
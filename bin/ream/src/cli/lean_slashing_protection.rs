@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use ream_network_spec::{cli::lean_network_parser, networks::LeanNetworkSpec};
+
+#[derive(Debug, Parser)]
+pub struct LeanSlashingProtectionConfig {
+    #[arg(
+        long,
+        help = "Provide a path to a YAML config file, or use 'ephemery' for the Ephemery network",
+        value_parser = lean_network_parser
+    )]
+    pub network: LeanNetworkSpec,
+
+    #[command(subcommand)]
+    pub command: LeanSlashingProtectionCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LeanSlashingProtectionCommand {
+    /// Import an EIP-3076 style interchange file into the slashing protection database
+    Import {
+        #[arg(long, help = "Path to the interchange JSON file to import")]
+        input_path: PathBuf,
+    },
+
+    /// Export the slashing protection database to an EIP-3076 style interchange file
+    Export {
+        #[arg(long, help = "Path to write the interchange JSON file to")]
+        output_path: PathBuf,
+    },
+}
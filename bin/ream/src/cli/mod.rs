@@ -1,10 +1,12 @@
 pub mod account_manager;
 pub mod beacon_node;
 pub mod constants;
+pub mod db;
 pub mod generate_private_key;
 pub mod generate_validator_registry;
 pub mod import_keystores;
 pub mod lean_node;
+pub mod lean_slashing_protection;
 pub mod validator_node;
 pub mod verbosity;
 pub mod voluntary_exit;
@@ -13,13 +15,19 @@ use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
 use ream_node::version::FULL_VERSION;
+use ream_req_resp::{
+    Chain,
+    rate_limiter::{ProtocolQuota, validate_protocol_quota},
+};
 
 use crate::cli::{
     account_manager::AccountManagerConfig,
     beacon_node::BeaconNodeConfig,
+    db::DbConfig,
     generate_private_key::GeneratePrivateKeyConfig,
     generate_validator_registry::GenerateValidatorRegistryConfig,
     lean_node::LeanNodeConfig,
+    lean_slashing_protection::LeanSlashingProtectionConfig,
     validator_node::ValidatorNodeConfig,
     verbosity::{Verbosity, verbosity_parser},
     voluntary_exit::VoluntaryExitConfig,
@@ -62,6 +70,7 @@ impl Cli {
     pub fn validate(&self) -> Result<(), clap::Error> {
         match &self.command {
             Commands::LeanNode(config) => config.validate(),
+            Commands::AccountManager(config) => config.validate(),
             _ => Ok(()),
         }
     }
@@ -89,13 +98,30 @@ pub enum Commands {
     #[command(name = "voluntary_exit")]
     VoluntaryExit(Box<VoluntaryExitConfig>),
 
-    /// Generate a secp256k1 keypair for lean node
+    /// Generate a secp256k1 keypair for the lean or beacon node
     #[command(name = "generate_private_key")]
     GeneratePrivateKey(Box<GeneratePrivateKeyConfig>),
 
     /// Generate a validator registry config
     #[command(name = "generate_validator_registry")]
     GenerateKeystore(Box<GenerateValidatorRegistryConfig>),
+
+    /// Import or export the lean validator slashing protection database
+    #[command(name = "lean_slashing_protection")]
+    LeanSlashingProtection(Box<LeanSlashingProtectionConfig>),
+
+    /// Inspect, prune, export, import, compact or verify the lean or beacon database
+    #[command(name = "db")]
+    Db(Box<DbConfig>),
+}
+
+/// Parse an `--inbound-rate-limits` quota, which has to name a req/resp protocol of `chain`.
+pub fn inbound_rate_limit_parser(chain: Chain, value: &str) -> Result<ProtocolQuota, String> {
+    let quota = value
+        .parse::<ProtocolQuota>()
+        .map_err(|err| err.to_string())?;
+    validate_protocol_quota(chain, &quota).map_err(|err| err.to_string())?;
+    Ok(quota)
 }
 
 #[cfg(test)]
@@ -105,11 +131,17 @@ mod tests {
         time::Duration,
     };
 
+    use alloy_primitives::B256;
     use ream_network_spec::networks::Network;
     use url::Url;
 
     use super::*;
-    use crate::cli::constants::DEFAULT_BEACON_API_ENDPOINT;
+    use crate::cli::{
+        account_manager::{KeystoreKdf, WithdrawalCredentialsType},
+        constants::DEFAULT_BEACON_API_ENDPOINT,
+        db::{DbChain, DbCommand, InspectFormat, InspectObject},
+        lean_slashing_protection::LeanSlashingProtectionCommand,
+    };
 
     #[test]
     fn test_cli_lean_node_command() {
@@ -172,6 +204,234 @@ mod tests {
         assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
     }
 
+    #[test]
+    fn test_cli_lean_node_archive() {
+        let cli = Cli::parse_from([
+            "program",
+            "lean_node",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "--validator-registry-path",
+            "./assets/lean/validator_registry.yml",
+            "--archive",
+            "--archive-snapshot-interval",
+            "32",
+        ]);
+
+        match cli.command {
+            Commands::LeanNode(config) => {
+                assert!(config.archive);
+                assert_eq!(config.archive_snapshot_interval, 32);
+            }
+            _ => unreachable!("This test should only validate the lean node cli"),
+        }
+
+        let err = Cli::try_parse_from([
+            "program",
+            "lean_node",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "--validator-registry-path",
+            "./assets/lean/validator_registry.yml",
+            "--archive-snapshot-interval",
+            "32",
+        ])
+        .unwrap_err();
+
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn test_cli_lean_node_keystores_require_password() {
+        let cli = Cli::parse_from([
+            "program",
+            "lean_node",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "--validator-registry-path",
+            "./assets/lean/validator_registry.yml",
+            "--keystore-path",
+            "./keystores",
+            "--password-file",
+            "./password.txt",
+        ]);
+
+        match cli.command {
+            Commands::LeanNode(config) => {
+                assert_eq!(config.keystore_path, Some(PathBuf::from("./keystores")));
+                assert_eq!(config.password_file, Some(PathBuf::from("./password.txt")));
+            }
+            _ => unreachable!("This test should only validate the lean node cli"),
+        }
+
+        let err = Cli::try_parse_from([
+            "program",
+            "lean_node",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "--validator-registry-path",
+            "./assets/lean/validator_registry.yml",
+            "--keystore-path",
+            "./keystores",
+        ])
+        .unwrap_err();
+
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn test_cli_lean_node_checkpoint_sync() {
+        let cli = Cli::parse_from([
+            "program",
+            "lean_node",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "--validator-registry-path",
+            "./assets/lean/validator_registry.yml",
+            "--checkpoint-sync-url",
+            "http://127.0.0.1:5052,http://127.0.0.1:5053",
+            "--checkpoint-sync-url",
+            "http://127.0.0.1:5054",
+            "--checkpoint-sync-quorum",
+            "2",
+            "--weak-subjectivity-checkpoint",
+            "0x0101010101010101010101010101010101010101010101010101010101010101:64",
+        ]);
+
+        match cli.command {
+            Commands::LeanNode(config) => {
+                assert_eq!(config.checkpoint_sync_url.len(), 3);
+                assert_eq!(config.checkpoint_sync_quorum, Some(2));
+                let weak_subjectivity_checkpoint = config.weak_subjectivity_checkpoint.unwrap();
+                assert_eq!(weak_subjectivity_checkpoint.root, B256::repeat_byte(0x01));
+                assert_eq!(weak_subjectivity_checkpoint.slot, 64);
+            }
+            _ => unreachable!("This test should only validate the lean node cli"),
+        }
+
+        let err = Cli::try_parse_from([
+            "program",
+            "lean_node",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "--validator-registry-path",
+            "./assets/lean/validator_registry.yml",
+            "--checkpoint-sync-url",
+            "http://127.0.0.1:5052,http://127.0.0.1:5052",
+            "--checkpoint-sync-quorum",
+            "2",
+        ])
+        .and_then(|cli| cli.validate())
+        .unwrap_err();
+
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_cli_lean_node_discovery_port() {
+        let cli = Cli::parse_from([
+            "program",
+            "lean_node",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "--validator-registry-path",
+            "./assets/lean/validator_registry.yml",
+        ]);
+        cli.validate().unwrap();
+
+        match cli.command {
+            Commands::LeanNode(config) => {
+                assert_eq!(config.socket_port, 9000);
+                assert_eq!(config.discovery_port, 9001);
+                assert!(!config.disable_discovery);
+            }
+            _ => unreachable!("This test should only validate the lean node cli"),
+        }
+
+        // discv5 and QUIC both listen on UDP
+        let err = Cli::try_parse_from([
+            "program",
+            "lean_node",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "--validator-registry-path",
+            "./assets/lean/validator_registry.yml",
+            "--discovery-port",
+            "9000",
+        ])
+        .and_then(|cli| cli.validate())
+        .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+
+        // The ENR can't advertise lean subnets beyond the attnets bitfield
+        let err = Cli::try_parse_from([
+            "program",
+            "lean_node",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "--validator-registry-path",
+            "./assets/lean/validator_registry.yml",
+            "--attestation-committee-count",
+            "65",
+        ])
+        .and_then(|cli| cli.validate())
+        .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+
+        let cli = Cli::parse_from([
+            "program",
+            "lean_node",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "--validator-registry-path",
+            "./assets/lean/validator_registry.yml",
+            "--attestation-committee-count",
+            "65",
+            "--disable-discovery",
+        ]);
+        cli.validate().unwrap();
+    }
+
+    #[test]
+    fn test_cli_lean_node_inbound_rate_limits() {
+        let cli = Cli::parse_from([
+            "program",
+            "lean_node",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "--validator-registry-path",
+            "./assets/lean/validator_registry.yml",
+            "--inbound-rate-limits",
+            "blocks_by_range:512/10,status:10/15",
+        ]);
+
+        match cli.command {
+            Commands::LeanNode(config) => {
+                let quotas = config
+                    .inbound_rate_limits
+                    .iter()
+                    .map(|quota| quota.to_string())
+                    .collect::<Vec<_>>();
+                assert_eq!(quotas, vec!["blocks_by_range:512/10", "status:10/15"]);
+            }
+            _ => unreachable!("This test should only validate the lean node cli"),
+        }
+
+        // beacon_blocks_by_range is not a lean protocol
+        let err = Cli::try_parse_from([
+            "program",
+            "lean_node",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "--validator-registry-path",
+            "./assets/lean/validator_registry.yml",
+            "--inbound-rate-limits",
+            "beacon_blocks_by_range:512/10",
+        ])
+        .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
     #[test]
     fn test_cli_beacon_node_command() {
         let cli = Cli::parse_from([
@@ -266,6 +526,128 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_account_manager_beacon_command() {
+        let cli = Cli::parse_from([
+            "program",
+            "account_manager",
+            "--beacon",
+            "--network",
+            "hoodi",
+            "--num-validators",
+            "4",
+            "--withdrawal-address",
+            "0x003Fb16e421E42084EBC54bcdc7F0fa344cF9316",
+            "--withdrawal-credentials-type",
+            "0x02",
+            "--amount",
+            "64000000000",
+            "--kdf",
+            "pbkdf2",
+        ]);
+        cli.validate().unwrap();
+
+        match cli.command {
+            Commands::AccountManager(config) => {
+                assert!(config.beacon);
+                assert_eq!(config.network.network, Network::Hoodi);
+                assert_eq!(config.num_validators, 4);
+                assert_eq!(config.start_index, 0);
+                assert_eq!(
+                    config.withdrawal_credentials_type,
+                    WithdrawalCredentialsType::Compounding
+                );
+                assert_eq!(config.amount, 64_000_000_000);
+                assert_eq!(config.kdf, KeystoreKdf::Pbkdf2);
+            }
+            _ => unreachable!("This test should only validate the account manager cli"),
+        }
+
+        // Regular validators can't be deposited more than 32 ETH
+        let cli = Cli::parse_from([
+            "program",
+            "account_manager",
+            "--beacon",
+            "--withdrawal-address",
+            "0x003Fb16e421E42084EBC54bcdc7F0fa344cF9316",
+            "--amount",
+            "64000000000",
+        ]);
+        assert!(cli.validate().is_err());
+
+        // Beacon options need the beacon mode, which needs a withdrawal address
+        assert!(
+            Cli::try_parse_from(["program", "account_manager", "--num-validators", "4"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["program", "account_manager", "--beacon"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_lean_slashing_protection_command() {
+        let cli = Cli::parse_from([
+            "program",
+            "lean_slashing_protection",
+            "--network",
+            "./assets/lean/config-devnet4.yaml",
+            "export",
+            "--output-path",
+            "interchange.json",
+        ]);
+
+        match cli.command {
+            Commands::LeanSlashingProtection(config) => match config.command {
+                LeanSlashingProtectionCommand::Export { output_path } => {
+                    assert_eq!(output_path.to_str().unwrap(), "interchange.json");
+                }
+                _ => unreachable!("Expected the export subcommand"),
+            },
+            _ => unreachable!("This test should only validate the lean slashing protection cli"),
+        }
+    }
+
+    #[test]
+    fn test_cli_db_command() {
+        let cli = Cli::parse_from([
+            "program", "db", "--chain", "lean", "inspect", "state", "--slot", "42", "--format",
+            "ssz",
+        ]);
+
+        match cli.command {
+            Commands::Db(config) => {
+                assert_eq!(config.chain, Some(DbChain::Lean));
+                match config.command {
+                    DbCommand::Inspect {
+                        object,
+                        root,
+                        slot,
+                        format,
+                        output_path,
+                    } => {
+                        assert_eq!(object, InspectObject::State);
+                        assert_eq!(root, None);
+                        assert_eq!(slot, Some(42));
+                        assert_eq!(format, InspectFormat::Ssz);
+                        assert_eq!(output_path, None);
+                    }
+                    _ => unreachable!("Expected the inspect subcommand"),
+                }
+            }
+            _ => unreachable!("This test should only validate the db cli"),
+        }
+
+        let result = Cli::try_parse_from([
+            "program",
+            "db",
+            "inspect",
+            "block",
+            "--slot",
+            "42",
+            "--root",
+            "0x0000000000000000000000000000000000000000000000000000000000000000",
+        ]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_verbosity_levels() {
         // Test error level (1)
@@ -24,6 +24,12 @@ pub struct ValidatorNodeConfig {
     #[arg(long, help = "Set HTTP Port of the key manager server", default_value_t = DEFAULT_KEY_MANAGER_HTTP_PORT)]
     pub key_manager_http_port: u16,
 
+    #[arg(
+        long,
+        help = "The file containing the bearer token for the key manager server. A random token is generated there if the file doesn't exist [default: <DATA_DIR>/api-token.txt]"
+    )]
+    pub key_manager_token_file: Option<PathBuf>,
+
     #[arg(
         long,
         help = "Choose mainnet, sepolia, hoodi, dev or provide a path to a YAML config file",
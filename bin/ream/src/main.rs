@@ -1,66 +1,99 @@
 use std::{
     collections::BTreeSet,
-    env, fs,
+    env,
+    fmt::Write as _,
+    fs,
+    io::{self, Write},
     net::SocketAddr,
     path::{Path, PathBuf},
     process,
+    str::FromStr,
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use alloy_primitives::hex;
+use anyhow::{anyhow, bail};
 use bip39::Mnemonic;
 use libp2p_identity::secp256k1;
 use ream::{
     cli::{
         Cli, Commands,
-        account_manager::AccountManagerConfig,
+        account_manager::{AccountManagerConfig, KeystoreKdf},
         beacon_node::BeaconNodeConfig,
+        db::{DbChain, DbCommand, DbConfig, InspectFormat, InspectObject},
         generate_private_key::GeneratePrivateKeyConfig,
         generate_validator_registry::run_generate_validator_registry,
-        import_keystores::{load_keystore_directory, load_password_from_config, process_password},
+        import_keystores::{
+            load_keystore_directory, load_password_file, load_password_from_config,
+            process_password, prompt_new_password,
+        },
         lean_node::LeanNodeConfig,
+        lean_slashing_protection::{LeanSlashingProtectionCommand, LeanSlashingProtectionConfig},
         validator_node::ValidatorNodeConfig,
         verbosity::Verbosity,
         voluntary_exit::VoluntaryExitConfig,
     },
     startup_message::startup_message,
 };
-use ream_account_manager::{message_types::MessageType, seed::derive_seed_with_user_input};
+use ream_account_manager::{
+    deposit::{DepositDataEntry, execution_withdrawal_credentials},
+    message_types::MessageType,
+    seed::derive_seed_with_user_input,
+};
 use ream_api_types_beacon::id::ValidatorID;
 use ream_api_types_common::{content_type::ContentType, id::ID};
+use ream_bls::{PrivateKey as BLSPrivateKey, traits::PublicKeyDerivable};
 use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_chain_lean::{
-    messages::LeanChainServiceMessage, p2p_request::LeanP2PRequest, service::LeanChainService,
+    clock::get_initial_tick_count, messages::LeanChainServiceMessage, p2p_request::LeanP2PRequest,
+    service::LeanChainService,
 };
 use ream_checkpoint_sync_beacon::{
     initialize_db_from_checkpoint, initialize_db_from_genesis_state,
 };
-use ream_checkpoint_sync_lean::{LeanCheckpointClient, verify_checkpoint_state};
+#[cfg(feature = "devnet5")]
+use ream_checkpoint_sync_lean::verify_checkpoint_block;
+use ream_checkpoint_sync_lean::{
+    LeanCheckpointClient,
+    checkpoint::{default_quorum, fetch_checkpoint_anchor, get_checkpoint_sync_sources},
+    verify_checkpoint_state,
+    weak_subjectivity::verify_state_from_weak_subjectivity_checkpoint,
+};
 #[cfg(feature = "devnet5")]
 use ream_consensus_lean::attestation::MultiMessageAggregate;
 use ream_consensus_lean::{block::SignedBlock, validator::Validator};
 use ream_consensus_misc::{
     constants::{
         beacon::set_genesis_validator_root,
-        lean::{attestation_committee_count, set_attestation_committee_count},
+        lean::{INTERVALS_PER_SLOT, attestation_committee_count, set_attestation_committee_count},
     },
     misc::compute_epoch_at_slot,
 };
+use ream_discv5::{config::LeanDiscoveryConfig, subnet::AttestationSubnets};
+use ream_era::{
+    beacon::{export_eras, import_eras},
+    lean::{export_lean_chain, import_lean_chain},
+};
 use ream_events_beacon::BeaconEvent;
+use ream_events_lean::LeanEvent;
 use ream_execution_engine::ExecutionEngine;
 use ream_executor::ReamExecutor;
 use ream_fork_choice_lean::{
     genesis::setup_genesis,
     store::{Store, compute_subnet_id},
 };
-use ream_keystore::keystore::EncryptedKeystore;
+use ream_keystore::{
+    key_derivation::{derive_private_key, signing_key_path},
+    keystore::{EncryptedKeystore, KdfParams, KeyTypeParams},
+};
 use ream_metrics::{
     ATTESTATION_COMMITTEE_SUBNET, NODE_INFO, NODE_START_TIME_SECONDS, set_int_gauge_vec,
 };
 use ream_network_manager::service::NetworkManagerService;
 use ream_network_spec::networks::{
-    beacon_network_spec, lean_network_spec, set_beacon_network_spec, set_lean_network_spec,
+    LeanNetworkSpec, beacon_network_spec, lean_network_spec, set_beacon_network_spec,
+    set_lean_network_spec,
 };
 use ream_network_state_lean::AggregatorState;
 use ream_node::version::REAM_VERSION;
@@ -77,11 +110,13 @@ use ream_post_quantum_crypto::lean_multisig::type_2::{type_2_setup, type_2_setup
 use ream_post_quantum_crypto::leansig::{
     private_key::PrivateKey as LeanSigPrivateKey, public_key::PublicKey,
 };
+use ream_req_resp::{Chain, rate_limiter::RateLimiterConfig};
 use ream_rpc_common::config::RpcServerConfig;
+use ream_rpc_keymanager::auth::{API_TOKEN_FILENAME, ApiToken};
 use ream_rpc_lean::{handlers::test_driver::test_driver_enabled, server::start_test_driver};
 use ream_storage::{
     cache::{BeaconCacheDB, LeanCacheDB},
-    db::{ReamDB, reset_db},
+    db::{ReamDB, beacon::BeaconDB, lean::LeanDB, reset_db},
     dir::setup_data_dir,
     tables::table::REDBTable,
 };
@@ -90,19 +125,30 @@ use ream_sync_committee_pool::SyncCommitteePool;
 use ream_validator_beacon::{
     beacon_api_client::BeaconApiClient,
     builder::builder_client::{BuilderClient, BuilderConfig},
+    slashing_protection::SlashingProtectionDB as BeaconSlashingProtectionDB,
     validator::ValidatorService,
+    validator_store::ValidatorStore,
     voluntary_exit::process_voluntary_exit,
 };
 use ream_validator_lean::{
-    registry::load_validator_registry, service::ValidatorService as LeanValidatorService,
+    key_usage::{KEY_USAGE_FOLDER_NAME, ManagedKeystore},
+    registry::{
+        load_encrypted_keystores, load_validator_registry,
+        load_validator_registry_with_private_keys,
+    },
+    service::ValidatorService as LeanValidatorService,
+    slashing_protection::{SlashingProtectionDB, interchange::Interchange},
 };
+use serde::Serialize;
+use ssz::Encode;
 use ssz_types::VariableList;
 use tokio::{
     sync::{broadcast, mpsc::unbounded_channel},
     time::{self, Instant},
 };
-use tracing::{Instrument, error, info};
+use tracing::{Instrument, error, info, warn};
 use tracing_subscriber::EnvFilter;
+use tree_hash::TreeHash;
 
 #[global_allocator]
 static ALLOC: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
@@ -164,9 +210,8 @@ fn main() {
                 ReamDB::new(ream_directory.clone()).expect("unable to init Ream Database");
             executor_clone.spawn(async move { run_beacon_node(*config, executor, ream_db).await })
         }
-        Commands::ValidatorNode(config) => {
-            executor_clone.spawn(async move { run_validator_node(*config, executor).await })
-        }
+        Commands::ValidatorNode(config) => executor_clone
+            .spawn(async move { run_validator_node(*config, executor, ream_directory).await }),
         Commands::AccountManager(config) => {
             executor_clone.spawn(async move { run_account_manager(*config, ream_directory).await })
         }
@@ -180,6 +225,11 @@ fn main() {
             run_generate_validator_registry(*config).expect("failed to generate hash-sig keystore");
             process::exit(0);
         }
+        Commands::LeanSlashingProtection(config) => executor_clone
+            .spawn(async move { run_lean_slashing_protection(*config, ream_directory).await }),
+        Commands::Db(config) => {
+            executor_clone.spawn(async move { run_db(*config, ream_directory).await })
+        }
     };
 
     executor_clone.runtime().block_on(async {
@@ -235,8 +285,23 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
 
     set_attestation_committee_count(config.attestation_committee_count);
 
-    let keystores = load_validator_registry(&config.validator_registry_path, &config.node_id)
-        .expect("Failed to load validator registry");
+    let keystores = match &config.keystore_path {
+        Some(keystore_path) => {
+            let password = process_password(
+                load_password_from_config(config.password_file.as_ref(), config.password.clone())
+                    .expect("Failed to load password"),
+            );
+            let private_keys = load_encrypted_keystores(keystore_path, password.as_bytes())
+                .expect("Failed to load keystores");
+            load_validator_registry_with_private_keys(
+                &config.validator_registry_path,
+                &config.node_id,
+                private_keys,
+            )
+        }
+        None => load_validator_registry(&config.validator_registry_path, &config.node_id),
+    }
+    .expect("Failed to load validator registry");
 
     if let Some(keystore) = keystores.first() {
         set_int_gauge_vec(
@@ -270,6 +335,14 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
         .init_lean_db()
         .expect("unable to init Ream Lean Database")
         .with_cache(cache);
+    ream_db
+        .check_network(&lean_network_name(&lean_network_spec()))
+        .expect("Lean database belongs to another network, use another --data-dir or --purge-db");
+
+    if let Err(err) = lean_db.check_consistency() {
+        error!("Lean database is inconsistent, resync with --purge-db: {err:?}");
+        process::exit(1);
+    }
 
     info!("ream lean database has been initialized");
 
@@ -277,14 +350,37 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
     let (chain_sender, chain_receiver) = unbounded_channel::<LeanChainServiceMessage>();
     let (outbound_p2p_sender, outbound_p2p_receiver) = unbounded_channel::<LeanP2PRequest>();
 
-    let (anchor_signed_block, anchor_state) = if let Some(url) = config.checkpoint_sync_url.clone()
-    {
-        let (state, signed_block) = LeanCheckpointClient::new()
-            .fetch_finalized_anchor(&url)
-            .await
-            .expect("Failed to fetch checkpoint anchor");
+    let (anchor_signed_block, anchor_state) = if !config.checkpoint_sync_url.is_empty() {
+        let sources = get_checkpoint_sync_sources(config.checkpoint_sync_url.clone());
+        let quorum = config
+            .checkpoint_sync_quorum
+            .map(|quorum| quorum as usize)
+            .unwrap_or_else(|| default_quorum(sources.len()));
+        let (state, signed_block) =
+            fetch_checkpoint_anchor(&LeanCheckpointClient::new(), &sources, quorum)
+                .await
+                .expect("Failed to fetch checkpoint anchor");
 
         verify_checkpoint_state(&state).expect("Downloaded checkpoint state failed to verify");
+        #[cfg(feature = "devnet5")]
+        verify_checkpoint_block(&state, &signed_block)
+            .expect("Downloaded checkpoint block failed to verify");
+
+        if let Some(weak_subjectivity_checkpoint) = &config.weak_subjectivity_checkpoint {
+            let verified = verify_state_from_weak_subjectivity_checkpoint(
+                &state,
+                signed_block.block.tree_hash_root(),
+                weak_subjectivity_checkpoint,
+            )
+            .expect("Checkpoint sync anchor doesn't contain the weak subjectivity checkpoint");
+            if !verified {
+                panic!(
+                    "Weak subjectivity checkpoint at slot {} is ahead of the checkpoint sync anchor at slot {}",
+                    weak_subjectivity_checkpoint.slot, state.slot
+                );
+            }
+            info!("Checkpoint sync anchor contains the weak subjectivity checkpoint");
+        }
 
         (signed_block, state)
     } else {
@@ -310,6 +406,7 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
         };
         (signed_genesis, genesis_state)
     };
+    let (lean_event_sender, _) = broadcast::channel::<LeanEvent>(1024);
     let (lean_chain_writer, lean_chain_reader) = Writer::new(
         Store::get_forkchoice_store(
             anchor_signed_block,
@@ -319,7 +416,8 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
             keystores.first().map(|keystore| keystore.index),
         )
         .expect("Could not get forkchoice store")
-        .with_block_production_strategy(config.block_production),
+        .with_block_production_strategy(config.block_production)
+        .with_event_sender(lean_event_sender.clone()),
     );
 
     let test_driver_enabled = test_driver_enabled();
@@ -339,6 +437,7 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
             lean_chain_writer,
             network_state,
             aggregator_state,
+            lean_event_sender,
         )
         .await
         .expect("Lean test-driver RPC service stopped unexpectedly");
@@ -395,8 +494,29 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
         "Computed attestation subnet subscriptions"
     );
 
+    let mut attestation_subnets = AttestationSubnets::new();
+    for subnet_id in &subscribed_subnets {
+        if let Err(err) = attestation_subnets.enable_attestation_subnet(*subnet_id) {
+            warn!("Attestation subnet {subnet_id} is not advertised in the ENR: {err}");
+        }
+    }
+
     let mut network_service = LeanNetworkService::new(
         Arc::new(LeanNetworkConfig {
+            discv5_config: LeanDiscoveryConfig {
+                discv5_config: discv5::ConfigBuilder::new(discv5::ListenConfig::from_ip(
+                    config.socket_address,
+                    config.discovery_port,
+                ))
+                .build(),
+                bootnodes: config.bootnodes.to_enrs_lean(),
+                socket_address: config.socket_address,
+                socket_port: config.socket_port,
+                discovery_port: config.discovery_port,
+                disable_discovery: config.disable_discovery,
+                fork_digest: fork.parse().expect("Lean fork digest is valid hex"),
+                attestation_subnets,
+            },
             gossipsub_config: LeanGossipsubConfig {
                 topics,
                 ..Default::default()
@@ -404,6 +524,9 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
             socket_address: config.socket_address,
             socket_port: config.socket_port,
             private_key_path: config.private_key_path,
+            data_dir: ream_db.data_dir().to_path_buf(),
+            rate_limiter_config: RateLimiterConfig::new(Chain::Lean)
+                .with_quotas(&config.inbound_rate_limits),
         }),
         executor.clone(),
         chain_sender.clone(),
@@ -418,12 +541,35 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
         chain_receiver,
         outbound_p2p_sender,
         aggregator_state.clone(),
+        config.archive.then_some(config.archive_snapshot_interval),
     )
     .await;
 
-    let arc_keystores: Vec<Arc<_>> = keystores.into_iter().map(Arc::new).collect();
+    let slashing_protection = SlashingProtectionDB::open(ream_db.data_dir())
+        .expect("Failed to open slashing protection database");
+    if let Some(path) = &config.slashing_protection_import {
+        import_slashing_protection_interchange(&slashing_protection, path)
+            .expect("Failed to import slashing protection interchange");
+    }
 
-    let validator_service = LeanValidatorService::new(arc_keystores, chain_sender).await;
+    let key_usage_dir = ream_db.data_dir().join(KEY_USAGE_FOLDER_NAME);
+    let current_slot = get_initial_tick_count() / INTERVALS_PER_SLOT;
+    let arc_keystores: Vec<Arc<_>> = keystores
+        .into_iter()
+        .map(|keystore| {
+            ManagedKeystore::new(keystore, &key_usage_dir, current_slot)
+                .map(Arc::new)
+                .expect("Failed to load key usage state")
+        })
+        .collect();
+
+    let validator_service = LeanValidatorService::new(
+        arc_keystores,
+        chain_sender,
+        slashing_protection,
+        config.key_lifetime_warning_epochs,
+    )
+    .await;
 
     let server_config = RpcServerConfig::new(
         config.http_address,
@@ -449,6 +595,7 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
                     lean_chain_reader,
                     network_state,
                     aggregator_state,
+                    lean_event_sender,
                 )
                 .await
             }
@@ -507,6 +654,14 @@ async fn run_beacon_node_inner(
         .init_beacon_db()
         .expect("unable to init Ream Beacon Database")
         .with_cache(cache.clone());
+    ream_db
+        .check_network(&config.network.network.to_string())
+        .expect("Beacon database belongs to another network, use another --data-dir or --purge-db");
+
+    if let Err(err) = beacon_db.check_consistency() {
+        error!("Beacon database is inconsistent, resync with --purge-db: {err:?}");
+        process::exit(1);
+    }
 
     info!("ream beacon database has been initialized");
 
@@ -540,11 +695,11 @@ async fn run_beacon_node_inner(
         set_genesis_validator_root(genesis_validators_root);
     }
 
-    let operation_pool = Arc::new(OperationPool::default());
-    let sync_committee_pool = Arc::new(SyncCommitteePool::default());
-
     let (event_sender, _) = broadcast::channel::<BeaconEvent>(1024);
 
+    let operation_pool = Arc::new(OperationPool::new(Some(event_sender.clone())));
+    let sync_committee_pool = Arc::new(SyncCommitteePool::default());
+
     let server_config = RpcServerConfig::new(
         config.http_address,
         config.http_port,
@@ -649,8 +804,13 @@ async fn run_beacon_node_for_test(
 ///
 /// This function initializes the validator node by setting up the network specification,
 /// loading the keystores, and creating a validator service.
-/// It also starts the validator service.
-pub async fn run_validator_node(config: ValidatorNodeConfig, executor: ReamExecutor) {
+/// It also starts the validator service and the Keymanager API server, which can add and remove
+/// keystores while the validator service is running.
+pub async fn run_validator_node(
+    config: ValidatorNodeConfig,
+    executor: ReamExecutor,
+    ream_directory: PathBuf,
+) {
     info!("starting up validator node...");
 
     set_beacon_network_spec(config.network.clone());
@@ -660,26 +820,69 @@ pub async fn run_validator_node(config: ValidatorNodeConfig, executor: ReamExecu
             .expect("Failed to load password"),
     );
 
-    let keystores = load_keystore_directory(&config.import_keystores)
-        .expect("Failed to load keystore directory")
-        .into_iter()
-        .map(|encrypted_keystore| {
-            encrypted_keystore
-                .decrypt(password.as_bytes())
-                .expect("Could not decrypt a keystore")
-        })
-        .collect::<Vec<_>>();
+    let validator_store = Arc::new(
+        ValidatorStore::load(
+            &config.import_keystores,
+            &ream_directory,
+            &password,
+            config.suggested_fee_recipient,
+        )
+        .expect("Failed to load keystores"),
+    );
+    let slashing_protection = BeaconSlashingProtectionDB::open(&ream_directory)
+        .expect("Failed to open slashing protection database");
+
+    let genesis_validators_root =
+        BeaconApiClient::new(config.beacon_api_endpoint.clone(), config.request_timeout)
+            .expect("Failed to create beacon API client")
+            .get_genesis()
+            .await
+            .expect("Failed to fetch genesis from the beacon node")
+            .data
+            .genesis_validators_root;
+
+    let api_token = ApiToken::load_or_generate(
+        &config
+            .key_manager_token_file
+            .unwrap_or_else(|| ream_directory.join(API_TOKEN_FILENAME)),
+    )
+    .expect("Failed to load key manager API token");
 
     let validator_service = ValidatorService::new(
-        keystores,
-        config.suggested_fee_recipient,
+        validator_store.clone(),
+        slashing_protection.clone(),
         config.beacon_api_endpoint,
         config.request_timeout,
-        executor,
+        executor.clone(),
     )
     .expect("Failed to create validator service");
 
-    validator_service.start().await;
+    let server_config = RpcServerConfig::new(
+        config.key_manager_http_address,
+        config.key_manager_http_port,
+        false,
+    );
+
+    let mut validator_task = AbortOnDrop(executor.spawn(validator_service.start()));
+    let mut http_task = AbortOnDrop(executor.spawn(async move {
+        ream_rpc_keymanager::server::start(
+            server_config,
+            api_token,
+            validator_store,
+            slashing_protection,
+            genesis_validators_root,
+        )
+        .await
+    }));
+
+    tokio::select! {
+        result = &mut validator_task.0 => {
+            error!("Validator service has stopped unexpectedly: {result:?}");
+        },
+        result = &mut http_task.0 => {
+            error!("Key manager server has stopped unexpectedly: {result:?}");
+        }
+    }
 }
 
 /// Runs the account manager.
@@ -695,7 +898,7 @@ pub async fn run_account_manager(config: AccountManagerConfig, ream_directory: P
     );
 
     // Get seed phrase or generate a new one
-    let seed_phrase = config.seed_phrase.unwrap_or_else(|| {
+    let seed_phrase = config.seed_phrase.clone().unwrap_or_else(|| {
         let mnemonic = Mnemonic::generate(24).expect("Failed to generate mnemonic");
         let seed_phrase = mnemonic.words().collect::<Vec<_>>().join(" ");
         info!("{}", "=".repeat(89));
@@ -704,6 +907,14 @@ pub async fn run_account_manager(config: AccountManagerConfig, ream_directory: P
         seed_phrase
     });
 
+    let password = process_password(
+        match &config.password_file {
+            Some(password_file) => load_password_file(password_file),
+            None => prompt_new_password(),
+        }
+        .expect("Failed to read keystore password"),
+    );
+
     // Create keystore directory as subdirectory of data directory
     let keystore_dir = match &config.keystore_path {
         Some(custom_path) => {
@@ -714,6 +925,8 @@ pub async fn run_account_manager(config: AccountManagerConfig, ream_directory: P
                 ream_directory.join(custom_path)
             }
         }
+        // The lean node loads every keystore in `keystores`, which must not hold beacon keystores
+        None if config.beacon => ream_directory.join("validator_keys"),
         None => ream_directory.join("keystores"),
     };
 
@@ -728,6 +941,19 @@ pub async fn run_account_manager(config: AccountManagerConfig, ream_directory: P
     // Measure key generation time
     let start_time = Instant::now();
 
+    if config.beacon {
+        generate_beacon_validator_keys(
+            &config,
+            &seed_phrase,
+            &password,
+            &keystore_dir,
+            &ream_directory,
+        );
+        info!("Key generation complete, took {:?}", start_time.elapsed());
+        info!("Account manager completed successfully");
+        process::exit(0);
+    }
+
     // Generate keys sequentially for each message type
     for (index, message_type) in MessageType::iter().enumerate() {
         info!(
@@ -752,17 +978,23 @@ pub async fn run_account_manager(config: AccountManagerConfig, ream_directory: P
             serde_json::to_string_pretty(&public_key).expect("Failed to serialize public key")
         );
 
-        // Create keystore file using Keystore
-        let keystore = EncryptedKeystore::from_seed_phrase(
-            &seed_phrase,
-            config.lifetime,
-            config.activation_epoch,
+        // Create keystore file holding the encrypted seed of the key
+        let keystore = EncryptedKeystore::from_seed(
+            seed,
+            public_key,
+            password.as_bytes(),
+            KeyTypeParams::XmssPoseidon2OtsSeed {
+                lifetime: config.lifetime,
+                activation_epoch: config.activation_epoch,
+                num_active_epochs: config.num_active_epochs,
+            },
             Some(format!("Ream validator keystore for {message_type}")),
             Some(format!("m/44'/60'/0'/0/{index}")),
-        );
+        )
+        .expect("Failed to encrypt keystore");
 
-        // Write keystore to file with enum name
-        let filename = message_type.to_string();
+        // Write keystore to file with enum name, the lean node only loads `.json` keystores
+        let filename = format!("{message_type}.json");
         let keystore_file_path = keystore_dir.join(filename);
         let keystore_json =
             ::serde_json::to_string_pretty(&keystore).expect("Failed to serialize keystore");
@@ -779,6 +1011,91 @@ pub async fn run_account_manager(config: AccountManagerConfig, ream_directory: P
     process::exit(0);
 }
 
+/// Writes an EIP-2335 keystore for each beacon validator key derived from `seed_phrase` at its
+/// EIP-2334 signing key path, followed by a `deposit_data-*.json` file depositing to all of them.
+fn generate_beacon_validator_keys(
+    config: &AccountManagerConfig,
+    seed_phrase: &str,
+    password: &str,
+    keystore_dir: &Path,
+    deposit_data_dir: &Path,
+) {
+    let seed = Mnemonic::from_str(seed_phrase)
+        .expect("Invalid mnemonic phrase")
+        .to_seed(config.passphrase.as_deref().unwrap_or(""));
+    let withdrawal_credentials = execution_withdrawal_credentials(
+        config.withdrawal_credentials_type.prefix(),
+        config
+            .withdrawal_address
+            .expect("Beacon mode requires a withdrawal address"),
+    );
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the Unix epoch")
+        .as_secs();
+
+    let mut deposit_data = vec![];
+    for index in config.start_index..config.start_index + config.num_validators {
+        let path = signing_key_path(index);
+        info!("Generating beacon validator key {path}...");
+
+        let private_key = BLSPrivateKey {
+            inner: derive_private_key(&seed, &path).expect("Failed to derive private key"),
+        };
+        let public_key = private_key
+            .public_key()
+            .expect("Failed to derive public key");
+        info!(
+            "Public key for {path}: 0x{}",
+            hex::encode(public_key.to_bytes())
+        );
+
+        let kdf_params = match config.kdf {
+            KeystoreKdf::Scrypt => KdfParams::new_scrypt(),
+            KeystoreKdf::Pbkdf2 => KdfParams::new_pbkdf2(),
+        };
+        let keystore = EncryptedKeystore::encrypt(
+            &private_key,
+            public_key,
+            password.as_bytes(),
+            kdf_params,
+            path.clone(),
+        )
+        .expect("Failed to encrypt keystore");
+        let keystore_file_path = keystore_dir.join(format!(
+            "keystore-{}-{timestamp}.json",
+            path.replace('/', "_")
+        ));
+        keystore
+            .save_to_file(&keystore_file_path)
+            .expect("Failed to write keystore file");
+        info!("Keystore written to path: {}", keystore_file_path.display());
+
+        deposit_data.push(
+            DepositDataEntry::new(
+                &private_key,
+                withdrawal_credentials,
+                config.amount,
+                config.network.genesis_fork_version,
+                config.network.network.to_string(),
+            )
+            .expect("Failed to sign deposit data"),
+        );
+    }
+
+    // Keystore directories are loaded as a whole, so the deposit data is written next to them
+    let deposit_data_path = deposit_data_dir.join(format!("deposit_data-{timestamp}.json"));
+    fs::write(
+        &deposit_data_path,
+        serde_json::to_string(&deposit_data).expect("Failed to serialize deposit data"),
+    )
+    .expect("Failed to write deposit data file");
+    info!(
+        "Deposit data written to path: {}",
+        deposit_data_path.display()
+    );
+}
+
 /// Runs the voluntary exit process.
 ///
 /// This function initializes the voluntary exit process by setting up the network specification,
@@ -849,8 +1166,8 @@ fn get_current_epoch(genesis_time: u64) -> u64 {
 
 /// Generates a new secp256k1 keypair and saves it to the specified path in hex encoding.
 ///
-/// This allows the lean node to reuse the same network identity across restarts by loading
-/// the saved key with the --private-key-path flag.
+/// This allows a node to reuse the same network identity across restarts by loading the saved
+/// key with the --private-key-path flag.
 pub async fn run_generate_private_key(config: GeneratePrivateKeyConfig) {
     info!("Generating new secp256k1 private key...");
 
@@ -878,6 +1195,380 @@ pub async fn run_generate_private_key(config: GeneratePrivateKeyConfig) {
     process::exit(0);
 }
 
+/// Imports or exports the lean validator slashing protection database stored in the data
+/// directory.
+pub async fn run_lean_slashing_protection(
+    config: LeanSlashingProtectionConfig,
+    ream_directory: PathBuf,
+) {
+    set_lean_network_spec(Arc::new(config.network));
+
+    let slashing_protection = SlashingProtectionDB::open(&ream_directory)
+        .expect("Failed to open slashing protection database");
+
+    match config.command {
+        LeanSlashingProtectionCommand::Import { input_path } => {
+            import_slashing_protection_interchange(&slashing_protection, &input_path)
+                .expect("Failed to import slashing protection interchange");
+        }
+        LeanSlashingProtectionCommand::Export { output_path } => {
+            let interchange = slashing_protection
+                .export_interchange(lean_network_spec().genesis_time)
+                .expect("Failed to export slashing protection interchange");
+            fs::write(
+                &output_path,
+                serde_json::to_string_pretty(&interchange)
+                    .expect("Failed to serialize slashing protection interchange"),
+            )
+            .expect("Failed to write slashing protection interchange file");
+            info!(
+                "Slashing protection interchange exported to: {}",
+                output_path.display()
+            );
+        }
+    }
+
+    process::exit(0);
+}
+
+fn import_slashing_protection_interchange(
+    slashing_protection: &SlashingProtectionDB,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let interchange = serde_json::from_str::<Interchange>(&fs::read_to_string(path)?)?;
+    slashing_protection.import_interchange(&interchange, lean_network_spec().genesis_time)?;
+    info!(
+        "Slashing protection interchange imported from: {}",
+        path.display()
+    );
+    Ok(())
+}
+
+/// Inspects, prunes, compacts or verifies the lean or beacon database stored in the data
+/// directory.
+pub async fn run_db(config: DbConfig, ream_directory: PathBuf) {
+    let ream_db = ReamDB::new(ream_directory).expect("unable to init Ream Database");
+    let chain = match config.chain {
+        Some(chain) => chain,
+        None => match ream_db
+            .get_chain()
+            .expect("Failed to read the chain of the database")
+            .as_deref()
+        {
+            Some("beacon") => DbChain::Beacon,
+            Some("lean") => DbChain::Lean,
+            _ => {
+                error!("The database doesn't record its chain, set it with --chain");
+                process::exit(1);
+            }
+        },
+    };
+
+    if let DbCommand::Compact = config.command {
+        let compacted = ream_db.compact().expect("Failed to compact database");
+        info!("Database compacted: {compacted}");
+        process::exit(0);
+    }
+
+    let result = match chain {
+        DbChain::Beacon => {
+            set_beacon_network_spec(config.network.clone());
+            let network = config.network.network.to_string();
+            let beacon_db = if config.command.is_read_only() {
+                ream_db.open_beacon_db(&network)
+            } else {
+                ream_db
+                    .check_network(&network)
+                    .and_then(|()| ream_db.init_beacon_db())
+            };
+            let beacon_db = beacon_db.unwrap_or_else(|err| {
+                error!("Unable to open the beacon database: {err}");
+                process::exit(1);
+            });
+            run_beacon_db_command(config.command, &ream_db, &beacon_db).await
+        }
+        DbChain::Lean => {
+            let lean_db = if config.command.is_read_only() {
+                ream_db.open_lean_db()
+            } else {
+                let Some(network) = config.lean_network else {
+                    error!("Set --lean-network to check the network of the lean database");
+                    process::exit(1);
+                };
+                ream_db
+                    .check_network(&lean_network_name(&network))
+                    .and_then(|()| ream_db.init_lean_db())
+            };
+            let lean_db = lean_db.unwrap_or_else(|err| {
+                error!("Unable to open the lean database: {err}");
+                process::exit(1);
+            });
+            run_lean_db_command(config.command, &ream_db, &lean_db)
+        }
+    };
+    if let Err(err) = result {
+        error!("Database command failed: {err}");
+        process::exit(1);
+    }
+
+    process::exit(0);
+}
+
+async fn run_beacon_db_command(
+    command: DbCommand,
+    ream_db: &ReamDB,
+    beacon_db: &BeaconDB,
+) -> anyhow::Result<()> {
+    match command {
+        DbCommand::Info => {
+            let slot_index_provider = beacon_db.slot_index_provider();
+            let mut report = String::new();
+            writeln!(
+                report,
+                "Slot index: {:?} to {:?}",
+                slot_index_provider.get_oldest_slot()?,
+                slot_index_provider.get_highest_slot()?
+            )?;
+            writeln!(
+                report,
+                "Justified checkpoint: {:?}",
+                beacon_db.justified_checkpoint_provider().get().ok()
+            )?;
+            writeln!(
+                report,
+                "Finalized checkpoint: {:?}",
+                beacon_db.finalized_checkpoint_provider().get().ok()
+            )?;
+            writeln!(
+                report,
+                "Latest state snapshot: {:?}",
+                beacon_db.cold_state_provider().get_highest_slot()?
+            )?;
+            write_table_sizes(&mut report, ream_db)?;
+            info!("\n{report}");
+        }
+        DbCommand::Inspect {
+            object,
+            root,
+            slot,
+            format,
+            output_path,
+        } => {
+            let block_root = match (root, slot) {
+                (Some(root), _) => Some(root),
+                (None, Some(slot)) => beacon_db.slot_index_provider().get(slot)?,
+                (None, None) => None,
+            };
+            match object {
+                InspectObject::Block => {
+                    let block_root = block_root.ok_or_else(|| anyhow!("Block not found"))?;
+                    let block = beacon_db
+                        .block_provider()
+                        .get(block_root)?
+                        .ok_or_else(|| anyhow!("Block not found: {block_root}"))?;
+                    write_inspect_output(&block, format, output_path)?;
+                }
+                InspectObject::State => {
+                    let state = match (block_root, slot) {
+                        (Some(block_root), _) => beacon_db.get_state(block_root).await?,
+                        // An empty slot of the canonical chain
+                        (None, Some(slot)) => beacon_db.get_state_at_slot(slot).await?,
+                        (None, None) => None,
+                    }
+                    .ok_or_else(|| anyhow!("State not found"))?;
+                    write_inspect_output(&state, format, output_path)?;
+                }
+            }
+        }
+        DbCommand::Prune {
+            non_canonical_blocks,
+            states_before_slot,
+        } => {
+            if non_canonical_blocks {
+                let deleted_count = beacon_db.prune_non_canonical_blocks()?;
+                info!("Deleted {deleted_count} non-canonical blocks");
+            }
+            if let Some(slot) = states_before_slot {
+                let deleted_count = beacon_db.prune_states_before(slot)?;
+                info!("Deleted {deleted_count} state snapshots before slot {slot}");
+            }
+        }
+        DbCommand::Export { output_path } => {
+            let exported_count = export_eras(beacon_db, &output_path).await?;
+            info!(
+                "Exported {exported_count} era files to: {}",
+                output_path.display()
+            );
+        }
+        DbCommand::Import { input_path } => {
+            let imported_count = import_eras(beacon_db, &input_path).await?;
+            info!(
+                "Imported {imported_count} era files from: {}",
+                input_path.display()
+            );
+        }
+        DbCommand::Compact => unreachable!("Compaction doesn't open the beacon database"),
+        DbCommand::Verify => report_problems(beacon_db.verify()?)?,
+    }
+
+    Ok(())
+}
+
+/// Lean networks have no name, devnets are told apart by their genesis time.
+fn lean_network_name(network_spec: &LeanNetworkSpec) -> String {
+    format!("genesis-{}", network_spec.genesis_time)
+}
+
+fn run_lean_db_command(
+    command: DbCommand,
+    ream_db: &ReamDB,
+    lean_db: &LeanDB,
+) -> anyhow::Result<()> {
+    match command {
+        DbCommand::Info => {
+            let slot_index_provider = lean_db.slot_index_provider();
+            let mut report = String::new();
+            writeln!(
+                report,
+                "Slot index: {:?} to {:?}",
+                slot_index_provider.get_oldest_slot()?,
+                slot_index_provider.get_highest_slot()?
+            )?;
+            writeln!(report, "Head: {:?}", lean_db.head_provider().get().ok())?;
+            writeln!(
+                report,
+                "Latest justified checkpoint: {:?}",
+                lean_db.latest_justified_provider().get().ok()
+            )?;
+            writeln!(
+                report,
+                "Latest finalized checkpoint: {:?}",
+                lean_db.latest_finalized_provider().get().ok()
+            )?;
+            write_table_sizes(&mut report, ream_db)?;
+            info!("\n{report}");
+        }
+        DbCommand::Inspect {
+            object,
+            root,
+            slot,
+            format,
+            output_path,
+        } => {
+            let block_root = match (root, slot) {
+                (Some(root), _) => root,
+                (None, Some(slot)) => lean_db
+                    .slot_index_provider()
+                    .get(slot)?
+                    .ok_or_else(|| anyhow!("No block at slot {slot}"))?,
+                (None, None) => unreachable!("Either a root or a slot is required"),
+            };
+            match object {
+                InspectObject::Block => {
+                    let block = lean_db
+                        .block_provider()
+                        .get(block_root)?
+                        .ok_or_else(|| anyhow!("Block not found: {block_root}"))?;
+                    write_inspect_output(&block, format, output_path)?;
+                }
+                InspectObject::State => {
+                    let state = lean_db
+                        .get_state(block_root)?
+                        .ok_or_else(|| anyhow!("State not found: {block_root}"))?;
+                    write_inspect_output(&state, format, output_path)?;
+                }
+            }
+        }
+        DbCommand::Prune {
+            non_canonical_blocks,
+            states_before_slot,
+        } => {
+            if non_canonical_blocks {
+                let deleted_count = lean_db.prune_non_canonical_blocks()?;
+                info!("Deleted {deleted_count} non-canonical blocks");
+            }
+            if let Some(slot) = states_before_slot {
+                let deleted_count = lean_db.prune_states_before(slot)?;
+                info!("Deleted {deleted_count} states before slot {slot}");
+            }
+        }
+        DbCommand::Export { output_path } => {
+            let exported_count = export_lean_chain(lean_db, &output_path)?;
+            info!(
+                "Exported {exported_count} blocks to: {}",
+                output_path.display()
+            );
+        }
+        DbCommand::Import { input_path } => {
+            let imported_count = import_lean_chain(lean_db, &input_path)?;
+            info!(
+                "Imported {imported_count} blocks from: {}",
+                input_path.display()
+            );
+        }
+        DbCommand::Compact => unreachable!("Compaction doesn't open the lean database"),
+        DbCommand::Verify => report_problems(lean_db.verify()?)?,
+    }
+
+    Ok(())
+}
+
+fn write_table_sizes(report: &mut String, ream_db: &ReamDB) -> anyhow::Result<()> {
+    writeln!(
+        report,
+        "{:<45} | {:>12} | {:>12}",
+        "Table Name", "Entries", "Size"
+    )?;
+    writeln!(report, "{:-<45}-|-{:->12}-|-{:->12}", "", "", "")?;
+    for table_size in ream_db.table_sizes()? {
+        writeln!(
+            report,
+            "{:<45} | {:>12} | {:>9.2} MB",
+            table_size.name,
+            table_size.entries,
+            table_size.bytes as f64 / (1024.0 * 1024.0)
+        )?;
+    }
+    Ok(())
+}
+
+fn write_inspect_output<T: Serialize + Encode>(
+    value: &T,
+    format: InspectFormat,
+    output_path: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let output = match format {
+        InspectFormat::Json => serde_json::to_vec_pretty(value)?,
+        InspectFormat::Ssz => value.as_ssz_bytes(),
+    };
+    match output_path {
+        Some(output_path) => {
+            fs::write(&output_path, output)?;
+            info!("Written to: {}", output_path.display());
+        }
+        None => {
+            let mut stdout = io::stdout();
+            match format {
+                InspectFormat::Json => stdout.write_all(&output)?,
+                InspectFormat::Ssz => stdout.write_all(hex::encode_prefixed(output).as_bytes())?,
+            }
+            writeln!(stdout)?;
+        }
+    }
+    Ok(())
+}
+
+fn report_problems(problems: Vec<String>) -> anyhow::Result<()> {
+    if problems.is_empty() {
+        info!("No problems found");
+        return Ok(());
+    }
+    for problem in &problems {
+        warn!("{problem}");
+    }
+    bail!("Found {} problems", problems.len())
+}
+
 // Countdown logs until the genesis timestamp reaches
 pub async fn countdown_for_genesis() {
     loop {
@@ -0,0 +1,167 @@
+use alloy_primitives::{Address, B256, aliases::B32, hex};
+use anyhow::anyhow;
+use ream_bls::{
+    PrivateKey,
+    traits::{PublicKeyDerivable, Signable},
+};
+use ream_consensus_misc::{
+    constants::beacon::DOMAIN_DEPOSIT,
+    deposit_data::DepositData,
+    deposit_message::DepositMessage,
+    misc::{compute_domain, compute_signing_root},
+};
+use serde::{Deserialize, Serialize};
+use tree_hash::TreeHash;
+
+/// Version of the staking deposit CLI whose `deposit_data-*.json` format is followed. The
+/// launchpad refuses files of older versions.
+pub const DEPOSIT_CLI_VERSION: &str = "2.7.0";
+
+/// An entry of a `deposit_data-*.json` file, with hex values without a `0x` prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositDataEntry {
+    pub pubkey: String,
+    pub withdrawal_credentials: String,
+    pub amount: u64,
+    pub signature: String,
+    pub deposit_message_root: String,
+    pub deposit_data_root: String,
+    pub fork_version: String,
+    pub network_name: String,
+    pub deposit_cli_version: String,
+}
+
+impl DepositDataEntry {
+    /// Sign a deposit of `amount` Gwei for `private_key`. Deposits are signed for the genesis fork
+    /// of the network, so they stay valid across forks.
+    pub fn new(
+        private_key: &PrivateKey,
+        withdrawal_credentials: B256,
+        amount: u64,
+        genesis_fork_version: B32,
+        network_name: String,
+    ) -> anyhow::Result<Self> {
+        let public_key = private_key
+            .public_key()
+            .map_err(|err| anyhow!("Failed to derive public key: {err:?}"))?;
+        let deposit_message = DepositMessage {
+            public_key: public_key.clone(),
+            withdrawal_credentials,
+            amount,
+        };
+        let domain = compute_domain(DOMAIN_DEPOSIT, Some(genesis_fork_version), None);
+        let signature = private_key
+            .sign(compute_signing_root(deposit_message.clone(), domain).as_slice())
+            .map_err(|err| anyhow!("Failed to sign deposit message: {err:?}"))?;
+        let deposit_data = DepositData {
+            public_key: public_key.clone(),
+            withdrawal_credentials,
+            amount,
+            signature: signature.clone(),
+        };
+
+        Ok(Self {
+            pubkey: hex::encode(public_key.to_bytes()),
+            withdrawal_credentials: hex::encode(withdrawal_credentials),
+            amount,
+            signature: hex::encode(signature.inner.as_slice()),
+            deposit_message_root: hex::encode(deposit_message.tree_hash_root()),
+            deposit_data_root: hex::encode(deposit_data.tree_hash_root()),
+            fork_version: hex::encode(genesis_fork_version),
+            network_name,
+            deposit_cli_version: DEPOSIT_CLI_VERSION.to_string(),
+        })
+    }
+}
+
+/// Withdrawal credentials paying out to the execution `address`. `prefix` is
+/// [ETH1_ADDRESS_WITHDRAWAL_PREFIX] or [COMPOUNDING_WITHDRAWAL_PREFIX].
+///
+/// [ETH1_ADDRESS_WITHDRAWAL_PREFIX]: ream_consensus_misc::constants::beacon::ETH1_ADDRESS_WITHDRAWAL_PREFIX
+/// [COMPOUNDING_WITHDRAWAL_PREFIX]: ream_consensus_misc::constants::beacon::COMPOUNDING_WITHDRAWAL_PREFIX
+pub fn execution_withdrawal_credentials(prefix: &[u8], address: Address) -> B256 {
+    let mut withdrawal_credentials = B256::ZERO;
+    withdrawal_credentials[..1].copy_from_slice(prefix);
+    withdrawal_credentials[12..].copy_from_slice(address.as_slice());
+    withdrawal_credentials
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, B256, aliases::B32, hex};
+    use ream_bls::{
+        BLSSignature, PrivateKey,
+        traits::{PublicKeyDerivable, Verifiable},
+    };
+    use ream_consensus_misc::{
+        constants::beacon::{
+            COMPOUNDING_WITHDRAWAL_PREFIX, DOMAIN_DEPOSIT, MIN_ACTIVATION_BALANCE,
+        },
+        deposit_message::DepositMessage,
+        misc::{compute_domain, compute_signing_root},
+    };
+
+    use super::{DepositDataEntry, execution_withdrawal_credentials};
+
+    #[test]
+    fn test_deposit_data_entry_is_signed_for_the_network() {
+        let private_key = PrivateKey {
+            inner: B256::from(hex!(
+                "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+            )),
+        };
+        let public_key = private_key.public_key().unwrap();
+        assert_eq!(
+            hex::encode(public_key.to_bytes()),
+            "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07"
+        );
+
+        let withdrawal_credentials = execution_withdrawal_credentials(
+            COMPOUNDING_WITHDRAWAL_PREFIX,
+            Address::repeat_byte(0x11),
+        );
+        assert_eq!(
+            hex::encode(withdrawal_credentials),
+            "0200000000000000000000001111111111111111111111111111111111111111"
+        );
+
+        let genesis_fork_version = B32::from(hex!("10000910"));
+        let entry = DepositDataEntry::new(
+            &private_key,
+            withdrawal_credentials,
+            MIN_ACTIVATION_BALANCE,
+            genesis_fork_version,
+            "hoodi".to_string(),
+        )
+        .unwrap();
+        assert_eq!(entry.pubkey, hex::encode(public_key.to_bytes()));
+        assert_eq!(entry.fork_version, "10000910");
+
+        let signature = BLSSignature {
+            inner: hex::decode(&entry.signature).unwrap().try_into().unwrap(),
+        };
+        let deposit_message = DepositMessage {
+            public_key: public_key.clone(),
+            withdrawal_credentials,
+            amount: MIN_ACTIVATION_BALANCE,
+        };
+        let signing_root = compute_signing_root(
+            deposit_message.clone(),
+            compute_domain(DOMAIN_DEPOSIT, Some(genesis_fork_version), None),
+        );
+        assert!(
+            signature
+                .verify(&public_key, signing_root.as_slice())
+                .unwrap()
+        );
+
+        // A deposit signed for another network doesn't verify
+        let mainnet_signing_root =
+            compute_signing_root(deposit_message, compute_domain(DOMAIN_DEPOSIT, None, None));
+        assert!(
+            !signature
+                .verify(&public_key, mainnet_signing_root.as_slice())
+                .unwrap_or(false)
+        );
+    }
+}
@@ -1,2 +1,3 @@
+pub mod deposit;
 pub mod message_types;
 pub mod seed;
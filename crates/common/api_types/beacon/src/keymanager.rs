@@ -0,0 +1,97 @@
+use alloy_primitives::Address;
+use ream_bls::PublicKey;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeystoreInfo {
+    pub validating_pubkey: PublicKey,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derivation_path: Option<String>,
+    pub readonly: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportKeystoresRequest {
+    pub keystores: Vec<String>,
+    pub passwords: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slashing_protection: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKeystoreStatus {
+    Imported,
+    Duplicate,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportKeystoreResult {
+    pub status: ImportKeystoreStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteKeystoresRequest {
+    pub pubkeys: Vec<PublicKey>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteKeystoreStatus {
+    Deleted,
+    NotActive,
+    NotFound,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteKeystoreResult {
+    pub status: DeleteKeystoreStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// The slashing protection interchange of the deleted keys is returned as a JSON string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteKeystoresResponse {
+    pub data: Vec<DeleteKeystoreResult>,
+    pub slashing_protection: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeRecipient {
+    pub pubkey: PublicKey,
+    pub ethaddress: Address,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetFeeRecipientRequest {
+    pub ethaddress: Address,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GasLimit {
+    pub pubkey: PublicKey,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub gas_limit: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetGasLimitRequest {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub gas_limit: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Graffiti {
+    pub pubkey: PublicKey,
+    pub graffiti: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetGraffitiRequest {
+    pub graffiti: String,
+}
@@ -3,6 +3,7 @@ pub mod committee;
 pub mod duties;
 pub mod error;
 pub mod id;
+pub mod keymanager;
 pub mod query;
 pub mod request;
 pub mod responses;
@@ -89,6 +89,11 @@ impl<T: Serialize> BeaconResponse<T> {
             finalized: FINALIZED,
         }
     }
+
+    pub fn with_execution_optimistic(mut self, execution_optimistic: bool) -> Self {
+        self.execution_optimistic = execution_optimistic;
+        self
+    }
 }
 
 /// A BeaconVersionedResponse data struct that can be used to wrap data type
@@ -120,6 +125,11 @@ impl<T: Serialize> BeaconVersionedResponse<T> {
             finalized: FINALIZED,
         }
     }
+
+    pub fn with_execution_optimistic(mut self, execution_optimistic: bool) -> Self {
+        self.execution_optimistic = execution_optimistic;
+        self
+    }
 }
 
 /// A DataVersionedResponse data struct that can be used to wrap data type
@@ -169,6 +179,11 @@ impl<T: Serialize + Encode + Decode> DutiesResponse<T> {
             data,
         }
     }
+
+    pub fn with_execution_optimistic(mut self, execution_optimistic: bool) -> Self {
+        self.execution_optimistic = execution_optimistic;
+        self
+    }
 }
 
 /// A SyncCommitteeDutiesResponse data struct that can be used to wrap duty data
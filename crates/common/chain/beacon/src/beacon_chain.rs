@@ -1,15 +1,31 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
 
-use anyhow::bail;
+use alloy_primitives::{Address, B64, B256};
+use anyhow::{anyhow, bail};
 use ream_consensus_beacon::{
     attestation::Attestation, attester_slashing::AttesterSlashing,
-    electra::beacon_block::SignedBeaconBlock,
+    electra::beacon_block::SignedBeaconBlock, single_attestation::SingleAttestation,
+};
+use ream_consensus_misc::{
+    checkpoint::Checkpoint,
+    constants::beacon::{FULU_FORK_EPOCH, SLOTS_PER_EPOCH, genesis_validators_root},
+    misc::{compute_epoch_at_slot, compute_start_slot_at_epoch, get_committee_indices},
+    withdrawal::Withdrawal,
+};
+use ream_events_beacon::{
+    BeaconEvent, BeaconEventSender,
+    event::chain::{BlockEvent, ChainReorgEvent, FinalizedCheckpointEvent, HeadEvent},
 };
-use ream_consensus_misc::constants::beacon::{FULU_FORK_EPOCH, genesis_validators_root};
-use ream_events_beacon::{BeaconEvent, BeaconEventSender, event::chain::BlockEvent};
 use ream_execution_engine::ExecutionEngine;
+use ream_execution_rpc_types::{
+    forkchoice_update::{ForkchoiceStateV1, PayloadAttributesV3},
+    payload_status::PayloadStatus,
+};
 use ream_fork_choice_beacon::{
-    handlers::{on_attestation, on_attester_slashing, on_block, on_tick},
+    handlers::{on_attestation, on_attestation_inner, on_attester_slashing, on_block, on_tick},
     store::Store,
 };
 use ream_network_spec::networks::beacon_network_spec;
@@ -20,14 +36,55 @@ use ream_storage::{
     tables::{field::REDBField, table::REDBTable},
 };
 use ream_sync_committee_pool::SyncCommitteePool;
+use ssz_types::{VariableList, typenum::U16};
 use tokio::sync::{Mutex, broadcast};
-use tracing::warn;
+use tracing::{trace, warn};
+
+/// Maximum number of attestations from the network deferred to the next slot. The oldest ones
+/// are dropped once it is reached.
+const MAX_PENDING_ATTESTATIONS: usize = 16_384;
 
 /// BeaconChain is the main struct which manages the nodes local beacon chain.
 pub struct BeaconChain {
     pub store: Mutex<Store>,
     pub execution_engine: Option<ExecutionEngine>,
     pub event_sender: Option<broadcast::Sender<BeaconEvent>>,
+    /// The head and finalized checkpoint as of the last emitted events, used to detect changes.
+    last_head: Mutex<Option<B256>>,
+    last_finalized_checkpoint: Mutex<Option<Checkpoint>>,
+    /// The forkchoice state last sent to the execution engine.
+    last_forkchoice_state: Mutex<Option<ForkchoiceStateV1>>,
+    /// Payloads the execution engine is building for upcoming proposals, by proposal slot.
+    prepared_payloads: Mutex<HashMap<u64, PreparedPayload>>,
+    /// The payload attributes last computed for an upcoming proposal.
+    payload_attributes_cache: Mutex<Option<CachedPayloadAttributes>>,
+    /// Attestations from the network which fork choice can only consider from the slot after
+    /// their own, oldest first.
+    pending_attestations: Mutex<VecDeque<Attestation>>,
+}
+
+/// A payload build started ahead of a proposal by sending payload attributes to the execution
+/// engine.
+#[derive(Debug, Clone, Copy)]
+struct PreparedPayload {
+    parent_beacon_block_root: B256,
+    suggested_fee_recipient: Address,
+    payload_id: B64,
+}
+
+/// Payload attributes for a proposal at `proposal_slot` on top of `head_root`.
+///
+/// Computing them needs the head state advanced to the proposal slot, so they are kept until the
+/// head or the proposal slot changes. The fee recipient is looked up on every use, as the
+/// proposer may prepare it later.
+#[derive(Debug, Clone)]
+struct CachedPayloadAttributes {
+    head_root: B256,
+    proposal_slot: u64,
+    proposer_index: u64,
+    timestamp: u64,
+    prev_randao: B256,
+    withdrawals: VariableList<Withdrawal, U16>,
 }
 
 impl BeaconChain {
@@ -43,6 +100,12 @@ impl BeaconChain {
             store: Mutex::new(Store::new(db, operation_pool, Some(sync_committee_pool))),
             execution_engine,
             event_sender,
+            last_head: Mutex::new(None),
+            last_finalized_checkpoint: Mutex::new(None),
+            last_forkchoice_state: Mutex::new(None),
+            prepared_payloads: Mutex::new(HashMap::new()),
+            payload_attributes_cache: Mutex::new(None),
+            pending_attestations: Mutex::new(VecDeque::new()),
         }
     }
 
@@ -58,13 +121,21 @@ impl BeaconChain {
         .await?;
 
         // Build and Emit Block event
-        let finalized_checkpoint = store.db.finalized_checkpoint_provider().get().ok();
-        let block_event =
-            BlockEvent::from_block(&signed_block, finalized_checkpoint, |block_root, epoch| {
-                store.get_checkpoint_block(block_root, epoch)
-            })?;
-        self.event_sender
-            .send_event(BeaconEvent::Block(block_event));
+        let block_root = signed_block.message.block_root();
+        self.event_sender.send_event(BeaconEvent::Block(BlockEvent {
+            slot: signed_block.message.slot,
+            block: block_root,
+            execution_optimistic: self.is_execution_optimistic(&store, block_root),
+        }));
+
+        if let Err(err) = self.emit_chain_events(&store).await {
+            warn!("Failed to emit chain events: {err}");
+        }
+        drop(store);
+
+        if let Err(err) = self.update_execution_forkchoice().await {
+            warn!("Failed to update execution engine forkchoice: {err}");
+        }
 
         Ok(())
     }
@@ -88,12 +159,346 @@ impl BeaconChain {
         Ok(())
     }
 
+    /// Imports a single attestation which passed gossip validation, by adding it to the naive
+    /// aggregation pool and applying it to fork choice.
+    pub async fn process_single_attestation(
+        &self,
+        single_attestation: &SingleAttestation,
+    ) -> anyhow::Result<()> {
+        let attestation = {
+            let store = self.store.lock().await;
+            let head_root = store.get_head()?;
+            let state = store
+                .db
+                .state_provider()
+                .get(head_root)?
+                .ok_or_else(|| anyhow!("No beacon state found for head root: {head_root}"))?;
+            let committee = state.get_beacon_committee(
+                single_attestation.data.slot,
+                single_attestation.committee_index,
+            )?;
+            let attestation = single_attestation.to_attestation(&committee)?;
+            store.operation_pool.insert_unaggregated_attestation(
+                attestation.clone(),
+                single_attestation.committee_index,
+            )?;
+            attestation
+        };
+
+        self.apply_network_attestation(attestation).await
+    }
+
+    /// Imports an aggregate which passed gossip validation, by adding it to the aggregates for
+    /// block packing and applying it to fork choice.
+    pub async fn process_aggregate(&self, aggregate: Attestation) -> anyhow::Result<()> {
+        let committee_index = get_committee_indices(&aggregate.committee_bits)
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("Aggregate has no committee bit set"))?;
+        self.store
+            .lock()
+            .await
+            .operation_pool
+            .insert_attestation(aggregate.clone(), committee_index);
+
+        self.apply_network_attestation(aggregate).await
+    }
+
+    /// Applies an attestation from the network to fork choice, or defers it until the slot after
+    /// its own if it is from the current slot. Its signature was verified by gossip validation
+    /// already, so fork choice doesn't check it again.
+    async fn apply_network_attestation(&self, attestation: Attestation) -> anyhow::Result<()> {
+        let mut store = self.store.lock().await;
+        if attestation.data.slot >= store.get_current_slot()? {
+            let mut pending_attestations = self.pending_attestations.lock().await;
+            if pending_attestations.len() >= MAX_PENDING_ATTESTATIONS {
+                pending_attestations.pop_front();
+            }
+            pending_attestations.push_back(attestation);
+            return Ok(());
+        }
+
+        on_attestation_inner(&mut store, attestation, false, false)
+    }
+
     pub async fn process_tick(&self, time: u64) -> anyhow::Result<()> {
         let mut store = self.store.lock().await;
         on_tick(&mut store, time)?;
+
+        let current_slot = store.get_current_slot()?;
+        let ready_attestations = {
+            let mut pending_attestations = self.pending_attestations.lock().await;
+            let (ready_attestations, still_pending) = pending_attestations
+                .drain(..)
+                .partition::<VecDeque<_>, _>(|attestation| attestation.data.slot < current_slot);
+            *pending_attestations = still_pending;
+            ready_attestations
+        };
+        for attestation in ready_attestations {
+            if let Err(err) = on_attestation_inner(&mut store, attestation, false, false) {
+                trace!("Failed to apply deferred attestation to fork choice: {err}");
+            }
+        }
+
+        if let Err(err) = self.emit_chain_events(&store).await {
+            warn!("Failed to emit chain events: {err}");
+        }
+        drop(store);
+
+        if let Err(err) = self.update_execution_forkchoice().await {
+            warn!("Failed to update execution engine forkchoice: {err}");
+        }
+
+        Ok(())
+    }
+
+    /// Whether `block_root` was imported without its execution payload being verified.
+    pub fn is_execution_optimistic(&self, store: &Store, block_root: B256) -> bool {
+        store.is_optimistic(block_root).unwrap_or(true)
+    }
+
+    /// Emit the `head`, `chain_reorg` and `finalized_checkpoint` events if fork choice has
+    /// changed since they were last emitted.
+    ///
+    /// This runs after blocks and ticks, so head changes caused by attestations alone are
+    /// reported at the next slot at the latest.
+    async fn emit_chain_events(&self, store: &Store) -> anyhow::Result<()> {
+        if self.event_sender.is_none() {
+            return Ok(());
+        }
+
+        let finalized_checkpoint = store.db.finalized_checkpoint_provider().get()?;
+        let mut last_finalized_checkpoint = self.last_finalized_checkpoint.lock().await;
+        if last_finalized_checkpoint.is_some_and(|checkpoint| checkpoint != finalized_checkpoint) {
+            let finalized_block = get_block(store, finalized_checkpoint.root)?;
+            self.event_sender
+                .send_event(BeaconEvent::FinalizedCheckpoint(FinalizedCheckpointEvent {
+                    block: finalized_checkpoint.root,
+                    state: finalized_block.message.state_root,
+                    epoch: finalized_checkpoint.epoch,
+                    execution_optimistic: self
+                        .is_execution_optimistic(store, finalized_checkpoint.root),
+                }));
+        }
+        *last_finalized_checkpoint = Some(finalized_checkpoint);
+
+        let head_root = store.get_head()?;
+        let mut last_head = self.last_head.lock().await;
+        let Some(old_head_root) = last_head.replace(head_root) else {
+            return Ok(());
+        };
+        if old_head_root == head_root {
+            return Ok(());
+        }
+
+        let head_block = get_block(store, head_root)?;
+        let head_slot = head_block.message.slot;
+        let head_epoch = compute_epoch_at_slot(head_slot);
+        let execution_optimistic = self.is_execution_optimistic(store, head_root);
+
+        let old_head_block = get_block(store, old_head_root)?;
+        if store.get_ancestor(head_root, old_head_block.message.slot)? != old_head_root {
+            let common_ancestor_slot = get_common_ancestor_slot(store, old_head_root, head_root)?;
+            self.event_sender
+                .send_event(BeaconEvent::ChainReorg(ChainReorgEvent {
+                    slot: head_slot,
+                    depth: old_head_block.message.slot - common_ancestor_slot,
+                    old_head_block: old_head_root,
+                    new_head_block: head_root,
+                    old_head_state: old_head_block.message.state_root,
+                    new_head_state: head_block.message.state_root,
+                    epoch: head_epoch,
+                    execution_optimistic,
+                }));
+        }
+
+        // The dependent roots are the last blocks before the start of the previous and current
+        // epochs, falling back to the genesis block on underflow
+        let get_dependent_root = |epoch: u64| {
+            store.get_ancestor(
+                head_root,
+                compute_start_slot_at_epoch(epoch).saturating_sub(1),
+            )
+        };
+        self.event_sender.send_event(BeaconEvent::Head(HeadEvent {
+            slot: head_slot,
+            block: head_root,
+            state: head_block.message.state_root,
+            epoch_transition: head_slot % SLOTS_PER_EPOCH == 0,
+            previous_duty_dependent_root: get_dependent_root(head_epoch.saturating_sub(1))?,
+            current_duty_dependent_root: get_dependent_root(head_epoch)?,
+            execution_optimistic,
+        }));
+
+        Ok(())
+    }
+
+    /// The id of the payload the execution engine has been building for a proposal at `slot` on
+    /// top of `parent_beacon_block_root`, if it was prepared with the same fee recipient.
+    pub async fn get_prepared_payload_id(
+        &self,
+        slot: u64,
+        parent_beacon_block_root: B256,
+        suggested_fee_recipient: Address,
+    ) -> Option<B64> {
+        self.prepared_payloads
+            .lock()
+            .await
+            .get(&slot)
+            .filter(|prepared_payload| {
+                prepared_payload.parent_beacon_block_root == parent_beacon_block_root
+                    && prepared_payload.suggested_fee_recipient == suggested_fee_recipient
+            })
+            .map(|prepared_payload| prepared_payload.payload_id)
+    }
+
+    /// Send forkchoiceUpdated to the execution engine if the head, safe or finalized block
+    /// changed.
+    ///
+    /// If a validator with a prepared fee recipient proposes in the next slot, payload attributes
+    /// are sent along so the execution engine starts building the payload ahead of time.
+    ///
+    /// The store is only locked while reading from it and applying the returned payload status,
+    /// never during the engine call.
+    async fn update_execution_forkchoice(&self) -> anyhow::Result<()> {
+        let Some(execution_engine) = &self.execution_engine else {
+            return Ok(());
+        };
+
+        // Held until the update is sent, so concurrent updates reach the engine in order
+        let mut last_forkchoice_state = self.last_forkchoice_state.lock().await;
+
+        let (head_root, forkchoice_state, proposal_slot, payload_attributes) = {
+            let store = self.store.lock().await;
+            let head_root = store.get_head()?;
+            let justified_checkpoint = store.db.justified_checkpoint_provider().get()?;
+            let finalized_checkpoint = store.db.finalized_checkpoint_provider().get()?;
+            let forkchoice_state = ForkchoiceStateV1 {
+                head_block_hash: get_execution_block_hash(&store, head_root)?,
+                safe_block_hash: get_execution_block_hash(&store, justified_checkpoint.root)?,
+                finalized_block_hash: get_execution_block_hash(&store, finalized_checkpoint.root)?,
+            };
+
+            let proposal_slot = store.get_current_slot()? + 1;
+            let is_prepared = {
+                let mut prepared_payloads = self.prepared_payloads.lock().await;
+                prepared_payloads.retain(|slot, _| *slot + 1 >= proposal_slot);
+                prepared_payloads
+                    .get(&proposal_slot)
+                    .is_some_and(|prepared_payload| {
+                        prepared_payload.parent_beacon_block_root == head_root
+                    })
+            };
+            let payload_attributes = if is_prepared {
+                None
+            } else {
+                self.get_payload_attributes(&store, head_root, proposal_slot)
+                    .await?
+            };
+
+            (
+                head_root,
+                forkchoice_state,
+                proposal_slot,
+                payload_attributes,
+            )
+        };
+
+        if payload_attributes.is_none() && *last_forkchoice_state == Some(forkchoice_state) {
+            return Ok(());
+        }
+
+        let proposal = payload_attributes.as_ref().map(|payload_attributes| {
+            (
+                payload_attributes.parent_beacon_block_root,
+                payload_attributes.suggested_fee_recipient,
+            )
+        });
+        let result = execution_engine
+            .engine_forkchoice_updated_v3(forkchoice_state, payload_attributes)
+            .await?;
+        *last_forkchoice_state = Some(forkchoice_state);
+
+        // The engine may verify the head payload, or find it invalid, once it has synced
+        {
+            let store = self.store.lock().await;
+            match result.payload_status.status {
+                PayloadStatus::Valid => store.on_valid_execution_payload(head_root)?,
+                PayloadStatus::Invalid => store.on_invalid_execution_payload(
+                    head_root,
+                    result.payload_status.latest_valid_hash,
+                )?,
+                PayloadStatus::InvalidBlockHash => {
+                    store.on_invalid_execution_payload(head_root, None)?
+                }
+                PayloadStatus::Syncing | PayloadStatus::Accepted => {}
+            }
+        }
+
+        if let Some((parent_beacon_block_root, suggested_fee_recipient)) = proposal {
+            match result.payload_id {
+                Some(payload_id) => {
+                    self.prepared_payloads.lock().await.insert(
+                        proposal_slot,
+                        PreparedPayload {
+                            parent_beacon_block_root,
+                            suggested_fee_recipient,
+                            payload_id,
+                        },
+                    );
+                }
+                None => warn!(
+                    "No payload id returned for slot {proposal_slot}, payload status: {:?}",
+                    result.payload_status
+                ),
+            }
+        }
+
         Ok(())
     }
 
+    /// The payload attributes for a proposal at `proposal_slot` on top of `head_root`, or `None`
+    /// if the proposer hasn't prepared a fee recipient.
+    async fn get_payload_attributes(
+        &self,
+        store: &Store,
+        head_root: B256,
+        proposal_slot: u64,
+    ) -> anyhow::Result<Option<PayloadAttributesV3>> {
+        // Avoid loading the head state when no validators are connected
+        if store
+            .operation_pool
+            .get_all_proposer_preparations()
+            .is_empty()
+        {
+            return Ok(None);
+        }
+
+        let mut payload_attributes_cache = self.payload_attributes_cache.lock().await;
+        let cached_payload_attributes = match payload_attributes_cache.take() {
+            Some(cached_payload_attributes)
+                if cached_payload_attributes.head_root == head_root
+                    && cached_payload_attributes.proposal_slot == proposal_slot =>
+            {
+                cached_payload_attributes
+            }
+            _ => compute_payload_attributes(store, head_root, proposal_slot)?,
+        };
+        let payload_attributes = store
+            .operation_pool
+            .get_proposer_preparation(cached_payload_attributes.proposer_index)
+            .map(|suggested_fee_recipient| PayloadAttributesV3 {
+                timestamp: cached_payload_attributes.timestamp,
+                prev_randao: cached_payload_attributes.prev_randao,
+                suggested_fee_recipient,
+                withdrawals: cached_payload_attributes.withdrawals.clone(),
+                parent_beacon_block_root: head_root,
+            });
+        *payload_attributes_cache = Some(cached_payload_attributes);
+
+        Ok(payload_attributes)
+    }
+
     pub async fn build_status_request(&self) -> anyhow::Result<Status> {
         let Ok(finalized_checkpoint) = self
             .store
@@ -132,3 +537,67 @@ impl BeaconChain {
         })
     }
 }
+
+fn get_block(store: &Store, block_root: B256) -> anyhow::Result<SignedBeaconBlock> {
+    store
+        .db
+        .block_provider()
+        .get(block_root)?
+        .ok_or_else(|| anyhow!("Block not found for root: {block_root}"))
+}
+
+/// The hash of the execution block included in `block_root`.
+fn get_execution_block_hash(store: &Store, block_root: B256) -> anyhow::Result<B256> {
+    Ok(get_block(store, block_root)?
+        .message
+        .body
+        .execution_payload
+        .block_hash)
+}
+
+/// Advance the state of `head_root` to `proposal_slot` and compute the payload attributes of a
+/// proposal there.
+fn compute_payload_attributes(
+    store: &Store,
+    head_root: B256,
+    proposal_slot: u64,
+) -> anyhow::Result<CachedPayloadAttributes> {
+    let mut state = store
+        .db
+        .state_provider()
+        .get(head_root)?
+        .ok_or_else(|| anyhow!("State not found for root: {head_root}"))?;
+    let proposer_index = state.get_beacon_proposer_index(Some(proposal_slot))?;
+
+    if state.slot < proposal_slot {
+        state.process_slots(proposal_slot)?;
+    }
+    let (withdrawals, _) = state.get_expected_withdrawals()?;
+    Ok(CachedPayloadAttributes {
+        head_root,
+        proposal_slot,
+        proposer_index,
+        timestamp: state.compute_timestamp_at_slot(proposal_slot),
+        prev_randao: state.get_randao_mix(compute_epoch_at_slot(proposal_slot)),
+        withdrawals: withdrawals
+            .try_into()
+            .map_err(|err| anyhow!("Failed to convert withdrawals: {err:?}"))?,
+    })
+}
+
+/// Walk both chains back until they meet and return the slot of the common ancestor.
+fn get_common_ancestor_slot(store: &Store, root_a: B256, root_b: B256) -> anyhow::Result<u64> {
+    let mut block_a = get_block(store, root_a)?;
+    let mut block_b = get_block(store, root_b)?;
+    let (mut root_a, mut root_b) = (root_a, root_b);
+    while root_a != root_b {
+        if block_a.message.slot >= block_b.message.slot {
+            root_a = block_a.message.parent_root;
+            block_a = get_block(store, root_a)?;
+        } else {
+            root_b = block_b.message.parent_root;
+            block_b = get_block(store, root_b)?;
+        }
+    }
+    Ok(block_a.message.slot)
+}
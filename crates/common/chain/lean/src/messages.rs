@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use alloy_primitives::B256;
+use libp2p::gossipsub::MessageId;
 use libp2p_identity::PeerId;
 use ream_consensus_lean::{
     attestation::{AttestationData, SignedAggregatedAttestation, SignedAttestation},
@@ -17,6 +18,19 @@ pub struct Status {
     pub finalized: Checkpoint,
 }
 
+/// Where a block or attestation to process came from.
+#[derive(Debug, Clone)]
+pub enum MessageOrigin {
+    /// Produced by this node's validators, and published once processed.
+    Local,
+    /// Received over gossipsub. The outcome of processing it is reported back to gossipsub,
+    /// which only forwards accepted messages.
+    Gossip {
+        message_id: MessageId,
+        propagation_source: PeerId,
+    },
+}
+
 #[derive(Debug)]
 pub enum LeanChainServiceMessage {
     // Producers
@@ -32,16 +46,16 @@ pub enum LeanChainServiceMessage {
     // Processors
     ProcessBlock {
         signed_block: Box<SignedBlock>,
-        need_gossip: bool,
+        origin: MessageOrigin,
     },
     ProcessAttestation {
         signed_attestation: Box<SignedAttestation>,
         subnet_id: u64,
-        need_gossip: bool,
+        origin: MessageOrigin,
     },
     ProcessAggregatedAttestation {
         aggregated_attestation: Box<SignedAggregatedAttestation>,
-        need_gossip: bool,
+        origin: MessageOrigin,
     },
     CheckIfCanonicalCheckpoint {
         peer_id: PeerId,
@@ -1,4 +1,5 @@
 use alloy_primitives::B256;
+use libp2p::gossipsub::{MessageAcceptance, MessageId};
 use libp2p_identity::PeerId;
 use libp2p_swarm::ConnectionId;
 use ream_consensus_lean::{
@@ -8,6 +9,8 @@ use ream_consensus_lean::{
 use ream_req_resp::lean::{ResponseCallback, messages::LeanResponseMessage};
 use tokio::sync::mpsc;
 
+use crate::messages::MessageOrigin;
+
 #[derive(Debug, Clone)]
 pub enum LeanP2PRequest {
     GossipBlock(Box<SignedBlock>),
@@ -16,6 +19,13 @@ pub enum LeanP2PRequest {
         attestation: Box<SignedAttestation>,
     },
     GossipAggregatedAttestation(Box<SignedAggregatedAttestation>),
+    /// Tell gossipsub whether a received message is valid, so it forwards accepted messages and
+    /// penalizes the peers of rejected ones.
+    GossipValidationResult {
+        message_id: MessageId,
+        propagation_source: PeerId,
+        acceptance: MessageAcceptance,
+    },
     Request {
         peer_id: PeerId,
         callback: mpsc::Sender<ResponseCallback>,
@@ -40,6 +50,28 @@ pub enum LeanP2PRequest {
     },
 }
 
+impl LeanP2PRequest {
+    /// The request that finishes handling a processed message: `publish` if this node produced
+    /// it, otherwise reporting `acceptance` to gossipsub.
+    pub fn from_processed_message(
+        origin: MessageOrigin,
+        acceptance: MessageAcceptance,
+        publish: Option<Self>,
+    ) -> Option<Self> {
+        match origin {
+            MessageOrigin::Local => publish,
+            MessageOrigin::Gossip {
+                message_id,
+                propagation_source,
+            } => Some(Self::GossipValidationResult {
+                message_id,
+                propagation_source,
+                acceptance,
+            }),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum P2PCallbackRequest {
     BlocksByRoot { roots: Vec<B256> },
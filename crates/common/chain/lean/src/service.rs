@@ -10,6 +10,7 @@ use std::{
 use alloy_primitives::B256;
 use anyhow::anyhow;
 use futures::stream::{FuturesUnordered, StreamExt};
+use libp2p::gossipsub::MessageAcceptance;
 use libp2p_identity::PeerId;
 use libp2p_swarm::ConnectionId;
 use rand::seq::IndexedRandom;
@@ -25,9 +26,10 @@ use ream_consensus_lean::{
     checkpoint::Checkpoint,
 };
 use ream_consensus_misc::constants::lean::{INTERVALS_PER_SLOT, attestation_committee_count};
-use ream_fork_choice_lean::store::LeanStoreWriter;
+use ream_events_lean::{LeanEvent, LeanEventSender, event::sync::SyncStatusEvent};
 #[cfg(feature = "devnet5")]
 use ream_fork_choice_lean::store::prove_aggregation_jobs;
+use ream_fork_choice_lean::store::{GossipAttestationError, LeanStoreWriter};
 use ream_metrics::{
     ATTESTATION_COMMITTEE_COUNT as ATTESTATION_COMMITTEE_COUNT_METRIC,
     BLOCK_BUILDING_FAILURES_TOTAL, CURRENT_SLOT, IS_AGGREGATOR, LEAN_AGGREGATOR_SKIPPED_TOTAL,
@@ -52,7 +54,7 @@ use ssz_types::VariableList;
 #[cfg(feature = "devnet5")]
 use tokio::sync::Mutex;
 use tokio::{
-    sync::{mpsc, oneshot},
+    sync::{broadcast, mpsc, oneshot},
     task::JoinHandle,
 };
 use tracing::{Instrument, Level, debug, enabled, error, info, trace, warn};
@@ -60,7 +62,7 @@ use tree_hash::TreeHash;
 
 use crate::{
     clock::{create_lean_clock_interval, get_initial_tick_count},
-    messages::{LeanChainServiceMessage, ServiceResponse},
+    messages::{LeanChainServiceMessage, MessageOrigin, ServiceResponse},
     p2p_request::{LeanP2PRequest, P2PCallbackRequest},
     service::LeanP2PRequest::{
         EndOfStream, GossipAggregatedAttestation, GossipAttestation, GossipBlock, InvalidRequest,
@@ -261,6 +263,10 @@ pub struct LeanChainService {
     pending_callbacks: FuturesUnordered<CallbackFuture>,
     aggregator_state: Arc<AggregatorState>,
     telemetry: SyncTelemetry,
+    event_sender: Option<broadcast::Sender<LeanEvent>>,
+    /// Number of slots between the finalized states kept from pruning, if running in archive
+    /// mode.
+    archive_snapshot_interval: Option<u64>,
     #[cfg(feature = "devnet5")]
     pending_block_aggregates: Arc<Mutex<Vec<SignedAggregatedAttestation>>>,
     #[cfg(feature = "devnet5")]
@@ -273,8 +279,12 @@ impl LeanChainService {
         receiver: mpsc::UnboundedReceiver<LeanChainServiceMessage>,
         outbound_p2p: mpsc::UnboundedSender<LeanP2PRequest>,
         aggregator_state: Arc<AggregatorState>,
+        archive_snapshot_interval: Option<u64>,
     ) -> Self {
-        let network_state = store.read().await.network_state.clone();
+        let (network_state, event_sender) = {
+            let store = store.read().await;
+            (store.network_state.clone(), store.event_sender.clone())
+        };
         LeanChainService {
             clock_prebuilt_for: None,
             network_state,
@@ -293,6 +303,8 @@ impl LeanChainService {
             pending_job_requests: VecDeque::new(),
             aggregator_state,
             telemetry: SyncTelemetry::from_env(),
+            event_sender,
+            archive_snapshot_interval,
             #[cfg(feature = "devnet5")]
             pending_block_aggregates: Arc::new(Mutex::new(Vec::new())),
             #[cfg(feature = "devnet5")]
@@ -349,7 +361,14 @@ impl LeanChainService {
             tokio::select! {
                 _ = interval.tick() => {
                     if tick_count.is_multiple_of(INTERVALS_PER_SLOT) {
-                        self.sync_status = self.update_sync_status().await?;
+                        let sync_status = self.update_sync_status().await?;
+                        if sync_status != self.sync_status {
+                            self.event_sender.send_event(LeanEvent::SyncStatus(SyncStatusEvent {
+                                is_syncing: sync_status == SyncStatus::Syncing,
+                                head_slot: self.network_state.head_checkpoint.read().slot,
+                            }));
+                        }
+                        self.sync_status = sync_status;
                     }
                     if self.sync_status == SyncStatus::Synced {
                         let is_slot_start = tick_count.is_multiple_of(INTERVALS_PER_SLOT);
@@ -497,7 +516,7 @@ impl LeanChainService {
                                 inc_int_counter_vec(&LEAN_AGGREGATOR_SKIPPED_TOTAL, &["other"]);
                             }
                         }
-                        LeanChainServiceMessage::ProcessBlock { signed_block, need_gossip } => {
+                        LeanChainServiceMessage::ProcessBlock { signed_block, origin } => {
                             if self.sync_status != SyncStatus::Synced {
                                 if let Err(err) = self
                                     .handle_syncing_process_block(&signed_block)
@@ -507,6 +526,7 @@ impl LeanChainService {
                                         "Failed to handle ProcessBlock while backfill syncing: {err:?}"
                                     );
                                 }
+                                self.send_processed_message(origin, MessageAcceptance::Ignore, None);
                                 continue;
                             }
 
@@ -529,18 +549,21 @@ impl LeanChainService {
                                 );
                             }
 
-                            if let Err(err) = self.handle_process_block(&signed_block).await {
-                                warn!("Failed to handle process block message: {err:?}");
-                            }
-
-                            if need_gossip && let Err(err) = self.outbound_p2p.send(GossipBlock(signed_block)) {
-                                warn!("Failed to send item to outbound gossip channel: {err:?}");
-                            }
+                            // Blocks which can't be validated because of a local error are ignored
+                            let acceptance = match self.handle_process_block(&signed_block).await {
+                                Ok(acceptance) => acceptance,
+                                Err(err) => {
+                                    warn!("Failed to handle process block message: {err:?}");
+                                    MessageAcceptance::Ignore
+                                }
+                            };
+                            self.send_processed_message(origin, acceptance, Some(GossipBlock(signed_block)));
                         }
 
-                        LeanChainServiceMessage::ProcessAttestation { signed_attestation, subnet_id, need_gossip } => {
+                        LeanChainServiceMessage::ProcessAttestation { signed_attestation, subnet_id, origin } => {
                             if self.sync_status != SyncStatus::Synced {
                                 trace!("Received ProcessAttestation request while syncing. Ignoring.");
+                                self.send_processed_message(origin, MessageAcceptance::Ignore, None);
                                 continue;
                             }
 
@@ -554,33 +577,41 @@ impl LeanChainService {
                                 signed_attestation.validator_id,
                             );
 
-                            if let Err(err) = self.handle_process_attestation(*signed_attestation.clone()).await {
-                                warn!("Failed to handle process attestation message: {err:?}");
-                            }
-
-                            if need_gossip && let Err(err) = self.outbound_p2p.send(GossipAttestation { subnet_id, attestation: signed_attestation }) {
-                                warn!("Failed to send item to outbound gossip channel: {err:?}");
-                            }
+                            let acceptance = match self.handle_process_attestation(*signed_attestation.clone()).await {
+                                Ok(()) => MessageAcceptance::Accept,
+                                Err(err) => {
+                                    warn!("Failed to handle process attestation message: {err:?}");
+                                    attestation_error_acceptance(&err)
+                                }
+                            };
+                            self.send_processed_message(origin, acceptance, Some(GossipAttestation { subnet_id, attestation: signed_attestation }));
                         }
 
-                        LeanChainServiceMessage::ProcessAggregatedAttestation { aggregated_attestation, need_gossip } => {
+                        LeanChainServiceMessage::ProcessAggregatedAttestation { aggregated_attestation, origin } => {
                             if self.sync_status != SyncStatus::Synced {
                                 trace!("Received ProcessAggregatedAttestation request while syncing. Ignoring.");
                                 inc_int_counter_vec(&LEAN_AGGREGATOR_SKIPPED_TOTAL, &["not_synced"]);
+                                self.send_processed_message(origin, MessageAcceptance::Ignore, None);
                                 continue;
                             }
 
                             debug!(aggregated_attestation.data.slot, "Processing aggregated attestation");
 
-                            if let Err(err) = self.store.write().await.on_gossip_aggregated_attestation(*aggregated_attestation.clone()).await {
-                                warn!("Failed to handle process aggregated attestation message: {err:?}");
-                                inc_int_counter_vec(&LEAN_AGGREGATOR_SKIPPED_TOTAL, &["missing_state"]);
-                            }
-
-                            if need_gossip && let Err(err) = self.outbound_p2p.send(GossipAggregatedAttestation(aggregated_attestation)) {
-                                inc_int_counter_vec(&LEAN_AGGREGATOR_SKIPPED_TOTAL, &["other"]);
-                                warn!("Failed to send aggregated attestation to outbound gossip channel: {err:?}");
-                            }
+                            let acceptance = match self.store.write().await.on_gossip_aggregated_attestation(*aggregated_attestation.clone()).await {
+                                Ok(()) => MessageAcceptance::Accept,
+                                Err(err) => {
+                                    warn!("Failed to handle process aggregated attestation message: {err:?}");
+                                    let acceptance = attestation_error_acceptance(&err);
+                                    let reason = match err.downcast_ref::<GossipAttestationError>() {
+                                        Some(GossipAttestationError::MissingState(_)) => "missing_state",
+                                        _ if matches!(acceptance, MessageAcceptance::Reject) => "invalid",
+                                        _ => "other",
+                                    };
+                                    inc_int_counter_vec(&LEAN_AGGREGATOR_SKIPPED_TOTAL, &[reason]);
+                                    acceptance
+                                }
+                            };
+                            self.send_processed_message(origin, acceptance, Some(GossipAggregatedAttestation(aggregated_attestation)));
                         }
                         LeanChainServiceMessage::CheckIfCanonicalCheckpoint { peer_id, checkpoint, sender } => {
                             let slot_index_provider = self.store.read().await.store.lock().await.slot_index_provider();
@@ -2950,7 +2981,7 @@ impl LeanChainService {
     }
 
     async fn prune_old_state(&self, tick_count: u64) -> anyhow::Result<()> {
-        let (slot_index_provider, state_provider, latest_finalized_slot) = {
+        let (lean_db, latest_finalized_slot) = {
             let fork_choice = self.store.read().await;
             let store = fork_choice.store.lock().await;
 
@@ -2959,40 +2990,22 @@ impl LeanChainService {
             {
                 warn!("Failed to report storage metrics: {err:?}");
             }
-            (
-                store.slot_index_provider(),
-                store.state_provider(),
-                store.latest_finalized_provider().get()?.slot,
-            )
+            (store.clone(), store.latest_finalized_provider().get()?.slot)
         };
 
         if latest_finalized_slot > STATE_RETENTION_SLOTS {
-            let prune_target_slot = latest_finalized_slot - 1;
-            let mut scan = prune_target_slot;
-            let mut prune_root = None;
-            loop {
-                if let Some(root) = slot_index_provider.get(scan)? {
-                    prune_root = Some((scan, root));
-                    break;
-                }
-                if scan == 0 {
-                    break;
-                }
-                scan -= 1;
-            }
-
-            if let Some((prune_slot, block_root)) = prune_root {
-                info!(
+            match lean_db
+                .prune_state_at_or_before(latest_finalized_slot - 1, self.archive_snapshot_interval)
+            {
+                Ok(Some((prune_slot, block_root))) => info!(
                     slot = get_current_slot(),
                     tick = tick_count,
                     prune_slot,
                     prune_block_root = ?block_root,
-                    "Pruning old lean state"
-                );
-
-                if let Err(err) = state_provider.remove(block_root) {
-                    warn!("Failed to prune old lean state: {err:?}");
-                }
+                    "Pruned old lean state"
+                ),
+                Ok(None) => {}
+                Err(err) => warn!("Failed to prune old lean state: {err:?}"),
             }
         }
         Ok(())
@@ -3066,7 +3079,32 @@ impl LeanChainService {
 
         Ok(())
     }
-    async fn handle_process_block(&mut self, signed_block: &SignedBlock) -> anyhow::Result<()> {
+
+    /// Publish a processed message produced by this node, or report the outcome of processing a
+    /// gossiped one back to gossipsub.
+    fn send_processed_message(
+        &self,
+        origin: MessageOrigin,
+        acceptance: MessageAcceptance,
+        publish: Option<LeanP2PRequest>,
+    ) {
+        let Some(request) = LeanP2PRequest::from_processed_message(origin, acceptance, publish)
+        else {
+            return;
+        };
+        if let Err(err) = self.outbound_p2p.send(request) {
+            warn!("Failed to send item to outbound gossip channel: {err:?}");
+        }
+    }
+
+    /// Verify a block and import it, returning how gossipsub should treat it.
+    ///
+    /// Only blocks with invalid signatures are rejected. Blocks whose parent state is missing, or
+    /// which fail to import, are ignored.
+    async fn handle_process_block(
+        &mut self,
+        signed_block: &SignedBlock,
+    ) -> anyhow::Result<MessageAcceptance> {
         let parent_root = signed_block.block.parent_root;
         let parent_state = {
             let fork_choice = self.store.read().await;
@@ -3079,7 +3117,8 @@ impl LeanChainService {
                 parent_root = ?parent_root,
                 "Missing parent state while processing synced block; routing block to backfill path"
             );
-            return self.handle_syncing_process_block(signed_block).await;
+            self.handle_syncing_process_block(signed_block).await?;
+            return Ok(MessageAcceptance::Ignore);
         };
 
         #[cfg(feature = "devnet5")]
@@ -3089,18 +3128,25 @@ impl LeanChainService {
                 block_for_verify.verify_signatures(&_parent_state, true)
             })
             .await
-            .map_err(|err| anyhow!("block verify join error: {err:?}"))??;
-            if !verified {
-                return Err(anyhow!("Block signature verification failed"));
+            .map_err(|err| anyhow!("block verify join error: {err:?}"))?;
+            match verified {
+                Ok(true) => {}
+                Ok(false) => {
+                    warn!("Block signature verification failed");
+                    return Ok(MessageAcceptance::Reject);
+                }
+                Err(err) => {
+                    warn!("Block signatures are invalid: {err:?}");
+                    return Ok(MessageAcceptance::Reject);
+                }
+            }
+            if let Err(err) = self.store.write().await.on_block(signed_block, false).await {
+                warn!("Failed to import block: {err:?}");
+                return Ok(MessageAcceptance::Ignore);
             }
-            self.store
-                .write()
-                .await
-                .on_block(signed_block, false)
-                .await?;
         }
 
-        Ok(())
+        Ok(MessageAcceptance::Accept)
     }
 
     async fn handle_process_attestation(
@@ -3127,6 +3173,19 @@ impl LeanChainService {
     }
 }
 
+/// Attestations which are invalid whatever this node has received are rejected. Any other error,
+/// mostly a block or state which hasn't arrived yet, is ignored.
+fn attestation_error_acceptance(err: &anyhow::Error) -> MessageAcceptance {
+    match err.downcast_ref::<GossipAttestationError>() {
+        Some(GossipAttestationError::MissingState(_)) | None => MessageAcceptance::Ignore,
+        Some(
+            GossipAttestationError::UnknownValidator(_)
+            | GossipAttestationError::NoParticipants
+            | GossipAttestationError::InvalidSignature(_),
+        ) => MessageAcceptance::Reject,
+    }
+}
+
 fn pending_block_slot(block: &SignedBlock) -> u64 {
     block.block.slot
 }
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, ensure};
+use futures::future::join_all;
+use ream_consensus_lean::{block::SignedBlock, state::LeanState};
+use reqwest::Url;
+use tracing::{info, warn};
+use tree_hash::TreeHash;
+
+use crate::LeanCheckpointClient;
+
+/// The checkpoint sync sources passed on the command line, without duplicates so a source can
+/// only count once towards the quorum.
+pub fn get_checkpoint_sync_sources(checkpoint_sync_urls: Vec<Url>) -> Vec<Url> {
+    let mut sources: Vec<Url> = Vec::with_capacity(checkpoint_sync_urls.len());
+    for url in checkpoint_sync_urls {
+        if !sources.contains(&url) {
+            sources.push(url);
+        }
+    }
+    sources
+}
+
+/// The default quorum, a strict majority of the sources.
+pub fn default_quorum(source_count: usize) -> usize {
+    source_count / 2 + 1
+}
+
+/// Fetch the finalized anchor from every source and return the one whose block root at least
+/// `quorum` sources agree on. Sources which fail or report another root are logged and ignored.
+pub async fn fetch_checkpoint_anchor(
+    client: &LeanCheckpointClient,
+    sources: &[Url],
+    quorum: usize,
+) -> anyhow::Result<(LeanState, SignedBlock)> {
+    ensure!(
+        (1..=sources.len()).contains(&quorum),
+        "Checkpoint sync quorum must be between 1 and the number of sources ({}), got {quorum}",
+        sources.len()
+    );
+
+    let responses = join_all(sources.iter().map(|url| client.fetch_finalized_anchor(url))).await;
+
+    let mut anchors: HashMap<B256, (usize, LeanState, SignedBlock)> = HashMap::new();
+    for (url, response) in sources.iter().zip(responses) {
+        match response {
+            Ok((state, signed_block)) => {
+                let block_root = signed_block.block.tree_hash_root();
+                info!(
+                    "Checkpoint sync source {url} reports finalized block {block_root} at slot {}",
+                    signed_block.block.slot
+                );
+                anchors
+                    .entry(block_root)
+                    .or_insert((0, state, signed_block))
+                    .0 += 1;
+            }
+            Err(err) => warn!("Checkpoint sync source {url} failed: {err:?}"),
+        }
+    }
+
+    let (block_root, (agreeing_count, state, signed_block)) = anchors
+        .into_iter()
+        .max_by_key(|(_, (count, _, signed_block))| (*count, signed_block.block.slot))
+        .ok_or_else(|| anyhow!("No checkpoint sync source returned a finalized anchor"))?;
+    ensure!(
+        agreeing_count >= quorum,
+        "Only {agreeing_count} of {} checkpoint sync sources agree on finalized block \
+         {block_root}, {quorum} required. Sources may be at different finalized checkpoints; retry.",
+        sources.len()
+    );
+
+    info!(
+        "{agreeing_count} of {} checkpoint sync sources agree on finalized block {block_root}",
+        sources.len()
+    );
+    Ok((state, signed_block))
+}
@@ -1,3 +1,6 @@
+pub mod checkpoint;
+pub mod weak_subjectivity;
+
 use anyhow::{Result, anyhow, bail};
 use ream_consensus_lean::{block::SignedBlock, state::LeanState};
 use ream_consensus_misc::constants::lean::VALIDATOR_REGISTRY_LIMIT;
@@ -101,6 +104,24 @@ pub fn verify_checkpoint_state(state: &LeanState) -> Result<()> {
     Ok(())
 }
 
+/// Verify the signatures of the anchor block against the validators of the anchor state. The
+/// registry doesn't change after genesis, so the post-state of the block holds the same validators
+/// as its parent state. The genesis block carries no signatures.
+#[cfg(feature = "devnet5")]
+pub fn verify_checkpoint_block(state: &LeanState, signed_block: &SignedBlock) -> Result<()> {
+    if signed_block.block.slot == 0 {
+        return Ok(());
+    }
+
+    if !signed_block.verify_signatures(state, true)? {
+        bail!(
+            "Invalid anchor block: signatures of block at slot {} failed to verify",
+            signed_block.block.slot
+        );
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -119,11 +140,17 @@ mod tests {
     use ream_consensus_lean::attestation::MultiMessageAggregate;
     use ream_consensus_lean::{
         block::{Block, BlockBody, SignedBlock},
+        checkpoint::Checkpoint,
         state::LeanState,
         utils::generate_default_validators,
         validator::Validator,
     };
     use ream_consensus_misc::constants::lean::VALIDATOR_REGISTRY_LIMIT;
+    #[cfg(feature = "devnet5")]
+    use ream_post_quantum_crypto::{
+        lean_multisig::type_2::{type_1_aggregate, type_2_merge, type_2_to_wire},
+        leansig::private_key::PrivateKey,
+    };
     use reqwest::Url;
     use ssz::Encode;
     #[cfg(feature = "devnet5")]
@@ -134,7 +161,14 @@ mod tests {
     };
     use tree_hash::TreeHash;
 
-    use super::{LeanCheckpointClient, verify_checkpoint_state};
+    #[cfg(feature = "devnet5")]
+    use super::verify_checkpoint_block;
+    use super::{
+        LeanCheckpointClient,
+        checkpoint::{fetch_checkpoint_anchor, get_checkpoint_sync_sources},
+        verify_checkpoint_state,
+        weak_subjectivity::verify_state_from_weak_subjectivity_checkpoint,
+    };
 
     #[derive(Clone)]
     enum ResponseMode {
@@ -511,6 +545,49 @@ mod tests {
         }
     }
 
+    /// Build an anchor at slot 1 whose block is signed by the only validator of the state.
+    #[cfg(feature = "devnet5")]
+    fn make_signed_anchor() -> (LeanState, SignedBlock) {
+        let (public_key, private_key) = PrivateKey::generate_key_pair(0, 32);
+        let mut validators = generate_default_validators(1);
+        validators[0].proposal_public_key = public_key;
+        let mut state = LeanState::generate_genesis(0, Some(validators));
+        state.slot = 1;
+
+        let mut signed_block = make_anchor_signed_block(&state);
+        let block_root = signed_block.block.tree_hash_root();
+        let signature = private_key
+            .sign(&block_root.0, 1)
+            .expect("Failed to sign anchor block");
+        let proof = type_1_aggregate(&[], &[(public_key, signature)], &block_root.0, 1)
+            .and_then(|proposer_proof| type_2_merge(vec![proposer_proof]))
+            .expect("Failed to aggregate anchor block proof");
+        signed_block.proof = MultiMessageAggregate::new(
+            VariableList::new(type_2_to_wire(&proof)).expect("Anchor block proof is too large"),
+        );
+
+        (state, signed_block)
+    }
+
+    #[cfg(feature = "devnet5")]
+    #[test]
+    fn test_verify_checkpoint_block_accepts_signed_anchor() {
+        let (state, signed_block) = make_signed_anchor();
+
+        verify_checkpoint_block(&state, &signed_block).expect("Expected valid anchor block");
+    }
+
+    #[cfg(feature = "devnet5")]
+    #[test]
+    fn test_verify_checkpoint_block_rejects_tampered_signature() {
+        let (state, mut signed_block) = make_signed_anchor();
+        let middle = signed_block.proof.proof.len() / 2;
+        signed_block.proof.proof[middle] ^= 0xff;
+
+        verify_checkpoint_block(&state, &signed_block)
+            .expect_err("Expected tampered anchor block proof to fail");
+    }
+
     fn spawn_anchor_server(
         state: LeanState,
         signed_block: SignedBlock,
@@ -695,4 +772,98 @@ mod tests {
 
         server_handle.stop(true).await;
     }
+
+    #[tokio::test]
+    async fn test_fetch_checkpoint_anchor_requires_quorum() {
+        let state = make_state(10);
+        let signed_block = make_anchor_signed_block(&state);
+        let other_state = make_state(11);
+        let other_signed_block = make_anchor_signed_block(&other_state);
+
+        let (first_url, first_handle) = spawn_anchor_server(state.clone(), signed_block.clone());
+        let (second_url, second_handle) = spawn_anchor_server(state.clone(), signed_block.clone());
+        let (third_url, third_handle) = spawn_anchor_server(other_state, other_signed_block);
+        let sources = vec![first_url, second_url, third_url];
+        let client = LeanCheckpointClient::new();
+
+        let (fetched_state, fetched_signed_block) = fetch_checkpoint_anchor(&client, &sources, 2)
+            .await
+            .expect("Expected two agreeing sources to reach the quorum");
+        assert_eq!(fetched_state, state);
+        assert_eq!(fetched_signed_block, signed_block);
+
+        let err = fetch_checkpoint_anchor(&client, &sources, 3)
+            .await
+            .expect_err("Expected a disagreeing source to break a quorum of three");
+        assert!(
+            err.to_string()
+                .contains("Only 2 of 3 checkpoint sync sources agree"),
+            "unexpected error: {err}"
+        );
+
+        fetch_checkpoint_anchor(&client, &sources, 4)
+            .await
+            .expect_err("Expected a quorum above the number of sources to fail");
+
+        first_handle.stop(true).await;
+        second_handle.stop(true).await;
+        third_handle.stop(true).await;
+    }
+
+    #[test]
+    fn test_get_checkpoint_sync_sources_removes_duplicates() {
+        let first_url = Url::parse("http://127.0.0.1:5052").expect("Failed to parse URL");
+        let second_url = Url::parse("http://127.0.0.1:5053").expect("Failed to parse URL");
+
+        let sources = get_checkpoint_sync_sources(vec![
+            first_url.clone(),
+            second_url.clone(),
+            first_url.clone(),
+        ]);
+
+        assert_eq!(sources, vec![first_url, second_url]);
+    }
+
+    #[test]
+    fn test_verify_state_from_weak_subjectivity_checkpoint() {
+        let mut state = make_state(10);
+        state.slot = 2;
+        state.historical_block_hashes =
+            VariableList::try_from(vec![B256::repeat_byte(0x01), B256::ZERO])
+                .expect("Failed to create historical block hashes");
+        let anchor_root = B256::repeat_byte(0x02);
+
+        for (slot, root) in [(0, B256::repeat_byte(0x01)), (2, anchor_root)] {
+            assert!(
+                verify_state_from_weak_subjectivity_checkpoint(
+                    &state,
+                    anchor_root,
+                    &Checkpoint { root, slot },
+                )
+                .expect("Expected weak subjectivity checkpoint to be found")
+            );
+        }
+
+        verify_state_from_weak_subjectivity_checkpoint(
+            &state,
+            anchor_root,
+            &Checkpoint {
+                root: B256::repeat_byte(0x01),
+                slot: 1,
+            },
+        )
+        .expect_err("Expected a root which isn't on the chain to fail");
+
+        assert!(
+            !verify_state_from_weak_subjectivity_checkpoint(
+                &state,
+                anchor_root,
+                &Checkpoint {
+                    root: B256::repeat_byte(0x03),
+                    slot: 3,
+                },
+            )
+            .expect("Expected a checkpoint ahead of the anchor to be pending")
+        );
+    }
 }
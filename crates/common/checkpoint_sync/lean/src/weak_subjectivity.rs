@@ -0,0 +1,32 @@
+use alloy_primitives::B256;
+use anyhow::ensure;
+use ream_consensus_lean::{checkpoint::Checkpoint, state::LeanState};
+
+/// Check whether the anchor `state` of the block `anchor_root` contains the weak subjectivity
+/// checkpoint. Returns false if the checkpoint is newer than the anchor and can't be checked.
+pub fn verify_state_from_weak_subjectivity_checkpoint(
+    state: &LeanState,
+    anchor_root: B256,
+    weak_subjectivity_checkpoint: &Checkpoint,
+) -> anyhow::Result<bool> {
+    if weak_subjectivity_checkpoint.slot > state.slot {
+        return Ok(false);
+    }
+
+    let root = if weak_subjectivity_checkpoint.slot == state.slot {
+        anchor_root
+    } else {
+        state
+            .historical_block_hashes
+            .get(weak_subjectivity_checkpoint.slot as usize)
+            .copied()
+            .unwrap_or_default()
+    };
+    ensure!(
+        root == weak_subjectivity_checkpoint.root,
+        "Weak subjectivity checkpoint not found: block at slot {} is {root}, expected {}",
+        weak_subjectivity_checkpoint.slot,
+        weak_subjectivity_checkpoint.root
+    );
+    Ok(true)
+}
@@ -11,8 +11,8 @@ use anyhow::{anyhow, bail, ensure};
 use ethereum_hashing::{hash, hash_fixed};
 use itertools::Itertools;
 use ream_bls::{
-    BLSSignature, PublicKey,
-    traits::{Aggregatable, Verifiable},
+    BLSSignature, PublicKey, SignatureSet,
+    traits::{Aggregatable, BatchVerifiable, Verifiable},
 };
 use ream_consensus_misc::{
     attestation_data::AttestationData,
@@ -21,11 +21,10 @@ use ream_consensus_misc::{
     checkpoint::Checkpoint,
     consolidation_request::ConsolidationRequest,
     constants::beacon::{
-        BASE_REWARD_FACTOR, BEACON_STATE_MERKLE_DEPTH, BLS_WITHDRAWAL_PREFIX, CAPELLA_FORK_VERSION,
-        CHURN_LIMIT_QUOTIENT, COMPOUNDING_WITHDRAWAL_PREFIX, CURRENT_SYNC_COMMITTEE_INDEX,
-        DEPOSIT_CONTRACT_TREE_DEPTH, DOMAIN_BEACON_ATTESTER, DOMAIN_BEACON_PROPOSER,
-        DOMAIN_BLS_TO_EXECUTION_CHANGE, DOMAIN_DEPOSIT, DOMAIN_RANDAO, DOMAIN_SYNC_COMMITTEE,
-        DOMAIN_VOLUNTARY_EXIT, EFFECTIVE_BALANCE_INCREMENT, EJECTION_BALANCE,
+        BASE_REWARD_FACTOR, BEACON_STATE_MERKLE_DEPTH, BLS_WITHDRAWAL_PREFIX, CHURN_LIMIT_QUOTIENT,
+        COMPOUNDING_WITHDRAWAL_PREFIX, CURRENT_SYNC_COMMITTEE_INDEX, DEPOSIT_CONTRACT_TREE_DEPTH,
+        DOMAIN_BEACON_ATTESTER, DOMAIN_BEACON_PROPOSER, DOMAIN_DEPOSIT, DOMAIN_RANDAO,
+        DOMAIN_SYNC_COMMITTEE, EFFECTIVE_BALANCE_INCREMENT, EJECTION_BALANCE,
         EPOCHS_PER_ETH1_VOTING_PERIOD, EPOCHS_PER_HISTORICAL_VECTOR, EPOCHS_PER_SLASHINGS_VECTOR,
         EPOCHS_PER_SYNC_COMMITTEE_PERIOD, ETH1_ADDRESS_WITHDRAWAL_PREFIX, FAR_FUTURE_EPOCH,
         FINALIZED_CHECKPOINT_INDEX, FULL_EXIT_REQUEST_AMOUNT, GENESIS_EPOCH, GENESIS_SLOT,
@@ -64,7 +63,11 @@ use ream_consensus_misc::{
     withdrawal::Withdrawal,
     withdrawal_request::WithdrawalRequest,
 };
-use ream_execution_engine::{engine_trait::ExecutionApi, new_payload_request::NewPayloadRequest};
+use ream_execution_engine::{
+    ExecutionEngine,
+    engine_trait::{ExecutionApi, InvalidPayloadError, PayloadVerificationStatus},
+    new_payload_request::NewPayloadRequest,
+};
 use ream_execution_rpc_types::electra::{
     execution_payload::ExecutionPayload, execution_payload_header::ExecutionPayloadHeader,
 };
@@ -545,6 +548,16 @@ impl BeaconState {
     pub fn is_valid_indexed_attestation(
         &self,
         indexed_attestation: &IndexedAttestation,
+    ) -> anyhow::Result<bool> {
+        self.is_valid_indexed_attestation_inner(indexed_attestation, true)
+    }
+
+    /// Like [BeaconState::is_valid_indexed_attestation], but the aggregate signature is only
+    /// checked if ``verify_signature`` is set.
+    pub fn is_valid_indexed_attestation_inner(
+        &self,
+        indexed_attestation: &IndexedAttestation,
+        verify_signature: bool,
     ) -> anyhow::Result<bool> {
         let indices: Vec<usize> = indexed_attestation
             .attesting_indices
@@ -556,26 +569,13 @@ impl BeaconState {
             return Ok(false);
         }
 
-        let domain = self.get_domain(
-            DOMAIN_BEACON_ATTESTER,
-            Some(indexed_attestation.data.target.epoch),
-        );
-        let signing_root = compute_signing_root(&indexed_attestation.data, domain);
+        let signature_set = self.indexed_attestation_signature_set(indexed_attestation)?;
+        if !verify_signature {
+            return Ok(true);
+        }
 
-        indexed_attestation
-            .signature
-            .fast_aggregate_verify(
-                indices
-                    .iter()
-                    .map(|&index| {
-                        self.validators
-                            .get(index)
-                            .map(|validator| &validator.public_key)
-                            .ok_or(anyhow!("Invalid index"))
-                    })
-                    .collect::<anyhow::Result<Vec<_>>>()?,
-                signing_root.as_ref(),
-            )
+        signature_set
+            .verify()
             .map_err(|err| anyhow!("Invalid indexed attestation: {err}"))
     }
 
@@ -1174,6 +1174,14 @@ impl BeaconState {
     pub fn validate_bls_to_execution_change(
         &self,
         signed_bls_to_execution_change: &SignedBLSToExecutionChange,
+    ) -> anyhow::Result<()> {
+        self.validate_bls_to_execution_change_inner(signed_bls_to_execution_change, true)
+    }
+
+    fn validate_bls_to_execution_change_inner(
+        &self,
+        signed_bls_to_execution_change: &SignedBLSToExecutionChange,
+        verify_signature: bool,
     ) -> anyhow::Result<()> {
         let bls_to_execution_change = &signed_bls_to_execution_change.message;
 
@@ -1188,21 +1196,13 @@ impl BeaconState {
                 == hash(bls_to_execution_change.from_bls_public_key.to_bytes())[1..]
         );
 
-        // Fork-agnostic domain since address changes are valid across forks
-        let domain = compute_domain(
-            DOMAIN_BLS_TO_EXECUTION_CHANGE,
-            None,
-            Some(self.genesis_validators_root),
-        );
-
-        let signing_root = compute_signing_root(bls_to_execution_change, domain);
-        ensure!(
-            signed_bls_to_execution_change.signature.verify(
-                &bls_to_execution_change.from_bls_public_key,
-                signing_root.as_ref()
-            )?,
-            "BLS Signature verification failed!"
-        );
+        if verify_signature {
+            ensure!(
+                self.bls_to_execution_change_signature_set(signed_bls_to_execution_change)
+                    .verify()?,
+                "BLS Signature verification failed!"
+            );
+        }
 
         Ok(())
     }
@@ -1211,7 +1211,18 @@ impl BeaconState {
         &mut self,
         signed_bls_to_execution_change: &SignedBLSToExecutionChange,
     ) -> anyhow::Result<()> {
-        self.validate_bls_to_execution_change(signed_bls_to_execution_change)?;
+        self.process_bls_to_execution_change_inner(signed_bls_to_execution_change, true)
+    }
+
+    fn process_bls_to_execution_change_inner(
+        &mut self,
+        signed_bls_to_execution_change: &SignedBLSToExecutionChange,
+        verify_signature: bool,
+    ) -> anyhow::Result<()> {
+        self.validate_bls_to_execution_change_inner(
+            signed_bls_to_execution_change,
+            verify_signature,
+        )?;
 
         let bls_to_execution_change = &signed_bls_to_execution_change.message;
 
@@ -1235,6 +1246,14 @@ impl BeaconState {
     pub fn validate_voluntary_exit(
         &self,
         signed_voluntary_exit: &SignedVoluntaryExit,
+    ) -> anyhow::Result<()> {
+        self.validate_voluntary_exit_inner(signed_voluntary_exit, true)
+    }
+
+    fn validate_voluntary_exit_inner(
+        &self,
+        signed_voluntary_exit: &SignedVoluntaryExit,
+        verify_signature: bool,
     ) -> anyhow::Result<()> {
         let voluntary_exit = &signed_voluntary_exit.message;
         let validator_index = voluntary_exit.validator_index as usize;
@@ -1278,20 +1297,13 @@ impl BeaconState {
             "Validator has not been active long enough"
         );
 
-        // Compute signature domain
-        let domain = compute_domain(
-            DOMAIN_VOLUNTARY_EXIT,
-            Some(CAPELLA_FORK_VERSION),
-            Some(self.genesis_validators_root),
-        );
-        let signing_root = compute_signing_root(voluntary_exit, domain);
-
-        ensure!(
-            signed_voluntary_exit
-                .signature
-                .verify(&validator.public_key, signing_root.as_ref())?,
-            "BLS Signature verification failed!"
-        );
+        if verify_signature {
+            ensure!(
+                self.voluntary_exit_signature_set(signed_voluntary_exit)?
+                    .verify()?,
+                "BLS Signature verification failed!"
+            );
+        }
 
         Ok(())
     }
@@ -1300,7 +1312,15 @@ impl BeaconState {
         &mut self,
         signed_voluntary_exit: &SignedVoluntaryExit,
     ) -> anyhow::Result<()> {
-        self.validate_voluntary_exit(signed_voluntary_exit)?;
+        self.process_voluntary_exit_inner(signed_voluntary_exit, true)
+    }
+
+    fn process_voluntary_exit_inner(
+        &mut self,
+        signed_voluntary_exit: &SignedVoluntaryExit,
+        verify_signature: bool,
+    ) -> anyhow::Result<()> {
+        self.validate_voluntary_exit_inner(signed_voluntary_exit, verify_signature)?;
 
         // Initiate exit
         self.initiate_validator_exit(signed_voluntary_exit.message.validator_index)?;
@@ -1633,6 +1653,14 @@ impl BeaconState {
     pub fn validate_proposer_slashing(
         &self,
         proposer_slashing: &ProposerSlashing,
+    ) -> anyhow::Result<u64> {
+        self.validate_proposer_slashing_inner(proposer_slashing, true)
+    }
+
+    fn validate_proposer_slashing_inner(
+        &self,
+        proposer_slashing: &ProposerSlashing,
+        verify_signatures: bool,
     ) -> anyhow::Result<u64> {
         let header_1 = &proposer_slashing.signed_header_1.message;
         let header_2 = &proposer_slashing.signed_header_2.message;
@@ -1662,23 +1690,13 @@ impl BeaconState {
         );
 
         // Verify signatures
-        for signed_header in [
-            &proposer_slashing.signed_header_1,
-            &proposer_slashing.signed_header_2,
-        ] {
-            let domain = self.get_domain(
-                DOMAIN_BEACON_PROPOSER,
-                Some(compute_epoch_at_slot(signed_header.message.slot)),
-            );
-
-            let signing_root = compute_signing_root(&signed_header.message, domain);
-
-            ensure!(
-                signed_header
-                    .signature
-                    .verify(&proposer.public_key, signing_root.as_ref())?,
-                "BLS Signature verification failed!"
-            );
+        if verify_signatures {
+            for signature_set in self.proposer_slashing_signature_sets(proposer_slashing)? {
+                ensure!(
+                    signature_set.verify()?,
+                    "BLS Signature verification failed!"
+                );
+            }
         }
 
         Ok(proposer_index)
@@ -1688,7 +1706,16 @@ impl BeaconState {
         &mut self,
         proposer_slashing: &ProposerSlashing,
     ) -> anyhow::Result<()> {
-        let proposer_index = self.validate_proposer_slashing(proposer_slashing)?;
+        self.process_proposer_slashing_inner(proposer_slashing, true)
+    }
+
+    fn process_proposer_slashing_inner(
+        &mut self,
+        proposer_slashing: &ProposerSlashing,
+        verify_signatures: bool,
+    ) -> anyhow::Result<()> {
+        let proposer_index =
+            self.validate_proposer_slashing_inner(proposer_slashing, verify_signatures)?;
         // Slash the validator
         self.slash_validator(proposer_index, None)
     }
@@ -1712,6 +1739,14 @@ impl BeaconState {
     pub fn get_slashable_attester_indices(
         &self,
         attester_shashing: &AttesterSlashing,
+    ) -> anyhow::Result<(HashSet<u64>, HashSet<u64>)> {
+        self.get_slashable_attester_indices_inner(attester_shashing, true)
+    }
+
+    fn get_slashable_attester_indices_inner(
+        &self,
+        attester_shashing: &AttesterSlashing,
+        verify_signatures: bool,
     ) -> anyhow::Result<(HashSet<u64>, HashSet<u64>)> {
         let attestation_1 = &attester_shashing.attestation_1;
         let attestation_2 = &attester_shashing.attestation_2;
@@ -1724,11 +1759,11 @@ impl BeaconState {
 
         // Validate both attestations
         ensure!(
-            self.is_valid_indexed_attestation(attestation_1)?,
+            self.is_valid_indexed_attestation_inner(attestation_1, verify_signatures)?,
             "First attestation is invalid"
         );
         ensure!(
-            self.is_valid_indexed_attestation(attestation_2)?,
+            self.is_valid_indexed_attestation_inner(attestation_2, verify_signatures)?,
             "Second attestation is invalid"
         );
 
@@ -1750,7 +1785,16 @@ impl BeaconState {
         &mut self,
         attester_slashing: &AttesterSlashing,
     ) -> anyhow::Result<()> {
-        let (indices_1, indices_2) = self.get_slashable_attester_indices(attester_slashing)?;
+        self.process_attester_slashing_inner(attester_slashing, true)
+    }
+
+    fn process_attester_slashing_inner(
+        &mut self,
+        attester_slashing: &AttesterSlashing,
+        verify_signatures: bool,
+    ) -> anyhow::Result<()> {
+        let (indices_1, indices_2) =
+            self.get_slashable_attester_indices_inner(attester_slashing, verify_signatures)?;
         let current_epoch = self.get_current_epoch();
         let mut slashed_any = false;
 
@@ -1789,35 +1833,23 @@ impl BeaconState {
     }
 
     pub fn process_sync_aggregate(&mut self, sync_aggregate: &SyncAggregate) -> anyhow::Result<()> {
-        let committee_public_keys = &self.current_sync_committee.public_keys;
-        let mut participant_public_keys = vec![];
+        self.process_sync_aggregate_inner(sync_aggregate, true)
+    }
 
-        for (public_key, bit) in committee_public_keys
-            .iter()
-            .zip(sync_aggregate.sync_committee_bits.iter())
+    fn process_sync_aggregate_inner(
+        &mut self,
+        sync_aggregate: &SyncAggregate,
+        verify_signature: bool,
+    ) -> anyhow::Result<()> {
+        if verify_signature
+            && let Some(signature_set) = self.sync_aggregate_signature_set(sync_aggregate)?
         {
-            if bit {
-                participant_public_keys.push(public_key);
-            }
+            ensure!(
+                signature_set.verify()?,
+                "Sync aggregate signature verification failed."
+            );
         }
 
-        let previous_slot = max(self.slot, 1) - 1;
-        let domain = self.get_domain(
-            DOMAIN_SYNC_COMMITTEE,
-            Some(compute_epoch_at_slot(previous_slot)),
-        );
-        let signing_root =
-            compute_signing_root(self.get_block_root_at_slot(previous_slot)?, domain);
-
-        ensure!(
-            eth_fast_aggregate_verify(
-                &participant_public_keys,
-                signing_root,
-                &sync_aggregate.sync_committee_signature,
-            )?,
-            "Sync aggregate signature verification failed."
-        );
-
         let committee_indices = self.get_sync_committee_indices(&self.current_sync_committee)?;
         let (participant_reward, proposer_reward) = self.get_proposer_and_participant_rewards();
         let proposer_index = self.get_beacon_proposer_index(None)?;
@@ -2058,7 +2090,11 @@ impl BeaconState {
         Ok(())
     }
 
-    pub fn process_randao(&mut self, body: &BeaconBlockBody) -> anyhow::Result<()> {
+    pub fn process_randao(
+        &mut self,
+        body: &BeaconBlockBody,
+        verify_signature: bool,
+    ) -> anyhow::Result<()> {
         let epoch = self.get_current_epoch();
 
         // Verify RANDAO reveal
@@ -2066,13 +2102,15 @@ impl BeaconState {
             .validators
             .get(self.get_beacon_proposer_index(None)? as usize)
         {
-            let signing_root =
-                compute_signing_root(epoch, self.get_domain(DOMAIN_RANDAO, Some(epoch)));
-            ensure!(
-                body.randao_reveal
-                    .verify(&proposer.public_key, signing_root.as_ref())?,
-                "BLS Signature verification failed!"
-            );
+            if verify_signature {
+                let signing_root =
+                    compute_signing_root(epoch, self.get_domain(DOMAIN_RANDAO, Some(epoch)));
+                ensure!(
+                    body.randao_reveal
+                        .verify(&proposer.public_key, signing_root.as_ref())?,
+                    "BLS Signature verification failed!"
+                );
+            }
 
             // Mix in RANDAO reveal
             let mix = xor(
@@ -2104,6 +2142,14 @@ impl BeaconState {
     }
 
     pub fn process_attestation(&mut self, attestation: &Attestation) -> anyhow::Result<()> {
+        self.process_attestation_inner(attestation, true)
+    }
+
+    fn process_attestation_inner(
+        &mut self,
+        attestation: &Attestation,
+        verify_signature: bool,
+    ) -> anyhow::Result<()> {
         let data = &attestation.data;
         ensure!(
             data.target.epoch == self.get_previous_epoch()
@@ -2160,7 +2206,10 @@ impl BeaconState {
             self.get_attestation_participation_flag_indices(data, self.slot - data.slot)?;
         // Verify signature
         ensure!(
-            self.is_valid_indexed_attestation(&self.get_indexed_attestation(attestation)?)?,
+            self.is_valid_indexed_attestation_inner(
+                &self.get_indexed_attestation(attestation)?,
+                verify_signature
+            )?,
             "Attestation signature must be valid"
         );
 
@@ -2422,7 +2471,11 @@ impl BeaconState {
         Ok(())
     }
 
-    pub fn process_operations(&mut self, body: &BeaconBlockBody) -> anyhow::Result<()> {
+    pub fn process_operations(
+        &mut self,
+        body: &BeaconBlockBody,
+        verify_signatures: bool,
+    ) -> anyhow::Result<()> {
         // Disable former deposit mechanism once all prior deposits are processed
         let eth1_deposit_index_limit = min(
             self.eth1_data.deposit_count,
@@ -2438,22 +2491,22 @@ impl BeaconState {
         }
 
         for proposer_slashing in body.proposer_slashings.iter() {
-            self.process_proposer_slashing(proposer_slashing)?;
+            self.process_proposer_slashing_inner(proposer_slashing, verify_signatures)?;
         }
         for attester_slashing in body.attester_slashings.iter() {
-            self.process_attester_slashing(attester_slashing)?;
+            self.process_attester_slashing_inner(attester_slashing, verify_signatures)?;
         }
         for attestation in body.attestations.iter() {
-            self.process_attestation(attestation)?;
+            self.process_attestation_inner(attestation, verify_signatures)?;
         }
         for deposit in body.deposits.iter() {
             self.process_deposit(deposit)?;
         }
         for voluntary_exit in body.voluntary_exits.iter() {
-            self.process_voluntary_exit(voluntary_exit)?;
+            self.process_voluntary_exit_inner(voluntary_exit, verify_signatures)?;
         }
         for bls_to_execution_change in body.bls_to_execution_changes.iter() {
-            self.process_bls_to_execution_change(bls_to_execution_change)?;
+            self.process_bls_to_execution_change_inner(bls_to_execution_change, verify_signatures)?;
         }
         for deposit in body.execution_requests.deposits.iter() {
             self.process_deposit_request(deposit)?;
@@ -2688,11 +2741,13 @@ impl BeaconState {
         Ok(())
     }
 
+    /// Returns whether the payload was verified by the execution engine or imported
+    /// optimistically. Payloads are irrelevant if there is no execution engine to verify them.
     pub async fn process_execution_payload(
         &mut self,
         body: &BeaconBlockBody,
         execution_engine: &Option<impl ExecutionApi>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<PayloadVerificationStatus> {
         let payload = &body.execution_payload;
 
         // Verify consistency of the parent hash with respect to the previous execution payload
@@ -2714,40 +2769,48 @@ impl BeaconState {
             versioned_hashes.push(commitment.calculate_versioned_hash());
         }
 
-        if let Some(execution_engine) = execution_engine {
-            ensure!(
+        let verification_status = match execution_engine {
+            Some(execution_engine) => {
                 execution_engine
                     .verify_and_notify_new_payload(NewPayloadRequest {
                         execution_payload: payload.clone(),
                         versioned_hashes,
                         parent_beacon_block_root: self.latest_block_header.parent_root,
-                        execution_requests: body.execution_requests.clone()
+                        execution_requests: body.execution_requests.clone(),
                     })
                     .await?
-            );
+            }
+            None => PayloadVerificationStatus::Irrelevant,
+        };
+        if let PayloadVerificationStatus::Invalid { latest_valid_hash } = verification_status {
+            return Err(InvalidPayloadError { latest_valid_hash }.into());
         }
 
         // Cache execution payload header
         self.latest_execution_payload_header = payload.to_execution_payload_header();
 
-        Ok(())
+        Ok(verification_status)
     }
 
+    /// Apply ``block`` to the state. Signatures are only checked if ``verify_signatures`` is set,
+    /// callers that already verified them, e.g. as a batch, can skip them.
     pub async fn process_block(
         &mut self,
         block: &BeaconBlock,
         execution_engine: &Option<impl ExecutionApi>,
-    ) -> anyhow::Result<()> {
+        verify_signatures: bool,
+    ) -> anyhow::Result<PayloadVerificationStatus> {
         self.process_block_header(block)?;
         self.process_withdrawals(&block.body.execution_payload)?;
-        self.process_execution_payload(&block.body, execution_engine)
+        let verification_status = self
+            .process_execution_payload(&block.body, execution_engine)
             .await?;
-        self.process_randao(&block.body)?;
+        self.process_randao(&block.body, verify_signatures)?;
         self.process_eth1_data(&block.body)?;
-        self.process_operations(&block.body)?;
-        self.process_sync_aggregate(&block.body.sync_aggregate)?;
+        self.process_operations(&block.body, verify_signatures)?;
+        self.process_sync_aggregate_inner(&block.body.sync_aggregate, verify_signatures)?;
 
-        Ok(())
+        Ok(verification_status)
     }
 
     pub async fn state_transition(
@@ -2755,21 +2818,35 @@ impl BeaconState {
         signed_block: &SignedBeaconBlock,
         validate_result: bool,
         execution_engine: &Option<impl ExecutionApi>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<PayloadVerificationStatus> {
         let block = &signed_block.message;
         // Process slots (including those with no blocks) since block
         self.process_slots(block.slot)?;
 
-        // Verify signature
+        // Verify all signatures of the block as one batch
         if validate_result {
-            ensure!(self.verify_block_header_signature(&signed_block.signed_header())?)
+            ensure!(
+                SignatureSet::verify_batch(&self.block_signature_sets(signed_block)?)?,
+                "Block signature verification failed"
+            );
         }
-        // Process block
-        self.process_block(block, execution_engine).await?;
+        // Process block, skipping the signatures verified above
+        let verification_status = self
+            .process_block(block, execution_engine, !validate_result)
+            .await?;
         // Verify state root
         if validate_result {
             ensure!(block.state_root == self.tree_hash_root())
         }
+        Ok(verification_status)
+    }
+
+    /// Apply ``signed_block``, which was already imported, without verifying its signatures,
+    /// execution payload or state root again. Used to regenerate historical states.
+    pub async fn replay_block(&mut self, signed_block: &SignedBeaconBlock) -> anyhow::Result<()> {
+        self.process_slots(signed_block.message.slot)?;
+        self.process_block(&signed_block.message, &None::<ExecutionEngine>, false)
+            .await?;
         Ok(())
     }
 
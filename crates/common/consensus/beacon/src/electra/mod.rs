@@ -3,4 +3,5 @@ pub mod beacon_block_body;
 pub mod beacon_state;
 pub mod blinded_beacon_block;
 pub mod blinded_beacon_block_body;
+pub mod signature_sets;
 pub mod zkvm_types;
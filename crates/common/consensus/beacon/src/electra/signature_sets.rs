@@ -0,0 +1,307 @@
+use std::cmp::max;
+
+use anyhow::anyhow;
+use ream_bls::{BLSSignature, SignatureSet};
+use ream_consensus_misc::{
+    beacon_block_header::SignedBeaconBlockHeader,
+    constants::beacon::{
+        CAPELLA_FORK_VERSION, DOMAIN_BEACON_ATTESTER, DOMAIN_BEACON_PROPOSER,
+        DOMAIN_BLS_TO_EXECUTION_CHANGE, DOMAIN_RANDAO, DOMAIN_SYNC_COMMITTEE,
+        DOMAIN_VOLUNTARY_EXIT,
+    },
+    indexed_attestation::IndexedAttestation,
+    misc::{compute_domain, compute_epoch_at_slot, compute_signing_root},
+};
+
+use crate::{
+    bls_to_execution_change::SignedBLSToExecutionChange,
+    electra::{
+        beacon_block::SignedBeaconBlock, beacon_block_body::BeaconBlockBody,
+        beacon_state::BeaconState,
+    },
+    proposer_slashing::ProposerSlashing,
+    sync_aggregate::SyncAggregate,
+    voluntary_exit::SignedVoluntaryExit,
+};
+
+impl BeaconState {
+    /// Return the signature set of the block proposer's signature over ``signed_header``.
+    pub fn block_header_signature_set(
+        &self,
+        signed_header: &SignedBeaconBlockHeader,
+    ) -> anyhow::Result<SignatureSet> {
+        let proposer = self
+            .validators
+            .get(signed_header.message.proposer_index as usize)
+            .ok_or(anyhow!("Invalid block proposer index"))?;
+        let domain = self.get_domain(
+            DOMAIN_BEACON_PROPOSER,
+            Some(compute_epoch_at_slot(signed_header.message.slot)),
+        );
+
+        Ok(SignatureSet::single(
+            signed_header.signature.clone(),
+            proposer.public_key.clone(),
+            compute_signing_root(&signed_header.message, domain),
+        ))
+    }
+
+    /// Return the signature set of the RANDAO reveal in ``body``, signed by the proposer of the
+    /// current slot.
+    pub fn randao_signature_set(&self, body: &BeaconBlockBody) -> anyhow::Result<SignatureSet> {
+        let epoch = self.get_current_epoch();
+        let proposer = self
+            .validators
+            .get(self.get_beacon_proposer_index(None)? as usize)
+            .ok_or(anyhow!("Invalid block proposer index"))?;
+
+        Ok(SignatureSet::single(
+            body.randao_reveal.clone(),
+            proposer.public_key.clone(),
+            compute_signing_root(epoch, self.get_domain(DOMAIN_RANDAO, Some(epoch))),
+        ))
+    }
+
+    /// Return the signature set of the aggregate signature of ``indexed_attestation``.
+    pub fn indexed_attestation_signature_set(
+        &self,
+        indexed_attestation: &IndexedAttestation,
+    ) -> anyhow::Result<SignatureSet> {
+        let public_keys = indexed_attestation
+            .attesting_indices
+            .iter()
+            .map(|&index| {
+                self.validators
+                    .get(index as usize)
+                    .map(|validator| validator.public_key.clone())
+                    .ok_or(anyhow!("Invalid index"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let domain = self.get_domain(
+            DOMAIN_BEACON_ATTESTER,
+            Some(indexed_attestation.data.target.epoch),
+        );
+
+        Ok(SignatureSet::aggregate(
+            indexed_attestation.signature.clone(),
+            public_keys,
+            compute_signing_root(&indexed_attestation.data, domain),
+        ))
+    }
+
+    /// Return the signature sets of both headers of ``proposer_slashing``.
+    pub fn proposer_slashing_signature_sets(
+        &self,
+        proposer_slashing: &ProposerSlashing,
+    ) -> anyhow::Result<[SignatureSet; 2]> {
+        Ok([
+            self.block_header_signature_set(&proposer_slashing.signed_header_1)?,
+            self.block_header_signature_set(&proposer_slashing.signed_header_2)?,
+        ])
+    }
+
+    /// Return the signature set of ``signed_voluntary_exit``.
+    pub fn voluntary_exit_signature_set(
+        &self,
+        signed_voluntary_exit: &SignedVoluntaryExit,
+    ) -> anyhow::Result<SignatureSet> {
+        let voluntary_exit = &signed_voluntary_exit.message;
+        let validator = self
+            .validators
+            .get(voluntary_exit.validator_index as usize)
+            .ok_or(anyhow!("Invalid validator index"))?;
+        let domain = compute_domain(
+            DOMAIN_VOLUNTARY_EXIT,
+            Some(CAPELLA_FORK_VERSION),
+            Some(self.genesis_validators_root),
+        );
+
+        Ok(SignatureSet::single(
+            signed_voluntary_exit.signature.clone(),
+            validator.public_key.clone(),
+            compute_signing_root(voluntary_exit, domain),
+        ))
+    }
+
+    /// Return the signature set of ``signed_bls_to_execution_change``.
+    pub fn bls_to_execution_change_signature_set(
+        &self,
+        signed_bls_to_execution_change: &SignedBLSToExecutionChange,
+    ) -> SignatureSet {
+        let bls_to_execution_change = &signed_bls_to_execution_change.message;
+        // Fork-agnostic domain since address changes are valid across forks
+        let domain = compute_domain(
+            DOMAIN_BLS_TO_EXECUTION_CHANGE,
+            None,
+            Some(self.genesis_validators_root),
+        );
+
+        SignatureSet::single(
+            signed_bls_to_execution_change.signature.clone(),
+            bls_to_execution_change.from_bls_public_key.clone(),
+            compute_signing_root(bls_to_execution_change, domain),
+        )
+    }
+
+    /// Return the signature set of ``sync_aggregate``, or ``None`` if nobody participated and the
+    /// signature is the ``G2_POINT_AT_INFINITY``, which is valid without verification.
+    pub fn sync_aggregate_signature_set(
+        &self,
+        sync_aggregate: &SyncAggregate,
+    ) -> anyhow::Result<Option<SignatureSet>> {
+        let participant_public_keys = self
+            .current_sync_committee
+            .public_keys
+            .iter()
+            .zip(sync_aggregate.sync_committee_bits.iter())
+            .filter(|(_, bit)| *bit)
+            .map(|(public_key, _)| public_key.clone())
+            .collect::<Vec<_>>();
+        if participant_public_keys.is_empty()
+            && sync_aggregate.sync_committee_signature == BLSSignature::infinity()
+        {
+            return Ok(None);
+        }
+
+        let previous_slot = max(self.slot, 1) - 1;
+        let domain = self.get_domain(
+            DOMAIN_SYNC_COMMITTEE,
+            Some(compute_epoch_at_slot(previous_slot)),
+        );
+
+        Ok(Some(SignatureSet::aggregate(
+            sync_aggregate.sync_committee_signature.clone(),
+            participant_public_keys,
+            compute_signing_root(self.get_block_root_at_slot(previous_slot)?, domain),
+        )))
+    }
+
+    /// Return the signature sets of every signature in ``signed_block`` except deposits, whose
+    /// invalid signatures don't invalidate the block.
+    ///
+    /// The state must be at the slot of the block, after ``process_slots`` and before
+    /// ``process_block``.
+    pub fn block_signature_sets(
+        &self,
+        signed_block: &SignedBeaconBlock,
+    ) -> anyhow::Result<Vec<SignatureSet>> {
+        let body = &signed_block.message.body;
+        let mut signature_sets = vec![
+            self.block_header_signature_set(&signed_block.signed_header())?,
+            self.randao_signature_set(body)?,
+        ];
+        for proposer_slashing in body.proposer_slashings.iter() {
+            signature_sets.extend(self.proposer_slashing_signature_sets(proposer_slashing)?);
+        }
+        for attester_slashing in body.attester_slashings.iter() {
+            signature_sets
+                .push(self.indexed_attestation_signature_set(&attester_slashing.attestation_1)?);
+            signature_sets
+                .push(self.indexed_attestation_signature_set(&attester_slashing.attestation_2)?);
+        }
+        for attestation in body.attestations.iter() {
+            signature_sets.push(
+                self.indexed_attestation_signature_set(
+                    &self.get_indexed_attestation(attestation)?,
+                )?,
+            );
+        }
+        for voluntary_exit in body.voluntary_exits.iter() {
+            signature_sets.push(self.voluntary_exit_signature_set(voluntary_exit)?);
+        }
+        for bls_to_execution_change in body.bls_to_execution_changes.iter() {
+            signature_sets
+                .push(self.bls_to_execution_change_signature_set(bls_to_execution_change));
+        }
+        if let Some(signature_set) = self.sync_aggregate_signature_set(&body.sync_aggregate)? {
+            signature_sets.push(signature_set);
+        }
+
+        Ok(signature_sets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use ream_bls::{SignatureSet, traits::BatchVerifiable};
+    use ream_execution_engine::ExecutionEngine;
+    use ream_network_spec::networks::initialize_test_network_spec;
+    use snap::raw::Decoder;
+    use ssz::Decode;
+    use tree_hash::TreeHash;
+
+    use crate::electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState};
+
+    const PATH_TO_TEST_DATA_FOLDER: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../../testing/gossip-validation/tests/assets/sepolia"
+    );
+
+    fn read_ssz_snappy_file<T: Decode>(path: &str) -> anyhow::Result<T> {
+        let ssz_snappy = std::fs::read(format!("{PATH_TO_TEST_DATA_FOLDER}/{path}"))?;
+        let ssz = Decoder::new().decompress_vec(&ssz_snappy)?;
+        T::from_ssz_bytes(&ssz).map_err(|err| anyhow!("Failed to decode SSZ: {err:?}"))
+    }
+
+    /// A sepolia block and the post-state of its parent.
+    fn block_and_pre_state() -> anyhow::Result<(SignedBeaconBlock, BeaconState)> {
+        initialize_test_network_spec();
+        Ok((
+            read_ssz_snappy_file("blocks/parent_9552075.ssz_snappy")?,
+            read_ssz_snappy_file("states/grandparent_state_9552074.ssz_snappy")?,
+        ))
+    }
+
+    #[test]
+    fn test_block_signature_sets_are_valid() -> anyhow::Result<()> {
+        let (signed_block, mut state) = block_and_pre_state()?;
+        state.process_slots(signed_block.message.slot)?;
+
+        let signature_sets = state.block_signature_sets(&signed_block)?;
+        // Proposer, RANDAO reveal, every attestation and the sync aggregate
+        assert!(signature_sets.len() >= 2 + signed_block.message.body.attestations.len());
+        for signature_set in &signature_sets {
+            assert!(signature_set.verify()?);
+        }
+        assert!(SignatureSet::verify_batch(&signature_sets)?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_state_transition_verifies_signatures() -> anyhow::Result<()> {
+        let (signed_block, mut state) = block_and_pre_state()?;
+
+        state
+            .state_transition(&signed_block, true, &None::<ExecutionEngine>)
+            .await?;
+        assert_eq!(state.tree_hash_root(), signed_block.message.state_root);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_state_transition_rejects_one_invalid_signature() -> anyhow::Result<()> {
+        let (signed_block, state) = block_and_pre_state()?;
+        // A valid signature, but by the proposer over another message
+        let wrong_signature = signed_block.message.body.randao_reveal.clone();
+
+        let mut invalid_proposer_signature = signed_block.clone();
+        invalid_proposer_signature.signature = wrong_signature.clone();
+        let mut invalid_attestation_signature = signed_block;
+        invalid_attestation_signature.message.body.attestations[0].signature = wrong_signature;
+
+        for invalid_block in [invalid_proposer_signature, invalid_attestation_signature] {
+            let error = state
+                .clone()
+                .state_transition(&invalid_block, true, &None::<ExecutionEngine>)
+                .await
+                .expect_err("Block with an invalid signature was accepted");
+            assert!(
+                error
+                    .to_string()
+                    .contains("Block signature verification failed")
+            );
+        }
+        Ok(())
+    }
+}
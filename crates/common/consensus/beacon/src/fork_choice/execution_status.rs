@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+
+/// Whether the execution payload of a block has been verified by the execution engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+#[serde(rename_all = "snake_case")]
+#[ssz(enum_behaviour = "tag")]
+pub enum ExecutionStatus {
+    /// The execution engine verified the payload.
+    Valid,
+    /// The block was imported while the execution engine couldn't verify the payload yet.
+    Optimistic,
+    /// The execution engine found the payload, or one of its ancestors, to be invalid.
+    Invalid,
+    /// The node runs without an execution engine, so the payload is never verified. Such blocks
+    /// aren't optimistic, as no verification is pending.
+    Irrelevant,
+}
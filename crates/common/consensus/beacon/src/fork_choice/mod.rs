@@ -1 +1,2 @@
+pub mod execution_status;
 pub mod latest_message;
@@ -1,9 +1,16 @@
+use anyhow::anyhow;
 use ream_bls::BLSSignature;
 use ream_consensus_misc::attestation_data::AttestationData;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
+use ssz_types::{
+    BitList, BitVector,
+    typenum::{U64, U131072},
+};
 use tree_hash_derive::TreeHash;
 
+use crate::attestation::Attestation;
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
 pub struct SingleAttestation {
     pub committee_index: u64,
@@ -11,3 +18,32 @@ pub struct SingleAttestation {
     pub data: AttestationData,
     pub signature: BLSSignature,
 }
+
+impl SingleAttestation {
+    /// Converts to an [Attestation] with the bit of the attester set in `committee`, the beacon
+    /// committee of `self.committee_index` at `self.data.slot`.
+    pub fn to_attestation(&self, committee: &[u64]) -> anyhow::Result<Attestation> {
+        let index_in_committee = committee
+            .iter()
+            .position(|&index| index == self.attester_index)
+            .ok_or_else(|| anyhow!("Validator {} not found in committee", self.attester_index))?;
+
+        let mut aggregation_bits = BitList::<U131072>::with_capacity(committee.len())
+            .map_err(|err| anyhow!("Failed to create aggregation bits: {err:?}"))?;
+        aggregation_bits
+            .set(index_in_committee, true)
+            .map_err(|err| anyhow!("Failed to set aggregation bit: {err:?}"))?;
+
+        let mut committee_bits = BitVector::<U64>::new();
+        committee_bits
+            .set(self.committee_index as usize, true)
+            .map_err(|err| anyhow!("Failed to set committee bit: {err:?}"))?;
+
+        Ok(Attestation {
+            aggregation_bits,
+            data: self.data.clone(),
+            signature: self.signature.clone(),
+            committee_bits,
+        })
+    }
+}
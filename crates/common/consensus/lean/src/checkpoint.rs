@@ -1,4 +1,6 @@
-use alloy_primitives::B256;
+use std::str::FromStr;
+
+use alloy_primitives::{B256, hex::FromHexError};
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use tree_hash_derive::TreeHash;
@@ -22,3 +24,36 @@ pub struct Checkpoint {
     pub root: B256,
     pub slot: u64,
 }
+
+impl FromStr for Checkpoint {
+    type Err = CheckpointParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (root_str, slot_str) = s
+            .split_once(':')
+            .ok_or(CheckpointParseError::InvalidFormat)?;
+
+        let root = root_str
+            .strip_prefix("0x")
+            .ok_or(CheckpointParseError::MissingHexPrefix)
+            .and_then(|hex| B256::from_str(hex).map_err(CheckpointParseError::InvalidHex))?;
+
+        let slot = slot_str
+            .parse::<u64>()
+            .map_err(CheckpointParseError::InvalidSlot)?;
+
+        Ok(Self { root, slot })
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CheckpointParseError {
+    #[error("Expected format: 0x<block_root>:<slot>")]
+    InvalidFormat,
+    #[error("Missing '0x' prefix on block_root")]
+    MissingHexPrefix,
+    #[error("Invalid hex block_root (expected 32 bytes): {0}")]
+    InvalidHex(FromHexError),
+    #[error("Slot must be a valid u64 integer: {0}")]
+    InvalidSlot(std::num::ParseIntError),
+}
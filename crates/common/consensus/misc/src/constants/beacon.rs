@@ -55,6 +55,7 @@ pub const INACTIVITY_SCORE_RECOVERY_RATE: u64 = 16;
 pub const JUSTIFICATION_BITS_LENGTH: usize = 4;
 pub const KZG_COMMITMENTS_MERKLE_DEPTH: u64 = 12;
 pub const MAX_BLOBS_PER_BLOCK: usize = 4096;
+pub const MAX_ATTESTATIONS_ELECTRA: usize = 8;
 pub const MAX_COMMITTEES_PER_SLOT: u64 = 64;
 pub const MAX_DEPOSITS: u64 = 16;
 pub const MAX_SEED_LOOKAHEAD: u64 = 4;
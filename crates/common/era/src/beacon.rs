@@ -0,0 +1,551 @@
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, bail, ensure};
+use ream_consensus_beacon::electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState};
+use ream_consensus_misc::constants::beacon::SLOTS_PER_HISTORICAL_ROOT;
+use ream_fork_choice_beacon::{handlers::on_tick, store::get_forkchoice_store};
+use ream_network_spec::networks::beacon_network_spec;
+use ream_storage::{db::beacon::BeaconDB, tables::table::REDBTable};
+use tracing::{info, warn};
+use tree_hash::TreeHash;
+
+use crate::e2store::{
+    COMPRESSED_BEACON_STATE, COMPRESSED_SIGNED_BEACON_BLOCK, E2StoreReader, E2StoreWriter, Entry,
+    SLOT_INDEX, VERSION,
+};
+
+/// The blocks and the closing state of an era. Era `N` holds the blocks of the slots
+/// `[(N - 1) * SLOTS_PER_HISTORICAL_ROOT, N * SLOTS_PER_HISTORICAL_ROOT)` and the state at slot
+/// `N * SLOTS_PER_HISTORICAL_ROOT`.
+///
+/// The state is the post-state of the block at that slot if there is one, which is how the
+/// database stores its snapshots. Era files of other clients hold the state before that block,
+/// so the files written here are only meant to be read back by ream and don't interoperate.
+pub struct Era {
+    pub blocks: Vec<SignedBeaconBlock>,
+    pub state: BeaconState,
+}
+
+impl Era {
+    pub fn number(&self) -> u64 {
+        self.state.slot / SLOTS_PER_HISTORICAL_ROOT
+    }
+
+    /// The era file name, `<network>-<era number>-<short historical root>.era`.
+    pub fn file_name(&self) -> String {
+        let era = self.number();
+        let historical_root = if era == 0 {
+            self.state.genesis_validators_root
+        } else {
+            let index = (era - 1) as usize;
+            // Historical roots were frozen at Capella and continued as historical summaries
+            match self.state.historical_roots.get(index) {
+                Some(historical_root) => *historical_root,
+                None => self
+                    .state
+                    .historical_summaries
+                    .get(index - self.state.historical_roots.len())
+                    .map(|historical_summary| historical_summary.tree_hash_root())
+                    .unwrap_or_default(),
+            }
+        };
+
+        format!(
+            "{}-{era:05}-{}.era",
+            beacon_network_spec().network,
+            alloy_primitives::hex::encode(&historical_root[..4])
+        )
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        let mut writer = E2StoreWriter::new(BufWriter::new(File::create(path)?));
+        writer.write_entry(&Entry {
+            entry_type: VERSION,
+            data: vec![],
+        })?;
+
+        let era = self.number();
+        let mut block_positions = vec![0; SLOTS_PER_HISTORICAL_ROOT as usize];
+        for block in &self.blocks {
+            let position =
+                writer.write_entry(&Entry::compressed(COMPRESSED_SIGNED_BEACON_BLOCK, block)?)?;
+            block_positions[(block.message.slot % SLOTS_PER_HISTORICAL_ROOT) as usize] = position;
+        }
+        let state_position =
+            writer.write_entry(&Entry::compressed(COMPRESSED_BEACON_STATE, &self.state)?)?;
+
+        // The genesis era has no blocks and no block index
+        if era > 0 {
+            let index_position = writer.position();
+            let offsets = block_positions
+                .iter()
+                .map(|&position| match position {
+                    0 => 0,
+                    position => position as i64 - index_position as i64,
+                })
+                .collect::<Vec<_>>();
+            writer.write_entry(&Entry::slot_index(
+                (era - 1) * SLOTS_PER_HISTORICAL_ROOT,
+                &offsets,
+            ))?;
+        }
+        let index_position = writer.position();
+        writer.write_entry(&Entry::slot_index(
+            self.state.slot,
+            &[state_position as i64 - index_position as i64],
+        ))?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let mut reader = E2StoreReader::new(BufReader::new(File::open(path)?));
+        ensure!(
+            reader
+                .read_entry()?
+                .is_some_and(|entry| entry.entry_type == VERSION),
+            "{} is not an era file",
+            path.display()
+        );
+
+        let mut blocks = vec![];
+        let mut state = None;
+        for entry in reader {
+            let entry = entry?;
+            match entry.entry_type {
+                COMPRESSED_SIGNED_BEACON_BLOCK => blocks.push(entry.decompress()?),
+                COMPRESSED_BEACON_STATE => {
+                    ensure!(state.is_none(), "{} has two states", path.display());
+                    state = Some(entry.decompress()?);
+                }
+                // The entries are read in order, so the indices aren't needed
+                SLOT_INDEX => {}
+                entry_type => warn!(
+                    "Skipping unknown entry type {entry_type:?} in {}",
+                    path.display()
+                ),
+            }
+        }
+
+        Ok(Self {
+            blocks,
+            state: state.ok_or_else(|| anyhow!("{} has no state", path.display()))?,
+        })
+    }
+
+    /// Check that the blocks belong to the era and are the ones recorded in the block and state
+    /// roots of its state, and that they are linked to their parents. `previous_state_root` is
+    /// the root of the state of the era before, which the state roots of this era record.
+    pub fn verify(
+        &self,
+        mut parent_root: Option<B256>,
+        previous_state_root: Option<B256>,
+    ) -> anyhow::Result<()> {
+        ensure!(
+            self.state.slot % SLOTS_PER_HISTORICAL_ROOT == 0,
+            "State at slot {} is not at an era boundary",
+            self.state.slot
+        );
+
+        let start_slot = self.state.slot.saturating_sub(SLOTS_PER_HISTORICAL_ROOT);
+        if let Some(previous_state_root) = previous_state_root {
+            ensure!(
+                self.state.state_roots[(start_slot % SLOTS_PER_HISTORICAL_ROOT) as usize]
+                    == previous_state_root,
+                "State of era {} doesn't follow the state {previous_state_root} of the era before",
+                self.number()
+            );
+        }
+        let mut previous_slot = None;
+        for block in &self.blocks {
+            let slot = block.message.slot;
+            ensure!(
+                slot >= start_slot && slot < self.state.slot,
+                "Block at slot {slot} is not part of era {}",
+                self.number()
+            );
+            ensure!(
+                previous_slot.is_none_or(|previous_slot| slot > previous_slot),
+                "Blocks of era {} are not in slot order",
+                self.number()
+            );
+            previous_slot = Some(slot);
+
+            let index = (slot % SLOTS_PER_HISTORICAL_ROOT) as usize;
+            let block_root = block.message.tree_hash_root();
+            ensure!(
+                self.state.block_roots[index] == block_root,
+                "Block root {block_root} at slot {slot} doesn't match the era state"
+            );
+            ensure!(
+                self.state.state_roots[index] == block.message.state_root,
+                "State root of block {block_root} at slot {slot} doesn't match the era state"
+            );
+            if let Some(parent_root) = parent_root {
+                ensure!(
+                    block.message.parent_root == parent_root,
+                    "Block {block_root} at slot {slot} doesn't extend {parent_root}"
+                );
+            }
+            parent_root = Some(block_root);
+        }
+
+        Ok(())
+    }
+}
+
+/// Write an era file to `output_dir` for every era of the finalized chain whose closing state can
+/// be regenerated. Returns the number of written era files.
+///
+/// The closing state includes the block at the era boundary, see [Era].
+pub async fn export_eras(db: &BeaconDB, output_dir: &Path) -> anyhow::Result<usize> {
+    fs::create_dir_all(output_dir)?;
+
+    let finalized_root = db.finalized_checkpoint_provider().get()?.root;
+    let finalized_slot = db
+        .block_provider()
+        .get(finalized_root)?
+        .ok_or_else(|| anyhow!("Finalized block not found: {finalized_root}"))?
+        .message
+        .slot;
+    let slot_index_provider = db.slot_index_provider();
+    let oldest_slot = slot_index_provider
+        .get_oldest_slot()?
+        .ok_or_else(|| anyhow!("Database has no blocks"))?;
+
+    let block_provider = db.block_provider();
+    let mut exported_count = 0;
+    for era in
+        oldest_slot.div_ceil(SLOTS_PER_HISTORICAL_ROOT)..=finalized_slot / SLOTS_PER_HISTORICAL_ROOT
+    {
+        let era_slot = era * SLOTS_PER_HISTORICAL_ROOT;
+        let Some(state) = db.get_state_at_slot(era_slot).await? else {
+            warn!("Skipping era {era}, its state can't be regenerated");
+            continue;
+        };
+
+        // The genesis era has no blocks
+        let blocks = slot_index_provider
+            .get_range(era_slot.saturating_sub(SLOTS_PER_HISTORICAL_ROOT)..era_slot)?
+            .into_iter()
+            .map(|(_, block_root)| {
+                block_provider
+                    .get(block_root)?
+                    .ok_or_else(|| anyhow!("Block not found: {block_root}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let era = Era { blocks, state };
+        let path = output_dir.join(era.file_name());
+        era.write(&path)?;
+        info!("Exported era {} to {}", era.number(), path.display());
+        exported_count += 1;
+    }
+
+    Ok(exported_count)
+}
+
+/// Import the era files in `input_dir` in order, verifying the roots of their blocks against
+/// their states. The blocks are added to the slot index and the states kept as snapshots.
+///
+/// An empty database is anchored at the last imported block afterwards, which needs the state of
+/// the era before it. A database which already has a fork choice store only takes eras up to
+/// its finalized block. Returns the number of imported eras.
+pub async fn import_eras(db: &BeaconDB, input_dir: &Path) -> anyhow::Result<usize> {
+    let mut paths = fs::read_dir(input_dir)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<anyhow::Result<Vec<PathBuf>>>()?;
+    paths.retain(|path| path.extension().is_some_and(|extension| extension == "era"));
+    // The zero padded era number sorts the files of a network by era
+    paths.sort();
+
+    let is_initialized = db.is_initialized();
+    ensure!(
+        is_initialized || paths.len() >= 2,
+        "Seeding an empty database needs at least two consecutive era files"
+    );
+    let finalized_slot = if is_initialized {
+        let finalized_root = db.finalized_checkpoint_provider().get()?.root;
+        db.block_provider()
+            .get(finalized_root)?
+            .map(|block| block.message.slot)
+    } else {
+        None
+    };
+
+    let block_provider = db.block_provider();
+    let slot_index_provider = db.slot_index_provider();
+    let cold_state_provider = db.cold_state_provider();
+    let mut previous_era = None;
+    let mut previous_state_root = None;
+    let mut last_block = None;
+    let mut imported_count = 0;
+    for path in paths {
+        let era = Era::read(&path)?;
+        let era_number = era.number();
+        if finalized_slot.is_some_and(|finalized_slot| era.state.slot > finalized_slot) {
+            warn!("Skipping era {era_number}, it is past the finalized block");
+            continue;
+        }
+        if let Some(previous_era) = previous_era {
+            ensure!(
+                era_number == previous_era + 1,
+                "Era {era_number} doesn't follow era {previous_era}"
+            );
+        }
+
+        // Link the first block to the stored block before it, if there is one
+        let parent_root = match last_block {
+            Some(block_root) => Some(block_root),
+            None => match era.blocks.first() {
+                Some(block) => slot_index_provider
+                    .get_range(..block.message.slot)?
+                    .last()
+                    .map(|(_, block_root)| *block_root),
+                None => None,
+            },
+        };
+        era.verify(parent_root, previous_state_root)?;
+
+        let mut batch = db.write_batch()?;
+        for block in &era.blocks {
+            block_provider.insert_batch(
+                &mut batch,
+                block.message.tree_hash_root(),
+                block.clone(),
+            )?;
+        }
+        cold_state_provider.insert_batch(&mut batch, era.state.slot, era.state.clone())?;
+        batch.commit()?;
+
+        if let Some(block) = era.blocks.last() {
+            last_block = Some(block.message.tree_hash_root());
+        }
+        previous_era = Some(era_number);
+        previous_state_root = Some(era.state.tree_hash_root());
+        imported_count += 1;
+        info!("Imported era {era_number} from {}", path.display());
+    }
+
+    if !is_initialized {
+        anchor_at_last_block(db).await?;
+    }
+
+    Ok(imported_count)
+}
+
+/// Initialize the fork choice store of a database seeded from era files, anchored at its latest
+/// block. Its state is regenerated from the snapshot of the era before it.
+async fn anchor_at_last_block(db: &BeaconDB) -> anyhow::Result<()> {
+    let block_root = db
+        .slot_index_provider()
+        .get_highest_root()?
+        .ok_or_else(|| anyhow!("No blocks were imported"))?;
+    let block = db
+        .block_provider()
+        .get(block_root)?
+        .ok_or_else(|| anyhow!("Block not found: {block_root}"))?;
+    let Some(mut state) = db
+        .cold_state_provider()
+        .get_at_or_before(block.message.slot)?
+    else {
+        bail!("No state before block {block_root} to anchor the database on");
+    };
+
+    let block_provider = db.block_provider();
+    for (_, block_root) in db
+        .slot_index_provider()
+        .get_range(state.slot + 1..=block.message.slot)?
+    {
+        let block = block_provider
+            .get(block_root)?
+            .ok_or_else(|| anyhow!("Block not found: {block_root}"))?;
+        state.replay_block(&block).await?;
+    }
+    ensure!(
+        state.tree_hash_root() == block.message.state_root,
+        "Regenerated state of block {block_root} doesn't match its state root"
+    );
+
+    let slot = block.message.slot;
+    let genesis_time = state.genesis_time;
+    let mut store = get_forkchoice_store(state, block.message, db.clone())?;
+    on_tick(
+        &mut store,
+        genesis_time + beacon_network_spec().seconds_per_slot() * (slot + 1),
+    )?;
+    info!("Anchored the database at block {block_root} at slot {slot}");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use anyhow::anyhow;
+    use ream_consensus_beacon::electra::{
+        beacon_block::SignedBeaconBlock, beacon_state::BeaconState,
+    };
+    use ream_consensus_misc::{
+        checkpoint::Checkpoint, constants::beacon::SLOTS_PER_HISTORICAL_ROOT,
+        misc::compute_epoch_at_slot,
+    };
+    use ream_network_spec::networks::initialize_test_network_spec;
+    use ream_storage::{
+        db::{ReamDB, beacon::BeaconDB},
+        tables::{field::REDBField, table::REDBTable},
+    };
+    use snap::raw::Decoder;
+    use ssz::Decode;
+    use tempdir::TempDir;
+    use tree_hash::TreeHash;
+
+    use super::{Era, export_eras, import_eras};
+
+    const PATH_TO_TEST_DATA_FOLDER: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../testing/gossip-validation/tests/assets/sepolia"
+    );
+
+    fn read_ssz_snappy_file<T: Decode>(path: &str) -> anyhow::Result<T> {
+        let ssz_snappy = std::fs::read(format!("{PATH_TO_TEST_DATA_FOLDER}/{path}"))?;
+        let ssz = Decoder::new().decompress_vec(&ssz_snappy)?;
+        T::from_ssz_bytes(&ssz).map_err(|err| anyhow!("Failed to decode SSZ: {err:?}"))
+    }
+
+    /// An era of three consecutive sepolia blocks. Its state is a sepolia state moved to the end
+    /// of the era, recording the roots of the blocks.
+    fn test_era() -> anyhow::Result<Era> {
+        initialize_test_network_spec();
+        let blocks = [
+            "blocks/grandparent_9552074.ssz_snappy",
+            "blocks/parent_9552075.ssz_snappy",
+            "blocks/child_9552076.ssz_snappy",
+        ]
+        .into_iter()
+        .map(read_ssz_snappy_file::<SignedBeaconBlock>)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut state =
+            read_ssz_snappy_file::<BeaconState>("states/parent_state_9552075.ssz_snappy")?;
+        state.slot = state.slot.div_ceil(SLOTS_PER_HISTORICAL_ROOT) * SLOTS_PER_HISTORICAL_ROOT;
+        for block in &blocks {
+            let index = (block.message.slot % SLOTS_PER_HISTORICAL_ROOT) as usize;
+            state.block_roots[index] = block.message.tree_hash_root();
+            state.state_roots[index] = block.message.state_root;
+        }
+
+        Ok(Era { blocks, state })
+    }
+
+    fn init_db(tmp_dir: &TempDir) -> anyhow::Result<BeaconDB> {
+        Ok(ReamDB::new(tmp_dir.path().to_path_buf())?.init_beacon_db()?)
+    }
+
+    /// Store a block at the slot of the era state and finalize it, so the era is part of the
+    /// finalized chain of `db`.
+    fn finalize_era(db: &BeaconDB, era: &Era) -> anyhow::Result<B256> {
+        let mut finalized_block = era
+            .blocks
+            .last()
+            .ok_or_else(|| anyhow!("Era has no blocks"))?
+            .clone();
+        finalized_block.message.parent_root = finalized_block.message.tree_hash_root();
+        finalized_block.message.slot = era.state.slot;
+        let finalized_root = finalized_block.message.tree_hash_root();
+
+        db.block_provider()
+            .insert(finalized_root, finalized_block)?;
+        db.finalized_checkpoint_provider().insert(Checkpoint {
+            epoch: compute_epoch_at_slot(era.state.slot),
+            root: finalized_root,
+        })?;
+        Ok(finalized_root)
+    }
+
+    #[test]
+    fn test_era_write_read() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_era_write_read")?;
+        let era = test_era()?;
+        let path = tmp_dir.path().join(era.file_name());
+        era.write(&path)?;
+
+        let read_era = Era::read(&path)?;
+        assert_eq!(read_era.number(), era.number());
+        assert_eq!(read_era.blocks, era.blocks);
+        assert_eq!(read_era.state, era.state);
+        read_era.verify(Some(era.blocks[0].message.parent_root), None)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_era_verify_rejects_tampered_block() -> anyhow::Result<()> {
+        let mut era = test_era()?;
+        era.verify(None, None)?;
+
+        era.blocks[1].message.body.graffiti = B256::repeat_byte(0xff);
+        assert!(era.verify(None, None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_era_verify_links_to_previous_era() -> anyhow::Result<()> {
+        let era = test_era()?;
+        let previous_state_root = era.state.state_roots[0];
+        era.verify(None, Some(previous_state_root))?;
+
+        assert!(era.verify(None, Some(B256::repeat_byte(0xff))).is_err());
+        // The first block has to extend the last block of the era before
+        assert!(era.verify(Some(B256::repeat_byte(0xff)), None).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export_import_eras() -> anyhow::Result<()> {
+        let era = test_era()?;
+
+        let source_dir = TempDir::new("test_export_eras")?;
+        let source_db = init_db(&source_dir)?;
+        for block in &era.blocks {
+            source_db
+                .block_provider()
+                .insert(block.message.tree_hash_root(), block.clone())?;
+        }
+        let finalized_root = finalize_era(&source_db, &era)?;
+        source_db
+            .state_provider()
+            .insert(finalized_root, era.state.clone())?;
+
+        let era_dir = TempDir::new("test_era_files")?;
+        assert_eq!(export_eras(&source_db, era_dir.path()).await?, 1);
+
+        // The target database already has a fork choice store, so it takes the era as is
+        let target_dir = TempDir::new("test_import_eras")?;
+        let target_db = init_db(&target_dir)?;
+        finalize_era(&target_db, &era)?;
+        assert_eq!(import_eras(&target_db, era_dir.path()).await?, 1);
+
+        for block in &era.blocks {
+            let block_root = block.message.tree_hash_root();
+            assert_eq!(
+                target_db.block_provider().get(block_root)?.as_ref(),
+                Some(block)
+            );
+            assert_eq!(
+                target_db.slot_index_provider().get(block.message.slot)?,
+                Some(block_root)
+            );
+        }
+        assert_eq!(
+            target_db.cold_state_provider().get(era.state.slot)?,
+            Some(era.state)
+        );
+        Ok(())
+    }
+}
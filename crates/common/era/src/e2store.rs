@@ -0,0 +1,176 @@
+use std::io::{self, Read, Write};
+
+use anyhow::{anyhow, ensure};
+use snap::{read::FrameDecoder, write::FrameEncoder};
+use ssz::{Decode, Encode};
+
+/// Size of the header in front of every entry: type, length and reserved bytes.
+pub const HEADER_SIZE: u64 = 8;
+
+pub const VERSION: [u8; 2] = [0x65, 0x32];
+pub const COMPRESSED_SIGNED_BEACON_BLOCK: [u8; 2] = [0x01, 0x00];
+pub const COMPRESSED_BEACON_STATE: [u8; 2] = [0x02, 0x00];
+pub const SLOT_INDEX: [u8; 2] = [0x69, 0x32];
+
+/// Lean chain entries, which aren't part of the era format. The second byte is outside the
+/// range used by the beacon chain entry types.
+pub const COMPRESSED_LEAN_SIGNED_BLOCK: [u8; 2] = [0x01, 0x80];
+pub const COMPRESSED_LEAN_STATE: [u8; 2] = [0x02, 0x80];
+
+/// A typed record of an e2store file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub entry_type: [u8; 2],
+    pub data: Vec<u8>,
+}
+
+impl Entry {
+    /// An entry holding the snappy framed SSZ encoding of `value`.
+    pub fn compressed(entry_type: [u8; 2], value: &impl Encode) -> anyhow::Result<Self> {
+        let mut encoder = FrameEncoder::new(vec![]);
+        encoder.write_all(&value.as_ssz_bytes())?;
+        let data = encoder
+            .into_inner()
+            .map_err(|err| anyhow!("Failed to compress entry: {err}"))?;
+        Ok(Self { entry_type, data })
+    }
+
+    /// Decode the snappy framed SSZ value of the entry.
+    pub fn decompress<T: Decode>(&self) -> anyhow::Result<T> {
+        let mut bytes = vec![];
+        FrameDecoder::new(self.data.as_slice()).read_to_end(&mut bytes)?;
+        T::from_ssz_bytes(&bytes).map_err(|err| anyhow!("Failed to decode entry: {err:?}"))
+    }
+
+    /// A slot index, mapping the `offsets.len()` slots from `starting_slot` on to the offsets of
+    /// their entries, relative to the start of the slot index. Empty slots have an offset of 0.
+    pub fn slot_index(starting_slot: u64, offsets: &[i64]) -> Self {
+        let mut data = Vec::with_capacity(16 + offsets.len() * 8);
+        data.extend_from_slice(&starting_slot.to_le_bytes());
+        for offset in offsets {
+            data.extend_from_slice(&offset.to_le_bytes());
+        }
+        data.extend_from_slice(&(offsets.len() as u64).to_le_bytes());
+        Self {
+            entry_type: SLOT_INDEX,
+            data,
+        }
+    }
+
+    fn size(&self) -> u64 {
+        HEADER_SIZE + self.data.len() as u64
+    }
+}
+
+/// Writes entries to an e2store file, keeping track of their positions.
+pub struct E2StoreWriter<W: Write> {
+    writer: W,
+    position: u64,
+}
+
+impl<W: Write> E2StoreWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            position: 0,
+        }
+    }
+
+    /// The position the next entry is written at.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Write `entry`, returning the position it was written at.
+    pub fn write_entry(&mut self, entry: &Entry) -> anyhow::Result<u64> {
+        let length = u32::try_from(entry.data.len())
+            .map_err(|_| anyhow!("Entry of {} bytes is too large", entry.data.len()))?;
+        self.writer.write_all(&entry.entry_type)?;
+        self.writer.write_all(&length.to_le_bytes())?;
+        self.writer.write_all(&[0; 2])?;
+        self.writer.write_all(&entry.data)?;
+
+        let position = self.position;
+        self.position += entry.size();
+        Ok(position)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Reads the entries of an e2store file in order.
+pub struct E2StoreReader<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> E2StoreReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Read the next entry, or `None` at the end of the file.
+    pub fn read_entry(&mut self) -> anyhow::Result<Option<Entry>> {
+        let mut header = [0; HEADER_SIZE as usize];
+        match self.reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err.into()),
+        }
+        ensure!(
+            header[6..] == [0; 2],
+            "Reserved bytes of the entry header are not zero"
+        );
+
+        let length = u32::from_le_bytes([header[2], header[3], header[4], header[5]]);
+        let mut data = vec![0; length as usize];
+        self.reader.read_exact(&mut data)?;
+        Ok(Some(Entry {
+            entry_type: [header[0], header[1]],
+            data,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for E2StoreReader<R> {
+    type Item = anyhow::Result<Entry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_entry().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+
+    use super::{COMPRESSED_BEACON_STATE, E2StoreReader, E2StoreWriter, Entry, VERSION};
+
+    #[test]
+    fn test_entries_round_trip() -> anyhow::Result<()> {
+        let value = B256::repeat_byte(7);
+        let entries = vec![
+            Entry {
+                entry_type: VERSION,
+                data: vec![],
+            },
+            Entry::compressed(COMPRESSED_BEACON_STATE, &value)?,
+            Entry::slot_index(8192, &[-40]),
+        ];
+
+        let mut writer = E2StoreWriter::new(vec![]);
+        for entry in &entries {
+            writer.write_entry(entry)?;
+        }
+        let position = writer.position();
+        let bytes = writer.writer;
+        assert_eq!(position, bytes.len() as u64);
+
+        let read_entries =
+            E2StoreReader::new(bytes.as_slice()).collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(read_entries, entries);
+        assert_eq!(read_entries[1].decompress::<B256>()?, value);
+        Ok(())
+    }
+}
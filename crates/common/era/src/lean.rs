@@ -0,0 +1,140 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, ensure};
+use ream_consensus_lean::{block::SignedBlock, state::LeanState};
+use ream_storage::{db::lean::LeanDB, tables::table::REDBTable};
+use tracing::info;
+use tree_hash::TreeHash;
+
+use crate::e2store::{
+    COMPRESSED_LEAN_SIGNED_BLOCK, COMPRESSED_LEAN_STATE, E2StoreReader, E2StoreWriter, Entry,
+    VERSION,
+};
+
+/// Number of blocks committed to the database at once during an import.
+const IMPORT_BATCH_SIZE: usize = 1024;
+
+/// Write the canonical chain up to the latest finalized block to `output_path`: the state of the
+/// oldest block whose state is stored, followed by that block and all of its descendants on the
+/// chain. Returns the number of exported blocks.
+pub fn export_lean_chain(db: &LeanDB, output_path: &Path) -> anyhow::Result<usize> {
+    let block_provider = db.block_provider();
+    let state_provider = db.state_provider();
+    let stored_states = state_provider
+        .iter_keys()?
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    let mut blocks = vec![];
+    let mut anchor_index = None;
+    let mut block_root = db.latest_finalized_provider().get()?.root;
+    while let Some(signed_block) = block_provider.get(block_root)? {
+        if stored_states.contains(&block_root) {
+            anchor_index = Some(blocks.len());
+        }
+        block_root = signed_block.block.parent_root;
+        blocks.push(signed_block);
+    }
+    let anchor_index =
+        anchor_index.ok_or_else(|| anyhow!("No state of the canonical chain is stored"))?;
+    blocks.truncate(anchor_index + 1);
+    blocks.reverse();
+
+    let anchor_root = blocks[0].block.tree_hash_root();
+    let anchor_state = state_provider
+        .get(anchor_root)?
+        .ok_or_else(|| anyhow!("State not found: {anchor_root}"))?;
+
+    let mut writer = E2StoreWriter::new(BufWriter::new(File::create(output_path)?));
+    writer.write_entry(&Entry {
+        entry_type: VERSION,
+        data: vec![],
+    })?;
+    writer.write_entry(&Entry::compressed(COMPRESSED_LEAN_STATE, &anchor_state)?)?;
+    for signed_block in &blocks {
+        writer.write_entry(&Entry::compressed(
+            COMPRESSED_LEAN_SIGNED_BLOCK,
+            signed_block,
+        )?)?;
+    }
+    writer.flush()?;
+
+    Ok(blocks.len())
+}
+
+/// Import a chain written by [export_lean_chain] from `input_path`. Every block is applied on
+/// top of the anchor state, which verifies its state root. The anchor state and the state of the
+/// last block are stored, the states in between are regenerated when needed. Returns the number
+/// of imported blocks.
+pub fn import_lean_chain(db: &LeanDB, input_path: &Path) -> anyhow::Result<usize> {
+    let mut reader = E2StoreReader::new(BufReader::new(File::open(input_path)?));
+    ensure!(
+        reader
+            .read_entry()?
+            .is_some_and(|entry| entry.entry_type == VERSION),
+        "{} is not a lean chain export",
+        input_path.display()
+    );
+    let state_entry = reader
+        .read_entry()?
+        .filter(|entry| entry.entry_type == COMPRESSED_LEAN_STATE)
+        .ok_or_else(|| anyhow!("{} has no anchor state", input_path.display()))?;
+    let mut state = state_entry.decompress::<LeanState>()?;
+
+    let block_provider = db.block_provider();
+    let state_provider = db.state_provider();
+    let mut batch = db.write_batch()?;
+    let mut parent_root: Option<B256> = None;
+    let mut imported_count = 0;
+    for entry in reader {
+        let entry = entry?;
+        ensure!(
+            entry.entry_type == COMPRESSED_LEAN_SIGNED_BLOCK,
+            "Unexpected entry type {:?} in {}",
+            entry.entry_type,
+            input_path.display()
+        );
+        let signed_block = entry.decompress::<SignedBlock>()?;
+        let block_root = signed_block.block.tree_hash_root();
+
+        match parent_root {
+            Some(parent_root) => {
+                ensure!(
+                    signed_block.block.parent_root == parent_root,
+                    "Block {block_root} doesn't extend {parent_root}"
+                );
+                // Signatures were verified when the blocks were imported by the exporting node
+                state.state_transition(&signed_block.block, true)?;
+            }
+            None => {
+                ensure!(
+                    signed_block.block.state_root == state.tree_hash_root(),
+                    "Anchor state doesn't match the state root of block {block_root}"
+                );
+                state_provider.insert_batch(&mut batch, block_root, state.clone())?;
+            }
+        }
+
+        block_provider.insert_ref_batch(&mut batch, block_root, &signed_block)?;
+        parent_root = Some(block_root);
+        imported_count += 1;
+        if imported_count % IMPORT_BATCH_SIZE == 0 {
+            batch.commit()?;
+            batch = db.write_batch()?;
+            info!("Imported {imported_count} blocks");
+        }
+    }
+
+    if let Some(block_root) = parent_root {
+        state_provider.insert_batch(&mut batch, block_root, state)?;
+    }
+    batch.commit()?;
+
+    Ok(imported_count)
+}
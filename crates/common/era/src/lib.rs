@@ -0,0 +1,3 @@
+pub mod beacon;
+pub mod e2store;
+pub mod lean;
@@ -1,4 +1,5 @@
 use ream_bls::BLSSignature;
+use ream_consensus_beacon::{attestation::Attestation, single_attestation::SingleAttestation};
 use ream_consensus_misc::attestation_data::AttestationData;
 use serde::{Deserialize, Serialize};
 use ssz_types::{
@@ -18,6 +19,17 @@ pub struct AttestationEvent {
     pub committee_bits: BitVector<U64>,
 }
 
+impl From<Attestation> for AttestationEvent {
+    fn from(attestation: Attestation) -> Self {
+        Self {
+            aggregation_bits: attestation.aggregation_bits,
+            data: attestation.data,
+            signature: attestation.signature,
+            committee_bits: attestation.committee_bits,
+        }
+    }
+}
+
 /// Single attestation event.
 ///
 /// The node has received a SingleAttestation (from P2P or API) that passes validation
@@ -31,3 +43,14 @@ pub struct SingleAttestationEvent {
     pub data: AttestationData,
     pub signature: BLSSignature,
 }
+
+impl From<SingleAttestation> for SingleAttestationEvent {
+    fn from(single_attestation: SingleAttestation) -> Self {
+        Self {
+            committee_index: single_attestation.committee_index,
+            attester_index: single_attestation.attester_index,
+            data: single_attestation.data,
+            signature: single_attestation.signature,
+        }
+    }
+}
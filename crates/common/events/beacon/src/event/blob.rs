@@ -1,6 +1,8 @@
 use alloy_primitives::B256;
+use ream_consensus_beacon::{blob_sidecar::BlobSidecar, data_column_sidecar::DataColumnSidecar};
 use ream_consensus_misc::polynomial_commitments::kzg_commitment::KZGCommitment;
 use serde::{Deserialize, Serialize};
+use tree_hash::TreeHash;
 
 /// Blob sidecar event.
 ///
@@ -17,6 +19,18 @@ pub struct BlobSidecarEvent {
     pub versioned_hash: B256,
 }
 
+impl From<&BlobSidecar> for BlobSidecarEvent {
+    fn from(blob_sidecar: &BlobSidecar) -> Self {
+        Self {
+            block_root: blob_sidecar.signed_block_header.message.tree_hash_root(),
+            index: blob_sidecar.index,
+            slot: blob_sidecar.signed_block_header.message.slot,
+            kzg_commitment: blob_sidecar.kzg_commitment,
+            versioned_hash: blob_sidecar.kzg_commitment.calculate_versioned_hash(),
+        }
+    }
+}
+
 /// Data column sidecar event.
 ///
 /// The node has received a DataColumnSidecar (from P2P or API) that passes all gossip
@@ -30,3 +44,17 @@ pub struct DataColumnSidecarEvent {
     pub slot: u64,
     pub kzg_commitments: Vec<KZGCommitment>,
 }
+
+impl From<&DataColumnSidecar> for DataColumnSidecarEvent {
+    fn from(data_column_sidecar: &DataColumnSidecar) -> Self {
+        Self {
+            block_root: data_column_sidecar
+                .signed_block_header
+                .message
+                .tree_hash_root(),
+            index: data_column_sidecar.index,
+            slot: data_column_sidecar.signed_block_header.message.slot,
+            kzg_commitments: data_column_sidecar.kzg_commitments.to_vec(),
+        }
+    }
+}
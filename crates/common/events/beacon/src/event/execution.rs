@@ -1,3 +1,5 @@
+use alloy_primitives::{Address, B256};
+use ream_consensus_misc::withdrawal::Withdrawal;
 use serde::{Deserialize, Serialize};
 
 /// Payload attributes event.
@@ -22,5 +24,29 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayloadAttributesEvent {
     pub version: String,
-    pub data: serde_json::Value, // TODO: Properly type this
+    pub data: PayloadAttributesData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayloadAttributesData {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposal_slot: u64,
+    pub parent_block_root: B256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub parent_block_number: u64,
+    pub parent_block_hash: B256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    pub payload_attributes: PayloadAttributes,
+}
+
+/// Beacon API encoding of `PayloadAttributesV3`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayloadAttributes {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub timestamp: u64,
+    pub prev_randao: B256,
+    pub suggested_fee_recipient: Address,
+    pub withdrawals: Vec<Withdrawal>,
+    pub parent_beacon_block_root: B256,
 }
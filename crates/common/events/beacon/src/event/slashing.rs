@@ -1,3 +1,6 @@
+use ream_consensus_beacon::{
+    attester_slashing::AttesterSlashing, proposer_slashing::ProposerSlashing,
+};
 use ream_consensus_misc::{
     beacon_block_header::SignedBeaconBlockHeader, indexed_attestation::IndexedAttestation,
 };
@@ -9,8 +12,17 @@ use serde::{Deserialize, Serialize};
 /// validation rules of the `proposer_slashing` topic.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProposerSlashingEvent {
-    pub signed_header_1: SignedBeaconBlockHeader, // TODO: Properly type this
-    pub signed_header_2: SignedBeaconBlockHeader, // TODO: Properly type this
+    pub signed_header_1: SignedBeaconBlockHeader,
+    pub signed_header_2: SignedBeaconBlockHeader,
+}
+
+impl From<ProposerSlashing> for ProposerSlashingEvent {
+    fn from(proposer_slashing: ProposerSlashing) -> Self {
+        Self {
+            signed_header_1: proposer_slashing.signed_header_1,
+            signed_header_2: proposer_slashing.signed_header_2,
+        }
+    }
 }
 
 /// Attester slashing event.
@@ -22,3 +34,12 @@ pub struct AttesterSlashingEvent {
     pub attestation_1: IndexedAttestation,
     pub attestation_2: IndexedAttestation,
 }
+
+impl From<AttesterSlashing> for AttesterSlashingEvent {
+    fn from(attester_slashing: AttesterSlashing) -> Self {
+        Self {
+            attestation_1: attester_slashing.attestation_1,
+            attestation_2: attester_slashing.attestation_2,
+        }
+    }
+}
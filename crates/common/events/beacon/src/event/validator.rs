@@ -1,6 +1,7 @@
 use ream_bls::BLSSignature;
 use ream_consensus_beacon::{
-    bls_to_execution_change::BLSToExecutionChange, voluntary_exit::VoluntaryExit,
+    bls_to_execution_change::{BLSToExecutionChange, SignedBLSToExecutionChange},
+    voluntary_exit::{SignedVoluntaryExit, VoluntaryExit},
 };
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +15,15 @@ pub struct VoluntaryExitEvent {
     pub signature: BLSSignature,
 }
 
+impl From<SignedVoluntaryExit> for VoluntaryExitEvent {
+    fn from(signed_voluntary_exit: SignedVoluntaryExit) -> Self {
+        Self {
+            message: signed_voluntary_exit.message,
+            signature: signed_voluntary_exit.signature,
+        }
+    }
+}
+
 /// BLS to execution change event.
 ///
 /// The node has received a SignedBLSToExecutionChange (from P2P or API) that passes
@@ -23,3 +33,12 @@ pub struct BlsToExecutionChangeEvent {
     pub message: BLSToExecutionChange,
     pub signature: BLSSignature,
 }
+
+impl From<SignedBLSToExecutionChange> for BlsToExecutionChangeEvent {
+    fn from(signed_bls_to_execution_change: SignedBLSToExecutionChange) -> Self {
+        Self {
+            message: signed_bls_to_execution_change.message,
+            signature: signed_bls_to_execution_change.signature,
+        }
+    }
+}
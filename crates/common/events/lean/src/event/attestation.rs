@@ -0,0 +1,21 @@
+use ream_consensus_lean::attestation::AttestationData;
+use serde::{Deserialize, Serialize};
+
+/// Attestation event.
+///
+/// The node has received an attestation over gossip which passed validation and signature
+/// verification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationEvent {
+    pub validator_id: u64,
+    pub data: AttestationData,
+}
+
+/// Aggregated attestation event.
+///
+/// The node has received an aggregated attestation over gossip whose proof was verified.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregatedAttestationEvent {
+    pub data: AttestationData,
+    pub validator_ids: Vec<u64>,
+}
@@ -0,0 +1,35 @@
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+
+/// Head event.
+///
+/// Fork choice has selected a new head block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeadEvent {
+    pub slot: u64,
+    pub block: B256,
+    pub state: B256,
+}
+
+/// Block event.
+///
+/// A block has been imported into fork choice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockEvent {
+    pub slot: u64,
+    pub block: B256,
+    pub proposer_index: u64,
+    pub parent_root: B256,
+}
+
+/// Reorg event.
+///
+/// The new head is not a descendant of the previous head. `depth` is the number of slots between
+/// the old head and the common ancestor of both heads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReorgEvent {
+    pub slot: u64,
+    pub depth: u64,
+    pub old_head_block: B256,
+    pub new_head_block: B256,
+}
@@ -0,0 +1,3 @@
+pub mod attestation;
+pub mod chain;
+pub mod sync;
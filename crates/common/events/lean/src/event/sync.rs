@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// Sync status event.
+///
+/// The node has switched between syncing and following the head of the chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncStatusEvent {
+    pub is_syncing: bool,
+    pub head_slot: u64,
+}
@@ -0,0 +1,135 @@
+pub mod event;
+
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use ream_consensus_lean::checkpoint::Checkpoint;
+use serde::{Deserialize, Serialize};
+
+use crate::event::{
+    attestation::{AggregatedAttestationEvent, AttestationEvent},
+    chain::{BlockEvent, HeadEvent, ReorgEvent},
+    sync::SyncStatusEvent,
+};
+
+/// Event topic enum for filtering events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeanEventTopic {
+    Head,
+    Block,
+    Justified,
+    Finalized,
+    Reorg,
+    Attestation,
+    AggregatedAttestation,
+    SyncStatus,
+}
+
+impl FromStr for LeanEventTopic {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "head" => LeanEventTopic::Head,
+            "block" => LeanEventTopic::Block,
+            "justified" => LeanEventTopic::Justified,
+            "finalized" => LeanEventTopic::Finalized,
+            "reorg" => LeanEventTopic::Reorg,
+            "attestation" => LeanEventTopic::Attestation,
+            "aggregated_attestation" => LeanEventTopic::AggregatedAttestation,
+            "sync_status" => LeanEventTopic::SyncStatus,
+            _ => return Err(anyhow!("Invalid Event Topic: {s}")),
+        })
+    }
+}
+
+impl std::fmt::Display for LeanEventTopic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                LeanEventTopic::Head => "head",
+                LeanEventTopic::Block => "block",
+                LeanEventTopic::Justified => "justified",
+                LeanEventTopic::Finalized => "finalized",
+                LeanEventTopic::Reorg => "reorg",
+                LeanEventTopic::Attestation => "attestation",
+                LeanEventTopic::AggregatedAttestation => "aggregated_attestation",
+                LeanEventTopic::SyncStatus => "sync_status",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+pub enum LeanEvent {
+    Head(HeadEvent),
+    Block(BlockEvent),
+    Justified(Checkpoint),
+    Finalized(Checkpoint),
+    Reorg(ReorgEvent),
+    Attestation(AttestationEvent),
+    AggregatedAttestation(AggregatedAttestationEvent),
+    SyncStatus(SyncStatusEvent),
+}
+
+impl LeanEvent {
+    pub fn topic(&self) -> LeanEventTopic {
+        match self {
+            LeanEvent::Head(_) => LeanEventTopic::Head,
+            LeanEvent::Block(_) => LeanEventTopic::Block,
+            LeanEvent::Justified(_) => LeanEventTopic::Justified,
+            LeanEvent::Finalized(_) => LeanEventTopic::Finalized,
+            LeanEvent::Reorg(_) => LeanEventTopic::Reorg,
+            LeanEvent::Attestation(_) => LeanEventTopic::Attestation,
+            LeanEvent::AggregatedAttestation(_) => LeanEventTopic::AggregatedAttestation,
+            LeanEvent::SyncStatus(_) => LeanEventTopic::SyncStatus,
+        }
+    }
+
+    /// Serializes only the event data (without the enum wrapper).
+    /// This is used for SSE where we send `event: <name>` and `data: <json>` separately.
+    pub fn serialize_data(&self) -> Result<String, serde_json::Error> {
+        match self {
+            LeanEvent::Head(data) => serde_json::to_string(data),
+            LeanEvent::Block(data) => serde_json::to_string(data),
+            LeanEvent::Justified(data) => serde_json::to_string(data),
+            LeanEvent::Finalized(data) => serde_json::to_string(data),
+            LeanEvent::Reorg(data) => serde_json::to_string(data),
+            LeanEvent::Attestation(data) => serde_json::to_string(data),
+            LeanEvent::AggregatedAttestation(data) => serde_json::to_string(data),
+            LeanEvent::SyncStatus(data) => serde_json::to_string(data),
+        }
+    }
+}
+
+/// Trait for sending lean events.
+///
+/// This trait provides a convenient way to send events through an optional broadcast sender,
+/// handling the None case and errors gracefully.
+pub trait LeanEventSender {
+    /// Send an event if the sender is available and anyone is subscribed.
+    fn send_event(&self, event: LeanEvent);
+}
+
+impl LeanEventSender for Option<tokio::sync::broadcast::Sender<LeanEvent>> {
+    fn send_event(&self, event: LeanEvent) {
+        let Some(sender) = self.as_ref() else {
+            return;
+        };
+
+        // Sending only fails when there are no subscribers, which is the normal case for a node
+        // nobody is streaming events from
+        if sender.receiver_count() == 0 {
+            return;
+        }
+
+        let topic = event.topic();
+        if let Err(err) = sender.send(event) {
+            tracing::warn!("Failed to send {topic} event: {err}");
+        }
+    }
+}
@@ -4,14 +4,38 @@ use ream_execution_rpc_types::get_blobs::{BlobAndProofV1, BlobAndProofV2};
 
 use super::new_payload_request::NewPayloadRequest;
 
+/// The result of verifying an execution payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadVerificationStatus {
+    /// The execution engine verified the payload.
+    Valid,
+    /// The execution engine is syncing and accepted the payload without verifying it.
+    Optimistic,
+    /// There is no execution engine to verify the payload, the node follows the chain without
+    /// one.
+    Irrelevant,
+    /// The payload is invalid. `latest_valid_hash` is the most recent valid ancestor of the
+    /// payload, if the execution engine knows it.
+    Invalid { latest_valid_hash: Option<B256> },
+}
+
+/// Returned when a block is rejected because its execution payload is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Execution payload is invalid, latest valid hash: {latest_valid_hash:?}")]
+pub struct InvalidPayloadError {
+    pub latest_valid_hash: Option<B256>,
+}
+
 #[async_trait]
 pub trait ExecutionApi {
-    /// Return ``True`` if and only if ``new_payload_request`` is valid with respect to
-    /// ``self.execution_state``.
+    /// Verify ``new_payload_request`` with respect to ``self.execution_state``.
+    ///
+    /// Payloads the execution engine accepted without verifying them, because it is syncing, are
+    /// [PayloadVerificationStatus::Optimistic].
     async fn verify_and_notify_new_payload(
         &self,
         new_payload_request: NewPayloadRequest,
-    ) -> anyhow::Result<bool>;
+    ) -> anyhow::Result<PayloadVerificationStatus>;
 
     async fn engine_get_blobs_v1(
         &self,
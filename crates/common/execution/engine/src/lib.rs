@@ -33,7 +33,10 @@ pub mod engine_trait;
 pub mod mock_engine;
 pub mod new_payload_request;
 
-use crate::{engine_trait::ExecutionApi, new_payload_request::NewPayloadRequest};
+use crate::{
+    engine_trait::{ExecutionApi, PayloadVerificationStatus},
+    new_payload_request::NewPayloadRequest,
+};
 
 #[derive(Clone)]
 pub struct ExecutionEngine {
@@ -77,11 +80,11 @@ impl ExecutionEngine {
                 .hash_slow()
     }
 
-    /// Return ``PayloadStatus`` of execution payload``.
+    /// Return ``PayloadStatusV1`` of execution payload``.
     pub async fn notify_new_payload(
         &self,
         new_payload_request: NewPayloadRequest,
-    ) -> anyhow::Result<PayloadStatus> {
+    ) -> anyhow::Result<PayloadStatusV1> {
         let NewPayloadRequest {
             execution_payload,
             versioned_hashes,
@@ -96,7 +99,7 @@ impl ExecutionEngine {
                 get_execution_requests_list(&execution_requests),
             )
             .await?;
-        Ok(payload_status.status)
+        Ok(payload_status)
     }
 
     pub fn build_request(&self, rpc_request: JsonRpcRequest) -> anyhow::Result<Request> {
@@ -465,7 +468,10 @@ impl ExecutionApi for ExecutionEngine {
     async fn verify_and_notify_new_payload(
         &self,
         new_payload_request: NewPayloadRequest,
-    ) -> anyhow::Result<bool> {
+    ) -> anyhow::Result<PayloadVerificationStatus> {
+        let invalid = PayloadVerificationStatus::Invalid {
+            latest_valid_hash: None,
+        };
         let execution_requests_list =
             get_execution_requests_list(&new_payload_request.execution_requests);
         if new_payload_request
@@ -473,7 +479,7 @@ impl ExecutionApi for ExecutionEngine {
             .transactions
             .contains(&VariableList::empty())
         {
-            return Ok(false);
+            return Ok(invalid);
         }
 
         if !self.is_valid_block_hash(
@@ -481,14 +487,24 @@ impl ExecutionApi for ExecutionEngine {
             new_payload_request.parent_beacon_block_root,
             &execution_requests_list,
         ) {
-            return Ok(false);
+            return Ok(invalid);
         }
 
         if !is_valid_versioned_hashes(&new_payload_request)? {
-            return Ok(false);
+            return Ok(invalid);
         }
 
-        return Ok(self.notify_new_payload(new_payload_request).await? == PayloadStatus::Valid);
+        let payload_status = self.notify_new_payload(new_payload_request).await?;
+        Ok(match payload_status.status {
+            PayloadStatus::Valid => PayloadVerificationStatus::Valid,
+            PayloadStatus::Syncing | PayloadStatus::Accepted => {
+                PayloadVerificationStatus::Optimistic
+            }
+            PayloadStatus::Invalid => PayloadVerificationStatus::Invalid {
+                latest_valid_hash: payload_status.latest_valid_hash,
+            },
+            PayloadStatus::InvalidBlockHash => invalid,
+        })
     }
 
     async fn engine_get_blobs_v1(
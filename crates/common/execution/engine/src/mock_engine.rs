@@ -6,7 +6,10 @@ use async_trait::async_trait;
 use ream_execution_rpc_types::get_blobs::{BlobAndProofV1, BlobAndProofV2};
 use serde::Deserialize;
 
-use super::{engine_trait::ExecutionApi, new_payload_request::NewPayloadRequest};
+use super::{
+    engine_trait::{ExecutionApi, PayloadVerificationStatus},
+    new_payload_request::NewPayloadRequest,
+};
 
 #[derive(Deserialize, Debug, Default)]
 pub struct MockExecutionEngine {
@@ -35,8 +38,14 @@ impl ExecutionApi for MockExecutionEngine {
     async fn verify_and_notify_new_payload(
         &self,
         _new_payload_request: NewPayloadRequest,
-    ) -> anyhow::Result<bool> {
-        Ok(self.execution_valid)
+    ) -> anyhow::Result<PayloadVerificationStatus> {
+        Ok(if self.execution_valid {
+            PayloadVerificationStatus::Valid
+        } else {
+            PayloadVerificationStatus::Invalid {
+                latest_valid_hash: None,
+            }
+        })
     }
 
     async fn engine_get_blobs_v1(
@@ -5,7 +5,7 @@ use ssz_types::{VariableList, typenum::U16};
 
 use super::payload_status::PayloadStatusV1;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ForkchoiceStateV1 {
     pub head_block_hash: B256,
@@ -13,7 +13,7 @@ pub struct ForkchoiceStateV1 {
     pub finalized_block_hash: B256,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PayloadAttributesV3 {
     #[serde(with = "serde_utils::u64_hex_be")]
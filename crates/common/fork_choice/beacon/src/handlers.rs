@@ -2,12 +2,15 @@ use alloy_primitives::{B256, map::HashSet};
 use anyhow::{anyhow, ensure};
 use ream_consensus_beacon::{
     attestation::Attestation, attester_slashing::AttesterSlashing,
-    electra::beacon_block::SignedBeaconBlock, predicates::is_slashable_attestation_data,
+    electra::beacon_block::SignedBeaconBlock, fork_choice::execution_status::ExecutionStatus,
+    predicates::is_slashable_attestation_data,
 };
 use ream_consensus_misc::{
     constants::beacon::INTERVALS_PER_SLOT, misc::compute_start_slot_at_epoch,
 };
-use ream_execution_engine::engine_trait::ExecutionApi;
+use ream_execution_engine::engine_trait::{
+    ExecutionApi, InvalidPayloadError, PayloadVerificationStatus,
+};
 use ream_network_spec::networks::beacon_network_spec;
 use ream_storage::{
     errors::StoreError,
@@ -38,6 +41,12 @@ pub async fn on_block(
         "Missing parent block state for parent_root: {parent_root:x}",
     );
 
+    // Descendants of blocks with an invalid execution payload are invalid too
+    ensure!(
+        store.db.get_execution_status(parent_root)? != ExecutionStatus::Invalid,
+        "Parent block has an invalid execution payload: {parent_root:x}",
+    );
+
     // Blocks cannot be in the future. If they are, their consideration must be delayed until they
     // are in the past.
     let current_slot = store.get_current_slot()?;
@@ -81,21 +90,54 @@ pub async fn on_block(
         .get(parent_root)?
         .ok_or_else(|| anyhow!("beacon state not found"))?
         .clone();
-    state
+    let verification_status = match state
         .state_transition(signed_block, true, execution_engine)
-        .await?;
+        .await
+    {
+        Ok(verification_status) => verification_status,
+        Err(err) => {
+            // The execution engine told which ancestors are invalid too
+            if let Some(InvalidPayloadError {
+                latest_valid_hash: Some(latest_valid_hash),
+            }) = err.downcast_ref::<InvalidPayloadError>()
+            {
+                store.on_invalid_execution_payload(parent_root, Some(*latest_valid_hash))?;
+            }
+            return Err(err);
+        }
+    };
+
+    // Stage the block and everything derived from it, so they are committed atomically
+    let mut batch = store.db.write_batch()?;
 
     // Add new block to the store
     store
         .db
         .block_provider()
-        .insert(block_root, signed_block.clone())?;
+        .insert_batch(&mut batch, block_root, signed_block.clone())?;
 
     // Add new state for this block to the store
     store
         .db
         .state_provider()
-        .insert(block_root, state.clone())?;
+        .insert_batch(&mut batch, block_root, state.clone())?;
+    store.db.justifications_provider().insert_batch(
+        &mut batch,
+        block_root,
+        state.current_justified_checkpoint,
+    )?;
+
+    // Record whether the payload was verified. A valid payload implies valid ancestors
+    let execution_status = match verification_status {
+        PayloadVerificationStatus::Valid => ExecutionStatus::Valid,
+        PayloadVerificationStatus::Optimistic => ExecutionStatus::Optimistic,
+        PayloadVerificationStatus::Irrelevant => ExecutionStatus::Irrelevant,
+        PayloadVerificationStatus::Invalid { .. } => ExecutionStatus::Invalid,
+    };
+    store
+        .db
+        .execution_status_provider()
+        .insert_batch(&mut batch, block_root, execution_status)?;
 
     // Add block timeliness to the store
     let time_into_slot = (store.db.time_provider().get()?
@@ -107,13 +149,21 @@ pub async fn on_block(
     store
         .db
         .block_timeliness_provider()
-        .insert(block_root, is_timely)?;
+        .insert_batch(&mut batch, block_root, is_timely)?;
 
     // Add proposer score boost if the block is timely and not conflicting with an existing block
     let is_first_block = store.db.proposer_boost_root_provider().get()? == B256::ZERO;
 
     if is_timely && is_first_block {
-        store.db.proposer_boost_root_provider().insert(block_root)?;
+        store
+            .db
+            .proposer_boost_root_provider()
+            .insert_batch(&mut batch, block_root)?;
+    }
+    batch.commit()?;
+
+    if execution_status == ExecutionStatus::Valid {
+        store.on_valid_execution_payload(parent_root)?;
     }
 
     // Update checkpoints in store if necessary
@@ -125,6 +175,23 @@ pub async fn on_block(
     // Eagerly compute unrealized justification and finality.
     store.compute_pulled_up_tip(block_root)?;
 
+    let unrealized_justified_checkpoint = store
+        .db
+        .unrealized_justifications_provider()
+        .get(block_root)?
+        .ok_or_else(|| anyhow!("unrealized_justifications not found"))?;
+    store.with_proto_array(|proto_array| {
+        proto_array.on_block(
+            block_root,
+            parent_root,
+            block_slot,
+            state.current_justified_checkpoint,
+            unrealized_justified_checkpoint,
+            execution_status,
+        );
+        Ok(())
+    })?;
+
     Ok(())
 }
 
@@ -167,14 +234,22 @@ pub fn on_attester_slashing(
         Err(err) => return Err(err.into()),
     };
 
-    for index in attestation_1_indices.intersection(&attestation_2_indices) {
-        equivocating.insert(*index);
-    }
+    let equivocating_indices = attestation_1_indices
+        .intersection(&attestation_2_indices)
+        .copied()
+        .collect::<Vec<_>>();
+    equivocating.extend(equivocating_indices.iter().copied());
 
     store
         .db
         .equivocating_indices_provider()
         .insert(equivocating)?;
+    store.with_proto_array(|proto_array| {
+        for &index in &equivocating_indices {
+            proto_array.process_equivocation(index);
+        }
+        Ok(())
+    })?;
 
     Ok(())
 }
@@ -203,6 +278,17 @@ pub fn on_attestation(
     store: &mut Store,
     attestation: Attestation,
     is_from_block: bool,
+) -> anyhow::Result<()> {
+    on_attestation_inner(store, attestation, is_from_block, true)
+}
+
+/// Like [on_attestation], but the aggregate signature is only checked if ``verify_signature`` is
+/// set. Attestations from gossip had their signature verified during validation already.
+pub fn on_attestation_inner(
+    store: &mut Store,
+    attestation: Attestation,
+    is_from_block: bool,
+    verify_signature: bool,
 ) -> anyhow::Result<()> {
     store.validate_on_attestation(&attestation, is_from_block)?;
 
@@ -215,7 +301,9 @@ pub fn on_attestation(
         .get(attestation.data.target)?
         .ok_or_else(|| anyhow!("checkpoint_states not found"))?;
     let indexed_attestation = target_state.get_indexed_attestation(&attestation)?;
-    ensure!(target_state.is_valid_indexed_attestation(&indexed_attestation)?);
+    ensure!(
+        target_state.is_valid_indexed_attestation_inner(&indexed_attestation, verify_signature)?
+    );
     // Update latest messages for attesting indices
     store.update_latest_messages(indexed_attestation.attesting_indices.to_vec(), attestation)?;
 
@@ -1,3 +1,4 @@
 pub mod constants;
 pub mod handlers;
+pub mod proto_array;
 pub mod store;
@@ -0,0 +1,589 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use alloy_primitives::B256;
+use anyhow::anyhow;
+use ream_consensus_beacon::{
+    electra::beacon_state::BeaconState, fork_choice::execution_status::ExecutionStatus,
+    helpers::get_total_active_balance,
+};
+use ream_consensus_misc::{
+    checkpoint::Checkpoint,
+    constants::beacon::{GENESIS_EPOCH, SLOTS_PER_EPOCH},
+    misc::{compute_epoch_at_slot, compute_start_slot_at_epoch},
+};
+use ream_storage::{
+    db::beacon::BeaconDB,
+    errors::StoreError,
+    tables::{field::CustomField, multimap_table::MultimapTable, table::REDBTable},
+};
+
+use crate::constants::PROPOSER_SCORE_BOOST;
+
+/// A block tracked by the [ProtoArray].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtoNode {
+    pub root: B256,
+    pub parent_root: B256,
+    pub slot: u64,
+    /// The current justified checkpoint of the block's post-state.
+    pub justified_checkpoint: Checkpoint,
+    /// The justified checkpoint of the block's post-state pulled up to the next epoch boundary.
+    pub unrealized_justified_checkpoint: Checkpoint,
+    /// Attesting balance of this block and its descendants, including the proposer boost.
+    pub weight: u64,
+    pub execution_status: ExecutionStatus,
+    parent: Option<usize>,
+    best_child: Option<usize>,
+    best_descendant: usize,
+}
+
+/// The latest message of a single validator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct VoteTracker {
+    /// The block the vote's balance is counted for, zero if it isn't counted for any block.
+    current_root: B256,
+    /// The block of the validator's latest message.
+    next_root: B256,
+    /// The target epoch of the validator's latest message.
+    next_epoch: u64,
+}
+
+/// Balances of the justified checkpoint state, which votes are weighted with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JustifiedBalances {
+    pub checkpoint: Checkpoint,
+    /// Effective balance of every active and unslashed validator, zero for all others.
+    pub balances: Vec<u64>,
+    pub proposer_score: u64,
+}
+
+impl JustifiedBalances {
+    pub fn from_state(checkpoint: Checkpoint, state: &BeaconState) -> Self {
+        let current_epoch = state.get_current_epoch();
+        let balances = state
+            .validators
+            .iter()
+            .map(|validator| {
+                if validator.is_active_validator(current_epoch) && !validator.slashed {
+                    validator.effective_balance
+                } else {
+                    0
+                }
+            })
+            .collect();
+        let committee_weight = get_total_active_balance(state) / SLOTS_PER_EPOCH;
+
+        Self {
+            checkpoint,
+            balances,
+            proposer_score: (committee_weight * PROPOSER_SCORE_BOOST) / 100,
+        }
+    }
+}
+
+/// Proto-array LMD GHOST fork choice, giving the same head as the spec's `get_head`.
+///
+/// Blocks are stored in insertion order, so a parent always comes before its children. Only the
+/// changes since the last update are applied to the weights: moved votes, the justified balances
+/// and the proposer boost. Weights, viability and best descendants are then updated in a single
+/// pass from the newest block to the oldest.
+#[derive(Debug, Default)]
+pub struct ProtoArray {
+    nodes: Vec<ProtoNode>,
+    indices: HashMap<B256, usize>,
+    votes: Vec<VoteTracker>,
+    equivocating_indices: HashSet<u64>,
+    justified_balances: JustifiedBalances,
+    /// The balances the current votes are counted with.
+    applied_balances: Vec<u64>,
+    /// The proposer boost currently counted in the weights.
+    applied_proposer_boost: Option<(B256, u64)>,
+}
+
+impl ProtoArray {
+    /// Rebuild the fork choice from the blocks descending from the finalized block and the
+    /// latest messages in `db`. States are only loaded for blocks imported before their justified
+    /// checkpoints were recorded.
+    pub fn from_db(db: &BeaconDB) -> anyhow::Result<Self> {
+        let finalized_root = db.finalized_checkpoint_provider().get()?.root;
+        let block_provider = db.block_provider();
+        let state_provider = db.state_provider();
+        let justifications_provider = db.justifications_provider();
+        let unrealized_justifications_provider = db.unrealized_justifications_provider();
+        let parent_root_index_provider = db.parent_root_index_multimap_provider();
+
+        let mut proto_array = Self::default();
+        let mut queue = VecDeque::from([finalized_root]);
+        while let Some(root) = queue.pop_front() {
+            let block = block_provider
+                .get(root)?
+                .ok_or_else(|| anyhow!("Block not found: {root}"))?
+                .message;
+            let justified_checkpoint = match justifications_provider.get(root)? {
+                Some(justified_checkpoint) => justified_checkpoint,
+                None => {
+                    state_provider
+                        .get(root)?
+                        .ok_or_else(|| anyhow!("State not found: {root}"))?
+                        .current_justified_checkpoint
+                }
+            };
+            let unrealized_justified_checkpoint = unrealized_justifications_provider
+                .get(root)?
+                .unwrap_or(justified_checkpoint);
+            proto_array.on_block(
+                root,
+                block.parent_root,
+                block.slot,
+                justified_checkpoint,
+                unrealized_justified_checkpoint,
+                db.get_execution_status(root)?,
+            );
+
+            queue.extend(parent_root_index_provider.get(root)?.unwrap_or_default());
+        }
+
+        proto_array.equivocating_indices = match db.equivocating_indices_provider().get() {
+            Ok(equivocating_indices) => equivocating_indices.into_iter().collect(),
+            Err(StoreError::FieldNotInitilized) => HashSet::new(),
+            Err(err) => return Err(err.into()),
+        };
+        for (validator_index, latest_message) in db.latest_messages_provider().iter()? {
+            proto_array.process_attestation(
+                validator_index,
+                latest_message.root,
+                latest_message.epoch,
+            );
+        }
+
+        Ok(proto_array)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains_block(&self, root: &B256) -> bool {
+        self.indices.contains_key(root)
+    }
+
+    pub fn get_node(&self, root: &B256) -> Option<&ProtoNode> {
+        self.indices.get(root).map(|&index| &self.nodes[index])
+    }
+
+    /// Add a block. Blocks whose parent isn't known are ignored, unless the array is empty, in
+    /// which case the block becomes the root.
+    pub fn on_block(
+        &mut self,
+        root: B256,
+        parent_root: B256,
+        slot: u64,
+        justified_checkpoint: Checkpoint,
+        unrealized_justified_checkpoint: Checkpoint,
+        execution_status: ExecutionStatus,
+    ) {
+        if self.indices.contains_key(&root) {
+            return;
+        }
+
+        let parent = self.indices.get(&parent_root).copied();
+        if parent.is_none() && !self.nodes.is_empty() {
+            return;
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(ProtoNode {
+            root,
+            parent_root,
+            slot,
+            justified_checkpoint,
+            unrealized_justified_checkpoint,
+            weight: 0,
+            execution_status,
+            parent,
+            best_child: None,
+            best_descendant: index,
+        });
+        self.indices.insert(root, index);
+    }
+
+    /// Record a latest message, if its target epoch is newer than the validator's current one.
+    pub fn process_attestation(&mut self, validator_index: u64, block_root: B256, epoch: u64) {
+        if self.equivocating_indices.contains(&validator_index) {
+            return;
+        }
+
+        let index = validator_index as usize;
+        if index >= self.votes.len() {
+            self.votes.resize(index + 1, VoteTracker::default());
+        }
+
+        let vote = &mut self.votes[index];
+        if vote.next_root == B256::ZERO || epoch > vote.next_epoch {
+            vote.next_root = block_root;
+            vote.next_epoch = epoch;
+        }
+    }
+
+    /// Set the execution status of `root`, if it is known.
+    pub fn set_execution_status(&mut self, root: B256, execution_status: ExecutionStatus) {
+        if let Some(&index) = self.indices.get(&root) {
+            self.nodes[index].execution_status = execution_status;
+        }
+    }
+
+    /// Mark `root` and all of its descendants invalid, so they can't become head anymore.
+    ///
+    /// Returns the roots of the blocks that weren't invalid before.
+    pub fn invalidate(&mut self, root: B256) -> Vec<B256> {
+        let Some(&invalid_index) = self.indices.get(&root) else {
+            return vec![];
+        };
+
+        let mut invalidated = vec![];
+        for index in invalid_index..self.nodes.len() {
+            // Children come after their parents, so the parent of every descendant has already
+            // been invalidated
+            let is_descendant = index == invalid_index
+                || self.nodes[index].parent.is_some_and(|parent| {
+                    parent >= invalid_index
+                        && self.nodes[parent].execution_status == ExecutionStatus::Invalid
+                });
+            if is_descendant && self.nodes[index].execution_status != ExecutionStatus::Invalid {
+                self.nodes[index].execution_status = ExecutionStatus::Invalid;
+                invalidated.push(self.nodes[index].root);
+            }
+        }
+        invalidated
+    }
+
+    /// Stop counting the votes of `validator_index` for good.
+    pub fn process_equivocation(&mut self, validator_index: u64) {
+        self.equivocating_indices.insert(validator_index);
+    }
+
+    /// The checkpoint of the balances votes are weighted with.
+    pub fn justified_balances_checkpoint(&self) -> Checkpoint {
+        self.justified_balances.checkpoint
+    }
+
+    /// Weight votes with `justified_balances` from the next update on.
+    pub fn set_justified_balances(&mut self, justified_balances: JustifiedBalances) {
+        self.justified_balances = justified_balances;
+    }
+
+    /// Apply the changes since the last update and recompute the best descendant of every block.
+    ///
+    /// A block is viable for head if its voting source and finalized ancestor agree with the
+    /// store's checkpoints, like in the spec's `filter_block_tree`. Only children leading to a
+    /// viable leaf are considered when picking the best child. Blocks with an invalid execution
+    /// payload are ignored entirely, so their parent can become a leaf.
+    pub fn apply_score_changes(
+        &mut self,
+        proposer_boost_root: B256,
+        justified_checkpoint: Checkpoint,
+        finalized_checkpoint: Checkpoint,
+        current_epoch: u64,
+    ) {
+        let mut deltas = self.compute_deltas();
+
+        if let Some((root, score)) = self.applied_proposer_boost.take()
+            && let Some(&index) = self.indices.get(&root)
+        {
+            deltas[index] -= score as i64;
+        }
+        if proposer_boost_root != B256::ZERO
+            && let Some(&index) = self.indices.get(&proposer_boost_root)
+        {
+            let score = self.justified_balances.proposer_score;
+            deltas[index] += score as i64;
+            self.applied_proposer_boost = Some((proposer_boost_root, score));
+        }
+
+        // The ancestor of every block at the finalized checkpoint's epoch boundary slot
+        let finalized_slot = compute_start_slot_at_epoch(finalized_checkpoint.epoch);
+        let mut finalized_ancestors = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let ancestor = match node.parent {
+                Some(parent) if node.slot > finalized_slot => finalized_ancestors[parent],
+                _ => node.root,
+            };
+            finalized_ancestors.push(ancestor);
+        }
+
+        let is_viable_for_head = |node: &ProtoNode, finalized_ancestor: B256| {
+            // Blocks from a prior epoch vote with their pulled-up justification
+            let voting_source = if current_epoch > compute_epoch_at_slot(node.slot) {
+                node.unrealized_justified_checkpoint
+            } else {
+                node.justified_checkpoint
+            };
+            let correct_justified = justified_checkpoint.epoch == GENESIS_EPOCH
+                || voting_source.epoch == justified_checkpoint.epoch
+                || voting_source.epoch + 2 >= current_epoch;
+            let correct_finalized = finalized_checkpoint.epoch == GENESIS_EPOCH
+                || finalized_checkpoint.root == finalized_ancestor;
+            correct_justified && correct_finalized
+        };
+
+        let mut has_children = vec![false; self.nodes.len()];
+        let mut leads_to_viable_head = vec![false; self.nodes.len()];
+        for node in &mut self.nodes {
+            node.best_child = None;
+        }
+
+        for index in (0..self.nodes.len()).rev() {
+            if !has_children[index] {
+                leads_to_viable_head[index] =
+                    is_viable_for_head(&self.nodes[index], finalized_ancestors[index]);
+            }
+            let node = &mut self.nodes[index];
+            node.weight = node.weight.saturating_add_signed(deltas[index]);
+            node.best_descendant = index;
+            let (parent, best_child) = (node.parent, node.best_child);
+            if let Some(best_child) = best_child {
+                self.nodes[index].best_descendant = self.nodes[best_child].best_descendant;
+            }
+
+            let Some(parent) = parent else {
+                continue;
+            };
+            deltas[parent] += deltas[index];
+            if self.nodes[index].execution_status == ExecutionStatus::Invalid {
+                continue;
+            }
+            has_children[parent] = true;
+            if !leads_to_viable_head[index] {
+                continue;
+            }
+            leads_to_viable_head[parent] = true;
+
+            // Prefer the heavier child, tiebreaking by the larger root
+            let is_best_child = match self.nodes[parent].best_child {
+                Some(current_best) => {
+                    (self.nodes[index].weight, self.nodes[index].root)
+                        > (
+                            self.nodes[current_best].weight,
+                            self.nodes[current_best].root,
+                        )
+                }
+                None => true,
+            };
+            if is_best_child {
+                self.nodes[parent].best_child = Some(index);
+            }
+        }
+    }
+
+    /// The head below `justified_root`, as of the last [ProtoArray::apply_score_changes].
+    pub fn find_head(&self, justified_root: B256) -> anyhow::Result<B256> {
+        let justified_index = self
+            .indices
+            .get(&justified_root)
+            .ok_or_else(|| anyhow!("Justified block not found in fork choice: {justified_root}"))?;
+        Ok(self.nodes[self.nodes[*justified_index].best_descendant].root)
+    }
+
+    /// The weight of `root`, as of the last [ProtoArray::apply_score_changes].
+    pub fn get_weight(&self, root: &B256) -> Option<u64> {
+        self.get_node(root).map(|node| node.weight)
+    }
+
+    /// Drop every block that isn't `finalized_root` or one of its descendants.
+    pub fn prune(&mut self, finalized_root: B256) {
+        let Some(&finalized_index) = self.indices.get(&finalized_root) else {
+            return;
+        };
+        if finalized_index == 0 {
+            return;
+        }
+
+        let mut new_indices = HashMap::with_capacity(self.nodes.len() - finalized_index);
+        let mut nodes = Vec::with_capacity(self.nodes.len() - finalized_index);
+        for (index, mut node) in self.nodes.drain(..).enumerate().skip(finalized_index) {
+            let parent = if index == finalized_index {
+                None
+            } else {
+                match node.parent.and_then(|parent| new_indices.get(&parent)) {
+                    Some(&parent) => Some(parent),
+                    None => continue,
+                }
+            };
+
+            let new_index = nodes.len();
+            new_indices.insert(index, new_index);
+            node.parent = parent;
+            node.best_child = None;
+            node.best_descendant = new_index;
+            nodes.push(node);
+        }
+
+        self.indices = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.root, index))
+            .collect();
+        self.nodes = nodes;
+    }
+
+    /// Move every changed vote to its new block, reweighting all votes if the justified balances
+    /// changed.
+    fn compute_deltas(&mut self) -> Vec<i64> {
+        let mut deltas = vec![0i64; self.nodes.len()];
+        let balances_changed = self.applied_balances != self.justified_balances.balances;
+
+        for (validator_index, vote) in self.votes.iter_mut().enumerate() {
+            let old_balance = self
+                .applied_balances
+                .get(validator_index)
+                .copied()
+                .unwrap_or_default();
+            if self
+                .equivocating_indices
+                .contains(&(validator_index as u64))
+            {
+                if let Some(&index) = self.indices.get(&vote.current_root) {
+                    deltas[index] -= old_balance as i64;
+                }
+                vote.current_root = B256::ZERO;
+                continue;
+            }
+
+            let new_balance = self
+                .justified_balances
+                .balances
+                .get(validator_index)
+                .copied()
+                .unwrap_or_default();
+            if vote.current_root == vote.next_root && old_balance == new_balance {
+                continue;
+            }
+
+            if let Some(&index) = self.indices.get(&vote.current_root) {
+                deltas[index] -= old_balance as i64;
+            }
+            // A vote for a pruned block isn't counted for any remaining block
+            vote.current_root = match self.indices.get(&vote.next_root) {
+                Some(&index) => {
+                    deltas[index] += new_balance as i64;
+                    vote.next_root
+                }
+                None => B256::ZERO,
+            };
+        }
+
+        if balances_changed {
+            self.applied_balances = self.justified_balances.balances.clone();
+        }
+
+        deltas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(byte: u8) -> B256 {
+        B256::repeat_byte(byte)
+    }
+
+    /// `1 <- 2 <- 3`, `2 <- 4` and `1 <- 5`, all optimistic
+    fn forked_proto_array() -> ProtoArray {
+        let mut proto_array = ProtoArray::default();
+        for (block_root, parent_root, slot) in [
+            (root(1), B256::ZERO, 0),
+            (root(2), root(1), 1),
+            (root(3), root(2), 2),
+            (root(4), root(2), 2),
+            (root(5), root(1), 1),
+        ] {
+            proto_array.on_block(
+                block_root,
+                parent_root,
+                slot,
+                Checkpoint::default(),
+                Checkpoint::default(),
+                ExecutionStatus::Optimistic,
+            );
+        }
+        proto_array.set_justified_balances(JustifiedBalances {
+            checkpoint: Checkpoint::default(),
+            balances: vec![1; 4],
+            proposer_score: 0,
+        });
+        proto_array
+    }
+
+    fn find_head(proto_array: &mut ProtoArray) -> B256 {
+        proto_array.apply_score_changes(
+            B256::ZERO,
+            Checkpoint::default(),
+            Checkpoint::default(),
+            0,
+        );
+        proto_array.find_head(root(1)).unwrap()
+    }
+
+    #[test]
+    fn test_invalidate_spreads_to_descendants() {
+        let mut proto_array = forked_proto_array();
+
+        assert_eq!(
+            proto_array.invalidate(root(2)),
+            vec![root(2), root(3), root(4)]
+        );
+        for block_root in [root(2), root(3), root(4)] {
+            assert_eq!(
+                proto_array.get_node(&block_root).unwrap().execution_status,
+                ExecutionStatus::Invalid
+            );
+        }
+        // The parent and the sibling branch are untouched
+        for block_root in [root(1), root(5)] {
+            assert_eq!(
+                proto_array.get_node(&block_root).unwrap().execution_status,
+                ExecutionStatus::Optimistic
+            );
+        }
+
+        // Blocks already invalid aren't reported again
+        assert!(proto_array.invalidate(root(3)).is_empty());
+        assert!(proto_array.invalidate(root(9)).is_empty());
+    }
+
+    #[test]
+    fn test_valid_execution_status_is_not_invalidated_by_sibling() {
+        let mut proto_array = forked_proto_array();
+        proto_array.set_execution_status(root(3), ExecutionStatus::Valid);
+
+        assert_eq!(proto_array.invalidate(root(4)), vec![root(4)]);
+        assert_eq!(
+            proto_array.get_node(&root(3)).unwrap().execution_status,
+            ExecutionStatus::Valid
+        );
+    }
+
+    #[test]
+    fn test_find_head_skips_invalid_blocks() {
+        let mut proto_array = forked_proto_array();
+        // Three votes for `3` against one for `5`
+        for validator_index in 0..3 {
+            proto_array.process_attestation(validator_index, root(3), 0);
+        }
+        proto_array.process_attestation(3, root(5), 0);
+        assert_eq!(find_head(&mut proto_array), root(3));
+
+        // The heavier branch is ignored once it is invalid, despite its votes
+        proto_array.invalidate(root(2));
+        assert_eq!(find_head(&mut proto_array), root(5));
+
+        // With every child invalid the root itself becomes head
+        proto_array.invalidate(root(5));
+        assert_eq!(find_head(&mut proto_array), root(1));
+    }
+}
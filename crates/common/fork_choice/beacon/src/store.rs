@@ -1,8 +1,9 @@
-use std::{cmp::Ordering, sync::Arc};
+use std::sync::Arc;
 
 use alloy_primitives::B256;
 use anyhow::{anyhow, bail, ensure};
 use hashbrown::HashMap;
+use parking_lot::Mutex;
 use ream_bls::BLSSignature;
 use ream_consensus_beacon::{
     attestation::Attestation,
@@ -11,7 +12,7 @@ use ream_consensus_beacon::{
         beacon_block::{BeaconBlock, SignedBeaconBlock},
         beacon_state::BeaconState,
     },
-    fork_choice::latest_message::LatestMessage,
+    fork_choice::{execution_status::ExecutionStatus, latest_message::LatestMessage},
     helpers::{calculate_committee_fraction, get_total_active_balance},
 };
 use ream_consensus_misc::{
@@ -33,9 +34,12 @@ use ream_storage::{
 use ream_sync_committee_pool::SyncCommitteePool;
 use tree_hash::TreeHash;
 
-use crate::constants::{
-    PROPOSER_SCORE_BOOST, REORG_HEAD_WEIGHT_THRESHOLD, REORG_MAX_EPOCHS_SINCE_FINALIZATION,
-    REORG_PARENT_WEIGHT_THRESHOLD,
+use crate::{
+    constants::{
+        PROPOSER_SCORE_BOOST, REORG_HEAD_WEIGHT_THRESHOLD, REORG_MAX_EPOCHS_SINCE_FINALIZATION,
+        REORG_PARENT_WEIGHT_THRESHOLD,
+    },
+    proto_array::{JustifiedBalances, ProtoArray},
 };
 
 #[derive(Debug)]
@@ -50,6 +54,8 @@ pub struct Store {
     pub db: BeaconDB,
     pub operation_pool: Arc<OperationPool>,
     pub sync_committee_pool: Arc<SyncCommitteePool>,
+    /// Built from the database on first use, see [Store::with_proto_array].
+    proto_array: Arc<Mutex<Option<ProtoArray>>>,
 }
 
 impl Store {
@@ -64,7 +70,50 @@ impl Store {
             db,
             operation_pool,
             sync_committee_pool,
+            proto_array: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Run `f` on the proto-array fork choice, rebuilding it from the database on first use.
+    pub(crate) fn with_proto_array<T>(
+        &self,
+        f: impl FnOnce(&mut ProtoArray) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let mut proto_array = self.proto_array.lock();
+        if proto_array.is_none() {
+            *proto_array = Some(ProtoArray::from_db(&self.db)?);
         }
+        f(proto_array
+            .as_mut()
+            .ok_or_else(|| anyhow!("Proto-array must be initialized"))?)
+    }
+
+    /// Bring the proto-array up to date with the store's checkpoints, proposer boost and time.
+    fn update_proto_array(&self, proto_array: &mut ProtoArray) -> anyhow::Result<()> {
+        let justified_checkpoint = self.db.justified_checkpoint_provider().get()?;
+        let finalized_checkpoint = self.db.finalized_checkpoint_provider().get()?;
+
+        proto_array.prune(finalized_checkpoint.root);
+        if proto_array.justified_balances_checkpoint() != justified_checkpoint {
+            let justified_state = self
+                .db
+                .checkpoint_states_provider()
+                .get(justified_checkpoint)?
+                .ok_or_else(|| anyhow!("checkpoint_states not found"))?;
+            proto_array.set_justified_balances(JustifiedBalances::from_state(
+                justified_checkpoint,
+                &justified_state,
+            ));
+        }
+
+        proto_array.apply_score_changes(
+            self.db.proposer_boost_root_provider().get()?,
+            justified_checkpoint,
+            finalized_checkpoint,
+            self.get_current_store_epoch()?,
+        );
+
+        Ok(())
     }
 
     pub fn is_previous_epoch_justified(&self) -> anyhow::Result<bool> {
@@ -200,43 +249,80 @@ impl Store {
     }
 
     pub fn get_head(&self) -> anyhow::Result<B256> {
-        // Get filtered block tree that only includes viable branches
-        let blocks = self.get_filtered_block_tree()?;
-        // Execute the LMD-GHOST fork choice
-        let mut head = self.db.justified_checkpoint_provider().get()?.root;
-
-        loop {
-            let mut children = vec![];
-            for root in blocks.keys() {
-                if blocks[root].block.parent_root == head {
-                    children.push(root);
-                }
-            }
+        let justified_root = self.db.justified_checkpoint_provider().get()?.root;
+        self.with_proto_array(|proto_array| {
+            self.update_proto_array(proto_array)?;
+            proto_array.find_head(justified_root)
+        })
+    }
 
-            if children.is_empty() {
-                return Ok(head);
-            }
+    /// Whether `block_root` was imported without its execution payload being verified.
+    pub fn is_optimistic(&self, block_root: B256) -> anyhow::Result<bool> {
+        Ok(self.db.get_execution_status(block_root)? == ExecutionStatus::Optimistic)
+    }
 
-            let mut weighted_children = children
-                .into_iter()
-                .map(|child| Ok((*child, self.get_weight(*child)?)))
-                .collect::<anyhow::Result<Vec<_>>>()?;
+    /// Mark `block_root` and its optimistic ancestors valid, once the execution engine verified
+    /// the payload of `block_root`.
+    pub fn on_valid_execution_payload(&self, block_root: B256) -> anyhow::Result<()> {
+        let block_provider = self.db.block_provider();
+        let execution_status_provider = self.db.execution_status_provider();
+        let mut root = block_root;
+        while self.db.get_execution_status(root)? == ExecutionStatus::Optimistic {
+            execution_status_provider.insert(root, ExecutionStatus::Valid)?;
+            self.with_proto_array(|proto_array| {
+                proto_array.set_execution_status(root, ExecutionStatus::Valid);
+                Ok(())
+            })?;
+
+            let Some(block) = block_provider.get(root)? else {
+                break;
+            };
+            root = block.message.parent_root;
+        }
 
-            // Sort by latest attesting balance with ties broken lexicographically
-            // Ties broken by favoring block with lexicographically higher root
-            weighted_children.sort_by(|(a, weight_a), (b, weight_b)| {
-                match weight_a.cmp(weight_b) {
-                    Ordering::Equal => a.cmp(b),
-                    other => other,
-                }
-            });
+        Ok(())
+    }
 
-            let Some((best_child, _)) = weighted_children.last() else {
-                bail!("Children should always be present");
+    /// Invalidate the optimistic blocks from `block_root` back to the block whose payload is
+    /// `latest_valid_hash`, together with all of their descendants, once the execution engine
+    /// found the payload of `block_root` invalid.
+    ///
+    /// Verified blocks are never invalidated. If the latest valid hash isn't known only
+    /// `block_root` and its descendants are invalidated.
+    pub fn on_invalid_execution_payload(
+        &self,
+        block_root: B256,
+        latest_valid_hash: Option<B256>,
+    ) -> anyhow::Result<()> {
+        let block_provider = self.db.block_provider();
+        let mut oldest_invalid_root = None;
+        let mut root = block_root;
+        while self.db.get_execution_status(root)? == ExecutionStatus::Optimistic {
+            let Some(block) = block_provider.get(root)? else {
+                break;
             };
+            if Some(block.message.body.execution_payload.block_hash) == latest_valid_hash {
+                break;
+            }
 
-            head = *best_child;
+            oldest_invalid_root = Some(root);
+            if latest_valid_hash.is_none() {
+                break;
+            }
+            root = block.message.parent_root;
+        }
+
+        let Some(oldest_invalid_root) = oldest_invalid_root else {
+            return Ok(());
+        };
+        let execution_status_provider = self.db.execution_status_provider();
+        for root in
+            self.with_proto_array(|proto_array| Ok(proto_array.invalidate(oldest_invalid_root)))?
+        {
+            execution_status_provider.insert(root, ExecutionStatus::Invalid)?;
         }
+
+        Ok(())
     }
 
     /// Update checkpoints in store if necessary
@@ -245,18 +331,29 @@ impl Store {
         justified_checkpoint: Checkpoint,
         finalized_checkpoint: Checkpoint,
     ) -> anyhow::Result<()> {
-        // Update justified checkpoint
-        if justified_checkpoint.epoch > self.db.justified_checkpoint_provider().get()?.epoch {
-            self.db
-                .justified_checkpoint_provider()
-                .insert(justified_checkpoint)?;
+        let is_justified_updated =
+            justified_checkpoint.epoch > self.db.justified_checkpoint_provider().get()?.epoch;
+        let is_finalized_updated =
+            finalized_checkpoint.epoch > self.db.finalized_checkpoint_provider().get()?.epoch;
+
+        // Both checkpoints are committed together, so finalization is never ahead of
+        // justification in the database
+        if is_justified_updated || is_finalized_updated {
+            let mut batch = self.db.write_batch()?;
+            if is_justified_updated {
+                self.db
+                    .justified_checkpoint_provider()
+                    .insert_batch(&mut batch, justified_checkpoint)?;
+            }
+            if is_finalized_updated {
+                self.db
+                    .finalized_checkpoint_provider()
+                    .insert_batch(&mut batch, finalized_checkpoint)?;
+            }
+            batch.commit()?;
         }
 
-        // Update finalized checkpoint
-        if finalized_checkpoint.epoch > self.db.finalized_checkpoint_provider().get()?.epoch {
-            self.db
-                .finalized_checkpoint_provider()
-                .insert(finalized_checkpoint)?;
+        if is_finalized_updated {
             // Clean operation pool
             if let Some(state) = self.db.state_provider().get(finalized_checkpoint.root)? {
                 self.operation_pool.clean_signed_voluntary_exits(&state);
@@ -277,6 +374,18 @@ impl Store {
                 }
             }
 
+            // Keep only snapshots of the newly finalized states and drop abandoned forks
+            match self.db.migrate_finalized_states(finalized_checkpoint.root) {
+                Ok(deleted_count) => {
+                    if deleted_count > 0 {
+                        tracing::info!("Migrated finalized states, deleted {deleted_count} states");
+                    }
+                }
+                Err(err) => {
+                    tracing::error!("Failed to migrate finalized states: {err}");
+                }
+            }
+
             // Prune old blobs based on the retention period
             let current_slot = self.get_current_slot()?;
             let min_retention_epochs = beacon_network_spec().min_epochs_for_blob_sidecars_requests;
@@ -379,67 +488,12 @@ impl Store {
     }
 
     pub fn get_weight(&self, root: B256) -> anyhow::Result<u64> {
-        let state = &self
-            .db
-            .checkpoint_states_provider()
-            .get(self.db.justified_checkpoint_provider().get()?)?
-            .ok_or_else(|| anyhow!("checkpoint_states not found"))?;
-
-        let unslashed_and_active_indices: Vec<u64> = state
-            .get_active_validator_indices(state.get_current_epoch())
-            .into_iter()
-            .filter(|&i| !state.validators[i as usize].slashed)
-            .collect();
-
-        let mut attestation_score: u64 = 0;
-        for index in unslashed_and_active_indices {
-            if self.db.latest_messages_provider().get(index)?.is_some()
-                && !self
-                    .db
-                    .equivocating_indices_provider()
-                    .get()?
-                    .contains(&index)
-                && self.get_ancestor(
-                    self.db
-                        .latest_messages_provider()
-                        .get(index)?
-                        .ok_or_else(|| anyhow!("latest_messages not found"))?
-                        .root,
-                    self.db
-                        .block_provider()
-                        .get(root)?
-                        .ok_or_else(|| anyhow!(" block not found"))?
-                        .message
-                        .slot,
-                )? == root
-            {
-                attestation_score += state.validators[index as usize].effective_balance;
-            }
-        }
-
-        if self.db.proposer_boost_root_provider().get()? == B256::ZERO {
-            // Return only attestation score if ``proposer_boost_root`` is not set
-            return Ok(attestation_score);
-        }
-
-        // Calculate proposer score if ``proposer_boost_root`` is set
-        // Boost is applied if ``root`` is an ancestor of ``proposer_boost_root``
-        let proposer_score = if self.get_ancestor(
-            self.db.proposer_boost_root_provider().get()?,
-            self.db
-                .block_provider()
-                .get(root)?
-                .ok_or_else(|| anyhow!("block not found"))?
-                .message
-                .slot,
-        )? == root
-        {
-            self.get_proposer_score()?
-        } else {
-            0
-        };
-
-        Ok(attestation_score + proposer_score)
+        self.with_proto_array(|proto_array| {
+            self.update_proto_array(proto_array)?;
+            proto_array
+                .get_weight(&root)
+                .ok_or_else(|| anyhow!("Block not found in fork choice: {root}"))
+        })
     }
 
     // Compute the voting source checkpoint in event that block with root ``block_root`` is the head
@@ -600,6 +654,13 @@ impl Store {
             }
         }
 
+        self.with_proto_array(|proto_array| {
+            for &index in &non_equivocating_attesting_indices {
+                proto_array.process_attestation(index, beacon_block_root, target.epoch);
+            }
+            Ok(())
+        })?;
+
         Ok(())
     }
 
@@ -620,6 +681,8 @@ impl Store {
                 .clean_sync_committee_messages(current_slot);
             self.sync_committee_pool
                 .clean_sync_committee_contributions(current_slot);
+
+            self.operation_pool.clean_attestations(current_slot);
         }
 
         // If a new epoch, pull-up justification and finalization from previous epoch
@@ -850,33 +913,47 @@ pub fn get_forkchoice_store(
         signature,
     };
 
-    db.time_provider().insert(
+    let mut batch = db.write_batch()?;
+    db.time_provider().insert_batch(
+        &mut batch,
         anchor_state.genesis_time + beacon_network_spec().seconds_per_slot() * anchor_state.slot,
     )?;
     db.genesis_time_provider()
-        .insert(anchor_state.genesis_time)?;
+        .insert_batch(&mut batch, anchor_state.genesis_time)?;
     db.justified_checkpoint_provider()
-        .insert(justified_checkpoint)?;
+        .insert_batch(&mut batch, justified_checkpoint)?;
     db.finalized_checkpoint_provider()
-        .insert(finalized_checkpoint)?;
+        .insert_batch(&mut batch, finalized_checkpoint)?;
     db.unrealized_justified_checkpoint_provider()
-        .insert(justified_checkpoint)?;
+        .insert_batch(&mut batch, justified_checkpoint)?;
     db.unrealized_finalized_checkpoint_provider()
-        .insert(finalized_checkpoint)?;
+        .insert_batch(&mut batch, finalized_checkpoint)?;
     db.proposer_boost_root_provider()
-        .insert(proposer_boost_root)?;
+        .insert_batch(&mut batch, proposer_boost_root)?;
     db.block_provider()
-        .insert(anchor_root, signed_anchor_block)?;
+        .insert_batch(&mut batch, anchor_root, signed_anchor_block)?;
     db.state_provider()
-        .insert(anchor_root, anchor_state.clone())?;
-    db.state_root_index_provider()
-        .insert(anchor_state.tree_hash_root(), anchor_root)?;
+        .insert_batch(&mut batch, anchor_root, anchor_state.clone())?;
+    db.state_root_index_provider().insert_batch(
+        &mut batch,
+        anchor_state.tree_hash_root(),
+        anchor_root,
+    )?;
     db.slot_index_provider()
-        .insert(anchor_state.slot, anchor_root)?;
+        .insert_batch(&mut batch, anchor_state.slot, anchor_root)?;
     db.checkpoint_states_provider()
-        .insert(justified_checkpoint, anchor_state)?;
-    db.unrealized_justifications_provider()
-        .insert(anchor_root, justified_checkpoint)?;
+        .insert_batch(&mut batch, justified_checkpoint, anchor_state)?;
+    db.justifications_provider().insert_batch(
+        &mut batch,
+        anchor_root,
+        anchor_state.current_justified_checkpoint,
+    )?;
+    db.unrealized_justifications_provider().insert_batch(
+        &mut batch,
+        anchor_root,
+        justified_checkpoint,
+    )?;
+    batch.commit()?;
 
     let operation_pool = Arc::new(OperationPool::default());
 
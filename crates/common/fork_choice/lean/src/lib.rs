@@ -1,3 +1,4 @@
 pub mod constants;
 pub mod genesis;
+pub mod proto_array;
 pub mod store;
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+
+use alloy_primitives::B256;
+use anyhow::anyhow;
+use ream_consensus_lean::attestation::AttestationData;
+use ream_storage::{
+    db::lean::LeanDB,
+    tables::{field::REDBField, table::REDBTable},
+};
+
+/// A block tracked by the [ProtoArray].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtoNode {
+    pub root: B256,
+    pub parent_root: B256,
+    pub slot: u64,
+    /// Number of latest votes for this block or any of its descendants.
+    pub weight: u64,
+    parent: Option<usize>,
+    best_child: Option<usize>,
+    best_descendant: usize,
+}
+
+/// The latest vote of a single validator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct VoteTracker {
+    /// The block the vote is counted for, zero if it isn't counted for any block.
+    current_root: B256,
+    /// The block the validator voted for in its latest attestation.
+    next_root: B256,
+    /// `(slot, data_root)` of the latest attestation, which decides between two attestations of
+    /// the same validator.
+    latest: (u64, B256),
+}
+
+/// In-memory LMD GHOST fork choice over the non-finalized blocks.
+///
+/// Blocks are stored in insertion order, so a parent always comes before its children. Every
+/// validator's latest vote is tracked, and only the votes that changed since the last call to
+/// [ProtoArray::find_head] are applied as weight deltas, walking the array once from the newest
+/// block to the oldest.
+#[derive(Debug, Clone, Default)]
+pub struct ProtoArray {
+    nodes: Vec<ProtoNode>,
+    indices: HashMap<B256, usize>,
+    votes: HashMap<u64, VoteTracker>,
+}
+
+impl ProtoArray {
+    /// Rebuild the fork choice from the blocks and latest known votes in `db`, starting at the
+    /// latest finalized block.
+    pub fn from_db(db: &LeanDB) -> anyhow::Result<Self> {
+        let finalized = db.latest_finalized_provider().get()?;
+        let index_map = db.children_index_provider().get_index_map()?;
+
+        let (finalized_parent_root, finalized_slot) = match index_map.get(&finalized.root) {
+            Some(&entry) => entry,
+            None => db
+                .block_provider()
+                .get(finalized.root)?
+                .map(|block| (block.block.parent_root, block.block.slot))
+                .ok_or_else(|| anyhow!("Finalized block not found: {}", finalized.root))?,
+        };
+
+        let mut proto_array = Self::default();
+        proto_array.on_block(finalized.root, finalized_parent_root, finalized_slot);
+
+        // Parents have lower slots than their children, so sorting by slot inserts every parent
+        // before its children
+        let mut blocks = index_map
+            .into_iter()
+            .filter(|(_, (_, slot))| *slot > finalized_slot)
+            .collect::<Vec<_>>();
+        blocks.sort_by_key(|(root, (_, slot))| (*slot, *root));
+        for (root, (parent_root, slot)) in blocks {
+            proto_array.on_block(root, parent_root, slot);
+        }
+
+        let attestation_data_by_root_provider = db.attestation_data_by_root_provider();
+        for signature_key in db.latest_known_aggregated_payloads_provider().iter_keys()? {
+            if let Some(data) = attestation_data_by_root_provider.get(signature_key.data_root)? {
+                proto_array.process_attestation(
+                    signature_key.validator_id,
+                    signature_key.data_root,
+                    &data,
+                );
+            }
+        }
+
+        Ok(proto_array)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains_block(&self, root: &B256) -> bool {
+        self.indices.contains_key(root)
+    }
+
+    pub fn get_node(&self, root: &B256) -> Option<&ProtoNode> {
+        self.indices.get(root).map(|&index| &self.nodes[index])
+    }
+
+    /// Add a block. Blocks whose parent isn't known are ignored, unless the array is empty, in
+    /// which case the block becomes the root.
+    pub fn on_block(&mut self, root: B256, parent_root: B256, slot: u64) {
+        if self.indices.contains_key(&root) {
+            return;
+        }
+
+        let parent = self.indices.get(&parent_root).copied();
+        if parent.is_none() && !self.nodes.is_empty() {
+            return;
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(ProtoNode {
+            root,
+            parent_root,
+            slot,
+            weight: 0,
+            parent,
+            best_child: None,
+            best_descendant: index,
+        });
+        self.indices.insert(root, index);
+    }
+
+    /// Record an attestation of `validator_id`, if it is newer than the validator's latest one.
+    ///
+    /// Attestations are ordered by slot and then by data root, like
+    /// [crate::store::Store::extract_attestations_from_aggregated_payloads] does.
+    pub fn process_attestation(
+        &mut self,
+        validator_id: u64,
+        data_root: B256,
+        attestation_data: &AttestationData,
+    ) {
+        let latest = (attestation_data.slot, data_root);
+        let next_root = attestation_data.head.root;
+        self.votes
+            .entry(validator_id)
+            .and_modify(|vote| {
+                if latest > vote.latest {
+                    vote.next_root = next_root;
+                    vote.latest = latest;
+                }
+            })
+            .or_insert(VoteTracker {
+                current_root: B256::ZERO,
+                next_root,
+                latest,
+            });
+    }
+
+    /// Apply the votes that changed and return the head below `justified_root` together with
+    /// its slot. Falls back to the oldest block if `justified_root` isn't known.
+    pub fn find_head(&mut self, justified_root: B256) -> anyhow::Result<(B256, u64)> {
+        if self.nodes.is_empty() {
+            return Err(anyhow!("No blocks found to calculate fork choice"));
+        }
+
+        self.apply_vote_changes();
+
+        let justified_index = self.indices.get(&justified_root).copied().unwrap_or(0);
+        let head = &self.nodes[self.nodes[justified_index].best_descendant];
+        Ok((head.root, head.slot))
+    }
+
+    /// Drop every block that isn't `finalized_root` or one of its descendants.
+    pub fn prune(&mut self, finalized_root: B256) {
+        let Some(&finalized_index) = self.indices.get(&finalized_root) else {
+            return;
+        };
+        if finalized_index == 0 {
+            return;
+        }
+
+        let mut new_indices = HashMap::with_capacity(self.nodes.len() - finalized_index);
+        let mut nodes = Vec::with_capacity(self.nodes.len() - finalized_index);
+        for (index, mut node) in self.nodes.drain(..).enumerate().skip(finalized_index) {
+            let parent = if index == finalized_index {
+                None
+            } else {
+                match node.parent.and_then(|parent| new_indices.get(&parent)) {
+                    Some(&parent) => Some(parent),
+                    None => continue,
+                }
+            };
+
+            let new_index = nodes.len();
+            new_indices.insert(index, new_index);
+            node.parent = parent;
+            node.best_child = None;
+            node.best_descendant = new_index;
+            nodes.push(node);
+        }
+
+        self.indices = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.root, index))
+            .collect();
+        self.nodes = nodes;
+    }
+
+    /// Move every changed vote to its new block, then update the weights and best descendants
+    /// in a single pass from the newest block to the oldest.
+    fn apply_vote_changes(&mut self) {
+        let mut deltas = vec![0i64; self.nodes.len()];
+        for vote in self.votes.values_mut() {
+            if vote.current_root == vote.next_root {
+                continue;
+            }
+
+            if let Some(&index) = self.indices.get(&vote.current_root) {
+                deltas[index] -= 1;
+            }
+            // A vote for a block that isn't known yet is counted once the block is added
+            vote.current_root = match self.indices.get(&vote.next_root) {
+                Some(&index) => {
+                    deltas[index] += 1;
+                    vote.next_root
+                }
+                None => B256::ZERO,
+            };
+        }
+
+        for node in &mut self.nodes {
+            node.best_child = None;
+        }
+
+        for index in (0..self.nodes.len()).rev() {
+            let node = &mut self.nodes[index];
+            node.weight = node.weight.saturating_add_signed(deltas[index]);
+            node.best_descendant = index;
+            let (parent, best_child) = (node.parent, node.best_child);
+            if let Some(best_child) = best_child {
+                self.nodes[index].best_descendant = self.nodes[best_child].best_descendant;
+            }
+
+            let Some(parent) = parent else {
+                continue;
+            };
+            deltas[parent] += deltas[index];
+
+            // Prefer the heavier child, tiebreaking by the larger root
+            let is_best_child = match self.nodes[parent].best_child {
+                Some(current_best) => {
+                    (self.nodes[index].weight, self.nodes[index].root)
+                        > (
+                            self.nodes[current_best].weight,
+                            self.nodes[current_best].root,
+                        )
+                }
+                None => true,
+            };
+            if is_best_child {
+                self.nodes[parent].best_child = Some(index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus_lean::checkpoint::Checkpoint;
+
+    use super::*;
+
+    fn root(byte: u8) -> B256 {
+        B256::repeat_byte(byte)
+    }
+
+    fn attestation_data(slot: u64, head: B256) -> AttestationData {
+        AttestationData {
+            slot,
+            head: Checkpoint { root: head, slot },
+            target: Checkpoint::default(),
+            source: Checkpoint::default(),
+        }
+    }
+
+    /// `1 <- 2 <- 3` and `1 <- 4`
+    fn forked_proto_array() -> ProtoArray {
+        let mut proto_array = ProtoArray::default();
+        proto_array.on_block(root(1), B256::ZERO, 0);
+        proto_array.on_block(root(2), root(1), 1);
+        proto_array.on_block(root(3), root(2), 2);
+        proto_array.on_block(root(4), root(1), 1);
+        proto_array
+    }
+
+    #[test]
+    fn test_find_head_follows_votes() {
+        let mut proto_array = forked_proto_array();
+        // Without votes the larger root wins the tie
+        assert_eq!(proto_array.find_head(root(1)).unwrap(), (root(4), 1));
+
+        proto_array.process_attestation(0, root(10), &attestation_data(2, root(3)));
+        assert_eq!(proto_array.find_head(root(1)).unwrap(), (root(3), 2));
+        assert_eq!(proto_array.get_node(&root(1)).unwrap().weight, 1);
+
+        proto_array.process_attestation(1, root(11), &attestation_data(2, root(4)));
+        proto_array.process_attestation(2, root(12), &attestation_data(2, root(4)));
+        assert_eq!(proto_array.find_head(root(1)).unwrap(), (root(4), 1));
+
+        // An older attestation doesn't replace the latest one
+        proto_array.process_attestation(1, root(13), &attestation_data(1, root(3)));
+        assert_eq!(proto_array.find_head(root(1)).unwrap(), (root(4), 1));
+
+        proto_array.process_attestation(1, root(14), &attestation_data(3, root(3)));
+        assert_eq!(proto_array.find_head(root(1)).unwrap(), (root(3), 2));
+        assert_eq!(proto_array.get_node(&root(2)).unwrap().weight, 2);
+        assert_eq!(proto_array.get_node(&root(4)).unwrap().weight, 1);
+        assert_eq!(proto_array.get_node(&root(1)).unwrap().weight, 3);
+    }
+
+    #[test]
+    fn test_vote_for_unknown_block_is_counted_once_the_block_is_added() {
+        let mut proto_array = forked_proto_array();
+        proto_array.process_attestation(0, root(10), &attestation_data(3, root(5)));
+        assert_eq!(proto_array.find_head(root(1)).unwrap(), (root(4), 1));
+
+        proto_array.on_block(root(5), root(2), 3);
+        assert_eq!(proto_array.find_head(root(1)).unwrap(), (root(5), 3));
+    }
+
+    #[test]
+    fn test_prune_keeps_only_descendants_of_finalized() {
+        let mut proto_array = forked_proto_array();
+        proto_array.process_attestation(0, root(10), &attestation_data(2, root(3)));
+        proto_array.process_attestation(1, root(11), &attestation_data(2, root(4)));
+        proto_array.find_head(root(1)).unwrap();
+
+        proto_array.prune(root(2));
+        assert_eq!(proto_array.len(), 2);
+        assert!(!proto_array.contains_block(&root(1)));
+        assert!(!proto_array.contains_block(&root(4)));
+        assert_eq!(proto_array.find_head(root(2)).unwrap(), (root(3), 2));
+
+        // Moving a vote away from a pruned block doesn't underflow the remaining weights
+        proto_array.process_attestation(1, root(12), &attestation_data(3, root(3)));
+        assert_eq!(proto_array.find_head(root(2)).unwrap(), (root(3), 2));
+        assert_eq!(proto_array.get_node(&root(2)).unwrap().weight, 2);
+    }
+}
@@ -24,6 +24,13 @@ use ream_consensus_misc::constants::lean::{
     GOSSIP_DISPARITY_INTERVALS, INTERVALS_PER_SLOT, MAX_ATTESTATIONS_DATA,
     MAX_HISTORICAL_BLOCK_HASHES, attestation_committee_count,
 };
+use ream_events_lean::{
+    LeanEvent, LeanEventSender,
+    event::{
+        attestation::{AggregatedAttestationEvent, AttestationEvent},
+        chain::{BlockEvent, HeadEvent, ReorgEvent},
+    },
+};
 use ream_metrics::{
     ATTESTATION_COMMITTEE_SUBNET, ATTESTATION_VALIDATION_TIME, ATTESTATIONS_INVALID_TOTAL,
     ATTESTATIONS_VALID_TOTAL, BLOCK_AGGREGATED_PAYLOADS, BLOCK_BUILDING_PAYLOAD_AGGREGATION_TIME,
@@ -66,14 +73,32 @@ use ssz_types::{
     BitList, VariableList,
     typenum::{U4096, U262144},
 };
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, broadcast};
+use tracing::warn;
 use tree_hash::TreeHash;
 
-use crate::constants::{ATTESTATION_RETENTION_SLOTS, JUSTIFICATION_LOOKBACK_SLOTS};
+use crate::{
+    constants::{ATTESTATION_RETENTION_SLOTS, JUSTIFICATION_LOOKBACK_SLOTS},
+    proto_array::ProtoArray,
+};
 
 pub type LeanStoreWriter = Writer<Store>;
 pub type LeanStoreReader = Reader<Store>;
 
+/// Returned when a gossiped attestation can't be verified, telling an attestation this node
+/// can't verify yet apart from one which is invalid.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum GossipAttestationError {
+    #[error("No state available for target {0}")]
+    MissingState(B256),
+    #[error("Validator {0} not found in state")]
+    UnknownValidator(u64),
+    #[error("Aggregated attestation has no participants")]
+    NoParticipants,
+    #[error("Signature verification failed: {0}")]
+    InvalidSignature(String),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 enum AttestationScoreTier {
     Finalize = 1,
@@ -179,6 +204,8 @@ pub struct Store {
     pub network_state: Arc<NetworkState>,
     pub tick_interval_duration: Option<Instant>,
     pub block_production_strategy: BlockProductionStrategy,
+    pub event_sender: Option<broadcast::Sender<LeanEvent>>,
+    pub proto_array: Arc<parking_lot::Mutex<ProtoArray>>,
 }
 
 impl Store {
@@ -209,45 +236,53 @@ impl Store {
             slot: anchor_slot,
         };
 
-        db.time_provider()
-            .insert(time.unwrap_or(anchor_slot * lean_network_spec().seconds_per_slot))
-            .expect("Failed to insert anchor slot");
+        let mut batch = db.write_batch()?;
+        db.time_provider().insert_batch(
+            &mut batch,
+            time.unwrap_or(anchor_slot * lean_network_spec().seconds_per_slot),
+        )?;
         db.block_provider()
-            .insert(anchor_root, anchor_block)
-            .expect("Failed to insert genesis block");
+            .insert_batch(&mut batch, anchor_root, anchor_block)?;
         db.slot_index_provider()
-            .insert(anchor_slot, anchor_root)
-            .expect("Failed to overwrite anchor slot index");
-        db.state_root_index_provider()
-            .insert(anchor_state.tree_hash_root(), anchor_root)
-            .expect("Failed to overwrite anchor state root index");
+            .insert_batch(&mut batch, anchor_slot, anchor_root)?;
+        db.state_root_index_provider().insert_batch(
+            &mut batch,
+            anchor_state.tree_hash_root(),
+            anchor_root,
+        )?;
         db.latest_finalized_provider()
-            .insert(anchor_checkpoint)
-            .expect("Failed to insert latest finalized checkpoint");
+            .insert_batch(&mut batch, anchor_checkpoint)?;
         db.latest_justified_provider()
-            .insert(anchor_checkpoint)
-            .expect("Failed to insert latest justified checkpoint");
+            .insert_batch(&mut batch, anchor_checkpoint)?;
         db.state_provider()
-            .insert(anchor_root, anchor_state)
-            .expect("Failed to insert genesis state");
-        db.head_provider()
-            .insert(anchor_root)
-            .expect("Failed to insert genesis block hash");
+            .insert_batch(&mut batch, anchor_root, anchor_state)?;
+        db.head_provider().insert_batch(&mut batch, anchor_root)?;
         db.safe_target_provider()
-            .insert(anchor_root)
-            .expect("Failed to insert genesis block hash");
+            .insert_batch(&mut batch, anchor_root)?;
         db.validator_id_provider()
-            .insert(validator_id)
-            .expect("Failed to insert validator id");
+            .insert_batch(&mut batch, validator_id)?;
+        batch
+            .commit()
+            .map_err(|err| anyhow!("Failed to store the anchor block and state: {err}"))?;
+
+        let proto_array = ProtoArray::from_db(&db)?;
 
         Ok(Store {
             store: Arc::new(Mutex::new(db)),
             network_state: Arc::new(NetworkState::new(anchor_checkpoint, anchor_checkpoint)),
             tick_interval_duration: None,
             block_production_strategy: BlockProductionStrategy::default(),
+            event_sender: None,
+            proto_array: Arc::new(parking_lot::Mutex::new(proto_array)),
         })
     }
 
+    /// Publish fork choice events to `event_sender`.
+    pub fn with_event_sender(mut self, event_sender: broadcast::Sender<LeanEvent>) -> Self {
+        self.event_sender = Some(event_sender);
+        self
+    }
+
     /// Override the block-production strategy. Defaults to round-based.
     pub fn with_block_production_strategy(mut self, strategy: BlockProductionStrategy) -> Self {
         self.block_production_strategy = strategy;
@@ -449,6 +484,14 @@ impl Store {
         set_int_gauge_vec(&LATEST_NEW_AGGREGATED_PAYLOADS, payloads.len() as i64, &[]);
 
         for (signature_key, mut new_proofs) in payloads {
+            if let Some(data) = attestation_data_by_root_provider.get(signature_key.data_root)? {
+                self.proto_array.lock().process_attestation(
+                    signature_key.validator_id,
+                    signature_key.data_root,
+                    &data,
+                );
+            }
+
             let mut existing_proofs = latest_known_aggregated_payloads_provider
                 .get(signature_key.clone())?
                 .unwrap_or_default();
@@ -562,8 +605,6 @@ impl Store {
             head_provider,
             block_provider,
             state_provider,
-            latest_known_aggregated_payloads_provider,
-            attestation_data_by_root_provider,
         ) = {
             let db = self.store.lock().await;
             (
@@ -572,40 +613,15 @@ impl Store {
                 db.head_provider(),
                 db.block_provider(),
                 db.state_provider(),
-                db.latest_known_aggregated_payloads_provider(),
-                db.attestation_data_by_root_provider(),
             )
         };
 
         let latest_finalized_checkpoint = latest_finalized_provider.get()?;
-        let finalized_slot = latest_finalized_checkpoint.slot;
-        let attestations = {
-            let mut relevant_keys = Vec::new();
-            for key in latest_known_aggregated_payloads_provider.iter_keys()? {
-                if let Some(data) = attestation_data_by_root_provider.get(key.data_root)?
-                    && data.head.slot > finalized_slot
-                {
-                    relevant_keys.push(key);
-                }
-            }
-
-            self.extract_attestations_from_aggregated_payloads(&relevant_keys)
-                .await?
-        };
-
+        let old_head = head_provider.get()?;
         let (new_head, new_head_slot) = self
-            .compute_lmd_ghost_head(
-                attestations.into_iter().map(|(validator, data)| {
-                    Ok(SignedAttestation {
-                        validator_id: validator,
-                        message: data,
-                        signature: Signature::blank(),
-                    })
-                }),
-                latest_justified_provider.get()?.root,
-                0,
-            )
-            .await?;
+            .proto_array
+            .lock()
+            .find_head(latest_justified_provider.get()?.root)?;
 
         let target_finalized_slot = state_provider
             .get(new_head)?
@@ -614,13 +630,9 @@ impl Store {
             .slot;
         let mut finalized_root = new_head;
 
-        let index_map = {
-            let db = self.store.lock().await;
-            db.children_index_provider().get_index_map()?
-        };
         let lookup_slot = |root: B256| -> anyhow::Result<Option<(B256, u64)>> {
-            if let Some(&(parent_root, slot)) = index_map.get(&root) {
-                return Ok(Some((parent_root, slot)));
+            if let Some(node) = self.proto_array.lock().get_node(&root) {
+                return Ok(Some((node.parent_root, node.slot)));
             }
             Ok(block_provider
                 .get(root)?
@@ -659,6 +671,74 @@ impl Store {
 
         head_provider.insert(new_head)?;
         latest_finalized_provider.insert(final_finalized_checkpoint)?;
+        if final_finalized_checkpoint != latest_finalized_checkpoint {
+            self.proto_array
+                .lock()
+                .prune(final_finalized_checkpoint.root);
+        }
+
+        if self.event_sender.is_some() {
+            if new_head != old_head
+                && let Err(err) =
+                    self.send_head_events(old_head, new_head, &block_provider, lookup_slot)
+            {
+                warn!("Failed to emit head events: {err}");
+            }
+            if final_finalized_checkpoint != latest_finalized_checkpoint {
+                self.event_sender
+                    .send_event(LeanEvent::Finalized(final_finalized_checkpoint));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Emit the `head` event, preceded by a `reorg` event if `new_head` doesn't descend from
+    /// `old_head`.
+    fn send_head_events(
+        &self,
+        old_head: B256,
+        new_head: B256,
+        block_provider: &LeanBlockTable,
+        lookup_slot: impl Fn(B256) -> anyhow::Result<Option<(B256, u64)>>,
+    ) -> anyhow::Result<()> {
+        let lookup = |root: B256| -> anyhow::Result<(B256, u64)> {
+            lookup_slot(root)?.ok_or_else(|| anyhow!("Block not found for root: {root}"))
+        };
+
+        let new_head_block = block_provider
+            .get(new_head)?
+            .ok_or_else(|| anyhow!("Block not found for head: {new_head}"))?;
+        let new_head_slot = new_head_block.block.slot;
+        let (_, old_head_slot) = lookup(old_head)?;
+
+        // Walk both chains back until they meet at the common ancestor
+        let (mut old_root, mut new_root) = (old_head, new_head);
+        let (mut old_entry, mut new_entry) = (lookup(old_root)?, lookup(new_root)?);
+        while old_root != new_root {
+            if old_entry.1 >= new_entry.1 {
+                old_root = old_entry.0;
+                old_entry = lookup(old_root)?;
+            } else {
+                new_root = new_entry.0;
+                new_entry = lookup(new_root)?;
+            }
+        }
+
+        if old_root != old_head {
+            self.event_sender.send_event(LeanEvent::Reorg(ReorgEvent {
+                slot: new_head_slot,
+                depth: old_head_slot - old_entry.1,
+                old_head_block: old_head,
+                new_head_block: new_head,
+            }));
+        }
+
+        self.event_sender.send_event(LeanEvent::Head(HeadEvent {
+            slot: new_head_slot,
+            block: new_head,
+            state: new_head_block.block.state_root,
+        }));
 
         Ok(())
     }
@@ -1693,20 +1773,15 @@ impl Store {
         signed_block.verify_signatures(&parent_state, verify_signatures)?;
         parent_state.state_transition(block, true)?;
 
-        let latest_justified = if parent_state.latest_justified.slot
-            > latest_justified_provider.get()?.slot
+        let previous_justified = latest_justified_provider.get()?;
+        let latest_justified = if parent_state.latest_justified.slot > previous_justified.slot
             && block_provider.contains_key(parent_state.latest_justified.root)
         {
             parent_state.latest_justified
         } else {
-            latest_justified_provider.get()?
+            previous_justified
         };
 
-        set_int_gauge_vec(&JUSTIFIED_SLOT, latest_justified.slot as i64, &[]);
-        set_int_gauge_vec(&LATEST_JUSTIFIED_SLOT, latest_justified.slot as i64, &[]);
-        block_provider.insert_ref(block_root, signed_block)?;
-        state_provider.insert(block_root, parent_state)?;
-        latest_justified_provider.insert(latest_justified)?;
         let aggregated_attestations = &block.body.attestations;
 
         let mut seen_attestation_data = HashSet::with_capacity(aggregated_attestations.len());
@@ -1719,30 +1794,75 @@ impl Store {
             );
         }
 
+        // Store the block, its post-state and everything derived from it atomically, so a crash
+        // can't leave the block imported without its state or indices
+        let mut batch = self.store.lock().await.write_batch()?;
+        block_provider.insert_ref_batch(&mut batch, block_root, signed_block)?;
+        state_provider.insert_batch(&mut batch, block_root, parent_state)?;
+        latest_justified_provider.insert_batch(&mut batch, latest_justified)?;
+
         #[cfg(feature = "devnet5")]
-        {
-            for attestation in aggregated_attestations.iter() {
-                let data_root = attestation.message.tree_hash_root();
-                attestation_data_by_root_provider.insert(data_root, attestation.message.clone())?;
+        for attestation in aggregated_attestations.iter() {
+            let data_root = attestation.message.tree_hash_root();
+            attestation_data_by_root_provider.insert_batch(
+                &mut batch,
+                data_root,
+                attestation.message.clone(),
+            )?;
 
-                let payload =
-                    PayloadProof::new(attestation.aggregation_bits.clone(), VariableList::empty());
+            let payload =
+                PayloadProof::new(attestation.aggregation_bits.clone(), VariableList::empty());
 
-                for (validator_id, participated) in attestation.aggregation_bits.iter().enumerate()
-                {
-                    if !participated {
-                        continue;
-                    }
-                    let key = SignatureKey::from_parts(validator_id as u64, data_root);
-                    let mut existing_proofs = latest_known_aggregated_payloads_provider
-                        .get(key.clone())?
-                        .unwrap_or_default();
-                    existing_proofs.push(payload.clone());
-                    latest_known_aggregated_payloads_provider.insert(key, existing_proofs)?;
+            for (validator_id, participated) in attestation.aggregation_bits.iter().enumerate() {
+                if !participated {
+                    continue;
+                }
+                let key = SignatureKey::from_parts(validator_id as u64, data_root);
+                let mut existing_proofs = latest_known_aggregated_payloads_provider
+                    .get(key.clone())?
+                    .unwrap_or_default();
+                existing_proofs.push(payload.clone());
+                latest_known_aggregated_payloads_provider.insert_batch(
+                    &mut batch,
+                    key,
+                    existing_proofs,
+                )?;
+            }
+        }
+        batch.commit()?;
+
+        set_int_gauge_vec(&JUSTIFIED_SLOT, latest_justified.slot as i64, &[]);
+        set_int_gauge_vec(&LATEST_JUSTIFIED_SLOT, latest_justified.slot as i64, &[]);
+
+        self.proto_array
+            .lock()
+            .on_block(block_root, block.parent_root, block.slot);
+
+        #[cfg(feature = "devnet5")]
+        for attestation in aggregated_attestations.iter() {
+            let data_root = attestation.message.tree_hash_root();
+            for (validator_id, participated) in attestation.aggregation_bits.iter().enumerate() {
+                if participated {
+                    self.proto_array.lock().process_attestation(
+                        validator_id as u64,
+                        data_root,
+                        &attestation.message,
+                    );
                 }
             }
         }
 
+        self.event_sender.send_event(LeanEvent::Block(BlockEvent {
+            slot: block.slot,
+            block: block_root,
+            proposer_index: block.proposer_index,
+            parent_root: block.parent_root,
+        }));
+        if latest_justified != previous_justified {
+            self.event_sender
+                .send_event(LeanEvent::Justified(latest_justified));
+        }
+
         self.update_head().await?;
 
         stop_timer(block_processing_timer);
@@ -1946,10 +2066,9 @@ impl Store {
             let data_root = data.tree_hash_root();
             let validator_ids = proof.to_validator_indices();
 
-            ensure!(
-                !validator_ids.is_empty(),
-                "Aggregated attestation has no participants"
-            );
+            if validator_ids.is_empty() {
+                return Err(GossipAttestationError::NoParticipants.into());
+            }
 
             let state = self
                 .store
@@ -1957,7 +2076,7 @@ impl Store {
                 .await
                 .state_provider()
                 .get(data.target.root)?
-                .ok_or_else(|| anyhow!("No state available for target {}", data.target.root))?;
+                .ok_or(GossipAttestationError::MissingState(data.target.root))?;
 
             let public_keys: Vec<_> = validator_ids
                 .iter()
@@ -1966,9 +2085,9 @@ impl Store {
                         .validators
                         .get(validator as usize)
                         .map(|validator| validator.attestation_public_key)
-                        .ok_or_else(|| anyhow!("Validator {validator} not found in state"))
+                        .ok_or(GossipAttestationError::UnknownValidator(validator))
                 })
-                .collect::<anyhow::Result<Vec<_>>>()?;
+                .collect::<Result<Vec<_>, _>>()?;
 
             // Mocked spec-test proofs (`verify == false`) cannot be checked
             if verify {
@@ -1993,7 +2112,9 @@ impl Store {
                         for _ in &validator_ids {
                             inc_int_counter_vec(&PQ_SIG_ATTESTATION_SIGNATURES_INVALID_TOTAL, &[]);
                         }
-                        return Err(anyhow!("Aggregated signature verification failed: {err}"));
+                        return Err(
+                            GossipAttestationError::InvalidSignature(err.to_string()).into()
+                        );
                     }
                 }
             }
@@ -2033,6 +2154,14 @@ impl Store {
 
                 latest_new_aggregated_payloads_provider.insert(key, proofs)?;
             }
+
+            self.event_sender
+                .send_event(LeanEvent::AggregatedAttestation(
+                    AggregatedAttestationEvent {
+                        data: data.clone(),
+                        validator_ids,
+                    },
+                ));
         }
 
         Ok(())
@@ -2440,22 +2569,23 @@ impl Store {
             }
         }
 
-        let key_state = state_provider
-            .get(attestation_data.target.root)?
-            .ok_or_else(|| anyhow!("No state available for signature verification"))?;
+        let key_state = state_provider.get(attestation_data.target.root)?.ok_or(
+            GossipAttestationError::MissingState(attestation_data.target.root),
+        )?;
 
-        ensure!(
-            validator_id < key_state.validators.len() as u64,
-            "Validator {validator_id} not found in state",
-        );
+        if validator_id >= key_state.validators.len() as u64 {
+            return Err(GossipAttestationError::UnknownValidator(validator_id).into());
+        }
 
         let verification_timer = start_timer(&PQ_SIG_ATTESTATION_VERIFICATION_TIME, &[]);
         let attestation_key = key_state.validators[validator_id as usize].attestation_public_key;
-        let signature_valid = signature.verify(
-            &attestation_key,
-            attestation_data.slot as u32,
-            &attestation_data.tree_hash_root(),
-        )?;
+        let signature_valid = signature
+            .verify(
+                &attestation_key,
+                attestation_data.slot as u32,
+                &attestation_data.tree_hash_root(),
+            )
+            .map_err(|err| GossipAttestationError::InvalidSignature(err.to_string()))?;
         stop_timer(verification_timer);
 
         if signature_valid {
@@ -2464,7 +2594,12 @@ impl Store {
             inc_int_counter_vec(&PQ_SIG_ATTESTATION_SIGNATURES_INVALID_TOTAL, &[]);
         }
 
-        ensure!(signature_valid, "Signature verification failed");
+        if !signature_valid {
+            return Err(GossipAttestationError::InvalidSignature(
+                "signature doesn't match the attestation".to_string(),
+            )
+            .into());
+        }
 
         let data_root = attestation_data.tree_hash_root();
 
@@ -2486,6 +2621,12 @@ impl Store {
 
         attestation_data_by_root_provider.insert(data_root, attestation_data.clone())?;
 
+        self.event_sender
+            .send_event(LeanEvent::Attestation(AttestationEvent {
+                validator_id,
+                data: attestation_data.clone(),
+            }));
+
         Ok(())
     }
 
@@ -2712,7 +2853,7 @@ mod tests {
     use ssz_types::VariableList;
     use tree_hash::TreeHash;
 
-    use super::{BlockProductionStrategy, Store};
+    use super::{BlockProductionStrategy, GossipAttestationError, Store};
 
     async fn sample_store_as_store(no_of_validators: usize) -> Store {
         let test_store = sample_store(no_of_validators).await;
@@ -2721,6 +2862,8 @@ mod tests {
             network_state: test_store.network_state,
             tick_interval_duration: None,
             block_production_strategy: BlockProductionStrategy::default(),
+            event_sender: None,
+            proto_array: test_store.proto_array,
         }
     }
 
@@ -2857,4 +3000,85 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn test_gossip_attestation_without_target_state_is_missing_state() {
+        let (mut store, canonical_1_root, canonical_2_root, _, _) =
+            store_with_finalized_orphaned_branch().await;
+
+        let genesis_root = { store.store.lock().await.head_provider().get().unwrap() };
+
+        let attestation_data = AttestationData {
+            slot: 2,
+            head: Checkpoint {
+                root: canonical_2_root,
+                slot: 2,
+            },
+            target: Checkpoint {
+                root: canonical_1_root,
+                slot: 1,
+            },
+            source: Checkpoint {
+                root: genesis_root,
+                slot: 0,
+            },
+        };
+
+        let err = store
+            .on_gossip_attestation(
+                SignedAttestation {
+                    validator_id: 0,
+                    message: attestation_data,
+                    signature: Signature::blank(),
+                },
+                false,
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<GossipAttestationError>(),
+            Some(&GossipAttestationError::MissingState(canonical_1_root))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gossip_attestation_from_unknown_validator_is_invalid() {
+        let (mut store, _, canonical_2_root, _, _) = store_with_finalized_orphaned_branch().await;
+
+        let genesis_root = { store.store.lock().await.head_provider().get().unwrap() };
+
+        let attestation_data = AttestationData {
+            slot: 2,
+            head: Checkpoint {
+                root: canonical_2_root,
+                slot: 2,
+            },
+            target: Checkpoint {
+                root: genesis_root,
+                slot: 0,
+            },
+            source: Checkpoint {
+                root: genesis_root,
+                slot: 0,
+            },
+        };
+
+        let err = store
+            .on_gossip_attestation(
+                SignedAttestation {
+                    validator_id: 10,
+                    message: attestation_data,
+                    signature: Signature::blank(),
+                },
+                false,
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<GossipAttestationError>(),
+            Some(&GossipAttestationError::UnknownValidator(10))
+        );
+    }
 }
@@ -115,6 +115,20 @@ lazy_static::lazy_static! {
         default_registry()
     ).expect("failed to create VALIDATORS_COUNT int gauge vec");
 
+    pub static ref VALIDATOR_KEY_REMAINING_EPOCHS: IntGaugeVec = register_int_gauge_vec_with_registry!(
+        "lean_validator_key_remaining_epochs",
+        "Number of epochs left in the activation interval of a validator key",
+        &["validator", "key"],
+        default_registry()
+    ).expect("failed to create VALIDATOR_KEY_REMAINING_EPOCHS int gauge vec");
+
+    pub static ref VALIDATOR_KEYS_EXPIRING: IntGaugeVec = register_int_gauge_vec_with_registry!(
+        "lean_validator_keys_expiring",
+        "Number of validator keys with fewer remaining epochs than the warning threshold",
+        &[],
+        default_registry()
+    ).expect("failed to create VALIDATOR_KEYS_EXPIRING int gauge vec");
+
     // Fork-Choice Metrics
     pub static ref FORK_CHOICE_BLOCK_PROCESSING_TIME: HistogramVec = {
         let opts = HistogramOpts::new(
@@ -617,6 +631,28 @@ lazy_static::lazy_static! {
             default_registry()
         ).expect("failed to create LEAN_BLOCK_PROPOSAL_AGGREGATES_SELECTED")
     };
+
+    // Req/Resp Rate Limiting Metrics
+    pub static ref REQ_RESP_INBOUND_REQUEST_COST_TOTAL: IntCounterVec = register_int_counter_vec_with_registry!(
+        "req_resp_inbound_request_cost_total",
+        "Total cost of the inbound req/resp requests, the number of requested items, labeled by protocol",
+        &["protocol"],
+        default_registry()
+    ).expect("failed to create REQ_RESP_INBOUND_REQUEST_COST_TOTAL int counter vec");
+
+    pub static ref REQ_RESP_RATE_LIMITED_REQUESTS_TOTAL: IntCounterVec = register_int_counter_vec_with_registry!(
+        "req_resp_rate_limited_requests_total",
+        "Number of inbound req/resp requests rejected by the rate limiter, labeled by protocol",
+        &["protocol"],
+        default_registry()
+    ).expect("failed to create REQ_RESP_RATE_LIMITED_REQUESTS_TOTAL int counter vec");
+
+    pub static ref REQ_RESP_RATE_LIMITED_PEERS_BANNED_TOTAL: IntCounterVec = register_int_counter_vec_with_registry!(
+        "req_resp_rate_limited_peers_banned_total",
+        "Number of peers banned for repeatedly exceeding the req/resp rate limits",
+        &[],
+        default_registry()
+    ).expect("failed to create REQ_RESP_RATE_LIMITED_PEERS_BANNED_TOTAL int counter vec");
 }
 
 pub fn init_aggregate_coverage_metrics() {
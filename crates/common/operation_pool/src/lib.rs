@@ -1,14 +1,31 @@
-use std::collections::HashMap;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, hash_map::Entry},
+};
 
 use alloy_primitives::{Address, B256, map::HashSet};
 use parking_lot::RwLock;
+use ream_bls::{BLSSignature, traits::Aggregatable};
 use ream_consensus_beacon::{
     attestation::Attestation, attester_slashing::AttesterSlashing,
     bls_to_execution_change::SignedBLSToExecutionChange, electra::beacon_state::BeaconState,
     proposer_slashing::ProposerSlashing, sync_aggregate::SyncAggregate,
     voluntary_exit::SignedVoluntaryExit,
 };
-use ream_consensus_misc::deposit::Deposit;
+use ream_consensus_misc::{
+    constants::beacon::{MAX_ATTESTATIONS_ELECTRA, MIN_ATTESTATION_INCLUSION_DELAY},
+    deposit::Deposit,
+    misc::compute_epoch_at_slot,
+};
+use ream_events_beacon::{
+    BeaconEvent, BeaconEventSender,
+    event::{
+        slashing::{AttesterSlashingEvent, ProposerSlashingEvent},
+        validator::{BlsToExecutionChangeEvent, VoluntaryExitEvent},
+    },
+};
+use ssz_types::{BitList, typenum::U131072};
+use tokio::sync::broadcast;
 use tree_hash::TreeHash;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -17,13 +34,23 @@ pub struct ProposerPreparation {
     pub submission_epoch: u64,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct AttestationKey {
     slot: u64,
     attestation_data_root: B256,
     committee_index: u64,
 }
 
+impl AttestationKey {
+    fn new(attestation: &Attestation, committee_index: u64) -> Self {
+        Self {
+            slot: attestation.data.slot,
+            attestation_data_root: attestation.data.tree_hash_root(),
+            committee_index,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct SyncAggregateKey {
     slot: u64,
@@ -37,17 +64,37 @@ pub struct OperationPool {
     proposer_preparations: RwLock<HashMap<u64, ProposerPreparation>>,
     attester_slashings: RwLock<HashSet<AttesterSlashing>>,
     proposer_slashings: RwLock<HashSet<ProposerSlashing>>,
+    /// Aggregates received from aggregators, none of which is a subset of another.
     attestations: RwLock<HashMap<AttestationKey, Vec<Attestation>>>,
+    /// Unaggregated attestations, aggregated as they arrive into one attestation per attestation
+    /// data and committee.
+    naive_aggregates: RwLock<HashMap<AttestationKey, Attestation>>,
     sync_aggregates: RwLock<HashMap<SyncAggregateKey, SyncAggregate>>,
     deposits: RwLock<HashSet<Deposit>>,
+    event_sender: Option<broadcast::Sender<BeaconEvent>>,
 }
 
 impl OperationPool {
+    /// Creates an operation pool which emits an event for every new voluntary exit, BLS to
+    /// execution change and slashing it receives.
+    pub fn new(event_sender: Option<broadcast::Sender<BeaconEvent>>) -> Self {
+        Self {
+            event_sender,
+            ..Default::default()
+        }
+    }
+
     pub fn insert_signed_voluntary_exit(&self, signed_voluntary_exit: SignedVoluntaryExit) {
-        self.signed_voluntary_exits.write().insert(
+        let previous = self.signed_voluntary_exits.write().insert(
             signed_voluntary_exit.message.validator_index,
-            signed_voluntary_exit,
+            signed_voluntary_exit.clone(),
         );
+        if previous.as_ref() != Some(&signed_voluntary_exit) {
+            self.event_sender
+                .send_event(BeaconEvent::VoluntaryExit(VoluntaryExitEvent::from(
+                    signed_voluntary_exit,
+                )));
+        }
     }
 
     pub fn get_signed_voluntary_exits(&self) -> Vec<SignedVoluntaryExit> {
@@ -71,10 +118,16 @@ impl OperationPool {
         &self,
         signed_bls_to_execution_change: SignedBLSToExecutionChange,
     ) {
-        self.signed_bls_to_execution_changes.write().insert(
+        let previous = self.signed_bls_to_execution_changes.write().insert(
             signed_bls_to_execution_change.tree_hash_root(),
-            signed_bls_to_execution_change,
+            signed_bls_to_execution_change.clone(),
         );
+        if previous.is_none() {
+            self.event_sender
+                .send_event(BeaconEvent::BlsToExecutionChange(
+                    BlsToExecutionChangeEvent::from(signed_bls_to_execution_change),
+                ));
+        }
     }
 
     pub fn get_signed_bls_to_execution_changes(&self) -> Vec<SignedBLSToExecutionChange> {
@@ -128,7 +181,11 @@ impl OperationPool {
     }
 
     pub fn insert_attester_slashing(&self, slashing: AttesterSlashing) {
-        self.attester_slashings.write().insert(slashing);
+        if self.attester_slashings.write().insert(slashing.clone()) {
+            self.event_sender.send_event(BeaconEvent::AttesterSlashing(
+                AttesterSlashingEvent::from(slashing),
+            ));
+        }
     }
 
     pub fn get_all_attester_slashings(&self) -> Vec<AttesterSlashing> {
@@ -140,7 +197,11 @@ impl OperationPool {
     }
 
     pub fn insert_proposer_slashing(&self, slashing: ProposerSlashing) {
-        self.proposer_slashings.write().insert(slashing);
+        if self.proposer_slashings.write().insert(slashing.clone()) {
+            self.event_sender.send_event(BeaconEvent::ProposerSlashing(
+                ProposerSlashingEvent::from(slashing),
+            ));
+        }
     }
 
     pub fn get_attestations(
@@ -149,52 +210,175 @@ impl OperationPool {
         committee_index: Option<u64>,
         attestation_data_root: Option<B256>,
     ) -> Vec<Attestation> {
-        self.attestations
+        let is_match = |key: &AttestationKey| {
+            if key.slot != slot {
+                return false;
+            }
+
+            if let Some(c_index) = committee_index
+                && key.committee_index != c_index
+            {
+                return false;
+            }
+
+            if let Some(data_root) = attestation_data_root
+                && key.attestation_data_root != data_root
+            {
+                return false;
+            }
+
+            true
+        };
+
+        let mut attestations = self
+            .naive_aggregates
             .read()
             .iter()
-            .filter(|(key, _)| {
-                if key.slot != slot {
-                    return false;
-                }
+            .filter(|(key, _)| is_match(key))
+            .map(|(_, attestation)| attestation.clone())
+            .collect::<Vec<_>>();
+        attestations.extend(
+            self.attestations
+                .read()
+                .iter()
+                .filter(|(key, _)| is_match(key))
+                .flat_map(|(_, attestations)| attestations.iter().cloned()),
+        );
+        attestations
+    }
 
-                if let Some(c_index) = committee_index
-                    && key.committee_index != c_index
-                {
-                    return false;
-                }
+    /// Returns the attestation with the most attesters for `attestation_data_root` by the
+    /// committee `committee_index` at `slot`.
+    pub fn get_aggregate_attestation(
+        &self,
+        slot: u64,
+        attestation_data_root: B256,
+        committee_index: u64,
+    ) -> Option<Attestation> {
+        let key = AttestationKey {
+            slot,
+            attestation_data_root,
+            committee_index,
+        };
+        let naive_aggregate = self.naive_aggregates.read().get(&key).cloned();
+        let attestations = self.attestations.read();
+        naive_aggregate
+            .into_iter()
+            .chain(attestations.get(&key).into_iter().flatten().cloned())
+            .max_by_key(|attestation| attestation.aggregation_bits.num_set_bits())
+    }
 
-                if let Some(data_root) = attestation_data_root
-                    && key.attestation_data_root != data_root
+    /// Inserts an aggregate of the committee `committee_index`, unless an aggregate with a
+    /// superset of its attesters is already known. Known aggregates whose attesters are a subset
+    /// of the new one are dropped.
+    pub fn insert_attestation(&self, attestation: Attestation, committee_index: u64) {
+        let mut map = self.attestations.write();
+        let aggregates = map
+            .entry(AttestationKey::new(&attestation, committee_index))
+            .or_default();
+        if aggregates.iter().any(|aggregate| {
+            attestation
+                .aggregation_bits
+                .is_subset(&aggregate.aggregation_bits)
+        }) {
+            return;
+        }
+        aggregates.retain(|aggregate| {
+            !aggregate
+                .aggregation_bits
+                .is_subset(&attestation.aggregation_bits)
+        });
+        aggregates.push(attestation);
+    }
+
+    /// Adds an unaggregated attestation of the committee `committee_index` to the naive
+    /// aggregate of its attestation data. Attestations of attesters already in the aggregate are
+    /// skipped, as their signature can't be aggregated twice.
+    pub fn insert_unaggregated_attestation(
+        &self,
+        attestation: Attestation,
+        committee_index: u64,
+    ) -> anyhow::Result<()> {
+        let mut naive_aggregates = self.naive_aggregates.write();
+        match naive_aggregates.entry(AttestationKey::new(&attestation, committee_index)) {
+            Entry::Vacant(entry) => {
+                entry.insert(attestation);
+            }
+            Entry::Occupied(mut entry) => {
+                let aggregate = entry.get_mut();
+                if !aggregate
+                    .aggregation_bits
+                    .intersection(&attestation.aggregation_bits)
+                    .is_zero()
                 {
-                    return false;
+                    return Ok(());
                 }
-
-                true
-            })
-            .flat_map(|(_, attestations)| attestations.iter().cloned())
-            .collect()
+                aggregate.signature =
+                    BLSSignature::aggregate(&[&aggregate.signature, &attestation.signature])?;
+                aggregate.aggregation_bits = aggregate
+                    .aggregation_bits
+                    .union(&attestation.aggregation_bits);
+            }
+        }
+        Ok(())
     }
 
-    pub fn get_all_attestations(&self) -> Vec<Attestation> {
-        self.attestations
+    /// Selects the attestations to include in a block on top of `state`, grouped by the on-chain
+    /// aggregate each group should be combined into.
+    ///
+    /// Attestations are picked greedily by the number of attesters they add to the ones already
+    /// picked (max-cover). Every group holds attestations of the same attestation data, with at
+    /// most one per committee, and the groups adding the most attesters are kept.
+    pub fn get_attestations_for_block(&self, state: &BeaconState) -> Vec<Vec<Attestation>> {
+        let current_epoch = state.get_current_epoch();
+        let previous_epoch = state.get_previous_epoch();
+        let is_includable = |attestation: &Attestation| {
+            if attestation.data.slot + MIN_ATTESTATION_INCLUSION_DELAY > state.slot {
+                return false;
+            }
+
+            if attestation.data.target.epoch == current_epoch {
+                attestation.data.source == state.current_justified_checkpoint
+            } else if attestation.data.target.epoch == previous_epoch {
+                attestation.data.source == state.previous_justified_checkpoint
+            } else {
+                false
+            }
+        };
+
+        let candidates = self
+            .naive_aggregates
             .read()
-            .values()
-            .flat_map(|attestations| attestations.clone())
-            .collect()
+            .iter()
+            .map(|(key, attestation)| (*key, attestation.clone()))
+            .chain(
+                self.attestations
+                    .read()
+                    .iter()
+                    .flat_map(|(key, attestations)| {
+                        attestations
+                            .iter()
+                            .map(|attestation| (*key, attestation.clone()))
+                    }),
+            )
+            .filter(|(_, attestation)| is_includable(attestation))
+            .collect::<Vec<_>>();
+
+        select_attestations(candidates)
     }
 
-    pub fn insert_attestation(&self, attestation: Attestation, committee_index: u64) {
-        let key = AttestationKey {
-            slot: attestation.data.slot,
-            attestation_data_root: attestation.data.tree_hash_root(),
-            committee_index,
-        };
-        let mut map = self.attestations.write();
-        if let Some(attestations) = map.get_mut(&key) {
-            attestations.push(attestation);
-        } else {
-            map.insert(key, vec![attestation]);
-        }
+    /// Drops the attestations which can no longer be included in a block, which are the ones
+    /// from before the previous epoch.
+    pub fn clean_attestations(&self, current_slot: u64) {
+        let previous_epoch = compute_epoch_at_slot(current_slot).saturating_sub(1);
+        let is_includable =
+            |key: &AttestationKey| compute_epoch_at_slot(key.slot) >= previous_epoch;
+        self.attestations
+            .write()
+            .retain(|key, _| is_includable(key));
+        self.naive_aggregates
+            .write()
+            .retain(|key, _| is_includable(key));
     }
 
     pub fn get_sync_aggregate(&self, slot: u64, beacon_block_root: B256) -> Option<SyncAggregate> {
@@ -233,10 +417,185 @@ impl OperationPool {
     }
 }
 
+/// Picks attestations greedily by the number of attesters they add to the ones already picked,
+/// and groups them into at most [MAX_ATTESTATIONS_ELECTRA] on-chain aggregates.
+fn select_attestations(
+    mut candidates: Vec<(AttestationKey, Attestation)>,
+) -> Vec<Vec<Attestation>> {
+    let mut covered: HashMap<AttestationKey, BitList<U131072>> = HashMap::new();
+    let mut selected = vec![];
+    loop {
+        let best = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, (key, attestation))| {
+                let new_attesters = match covered.get(key) {
+                    Some(covered) => attestation
+                        .aggregation_bits
+                        .difference(covered)
+                        .num_set_bits(),
+                    None => attestation.aggregation_bits.num_set_bits(),
+                };
+                (index, new_attesters)
+            })
+            .max_by_key(|(_, new_attesters)| *new_attesters);
+        let Some((index, new_attesters)) = best else {
+            break;
+        };
+        if new_attesters == 0 {
+            break;
+        }
+
+        let (key, attestation) = candidates.swap_remove(index);
+        covered
+            .entry(key)
+            .and_modify(|covered| *covered = covered.union(&attestation.aggregation_bits))
+            .or_insert_with(|| attestation.aggregation_bits.clone());
+        selected.push((key, attestation, new_attesters));
+    }
+
+    let mut groups: Vec<(B256, HashSet<u64>, Vec<Attestation>, usize)> = vec![];
+    for (key, attestation, new_attesters) in selected {
+        let group = groups
+            .iter_mut()
+            .find(|(data_root, committee_indices, ..)| {
+                *data_root == key.attestation_data_root
+                    && !committee_indices.contains(&key.committee_index)
+            });
+        match group {
+            Some((_, committee_indices, attestations, total_new_attesters)) => {
+                committee_indices.insert(key.committee_index);
+                attestations.push(attestation);
+                *total_new_attesters += new_attesters;
+            }
+            None => groups.push((
+                key.attestation_data_root,
+                HashSet::from_iter([key.committee_index]),
+                vec![attestation],
+                new_attesters,
+            )),
+        }
+    }
+
+    groups.sort_by_key(|(.., total_new_attesters)| Reverse(*total_new_attesters));
+    groups
+        .into_iter()
+        .take(MAX_ATTESTATIONS_ELECTRA)
+        .map(|(_, _, attestations, _)| attestations)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use ream_consensus_misc::{
+        attestation_data::AttestationData, checkpoint::Checkpoint,
+        constants::beacon::SLOTS_PER_EPOCH,
+    };
+    use ssz_types::BitVector;
+
     use super::*;
 
+    fn attestation(slot: u64, committee_index: u64, attesters: &[usize]) -> Attestation {
+        let mut aggregation_bits = BitList::with_capacity(8).unwrap();
+        for &attester in attesters {
+            aggregation_bits.set(attester, true).unwrap();
+        }
+        let mut committee_bits = BitVector::new();
+        committee_bits.set(committee_index as usize, true).unwrap();
+        Attestation {
+            aggregation_bits,
+            data: AttestationData {
+                slot,
+                index: 0,
+                beacon_block_root: B256::ZERO,
+                source: Checkpoint::default(),
+                target: Checkpoint::default(),
+            },
+            signature: BLSSignature::infinity(),
+            committee_bits,
+        }
+    }
+
+    #[test]
+    fn test_naive_aggregation() {
+        let operation_pool = OperationPool::default();
+        operation_pool
+            .insert_unaggregated_attestation(attestation(1, 0, &[0]), 0)
+            .unwrap();
+        operation_pool
+            .insert_unaggregated_attestation(attestation(1, 0, &[2]), 0)
+            .unwrap();
+        // Already part of the aggregate
+        operation_pool
+            .insert_unaggregated_attestation(attestation(1, 0, &[2]), 0)
+            .unwrap();
+        operation_pool
+            .insert_unaggregated_attestation(attestation(1, 1, &[3]), 1)
+            .unwrap();
+
+        let data_root = attestation(1, 0, &[]).data.tree_hash_root();
+        let aggregate = operation_pool
+            .get_aggregate_attestation(1, data_root, 0)
+            .unwrap();
+        assert_eq!(aggregate.aggregation_bits.num_set_bits(), 2);
+        assert!(aggregate.aggregation_bits.get(0).unwrap());
+        assert!(aggregate.aggregation_bits.get(2).unwrap());
+        assert_eq!(operation_pool.get_attestations(1, None, None).len(), 2);
+        assert_eq!(operation_pool.get_attestations(1, Some(1), None).len(), 1);
+
+        // A larger aggregate from an aggregator is preferred
+        operation_pool.insert_attestation(attestation(1, 0, &[1, 3, 4]), 0);
+        let aggregate = operation_pool
+            .get_aggregate_attestation(1, data_root, 0)
+            .unwrap();
+        assert_eq!(aggregate.aggregation_bits.num_set_bits(), 3);
+
+        operation_pool.clean_attestations(3 * SLOTS_PER_EPOCH);
+        assert!(operation_pool.get_attestations(1, None, None).is_empty());
+    }
+
+    #[test]
+    fn test_insert_attestation_drops_subsets() {
+        let operation_pool = OperationPool::default();
+        operation_pool.insert_attestation(attestation(1, 0, &[0, 1]), 0);
+        operation_pool.insert_attestation(attestation(1, 0, &[1]), 0);
+        assert_eq!(operation_pool.get_attestations(1, None, None).len(), 1);
+
+        operation_pool.insert_attestation(attestation(1, 0, &[2]), 0);
+        operation_pool.insert_attestation(attestation(1, 0, &[0, 1, 2]), 0);
+        let attestations = operation_pool.get_attestations(1, None, None);
+        assert_eq!(attestations.len(), 1);
+        assert_eq!(attestations[0].aggregation_bits.num_set_bits(), 3);
+    }
+
+    #[test]
+    fn test_select_attestations_max_cover() {
+        let candidate = |slot, committee_index, attesters: &[usize]| {
+            let attestation = attestation(slot, committee_index, attesters);
+            (
+                AttestationKey::new(&attestation, committee_index),
+                attestation,
+            )
+        };
+        let groups = select_attestations(vec![
+            candidate(1, 0, &[0, 1, 2]),
+            candidate(1, 0, &[2, 3]),
+            candidate(1, 0, &[0, 1]),
+            candidate(1, 1, &[0]),
+            candidate(2, 0, &[0, 1, 2, 3]),
+        ]);
+
+        // The subset of an already picked attestation adds no attesters and is left out
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].len(), 1);
+        assert_eq!(groups[0][0].data.slot, 2);
+        // One attestation per committee in an on-chain aggregate
+        assert_eq!(groups[1].len(), 2);
+        assert_eq!(groups[1][0].aggregation_bits.num_set_bits(), 3);
+        assert_eq!(groups[2].len(), 1);
+        assert_eq!(groups[2][0].aggregation_bits.num_set_bits(), 2);
+    }
+
     #[test]
     fn test_proposer_preparation_operations() {
         let operation_pool = OperationPool::default();
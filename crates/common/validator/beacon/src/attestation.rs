@@ -9,10 +9,7 @@ use ream_bls::{
 use ream_consensus_beacon::{attestation::Attestation, electra::beacon_state::BeaconState};
 use ream_consensus_misc::{
     attestation_data::AttestationData,
-    constants::beacon::{
-        DOMAIN_BEACON_ATTESTER, MAX_COMMITTEES_PER_SLOT, MAX_VALIDATORS_PER_COMMITTEE,
-        SLOTS_PER_EPOCH,
-    },
+    constants::beacon::{DOMAIN_BEACON_ATTESTER, MAX_COMMITTEES_PER_SLOT, SLOTS_PER_EPOCH},
     misc::{compute_domain, compute_epoch_at_slot, compute_signing_root, get_committee_indices},
 };
 use ream_network_spec::networks::beacon_network_spec;
@@ -62,16 +59,17 @@ pub fn compute_on_chain_aggregate(mut aggregates: Vec<Attestation>) -> anyhow::R
         a_index.cmp(&b_index)
     });
 
-    let aggregation_bits_size = (MAX_VALIDATORS_PER_COMMITTEE * MAX_COMMITTEES_PER_SLOT) as usize;
-    let mut aggregation_bits = BitList::<U131072>::with_capacity(aggregation_bits_size)
+    // The aggregation bits are the concatenation of the bits of every committee
+    let bits = aggregates
+        .iter()
+        .flat_map(|aggregate| aggregate.aggregation_bits.iter())
+        .collect::<Vec<_>>();
+    let mut aggregation_bits = BitList::<U131072>::with_capacity(bits.len())
         .map_err(|err| anyhow!("Failed to create BitList for aggregation_bits {err:?}"))?;
-
-    for aggregate in &aggregates {
-        for bit in aggregate.aggregation_bits.iter() {
-            aggregation_bits
-                .set(aggregation_bits.len(), bit)
-                .map_err(|err| anyhow!("Failed to set bit: {err:?}"))?;
-        }
+    for (index, bit) in bits.into_iter().enumerate() {
+        aggregation_bits
+            .set(index, bit)
+            .map_err(|err| anyhow!("Failed to set bit: {err:?}"))?;
     }
     let signatures: Vec<&BLSSignature> = aggregates.iter().map(|a| &a.signature).collect();
     let committee_indices = aggregates
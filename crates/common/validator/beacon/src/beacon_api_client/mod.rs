@@ -13,7 +13,7 @@ use ream_api_types_beacon::{
     duties::{AttesterDuty, ProposerDuty, SyncCommitteeDuty},
     error::ValidatorError,
     id::ValidatorID,
-    request::{SyncCommitteeRequestItem, ValidatorsPostRequest},
+    request::{PrepareBeaconProposerItem, SyncCommitteeRequestItem, ValidatorsPostRequest},
     responses::{
         BeaconResponse, DataResponse, DataVersionedResponse, DutiesResponse,
         ETH_CONSENSUS_VERSION_HEADER, RootResponse, SyncCommitteeDutiesResponse, VERSION,
@@ -44,7 +44,10 @@ use serde_json::json;
 use ssz::{Decode, Encode};
 use tracing::{error, info};
 
-use crate::aggregate_and_proof::SignedAggregateAndProof;
+use crate::{
+    aggregate_and_proof::SignedAggregateAndProof,
+    builder::validator_registration::SignedValidatorRegistrationV1,
+};
 
 #[derive(Clone)]
 pub struct BeaconApiClient {
@@ -405,6 +408,58 @@ impl BeaconApiClient {
         Ok(())
     }
 
+    pub async fn prepare_beacon_proposer(
+        &self,
+        preparations: Vec<PrepareBeaconProposerItem>,
+    ) -> anyhow::Result<(), ValidatorError> {
+        let response = self
+            .http_client
+            .execute(
+                self.http_client
+                    .post(
+                        "/eth/v1/validator/prepare_beacon_proposer".to_string(),
+                        ContentType::Json,
+                    )?
+                    .json(&preparations)
+                    .build()?,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ValidatorError::RequestFailed {
+                status_code: response.status(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub async fn register_validator(
+        &self,
+        registrations: Vec<SignedValidatorRegistrationV1>,
+    ) -> anyhow::Result<(), ValidatorError> {
+        let response = self
+            .http_client
+            .execute(
+                self.http_client
+                    .post(
+                        "/eth/v1/validator/register_validator".to_string(),
+                        ContentType::Json,
+                    )?
+                    .json(&registrations)
+                    .build()?,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ValidatorError::RequestFailed {
+                status_code: response.status(),
+            });
+        }
+
+        Ok(())
+    }
+
     pub async fn get_sync_committee_contribution(
         &self,
         slot: u64,
@@ -11,9 +11,11 @@ pub mod constants;
 pub mod contribution_and_proof;
 pub mod execution_requests;
 pub mod randao;
+pub mod slashing_protection;
 pub mod state;
 pub mod sync_committee;
 pub mod validator;
+pub mod validator_store;
 pub mod voluntary_exit;
 
 pub fn hash_signature_prefix_to_u64(signature: &BLSSignature) -> u64 {
@@ -0,0 +1,297 @@
+pub mod interchange;
+
+use std::path::Path;
+
+use alloy_primitives::B256;
+use ream_bls::PublicKey;
+use ream_slashing_protection::{
+    INTERCHANGE_FORMAT_VERSION, SignedAttestationRecord, SignedBlockRecord, SigningHistory,
+    SigningHistoryDB, error::SlashingProtectionError,
+};
+use ssz_types::FixedVector;
+
+use crate::slashing_protection::interchange::{
+    Interchange, InterchangeAttestation, InterchangeBlock, InterchangeData, InterchangeMetadata,
+};
+
+pub const SLASHING_PROTECTION_FILENAME: &str = "beacon_slashing_protection.redb";
+
+/// Signed blocks, keyed by (public_key, slot)
+const SIGNED_BLOCKS_TABLE: &str = "beacon_signed_blocks";
+
+/// Signed attestations, keyed by (public_key, target_epoch)
+const SIGNED_ATTESTATIONS_TABLE: &str = "beacon_signed_attestations";
+
+/// Persistent EIP-3076 record of every block and attestation signed by the local validators.
+///
+/// A validator may sign a single attestation per target epoch, and the epochs of its source and
+/// target checkpoints are checked for surround votes.
+#[derive(Clone, Debug)]
+pub struct SlashingProtectionDB {
+    db: SigningHistoryDB<48>,
+}
+
+impl SlashingProtectionDB {
+    /// Open the slashing protection database in `data_dir`, creating it if it doesn't exist.
+    pub fn open(data_dir: &Path) -> Result<Self, SlashingProtectionError> {
+        Ok(Self {
+            db: SigningHistoryDB::open(
+                &data_dir.join(SLASHING_PROTECTION_FILENAME),
+                SIGNED_BLOCKS_TABLE,
+                SIGNED_ATTESTATIONS_TABLE,
+            )?,
+        })
+    }
+
+    /// Check that signing a block at `slot` with `signing_root` is safe and record it.
+    pub fn check_and_insert_block(
+        &self,
+        public_key: &PublicKey,
+        slot: u64,
+        signing_root: B256,
+    ) -> Result<(), SlashingProtectionError> {
+        self.db
+            .check_and_insert_block(public_key_bytes(public_key), slot, signing_root)
+    }
+
+    /// Check that signing an attestation from `source_epoch` to `target_epoch` is safe and record
+    /// it.
+    pub fn check_and_insert_attestation(
+        &self,
+        public_key: &PublicKey,
+        source_epoch: u64,
+        target_epoch: u64,
+        signing_root: B256,
+    ) -> Result<(), SlashingProtectionError> {
+        self.db.check_and_insert_attestation(
+            public_key_bytes(public_key),
+            target_epoch,
+            source_epoch,
+            target_epoch,
+            signing_root,
+        )
+    }
+
+    /// Whether anything signed by `public_key` has been recorded.
+    pub fn has_records(&self, public_key: &PublicKey) -> Result<bool, SlashingProtectionError> {
+        self.db.has_records(public_key_bytes(public_key))
+    }
+
+    /// Export the signing history of every validator in the database.
+    pub fn export_interchange(
+        &self,
+        genesis_validators_root: B256,
+    ) -> Result<Interchange, SlashingProtectionError> {
+        self.export_interchange_for(genesis_validators_root, None)
+    }
+
+    /// Export the signing history of `public_keys`, or of every validator if `None`.
+    pub fn export_interchange_for(
+        &self,
+        genesis_validators_root: B256,
+        public_keys: Option<&[PublicKey]>,
+    ) -> Result<Interchange, SlashingProtectionError> {
+        let selected_keys = public_keys
+            .map(|public_keys| public_keys.iter().map(public_key_bytes).collect::<Vec<_>>());
+        let histories = self.db.export(|public_key| {
+            selected_keys
+                .as_ref()
+                .is_none_or(|selected_keys| selected_keys.contains(public_key))
+        })?;
+
+        let data = histories
+            .into_iter()
+            .map(|(public_key, history)| {
+                Ok(InterchangeData {
+                    pubkey: PublicKey {
+                        inner: FixedVector::try_from(public_key.to_vec()).map_err(|err| {
+                            SlashingProtectionError::InvalidPublicKey(format!("{err:?}"))
+                        })?,
+                    },
+                    signed_blocks: history
+                        .signed_blocks
+                        .into_iter()
+                        .map(|block| InterchangeBlock {
+                            slot: block.slot,
+                            signing_root: block.signing_root,
+                        })
+                        .collect(),
+                    signed_attestations: history
+                        .signed_attestations
+                        .into_iter()
+                        .map(|attestation| InterchangeAttestation {
+                            source_epoch: attestation.source,
+                            target_epoch: attestation.target,
+                            signing_root: attestation.signing_root,
+                        })
+                        .collect(),
+                })
+            })
+            .collect::<Result<_, SlashingProtectionError>>()?;
+
+        Ok(Interchange {
+            metadata: InterchangeMetadata {
+                interchange_format_version: INTERCHANGE_FORMAT_VERSION,
+                genesis_validators_root,
+            },
+            data,
+        })
+    }
+
+    /// Merge an interchange into the database.
+    ///
+    /// Imported records are added next to the existing history. If an imported record conflicts
+    /// with an existing one at the same slot or target epoch, its root is recorded as unknown, so
+    /// nothing can be signed there anymore.
+    pub fn import_interchange(
+        &self,
+        interchange: &Interchange,
+        genesis_validators_root: B256,
+    ) -> Result<(), SlashingProtectionError> {
+        if interchange.metadata.interchange_format_version != INTERCHANGE_FORMAT_VERSION {
+            return Err(SlashingProtectionError::UnsupportedInterchangeVersion(
+                interchange.metadata.interchange_format_version,
+            ));
+        }
+        if interchange.metadata.genesis_validators_root != genesis_validators_root {
+            return Err(SlashingProtectionError::GenesisValidatorsRootMismatch {
+                expected: genesis_validators_root,
+                found: interchange.metadata.genesis_validators_root,
+            });
+        }
+
+        let histories = interchange
+            .data
+            .iter()
+            .map(|data| {
+                (
+                    public_key_bytes(&data.pubkey),
+                    SigningHistory {
+                        signed_blocks: data
+                            .signed_blocks
+                            .iter()
+                            .map(|block| SignedBlockRecord {
+                                slot: block.slot,
+                                signing_root: block.signing_root,
+                            })
+                            .collect(),
+                        signed_attestations: data
+                            .signed_attestations
+                            .iter()
+                            .map(|attestation| SignedAttestationRecord {
+                                vote: attestation.target_epoch,
+                                source: attestation.source_epoch,
+                                target: attestation.target_epoch,
+                                signing_root: attestation.signing_root,
+                            })
+                            .collect(),
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        self.db.import(
+            histories
+                .iter()
+                .map(|(public_key, history)| (*public_key, history)),
+        )
+    }
+}
+
+fn public_key_bytes(public_key: &PublicKey) -> [u8; 48] {
+    let mut bytes = [0; 48];
+    bytes.copy_from_slice(public_key.to_bytes());
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_bls::PublicKey;
+    use ream_slashing_protection::{INTERCHANGE_FORMAT_VERSION, error::SlashingProtectionError};
+    use ssz_types::FixedVector;
+    use tempdir::TempDir;
+
+    use super::SlashingProtectionDB;
+
+    const GENESIS_VALIDATORS_ROOT: B256 = B256::repeat_byte(0xaa);
+
+    fn open_db(name: &str) -> (TempDir, SlashingProtectionDB) {
+        let tmp_dir = TempDir::new(name).unwrap();
+        let db = SlashingProtectionDB::open(tmp_dir.path()).unwrap();
+        (tmp_dir, db)
+    }
+
+    fn public_key(byte: u8) -> PublicKey {
+        PublicKey {
+            inner: FixedVector::try_from(vec![byte; 48]).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_interchange_round_trip() {
+        let (_source_dir, source) = open_db("test_interchange_source");
+        source
+            .check_and_insert_block(&public_key(1), 10, B256::repeat_byte(1))
+            .unwrap();
+        source
+            .check_and_insert_attestation(&public_key(2), 4, 8, B256::repeat_byte(2))
+            .unwrap();
+
+        let interchange = source.export_interchange(GENESIS_VALIDATORS_ROOT).unwrap();
+        assert_eq!(
+            interchange.metadata.interchange_format_version,
+            INTERCHANGE_FORMAT_VERSION
+        );
+        assert_eq!(
+            interchange.metadata.genesis_validators_root,
+            GENESIS_VALIDATORS_ROOT
+        );
+        let json = serde_json::to_string(&interchange).unwrap();
+        let interchange = serde_json::from_str(&json).unwrap();
+
+        let (_destination_dir, destination) = open_db("test_interchange_destination");
+        assert!(matches!(
+            destination.import_interchange(&interchange, B256::ZERO),
+            Err(SlashingProtectionError::GenesisValidatorsRootMismatch { .. })
+        ));
+        destination
+            .import_interchange(&interchange, GENESIS_VALIDATORS_ROOT)
+            .unwrap();
+
+        assert!(destination.has_records(&public_key(2)).unwrap());
+        assert!(!destination.has_records(&public_key(3)).unwrap());
+        assert_eq!(
+            destination
+                .export_interchange_for(GENESIS_VALIDATORS_ROOT, Some(&[public_key(2)]))
+                .unwrap()
+                .data,
+            source
+                .export_interchange_for(GENESIS_VALIDATORS_ROOT, Some(&[public_key(2)]))
+                .unwrap()
+                .data
+        );
+
+        // Imported attestations are keyed by their target epoch.
+        assert!(matches!(
+            destination.check_and_insert_attestation(&public_key(2), 5, 8, B256::repeat_byte(3)),
+            Err(SlashingProtectionError::DoubleVote { .. })
+        ));
+        assert!(matches!(
+            destination.check_and_insert_block(&public_key(1), 10, B256::repeat_byte(4)),
+            Err(SlashingProtectionError::DoubleBlockProposal { .. })
+        ));
+    }
+
+    #[test]
+    fn test_refuses_unsupported_interchange_version() {
+        let (_tmp_dir, db) = open_db("test_interchange_version");
+        let mut interchange = db.export_interchange(GENESIS_VALIDATORS_ROOT).unwrap();
+        interchange.metadata.interchange_format_version = INTERCHANGE_FORMAT_VERSION + 1;
+
+        assert!(matches!(
+            db.import_interchange(&interchange, GENESIS_VALIDATORS_ROOT),
+            Err(SlashingProtectionError::UnsupportedInterchangeVersion(_))
+        ));
+    }
+}
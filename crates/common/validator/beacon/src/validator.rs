@@ -6,14 +6,13 @@ use std::{
     vec,
 };
 
-use alloy_primitives::Address;
 use anyhow::{anyhow, bail};
 use futures::future::try_join_all;
 use ream_api_types_beacon::{
     block::{BroadcastValidation, ProduceBlockData},
     duties::{AttesterDuty, ProposerDuty, SyncCommitteeDuty},
     id::ValidatorID,
-    request::SyncCommitteeRequestItem,
+    request::{PrepareBeaconProposerItem, SyncCommitteeRequestItem},
 };
 use ream_api_types_common::id::ID;
 use ream_bls::{BLSSignature, PublicKey, traits::Signable};
@@ -23,7 +22,8 @@ use ream_consensus_beacon::{
 use ream_consensus_misc::{
     attestation_data::AttestationData,
     constants::beacon::{
-        DOMAIN_SYNC_COMMITTEE, INTERVALS_PER_SLOT, SLOTS_PER_EPOCH, SYNC_COMMITTEE_SIZE,
+        DOMAIN_BEACON_ATTESTER, DOMAIN_BEACON_PROPOSER, DOMAIN_SYNC_COMMITTEE, INTERVALS_PER_SLOT,
+        SLOTS_PER_EPOCH, SYNC_COMMITTEE_SIZE,
     },
     misc::{compute_domain, compute_epoch_at_slot, compute_signing_root},
 };
@@ -43,10 +43,13 @@ use crate::{
     attestation::{get_selection_proof, sign_attestation_data},
     beacon_api_client::BeaconApiClient,
     block::{sign_beacon_block, sign_blinded_beacon_block},
+    builder::validator_registration::{SignedValidatorRegistrationV1, ValidatorRegistrationV1},
     constants::SYNC_COMMITTEE_SUBNET_COUNT,
     contribution_and_proof::get_contribution_and_proof_signature,
     randao::sign_randao_reveal,
+    slashing_protection::SlashingProtectionDB,
     sync_committee::{get_sync_committee_selection_proof, is_sync_committee_aggregator},
+    validator_store::ValidatorStore,
     voluntary_exit::sign_voluntary_exit,
 };
 
@@ -73,12 +76,13 @@ pub struct SyncTaskInfo {
 
 pub struct ValidatorService {
     pub beacon_api_client: Arc<BeaconApiClient>,
-    pub validators: Vec<Arc<Keystore>>,
-    pub suggested_fee_recipient: Arc<Address>,
+    pub validator_store: Arc<ValidatorStore>,
+    pub slashing_protection: SlashingProtectionDB,
     pub executor: ReamExecutor,
     pub active_validator_count: usize,
     pub public_key_to_index: HashMap<PublicKey, u64>,
     pub validator_index_to_keystore: HashMap<u64, Arc<Keystore>>,
+    pub validator_registrations: HashMap<PublicKey, SignedValidatorRegistrationV1>,
     pub proposer_duties: Vec<ProposerDuty>,
     pub attester_duties: Vec<AttesterDuty>,
     pub sync_committee_duties: Vec<SyncCommitteeDuty>,
@@ -88,25 +92,24 @@ pub struct ValidatorService {
 
 impl ValidatorService {
     pub fn new(
-        keystores: Vec<Keystore>,
-        suggested_fee_recipient: Address,
+        validator_store: Arc<ValidatorStore>,
+        slashing_protection: SlashingProtectionDB,
         beacon_api_endpoint: Url,
         request_timeout: Duration,
         executor: ReamExecutor,
     ) -> anyhow::Result<Self> {
-        let validators = keystores.into_iter().map(Arc::new).collect::<Vec<_>>();
-
         Ok(Self {
             beacon_api_client: Arc::new(BeaconApiClient::new(
                 beacon_api_endpoint,
                 request_timeout,
             )?),
-            validators,
-            suggested_fee_recipient: Arc::new(suggested_fee_recipient),
+            validator_store,
+            slashing_protection,
             executor,
             active_validator_count: 0,
             public_key_to_index: HashMap::new(),
             validator_index_to_keystore: HashMap::new(),
+            validator_registrations: HashMap::new(),
             proposer_duties: Vec::new(),
             attester_duties: Vec::new(),
             sync_committee_duties: Vec::new(),
@@ -220,6 +223,7 @@ impl ValidatorService {
 
     // Runs on the start of every epoch prior to the per-slot code.
     // - Fetches validator indicies
+    // - Sends the fee recipients and validator registrations to the beacon node
     // - Fetches proposer and committee duties for the epoch
     pub async fn on_epoch(&mut self, epoch: u64) {
         info!("Current Epoch: {epoch}");
@@ -232,6 +236,13 @@ impl ValidatorService {
             return;
         }
 
+        if let Err(err) = self.prepare_beacon_proposers(&validator_indices).await {
+            warn!("Failed to prepare beacon proposers: {err:?}");
+        }
+        if let Err(err) = self.register_validators().await {
+            warn!("Failed to register validators: {err:?}");
+        }
+
         if let Some(proposer_duties) = self.fetch_proposer_duties(epoch, &validator_indices).await {
             self.proposer_duties = proposer_duties;
         }
@@ -291,13 +302,21 @@ impl ValidatorService {
     }
 
     pub async fn fetch_validator_indicies(&mut self) {
-        if self.active_validator_count < self.validators.len() {
+        // Forget validators whose keystores were deleted through the key manager.
+        self.public_key_to_index
+            .retain(|public_key, _| self.validator_store.contains(public_key));
+        self.validator_index_to_keystore
+            .retain(|_, keystore| self.validator_store.contains(&keystore.public_key));
+        self.active_validator_count = self.public_key_to_index.len();
+
+        let validators = self.validator_store.keystores();
+        if self.active_validator_count < validators.len() {
             let validator_states = self
                 .beacon_api_client
                 .get_state_validator_list(
                     ID::Head,
                     Some(
-                        self.validators
+                        validators
                             .iter()
                             .map(|validator_info| {
                                 ValidatorID::Address(validator_info.public_key.clone())
@@ -316,8 +335,7 @@ impl ValidatorService {
                     {
                         entry.insert(validator_data.index);
 
-                        if let Some(keystore) = self
-                            .validators
+                        if let Some(keystore) = validators
                             .iter()
                             .find(|keystore| {
                                 keystore.public_key == validator_data.validator.public_key
@@ -335,6 +353,77 @@ impl ValidatorService {
         }
     }
 
+    /// Send the fee recipient of each of `validator_indices` to the beacon node, so the payloads
+    /// it builds for them pay the recipient set through the key manager.
+    pub async fn prepare_beacon_proposers(&self, validator_indices: &[u64]) -> anyhow::Result<()> {
+        let preparations = validator_indices
+            .iter()
+            .filter_map(|&validator_index| {
+                let keystore = self.get_keystore(validator_index)?;
+                Some(PrepareBeaconProposerItem {
+                    validator_index,
+                    fee_recipient: self.validator_store.fee_recipient(&keystore.public_key)?,
+                })
+            })
+            .collect::<Vec<_>>();
+        if preparations.is_empty() {
+            return Ok(());
+        }
+
+        Ok(self
+            .beacon_api_client
+            .prepare_beacon_proposer(preparations)
+            .await?)
+    }
+
+    /// Send a signed builder registration with the fee recipient and gas limit of every known
+    /// validator to the beacon node.
+    ///
+    /// Registrations are only re-signed when the fee recipient or gas limit changes, so the
+    /// builder keeps seeing the same timestamp otherwise.
+    pub async fn register_validators(&mut self) -> anyhow::Result<()> {
+        self.validator_registrations
+            .retain(|public_key, _| self.validator_store.contains(public_key));
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        for keystore in self.validator_index_to_keystore.values() {
+            let (Some(fee_recipient), Some(gas_limit)) = (
+                self.validator_store.fee_recipient(&keystore.public_key),
+                self.validator_store.gas_limit(&keystore.public_key),
+            ) else {
+                continue;
+            };
+            if self
+                .validator_registrations
+                .get(&keystore.public_key)
+                .is_some_and(|registration| {
+                    registration.message.fee_recipient == fee_recipient
+                        && registration.message.gas_limit == gas_limit
+                })
+            {
+                continue;
+            }
+
+            let registration = ValidatorRegistrationV1 {
+                fee_recipient,
+                gas_limit,
+                timestamp,
+                public_key: keystore.public_key.clone(),
+            }
+            .create_signed_registration(&keystore.private_key)?;
+            self.validator_registrations
+                .insert(keystore.public_key.clone(), registration);
+        }
+        if self.validator_registrations.is_empty() {
+            return Ok(());
+        }
+
+        Ok(self
+            .beacon_api_client
+            .register_validator(self.validator_registrations.values().cloned().collect())
+            .await?)
+    }
+
     pub async fn fetch_proposer_duties(
         &self,
         epoch: u64,
@@ -391,18 +480,67 @@ impl ValidatorService {
         }
     }
 
-    pub async fn propose_block(&self, slot: u64, validator_index: u64) -> anyhow::Result<()> {
-        let keystore = self
-            .validator_index_to_keystore
+    /// The keystore of `validator_index`, unless it was deleted through the key manager.
+    fn get_keystore(&self, validator_index: u64) -> Option<Arc<Keystore>> {
+        self.validator_index_to_keystore
             .get(&validator_index)
+            .filter(|keystore| self.validator_store.contains(&keystore.public_key))
             .cloned()
+    }
+
+    pub async fn propose_block(&self, slot: u64, validator_index: u64) -> anyhow::Result<()> {
+        let keystore = self
+            .get_keystore(validator_index)
             .ok_or_else(|| anyhow!("keystore not found for validator: {validator_index}"))?;
+        let fee_recipient = self
+            .validator_store
+            .fee_recipient(&keystore.public_key)
+            .ok_or_else(|| anyhow!("fee recipient not found for validator: {validator_index}"))?;
+        // Fee recipients set through the key manager during the epoch apply to this block too.
+        self.beacon_api_client
+            .prepare_beacon_proposer(vec![PrepareBeaconProposerItem {
+                validator_index,
+                fee_recipient,
+            }])
+            .await?;
         let randao_reveal = sign_randao_reveal(slot, &keystore.private_key)?;
         let block_response = self
             .beacon_api_client
-            .produce_block(slot, randao_reveal, None, None, None)
+            .produce_block(
+                slot,
+                randao_reveal,
+                self.validator_store.graffiti_bytes(&keystore.public_key),
+                None,
+                None,
+            )
             .await?;
 
+        let domain = compute_domain(
+            DOMAIN_BEACON_PROPOSER,
+            Some(beacon_network_spec().electra_fork_version),
+            None,
+        );
+        let (signing_root, payload_fee_recipient) = match &block_response.data {
+            ProduceBlockData::Full(full_block) => (
+                compute_signing_root(&full_block.block, domain),
+                full_block.block.body.execution_payload.fee_recipient,
+            ),
+            ProduceBlockData::Blinded(blinded_block) => (
+                compute_signing_root(blinded_block, domain),
+                blinded_block.body.execution_payload_header.fee_recipient,
+            ),
+        };
+        if payload_fee_recipient != fee_recipient {
+            warn!(
+                "Block for validator {validator_index} at slot {slot} pays {payload_fee_recipient} instead of the configured fee recipient {fee_recipient}"
+            );
+        }
+        self.slashing_protection
+            .check_and_insert_block(&keystore.public_key, slot, signing_root)
+            .map_err(|err| {
+                anyhow!("Refusing to sign block for validator {validator_index}: {err}")
+            })?;
+
         match block_response.data {
             ProduceBlockData::Full(full_block) => {
                 let signed_beacon_block =
@@ -430,7 +568,7 @@ impl ValidatorService {
         self.sync_aggregator_infos.clear();
 
         for duty in &self.sync_committee_duties {
-            let Some(keystore) = self.validator_index_to_keystore.get(&duty.validator_index) else {
+            let Some(keystore) = self.get_keystore(duty.validator_index) else {
                 continue;
             };
             for &committee_index in &duty.validator_sync_committee_indices {
@@ -445,7 +583,7 @@ impl ValidatorService {
                     validator_index: duty.validator_index,
                     committee_index,
                     selection_proof,
-                    keystore: Arc::clone(keystore),
+                    keystore: keystore.clone(),
                 };
 
                 if is_sync_committee_aggregator(&task_info.selection_proof) {
@@ -480,7 +618,7 @@ impl ValidatorService {
         let payload = validator_indices
             .iter()
             .filter_map(|&validator_index| {
-                if let Some(keystore) = self.validator_index_to_keystore.get(&validator_index) {
+                if let Some(keystore) = self.get_keystore(validator_index) {
                     return match keystore.private_key.sign(signing_root.as_ref()) {
                         Ok(signature) => Some(Ok(SyncCommitteeRequestItem {
                             slot,
@@ -514,7 +652,7 @@ impl ValidatorService {
         ))
         .await;
 
-        let Some(keystore) = self.validator_index_to_keystore.get(&validator_index) else {
+        let Some(keystore) = self.get_keystore(validator_index) else {
             bail!("Keystore not found for validator: {validator_index}");
         };
 
@@ -523,6 +661,23 @@ impl ValidatorService {
             .get_attestation_data(slot, committee_index)
             .await?
             .data;
+
+        let domain = compute_domain(
+            DOMAIN_BEACON_ATTESTER,
+            Some(beacon_network_spec().electra_fork_version),
+            None,
+        );
+        self.slashing_protection
+            .check_and_insert_attestation(
+                &keystore.public_key,
+                attestation_data.source.epoch,
+                attestation_data.target.epoch,
+                compute_signing_root(&attestation_data, domain),
+            )
+            .map_err(|err| {
+                anyhow!("Refusing to sign attestation for validator {validator_index}: {err}")
+            })?;
+
         Ok(self
             .beacon_api_client
             .submit_attestation(vec![SingleAttestation {
@@ -542,9 +697,7 @@ impl ValidatorService {
         aggregator_index: u64,
     ) -> anyhow::Result<()> {
         let keystore = self
-            .validator_index_to_keystore
-            .get(&aggregator_index)
-            .cloned()
+            .get_keystore(aggregator_index)
             .ok_or_else(|| anyhow!("Keystore not found for validator: {aggregator_index}"))?;
 
         let aggregate_and_proof = AggregateAndProof {
@@ -580,8 +733,7 @@ impl ValidatorService {
                 epoch,
                 validator_index,
                 &self
-                    .validator_index_to_keystore
-                    .get(&validator_index)
+                    .get_keystore(validator_index)
                     .ok_or_else(|| anyhow!("Keystore not found for validator: {validator_index}"))?
                     .private_key,
             )?)
@@ -0,0 +1,335 @@
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::Write,
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use alloy_primitives::{Address, B256, hex};
+use anyhow::anyhow;
+use parking_lot::RwLock;
+use ream_bls::PublicKey;
+use ream_keystore::keystore::{EncryptedKeystore, Keystore};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::constants::DEFAULT_GAS_LIMIT;
+
+pub const VALIDATOR_SECRETS_FOLDER_NAME: &str = "validator_secrets";
+pub const VALIDATOR_SETTINGS_FILENAME: &str = "validator_settings.json";
+
+/// Per-validator overrides set through the key manager API.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_recipient: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graffiti: Option<String>,
+}
+
+impl ValidatorSettings {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ValidatorSettingsEntry {
+    pubkey: PublicKey,
+    #[serde(flatten)]
+    settings: ValidatorSettings,
+}
+
+struct ValidatorEntry {
+    keystore: Arc<Keystore>,
+    keystore_path: PathBuf,
+    derivation_path: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportStatus {
+    Imported,
+    Duplicate,
+}
+
+/// The set of validators the validator client signs for, which can change at runtime through the
+/// key manager API.
+///
+/// Keystores live in the keystores directory. Keystores imported at runtime are written there
+/// too, with their password stored under [VALIDATOR_SECRETS_FOLDER_NAME], readable only by the
+/// current user, so they can be decrypted again after a restart.
+pub struct ValidatorStore {
+    keystores_dir: PathBuf,
+    secrets_dir: PathBuf,
+    settings_path: PathBuf,
+    default_fee_recipient: Address,
+    validators: RwLock<HashMap<PublicKey, ValidatorEntry>>,
+    settings: RwLock<HashMap<PublicKey, ValidatorSettings>>,
+}
+
+impl ValidatorStore {
+    /// Load and decrypt every keystore in `keystores_dir`.
+    ///
+    /// Keystores with a stored secret are decrypted with it, all others with `password`.
+    pub fn load(
+        keystores_dir: &Path,
+        data_dir: &Path,
+        password: &str,
+        default_fee_recipient: Address,
+    ) -> anyhow::Result<Self> {
+        let secrets_dir = data_dir.join(VALIDATOR_SECRETS_FOLDER_NAME);
+        fs::create_dir_all(&secrets_dir)?;
+
+        let mut validators = HashMap::new();
+        for entry in fs::read_dir(keystores_dir).map_err(|err| {
+            anyhow!(
+                "Failed to read directory {}: {err:?}",
+                keystores_dir.display()
+            )
+        })? {
+            let keystore_path = entry?.path();
+            if !keystore_path.is_file()
+                || keystore_path
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    != Some("json")
+            {
+                continue;
+            }
+
+            let encrypted_keystore = match EncryptedKeystore::load_from_file(&keystore_path) {
+                Ok(encrypted_keystore) => encrypted_keystore,
+                Err(err) => {
+                    warn!(
+                        "Skipping {}, it isn't a valid keystore: {err}",
+                        keystore_path.display()
+                    );
+                    continue;
+                }
+            };
+            let secret_path = secret_path(&secrets_dir, &encrypted_keystore.public_key);
+            let password = if secret_path.exists() {
+                fs::read_to_string(&secret_path)?
+            } else {
+                password.to_string()
+            };
+            let keystore = encrypted_keystore
+                .decrypt(password.as_bytes())
+                .map_err(|err| {
+                    anyhow!(
+                        "Could not decrypt keystore {}: {err}",
+                        keystore_path.display()
+                    )
+                })?;
+
+            validators.insert(
+                keystore.public_key.clone(),
+                ValidatorEntry {
+                    keystore: Arc::new(keystore),
+                    keystore_path,
+                    derivation_path: encrypted_keystore.path,
+                },
+            );
+        }
+
+        let settings_path = data_dir.join(VALIDATOR_SETTINGS_FILENAME);
+        let settings = if settings_path.exists() {
+            serde_json::from_str::<Vec<ValidatorSettingsEntry>>(&fs::read_to_string(
+                &settings_path,
+            )?)?
+            .into_iter()
+            .map(|entry| (entry.pubkey, entry.settings))
+            .collect()
+        } else {
+            HashMap::new()
+        };
+
+        info!("Loaded {} validator keystore(s)", validators.len());
+
+        Ok(Self {
+            keystores_dir: keystores_dir.to_path_buf(),
+            secrets_dir,
+            settings_path,
+            default_fee_recipient,
+            validators: RwLock::new(validators),
+            settings: RwLock::new(settings),
+        })
+    }
+
+    pub fn keystores(&self) -> Vec<Arc<Keystore>> {
+        self.validators
+            .read()
+            .values()
+            .map(|entry| entry.keystore.clone())
+            .collect()
+    }
+
+    pub fn contains(&self, public_key: &PublicKey) -> bool {
+        self.validators.read().contains_key(public_key)
+    }
+
+    /// The public keys of all validators with their derivation paths.
+    pub fn list(&self) -> Vec<(PublicKey, String)> {
+        self.validators
+            .read()
+            .iter()
+            .map(|(public_key, entry)| (public_key.clone(), entry.derivation_path.clone()))
+            .collect()
+    }
+
+    /// Decrypt `keystore_json` with `password` and start signing with it.
+    pub fn import(&self, keystore_json: &str, password: &str) -> anyhow::Result<ImportStatus> {
+        let encrypted_keystore = serde_json::from_str::<EncryptedKeystore>(keystore_json)
+            .map_err(|err| anyhow!("Invalid keystore: {err}"))?;
+        if self.contains(&encrypted_keystore.public_key) {
+            return Ok(ImportStatus::Duplicate);
+        }
+
+        let keystore = encrypted_keystore.decrypt(password.as_bytes())?;
+
+        let public_key_hex = hex::encode(keystore.public_key.to_bytes());
+        let keystore_path = self
+            .keystores_dir
+            .join(format!("keystore-{public_key_hex}.json"));
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(secret_path(&self.secrets_dir, &keystore.public_key))?
+            .write_all(password.as_bytes())?;
+        encrypted_keystore.save_to_file(&keystore_path)?;
+
+        let mut validators = self.validators.write();
+        if validators.contains_key(&keystore.public_key) {
+            return Ok(ImportStatus::Duplicate);
+        }
+        validators.insert(
+            keystore.public_key.clone(),
+            ValidatorEntry {
+                keystore: Arc::new(keystore),
+                keystore_path,
+                derivation_path: encrypted_keystore.path,
+            },
+        );
+
+        info!("Imported validator keystore 0x{public_key_hex}");
+        Ok(ImportStatus::Imported)
+    }
+
+    /// Stop signing with `public_key` and remove its keystore from disk.
+    ///
+    /// Returns false if the validator isn't known. The validator is removed from memory first, so
+    /// it can't sign anything once this returns, even if the files can't be removed.
+    pub fn delete(&self, public_key: &PublicKey) -> anyhow::Result<bool> {
+        let Some(entry) = self.validators.write().remove(public_key) else {
+            return Ok(false);
+        };
+
+        fs::remove_file(&entry.keystore_path)?;
+        let secret_path = secret_path(&self.secrets_dir, public_key);
+        if secret_path.exists() {
+            fs::remove_file(secret_path)?;
+        }
+        if self.settings.write().remove(public_key).is_some()
+            && let Err(err) = self.persist_settings()
+        {
+            warn!("Failed to persist validator settings: {err}");
+        }
+
+        info!(
+            "Deleted validator keystore 0x{}",
+            hex::encode(public_key.to_bytes())
+        );
+        Ok(true)
+    }
+
+    /// The fee recipient of `public_key`, or `None` if the validator isn't known.
+    pub fn fee_recipient(&self, public_key: &PublicKey) -> Option<Address> {
+        self.setting(public_key, |settings| settings.fee_recipient)
+            .map(|fee_recipient| fee_recipient.unwrap_or(self.default_fee_recipient))
+    }
+
+    /// The gas limit of `public_key`, or `None` if the validator isn't known.
+    pub fn gas_limit(&self, public_key: &PublicKey) -> Option<u64> {
+        self.setting(public_key, |settings| settings.gas_limit)
+            .map(|gas_limit| gas_limit.unwrap_or(DEFAULT_GAS_LIMIT))
+    }
+
+    /// The graffiti of `public_key`, or `None` if the validator isn't known.
+    pub fn graffiti(&self, public_key: &PublicKey) -> Option<String> {
+        self.setting(public_key, |settings| settings.graffiti.clone())
+            .map(Option::unwrap_or_default)
+    }
+
+    /// The graffiti of `public_key` as it is included in a block, zero padded to 32 bytes.
+    pub fn graffiti_bytes(&self, public_key: &PublicKey) -> Option<B256> {
+        let graffiti = self.graffiti(public_key)?;
+        if graffiti.is_empty() || graffiti.len() > 32 {
+            return None;
+        }
+
+        let mut graffiti_bytes = B256::ZERO;
+        graffiti_bytes[..graffiti.len()].copy_from_slice(graffiti.as_bytes());
+        Some(graffiti_bytes)
+    }
+
+    /// Apply `update` to the settings of `public_key` and persist them.
+    ///
+    /// Returns false if the validator isn't known.
+    pub fn update_settings(
+        &self,
+        public_key: &PublicKey,
+        update: impl FnOnce(&mut ValidatorSettings),
+    ) -> anyhow::Result<bool> {
+        if !self.contains(public_key) {
+            return Ok(false);
+        }
+
+        {
+            let mut settings = self.settings.write();
+            let validator_settings = settings.entry(public_key.clone()).or_default();
+            update(validator_settings);
+            if validator_settings.is_empty() {
+                settings.remove(public_key);
+            }
+        }
+        self.persist_settings()?;
+        Ok(true)
+    }
+
+    fn setting<T>(
+        &self,
+        public_key: &PublicKey,
+        get: impl FnOnce(&ValidatorSettings) -> Option<T>,
+    ) -> Option<Option<T>> {
+        if !self.contains(public_key) {
+            return None;
+        }
+
+        Some(self.settings.read().get(public_key).and_then(get))
+    }
+
+    fn persist_settings(&self) -> anyhow::Result<()> {
+        let entries = self
+            .settings
+            .read()
+            .iter()
+            .map(|(public_key, settings)| ValidatorSettingsEntry {
+                pubkey: public_key.clone(),
+                settings: settings.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        fs::write(&self.settings_path, serde_json::to_string_pretty(&entries)?)?;
+        Ok(())
+    }
+}
+
+fn secret_path(secrets_dir: &Path, public_key: &PublicKey) -> PathBuf {
+    secrets_dir.join(format!("0x{}", hex::encode(public_key.to_bytes())))
+}
@@ -0,0 +1,251 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use alloy_primitives::hex;
+use anyhow::{anyhow, bail};
+use parking_lot::{Mutex, RwLock};
+use ream_keystore::lean_keystore::ValidatorKeystore;
+use ream_post_quantum_crypto::leansig::{
+    private_key::PrivateKey, public_key::PublicKey, signature::Signature,
+};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+pub const KEY_USAGE_FOLDER_NAME: &str = "key_usage";
+
+/// The persisted usage state of a single leansig one-time signature key.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyUsageState {
+    pub last_signed_epoch: Option<u64>,
+}
+
+impl KeyUsageState {
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| anyhow!("Failed to parse key usage state {}: {err}", path.display()))
+    }
+
+    /// Write the state to a temporary file and rename it over `path`, so a crash can never leave
+    /// a partially written state behind.
+    fn write(&self, path: &Path) -> anyhow::Result<()> {
+        let temp_path = path.with_extension("json.tmp");
+        let mut file = File::create(&temp_path)?;
+        file.write_all(serde_json::to_string(self)?.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+}
+
+/// A leansig private key whose used epochs are persisted to disk.
+///
+/// An XMSS epoch must never be used twice. Before a signature is released, the epoch is recorded
+/// in a state file next to the other keys' states, and any epoch at or below the last recorded one
+/// is refused, including after a restart.
+pub struct ManagedPrivateKey {
+    private_key: RwLock<PrivateKey>,
+    state: Mutex<KeyUsageState>,
+    state_path: PathBuf,
+    is_preparing: AtomicBool,
+}
+
+impl ManagedPrivateKey {
+    pub fn new(
+        private_key: PrivateKey,
+        public_key: &PublicKey,
+        key_usage_dir: &Path,
+    ) -> anyhow::Result<Self> {
+        let state_path = key_usage_dir.join(format!("{}.json", hex::encode(public_key.inner)));
+        let state = KeyUsageState::load(&state_path)?;
+
+        Ok(Self {
+            private_key: RwLock::new(private_key),
+            state: Mutex::new(state),
+            state_path,
+            is_preparing: AtomicBool::new(false),
+        })
+    }
+
+    pub fn last_signed_epoch(&self) -> Option<u64> {
+        self.state.lock().last_signed_epoch
+    }
+
+    /// Number of epochs left in the key's activation interval from `epoch` on.
+    pub fn remaining_epochs(&self, epoch: u64) -> u64 {
+        self.private_key
+            .read()
+            .get_activation_interval()
+            .end
+            .saturating_sub(epoch)
+    }
+
+    /// Record `epoch` as used and sign `message` with it.
+    ///
+    /// The state is persisted before signing, so a failure after this point can only waste an
+    /// epoch, never reuse one.
+    pub fn sign(&self, message: &[u8; 32], epoch: u32) -> anyhow::Result<Signature> {
+        let mut state = self.state.lock();
+        if let Some(last_signed_epoch) = state.last_signed_epoch
+            && epoch as u64 <= last_signed_epoch
+        {
+            bail!(
+                "Refusing to sign at epoch {epoch}, the key was already used up to epoch {last_signed_epoch}"
+            );
+        }
+
+        let private_key = self.private_key.read();
+        let activation_interval = private_key.get_activation_interval();
+        if !activation_interval.contains(&(epoch as u64)) {
+            bail!("Epoch {epoch} is outside the activation interval {activation_interval:?}");
+        }
+
+        let new_state = KeyUsageState {
+            last_signed_epoch: Some(epoch as u64),
+        };
+        new_state.write(&self.state_path)?;
+        *state = new_state;
+
+        Ok(private_key.sign(message, epoch)?)
+    }
+
+    /// Whether half of the prepared interval has passed at `epoch` and the key can still be
+    /// advanced.
+    pub fn needs_preparation(&self, epoch: u64) -> bool {
+        needs_preparation(&self.private_key.read(), epoch)
+    }
+
+    /// Advance the prepared interval until `epoch` is in its first half.
+    ///
+    /// The preparation runs on a copy of the key, so signing isn't blocked while it is computed.
+    /// Concurrent calls are skipped.
+    pub fn prepare_for_epoch(&self, epoch: u64) -> anyhow::Result<()> {
+        if !self.needs_preparation(epoch) || self.is_preparing.swap(true, Ordering::AcqRel) {
+            return Ok(());
+        }
+
+        let result = PrivateKey::from_bytes(&self.private_key.read().to_bytes())
+            .map_err(|err| anyhow!("Failed to copy private key for preparation: {err}"))
+            .map(|mut private_key| {
+                while needs_preparation(&private_key, epoch) {
+                    let prepared_interval = private_key.get_prepared_interval();
+                    private_key.prepare_signature();
+                    if private_key.get_prepared_interval() == prepared_interval {
+                        break;
+                    }
+                }
+                *self.private_key.write() = private_key;
+            });
+
+        self.is_preparing.store(false, Ordering::Release);
+        result
+    }
+}
+
+fn needs_preparation(private_key: &PrivateKey, epoch: u64) -> bool {
+    let prepared_interval = private_key.get_prepared_interval();
+    let halfway = prepared_interval.start + (prepared_interval.end - prepared_interval.start) / 2;
+    prepared_interval.end < private_key.get_activation_interval().end && epoch >= halfway
+}
+
+/// A validator's keys, with their usage tracked by [ManagedPrivateKey].
+pub struct ManagedKeystore {
+    pub index: u64,
+    pub attestation_public_key: PublicKey,
+    pub proposal_public_key: PublicKey,
+    pub attestation_private_key: ManagedPrivateKey,
+    pub proposal_private_key: ManagedPrivateKey,
+}
+
+impl ManagedKeystore {
+    /// Wrap a loaded keystore, reading its usage state from `key_usage_dir` and preparing both
+    /// keys for `epoch`.
+    pub fn new(
+        keystore: ValidatorKeystore,
+        key_usage_dir: &Path,
+        epoch: u64,
+    ) -> anyhow::Result<Self> {
+        fs::create_dir_all(key_usage_dir)?;
+
+        let ValidatorKeystore {
+            index,
+            attestation_public_key,
+            proposal_public_key,
+            attestation_private_key,
+            proposal_private_key,
+        } = keystore;
+
+        let managed_keystore = Self {
+            index,
+            attestation_private_key: ManagedPrivateKey::new(
+                attestation_private_key,
+                &attestation_public_key,
+                key_usage_dir,
+            )?,
+            proposal_private_key: ManagedPrivateKey::new(
+                proposal_private_key,
+                &proposal_public_key,
+                key_usage_dir,
+            )?,
+            attestation_public_key,
+            proposal_public_key,
+        };
+
+        for (name, key) in managed_keystore.keys() {
+            if let Some(last_signed_epoch) = key.last_signed_epoch() {
+                info!(
+                    last_signed_epoch,
+                    "Loaded {name} key usage state for Validator {index}"
+                );
+            }
+            key.prepare_for_epoch(epoch)?;
+        }
+
+        Ok(managed_keystore)
+    }
+
+    pub fn keys(&self) -> [(&'static str, &ManagedPrivateKey); 2] {
+        [
+            ("attestation", &self.attestation_private_key),
+            ("proposal", &self.proposal_private_key),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_signed_epochs_survive_reload() {
+        let temp_dir = TempDir::new("key_usage").unwrap();
+        let (public_key, private_key) = PrivateKey::generate_key_pair(0, 10);
+        let private_key_bytes = private_key.to_bytes();
+        let message = [0u8; 32];
+
+        let key = ManagedPrivateKey::new(private_key, &public_key, temp_dir.path()).unwrap();
+        key.sign(&message, 1).unwrap();
+        assert!(key.sign(&message, 1).is_err());
+        assert!(key.sign(&message, 0).is_err());
+
+        let reloaded_key = ManagedPrivateKey::new(
+            PrivateKey::from_bytes(&private_key_bytes).unwrap(),
+            &public_key,
+            temp_dir.path(),
+        )
+        .unwrap();
+        assert_eq!(reloaded_key.last_signed_epoch(), Some(1));
+        assert!(reloaded_key.sign(&message, 1).is_err());
+        reloaded_key.sign(&message, 2).unwrap();
+        assert_eq!(reloaded_key.last_signed_epoch(), Some(2));
+    }
+}
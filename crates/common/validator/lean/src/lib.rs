@@ -1,2 +1,4 @@
+pub mod key_usage;
 pub mod registry;
 pub mod service;
+pub mod slashing_protection;
@@ -1,8 +1,15 @@
-use std::{fmt::Debug, fs, path::Path};
+use std::{collections::HashMap, fmt::Debug, fs, path::Path};
 
 use anyhow::{anyhow, bail};
-use ream_keystore::lean_keystore::{AnnotatedValidatorRegistry, ValidatorKeystore};
-use ream_post_quantum_crypto::leansig::private_key::{LeanSigPrivateKey, PrivateKey};
+use ream_keystore::{
+    keystore::{CryptoV5, EncryptedKeystore},
+    lean_keystore::{AnnotatedValidatorEntry, AnnotatedValidatorRegistry, ValidatorKeystore},
+};
+use ream_post_quantum_crypto::leansig::{
+    private_key::{LeanSigPrivateKey, PrivateKey},
+    public_key::PublicKey,
+};
+use tracing::info;
 
 enum PrivateKeyFormat {
     Json,
@@ -29,6 +36,70 @@ impl TryFrom<&str> for PrivateKeyFormat {
 pub fn load_validator_registry<P: AsRef<Path> + Debug>(
     path: P,
     node_id: &str,
+) -> anyhow::Result<Vec<ValidatorKeystore>> {
+    let keys_dir = path
+        .as_ref()
+        .parent()
+        .ok_or_else(|| anyhow!("Invalid registry path"))?
+        .join("hash-sig-keys");
+
+    load_validator_entries(path, node_id, |entry| {
+        load_private_key(&keys_dir.join(&entry.private_key_file))
+    })
+}
+
+/// Load validator registry from annotated validators YAML file for a specific node, taking the
+/// private keys from `private_keys` by public key instead of from the key files of the registry.
+pub fn load_validator_registry_with_private_keys<P: AsRef<Path> + Debug>(
+    path: P,
+    node_id: &str,
+    mut private_keys: HashMap<PublicKey, PrivateKey>,
+) -> anyhow::Result<Vec<ValidatorKeystore>> {
+    load_validator_entries(path, node_id, |entry| {
+        private_keys.remove(&entry.public_key_hex).ok_or_else(|| {
+            anyhow!(
+                "No keystore found for validator {} with public key {:?}",
+                entry.index,
+                entry.public_key_hex
+            )
+        })
+    })
+}
+
+/// Decrypt the version 5 keystores written by the account manager in `keystore_dir`, returning
+/// their private keys by public key. Files without a `.json` extension are skipped.
+pub fn load_encrypted_keystores(
+    keystore_dir: &Path,
+    password: &[u8],
+) -> anyhow::Result<HashMap<PublicKey, PrivateKey>> {
+    let mut private_keys = HashMap::new();
+    for entry in fs::read_dir(keystore_dir).map_err(|err| {
+        anyhow!(
+            "Failed to read keystore directory {}: {err}",
+            keystore_dir.display()
+        )
+    })? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+
+        let keystore = EncryptedKeystore::<PublicKey, CryptoV5>::load_from_file(&path)
+            .map_err(|err| anyhow!("Failed to load keystore {}: {err}", path.display()))?;
+        info!("Decrypting keystore {}", path.display());
+        let private_key = keystore
+            .decrypt(password)
+            .map_err(|err| anyhow!("Failed to decrypt keystore {}: {err}", path.display()))?;
+        private_keys.insert(keystore.public_key, private_key);
+    }
+
+    Ok(private_keys)
+}
+
+fn load_validator_entries<P: AsRef<Path> + Debug>(
+    path: P,
+    node_id: &str,
+    mut load_key: impl FnMut(&AnnotatedValidatorEntry) -> anyhow::Result<PrivateKey>,
 ) -> anyhow::Result<Vec<ValidatorKeystore>> {
     let path = path.as_ref().to_path_buf();
     let validator_registry_yaml = fs::read_to_string(&path)
@@ -36,11 +107,6 @@ pub fn load_validator_registry<P: AsRef<Path> + Debug>(
     let registry = serde_yaml::from_str::<AnnotatedValidatorRegistry>(&validator_registry_yaml)
         .map_err(|err| anyhow!("Failed to parse annotated validator registry YAML: {err}"))?;
 
-    let keys_dir = path
-        .parent()
-        .ok_or_else(|| anyhow!("Invalid registry path"))?
-        .join("hash-sig-keys");
-
     let entries = registry
         .nodes
         .get(node_id)
@@ -66,10 +132,8 @@ pub fn load_validator_registry<P: AsRef<Path> + Debug>(
             );
         }
 
-        let attestation_private_key =
-            load_private_key(&keys_dir.join(&attester_entry.private_key_file))?;
-        let proposal_private_key =
-            load_private_key(&keys_dir.join(&proposer_entry.private_key_file))?;
+        let attestation_private_key = load_key(attester_entry)?;
+        let proposal_private_key = load_key(proposer_entry)?;
 
         validator_keystores.push(ValidatorKeystore {
             index: attester_entry.index,
@@ -7,7 +7,7 @@ use std::{
 use anyhow::anyhow;
 use ream_chain_lean::{
     clock::{create_lean_clock_interval, get_initial_tick_count},
-    messages::{LeanChainServiceMessage, ServiceResponse},
+    messages::{LeanChainServiceMessage, MessageOrigin, ServiceResponse},
 };
 #[cfg(feature = "devnet5")]
 use ream_consensus_lean::attestation::MultiMessageAggregate;
@@ -17,12 +17,11 @@ use ream_consensus_lean::{
 };
 use ream_consensus_misc::constants::lean::{INTERVALS_PER_SLOT, attestation_committee_count};
 use ream_fork_choice_lean::store::compute_subnet_id;
-use ream_keystore::lean_keystore::ValidatorKeystore;
 use ream_metrics::{
     ATTESTATIONS_PRODUCTION_TIME, LEAN_ATTESTATION_AGGREGATE_SUBNETS,
     LEAN_ATTESTATION_AGGREGATE_VALIDATORS, PQ_SIG_ATTESTATION_SIGNATURES_TOTAL,
-    PQ_SIG_ATTESTATION_SIGNING_TIME, VALIDATORS_COUNT, inc_int_counter_vec, set_int_gauge_vec,
-    start_timer, stop_timer,
+    PQ_SIG_ATTESTATION_SIGNING_TIME, VALIDATOR_KEY_REMAINING_EPOCHS, VALIDATOR_KEYS_EXPIRING,
+    VALIDATORS_COUNT, inc_int_counter_vec, set_int_gauge_vec, start_timer, stop_timer,
 };
 use ream_network_spec::networks::lean_network_spec;
 #[cfg(feature = "devnet5")]
@@ -33,31 +32,44 @@ use ream_post_quantum_crypto::lean_multisig::type_2::{
 use ssz_types::VariableList;
 use tokio::{
     sync::{mpsc, oneshot},
+    task,
     time::{Duration, sleep},
 };
 use tracing::{Level, debug, enabled, info, warn};
 use tree_hash::TreeHash;
 
+use crate::{key_usage::ManagedKeystore, slashing_protection::SlashingProtectionDB};
+
 /// ValidatorService is responsible for managing validator operations
 /// such as proposing blocks and submitting attestations on them. This service also holds the
 /// keystores for its validators, which are used to sign.
 /// Every first tick (t=0) it proposes a block if it's the validator's turn.
 /// Every second tick (t=1/4) it attestations on the proposed block.
+/// Every block and attestation is checked against and recorded in the [SlashingProtectionDB]
+/// before it is signed.
+/// Every fourth tick (t=3/4) it advances the preparation of keys that are halfway through their
+/// prepared interval, off the async runtime.
 /// NOTE: Other ticks should be handled by the other services, such as [LeanChainService].
 pub struct ValidatorService {
-    keystores: Vec<Arc<ValidatorKeystore>>,
+    keystores: Vec<Arc<ManagedKeystore>>,
     chain_sender: mpsc::UnboundedSender<LeanChainServiceMessage>,
+    slashing_protection: SlashingProtectionDB,
+    key_lifetime_warning_epochs: u64,
     prebuilding_slot: Option<u64>,
 }
 
 impl ValidatorService {
     pub async fn new(
-        keystores: Vec<Arc<ValidatorKeystore>>,
+        keystores: Vec<Arc<ManagedKeystore>>,
         chain_sender: mpsc::UnboundedSender<LeanChainServiceMessage>,
+        slashing_protection: SlashingProtectionDB,
+        key_lifetime_warning_epochs: u64,
     ) -> Self {
         ValidatorService {
             keystores,
             chain_sender,
+            slashing_protection,
+            key_lifetime_warning_epochs,
             prebuilding_slot: None,
         }
     }
@@ -74,6 +86,8 @@ impl ValidatorService {
 
         info!("ValidatorService starting at tick_count: {tick_count}");
 
+        self.report_key_lifetimes(tick_count / INTERVALS_PER_SLOT);
+
         let mut interval = create_lean_clock_interval()
             .map_err(|err| anyhow!("Expected Ream to be started before genesis time: {err:?}"))?;
 
@@ -88,8 +102,9 @@ impl ValidatorService {
                                 info!(slot = next_slot, tick = tick_count, "Pre-building block by Validator {}", keystore.index);
                                 self.prebuilding_slot = Some(next_slot);
                                 let chain_sender = self.chain_sender.clone();
+                                let slashing_protection = self.slashing_protection.clone();
                                 tokio::spawn(async move {
-                                    build_block(chain_sender, next_slot, keystore).await;
+                                    build_block(chain_sender, slashing_protection, next_slot, keystore).await;
                                 });
                             }
                         }
@@ -99,8 +114,9 @@ impl ValidatorService {
                             } else if slot > 0 && let Some(keystore) = self.is_proposer(slot) {
                                 info!(slot, tick = tick_count, "Proposing block by Validator {}", keystore.index);
                                 let chain_sender = self.chain_sender.clone();
+                                let slashing_protection = self.slashing_protection.clone();
                                 tokio::spawn(async move {
-                                    build_block(chain_sender, slot, keystore).await;
+                                    build_block(chain_sender, slashing_protection, slot, keystore).await;
                                 });
                             } else {
                                 let proposer_index = slot % lean_network_spec().num_validators;
@@ -157,9 +173,19 @@ impl ValidatorService {
                             let attestation_keystores = self.keystores.iter().collect::<Vec<_>>();
                             for keystore in attestation_keystores {
                                 let message = attestation_data.clone();
+                                if let Err(err) = self.slashing_protection.check_and_insert_attestation(keystore.index, &message) {
+                                    warn!(slot, "Refusing to sign attestation by Validator {}: {err}", keystore.index);
+                                    continue;
+                                }
                                 let message_root = message.tree_hash_root();
                                 let timer = start_timer(&PQ_SIG_ATTESTATION_SIGNING_TIME, &[]);
-                                let signature = keystore.attestation_private_key.sign(&message_root, slot as u32)?;
+                                let signature = match keystore.attestation_private_key.sign(&message_root, slot as u32) {
+                                    Ok(signature) => signature,
+                                    Err(err) => {
+                                        warn!(slot, "Failed to sign attestation by Validator {}: {err}", keystore.index);
+                                        continue;
+                                    }
+                                };
                                 stop_timer(timer);
                                 inc_int_counter_vec(&PQ_SIG_ATTESTATION_SIGNATURES_TOTAL, &[]);
                                 signed_attestations.push(SignedAttestation {
@@ -180,7 +206,7 @@ impl ValidatorService {
                                     .send(LeanChainServiceMessage::ProcessAttestation {
                                         signed_attestation: Box::new(signed_attestation.clone()),
                                         subnet_id,
-                                        need_gossip: true
+                                        origin: MessageOrigin::Local,
                                     })
                                     .map_err(|err| anyhow!("Failed to send attestation to LeanChainService: {err:?}"))?;
                             }
@@ -199,8 +225,12 @@ impl ValidatorService {
 
                             stop_timer(attestation_production_timer);
                         }
+                        3 => {
+                            // Fourth tick (t=3/4): Advance key preparation for the next slot.
+                            self.prepare_keys(slot + 1);
+                        }
                         _ => {
-                            // Other ticks (t=2/4, t=3/4): Do nothing.
+                            // Other ticks (t=2/4): Do nothing.
                         }
                     }
                     tick_count += 1;
@@ -209,8 +239,58 @@ impl ValidatorService {
         }
     }
 
+    /// Record the remaining lifetime of every key, warning about keys that are about to expire.
+    fn report_key_lifetimes(&self, slot: u64) {
+        let mut expiring_keys = 0;
+        for keystore in &self.keystores {
+            let validator_index = keystore.index.to_string();
+            for (name, key) in keystore.keys() {
+                let remaining_epochs = key.remaining_epochs(slot);
+                set_int_gauge_vec(
+                    &VALIDATOR_KEY_REMAINING_EPOCHS,
+                    remaining_epochs as i64,
+                    &[&validator_index, name],
+                );
+                if remaining_epochs < self.key_lifetime_warning_epochs {
+                    warn!(
+                        remaining_epochs,
+                        "The {name} key of Validator {} is close to the end of its lifetime",
+                        keystore.index
+                    );
+                    expiring_keys += 1;
+                }
+            }
+        }
+        set_int_gauge_vec(&VALIDATOR_KEYS_EXPIRING, expiring_keys, &[]);
+    }
+
+    /// Spawn a blocking task for each keystore with a key that needs preparation for `slot`.
+    fn prepare_keys(&self, slot: u64) {
+        for keystore in &self.keystores {
+            if !keystore
+                .keys()
+                .iter()
+                .any(|(_, key)| key.needs_preparation(slot))
+            {
+                continue;
+            }
+
+            let keystore = keystore.clone();
+            task::spawn_blocking(move || {
+                for (name, key) in keystore.keys() {
+                    if let Err(err) = key.prepare_for_epoch(slot) {
+                        warn!(
+                            "Failed to prepare {name} key of Validator {}: {err}",
+                            keystore.index
+                        );
+                    }
+                }
+            });
+        }
+    }
+
     /// Determine if one of the keystores is the proposer for the current slot.
-    fn is_proposer(&self, slot: u64) -> Option<Arc<ValidatorKeystore>> {
+    fn is_proposer(&self, slot: u64) -> Option<Arc<ManagedKeystore>> {
         let proposer_index = slot % lean_network_spec().num_validators;
 
         self.keystores
@@ -222,8 +302,9 @@ impl ValidatorService {
 
 pub async fn build_block(
     chain_sender: mpsc::UnboundedSender<LeanChainServiceMessage>,
+    slashing_protection: SlashingProtectionDB,
     slot: u64,
-    keystore: Arc<ValidatorKeystore>,
+    keystore: Arc<ManagedKeystore>,
 ) {
     let (tx, rx) = oneshot::channel();
 
@@ -259,7 +340,8 @@ pub async fn build_block(
         keystore.index,
     );
 
-    let signed_block = match sign_block(keystore, block_with_signatures, slot) {
+    let signed_block = match sign_block(keystore, &slashing_protection, block_with_signatures, slot)
+    {
         Ok(signed_block) => signed_block,
         Err(err) => {
             warn!("Failed to sign block for slot {slot}: {err}");
@@ -279,7 +361,7 @@ pub async fn build_block(
 
     if let Err(err) = chain_sender.send(LeanChainServiceMessage::ProcessBlock {
         signed_block: Box::new(signed_block),
-        need_gossip: true,
+        origin: MessageOrigin::Local,
     }) {
         warn!("Failed to send block to LeanChainService for slot {slot}: {err:?}");
     }
@@ -287,7 +369,8 @@ pub async fn build_block(
 
 #[cfg(feature = "devnet5")]
 fn sign_block(
-    keystore: Arc<ValidatorKeystore>,
+    keystore: Arc<ManagedKeystore>,
+    slashing_protection: &SlashingProtectionDB,
     block_with_signatures: BlockWithSignatures,
     slot: u64,
 ) -> anyhow::Result<SignedBlock> {
@@ -308,6 +391,15 @@ fn sign_block(
     let block_root = block.tree_hash_root();
     let block_root_bytes: [u8; 32] = block_root.into();
 
+    slashing_protection
+        .check_and_insert_block(keystore.index, block.slot, block_root)
+        .map_err(|err| {
+            anyhow!(
+                "Refusing to sign block by Validator {}: {err}",
+                keystore.index
+            )
+        })?;
+
     let timer = start_timer(&PQ_SIG_ATTESTATION_SIGNING_TIME, &[]);
     let proposer_signature = keystore
         .proposal_private_key
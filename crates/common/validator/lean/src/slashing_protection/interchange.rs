@@ -0,0 +1,49 @@
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+
+/// EIP-3076 style slashing protection interchange, adapted to lean consensus.
+///
+/// Lean consensus has no epochs, so attestations are recorded by their slot and the slots of their
+/// source and target checkpoints. Validators are identified by their registry index, and the chain
+/// is identified by its genesis time instead of a genesis validators root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interchange {
+    pub metadata: InterchangeMetadata,
+    pub data: Vec<InterchangeData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterchangeMetadata {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub interchange_format_version: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub genesis_time: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterchangeData {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    pub signed_blocks: Vec<InterchangeBlock>,
+    pub signed_attestations: Vec<InterchangeAttestation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterchangeBlock {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_root: Option<B256>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterchangeAttestation {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub source_slot: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub target_slot: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_root: Option<B256>,
+}
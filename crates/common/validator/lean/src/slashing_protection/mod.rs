@@ -0,0 +1,291 @@
+pub mod interchange;
+
+use std::path::Path;
+
+use alloy_primitives::B256;
+use ream_consensus_lean::attestation::AttestationData;
+use ream_slashing_protection::{
+    INTERCHANGE_FORMAT_VERSION, SignedAttestationRecord, SignedBlockRecord, SigningHistory,
+    SigningHistoryDB, error::SlashingProtectionError,
+};
+use tree_hash::TreeHash;
+
+use crate::slashing_protection::interchange::{
+    Interchange, InterchangeAttestation, InterchangeBlock, InterchangeData, InterchangeMetadata,
+};
+
+pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.redb";
+
+/// Signed blocks, keyed by (validator_index, slot)
+const SIGNED_BLOCKS_TABLE: &str = "lean_signed_blocks";
+
+/// Signed attestations, keyed by (validator_index, slot)
+const SIGNED_ATTESTATIONS_TABLE: &str = "lean_signed_attestations";
+
+/// Persistent record of every block and attestation signed by the local lean validators.
+///
+/// Validators are identified by the big-endian bytes of their registry index. A validator may sign
+/// a single attestation per slot, and the slots of its source and target checkpoints are checked
+/// for surround votes.
+#[derive(Clone, Debug)]
+pub struct SlashingProtectionDB {
+    db: SigningHistoryDB<8>,
+}
+
+impl SlashingProtectionDB {
+    /// Open the slashing protection database in `data_dir`, creating it if it doesn't exist.
+    pub fn open(data_dir: &Path) -> Result<Self, SlashingProtectionError> {
+        Ok(Self {
+            db: SigningHistoryDB::open(
+                &data_dir.join(SLASHING_PROTECTION_FILENAME),
+                SIGNED_BLOCKS_TABLE,
+                SIGNED_ATTESTATIONS_TABLE,
+            )?,
+        })
+    }
+
+    /// Check that signing a block at `slot` with `signing_root` is safe and record it.
+    pub fn check_and_insert_block(
+        &self,
+        validator_index: u64,
+        slot: u64,
+        signing_root: B256,
+    ) -> Result<(), SlashingProtectionError> {
+        self.db
+            .check_and_insert_block(validator_index.to_be_bytes(), slot, signing_root)
+    }
+
+    /// Check that signing `attestation_data` is safe and record it.
+    pub fn check_and_insert_attestation(
+        &self,
+        validator_index: u64,
+        attestation_data: &AttestationData,
+    ) -> Result<(), SlashingProtectionError> {
+        self.db.check_and_insert_attestation(
+            validator_index.to_be_bytes(),
+            attestation_data.slot,
+            attestation_data.source.slot,
+            attestation_data.target.slot,
+            attestation_data.tree_hash_root(),
+        )
+    }
+
+    /// Export the signing history of every validator in the database.
+    pub fn export_interchange(
+        &self,
+        genesis_time: u64,
+    ) -> Result<Interchange, SlashingProtectionError> {
+        self.export_interchange_for(genesis_time, None)
+    }
+
+    /// Export the signing history of `validator_indices`, or of every validator if `None`.
+    pub fn export_interchange_for(
+        &self,
+        genesis_time: u64,
+        validator_indices: Option<&[u64]>,
+    ) -> Result<Interchange, SlashingProtectionError> {
+        let histories = self.db.export(|validator| {
+            validator_indices
+                .is_none_or(|indices| indices.contains(&u64::from_be_bytes(*validator)))
+        })?;
+
+        Ok(Interchange {
+            metadata: InterchangeMetadata {
+                interchange_format_version: INTERCHANGE_FORMAT_VERSION,
+                genesis_time,
+            },
+            data: histories
+                .into_iter()
+                .map(|(validator, history)| InterchangeData {
+                    validator_index: u64::from_be_bytes(validator),
+                    signed_blocks: history
+                        .signed_blocks
+                        .into_iter()
+                        .map(|block| InterchangeBlock {
+                            slot: block.slot,
+                            signing_root: block.signing_root,
+                        })
+                        .collect(),
+                    signed_attestations: history
+                        .signed_attestations
+                        .into_iter()
+                        .map(|attestation| InterchangeAttestation {
+                            slot: attestation.vote,
+                            source_slot: attestation.source,
+                            target_slot: attestation.target,
+                            signing_root: attestation.signing_root,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Merge an interchange into the database.
+    ///
+    /// Imported records are added next to the existing history. If an imported record conflicts
+    /// with an existing one at the same slot, its root is recorded as unknown, so nothing can be
+    /// signed at that slot anymore.
+    pub fn import_interchange(
+        &self,
+        interchange: &Interchange,
+        genesis_time: u64,
+    ) -> Result<(), SlashingProtectionError> {
+        if interchange.metadata.interchange_format_version != INTERCHANGE_FORMAT_VERSION {
+            return Err(SlashingProtectionError::UnsupportedInterchangeVersion(
+                interchange.metadata.interchange_format_version,
+            ));
+        }
+        if interchange.metadata.genesis_time != genesis_time {
+            return Err(SlashingProtectionError::GenesisTimeMismatch {
+                expected: genesis_time,
+                found: interchange.metadata.genesis_time,
+            });
+        }
+
+        let histories = interchange
+            .data
+            .iter()
+            .map(|data| {
+                (
+                    data.validator_index.to_be_bytes(),
+                    SigningHistory {
+                        signed_blocks: data
+                            .signed_blocks
+                            .iter()
+                            .map(|block| SignedBlockRecord {
+                                slot: block.slot,
+                                signing_root: block.signing_root,
+                            })
+                            .collect(),
+                        signed_attestations: data
+                            .signed_attestations
+                            .iter()
+                            .map(|attestation| SignedAttestationRecord {
+                                vote: attestation.slot,
+                                source: attestation.source_slot,
+                                target: attestation.target_slot,
+                                signing_root: attestation.signing_root,
+                            })
+                            .collect(),
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        self.db.import(
+            histories
+                .iter()
+                .map(|(validator, history)| (*validator, history)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_consensus_lean::{attestation::AttestationData, checkpoint::Checkpoint};
+    use ream_slashing_protection::{INTERCHANGE_FORMAT_VERSION, error::SlashingProtectionError};
+    use tempdir::TempDir;
+
+    use super::SlashingProtectionDB;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn open_db(name: &str) -> (TempDir, SlashingProtectionDB) {
+        let tmp_dir = TempDir::new(name).unwrap();
+        let db = SlashingProtectionDB::open(tmp_dir.path()).unwrap();
+        (tmp_dir, db)
+    }
+
+    /// Attestation data at slot 10 with source slot 4 and target slot 8, told apart by `head_root`.
+    fn attestation_data(head_root: B256) -> AttestationData {
+        AttestationData {
+            slot: 10,
+            head: Checkpoint {
+                root: head_root,
+                slot: 10,
+            },
+            target: Checkpoint {
+                root: B256::repeat_byte(8),
+                slot: 8,
+            },
+            source: Checkpoint {
+                root: B256::repeat_byte(4),
+                slot: 4,
+            },
+        }
+    }
+
+    #[test]
+    fn test_interchange_round_trip() {
+        let (_source_dir, source) = open_db("test_interchange_source");
+        source
+            .check_and_insert_block(0, 10, B256::repeat_byte(1))
+            .unwrap();
+        source
+            .check_and_insert_attestation(0, &attestation_data(B256::repeat_byte(2)))
+            .unwrap();
+        source
+            .check_and_insert_attestation(1, &attestation_data(B256::repeat_byte(3)))
+            .unwrap();
+
+        let interchange = source.export_interchange(GENESIS_TIME).unwrap();
+        assert_eq!(
+            interchange.metadata.interchange_format_version,
+            INTERCHANGE_FORMAT_VERSION
+        );
+        assert_eq!(interchange.metadata.genesis_time, GENESIS_TIME);
+        let json = serde_json::to_string(&interchange).unwrap();
+        let interchange = serde_json::from_str(&json).unwrap();
+
+        let (_destination_dir, destination) = open_db("test_interchange_destination");
+        assert!(matches!(
+            destination.import_interchange(&interchange, GENESIS_TIME + 1),
+            Err(SlashingProtectionError::GenesisTimeMismatch { .. })
+        ));
+        destination
+            .check_and_insert_block(0, 10, B256::repeat_byte(4))
+            .unwrap();
+        destination
+            .import_interchange(&interchange, GENESIS_TIME)
+            .unwrap();
+
+        assert_eq!(
+            destination
+                .export_interchange_for(GENESIS_TIME, Some(&[1]))
+                .unwrap()
+                .data,
+            source
+                .export_interchange_for(GENESIS_TIME, Some(&[1]))
+                .unwrap()
+                .data
+        );
+
+        // The conflicting block at slot 10 blocks every root, including both known ones.
+        assert!(matches!(
+            destination.check_and_insert_block(0, 10, B256::repeat_byte(1)),
+            Err(SlashingProtectionError::DoubleBlockProposal { .. })
+        ));
+        assert!(matches!(
+            destination.check_and_insert_block(0, 10, B256::repeat_byte(4)),
+            Err(SlashingProtectionError::DoubleBlockProposal { .. })
+        ));
+        assert!(matches!(
+            destination.check_and_insert_attestation(0, &attestation_data(B256::repeat_byte(9))),
+            Err(SlashingProtectionError::DoubleVote { .. })
+        ));
+    }
+
+    #[test]
+    fn test_refuses_unsupported_interchange_version() {
+        let (_tmp_dir, db) = open_db("test_interchange_version");
+        let mut interchange = db.export_interchange(GENESIS_TIME).unwrap();
+        interchange.metadata.interchange_format_version = INTERCHANGE_FORMAT_VERSION + 1;
+
+        assert!(matches!(
+            db.import_interchange(&interchange, GENESIS_TIME),
+            Err(SlashingProtectionError::UnsupportedInterchangeVersion(_))
+        ));
+    }
+}
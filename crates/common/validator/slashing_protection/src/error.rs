@@ -0,0 +1,99 @@
+use alloy_primitives::B256;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SlashingProtectionError {
+    #[error("A different block was already signed at slot {slot}")]
+    DoubleBlockProposal { slot: u64 },
+
+    #[error(
+        "Cannot sign a block at slot {slot}, a block was already signed at slot {highest_slot}"
+    )]
+    BlockSlotNotAboveHighest { slot: u64, highest_slot: u64 },
+
+    #[error("A different attestation was already signed for vote {vote}")]
+    DoubleVote { vote: u64 },
+
+    #[error(
+        "Attestation source checkpoint {source_checkpoint} is after its target checkpoint {target_checkpoint}"
+    )]
+    SourceAfterTarget {
+        source_checkpoint: u64,
+        target_checkpoint: u64,
+    },
+
+    #[error(
+        "Attestation surrounds a previous attestation with source checkpoint {previous_source_checkpoint} and target checkpoint {previous_target_checkpoint}"
+    )]
+    SurroundingVote {
+        previous_source_checkpoint: u64,
+        previous_target_checkpoint: u64,
+    },
+
+    #[error(
+        "Attestation is surrounded by a previous attestation with source checkpoint {previous_source_checkpoint} and target checkpoint {previous_target_checkpoint}"
+    )]
+    SurroundedVote {
+        previous_source_checkpoint: u64,
+        previous_target_checkpoint: u64,
+    },
+
+    #[error("Unsupported interchange format version {0}")]
+    UnsupportedInterchangeVersion(u64),
+
+    #[error("Interchange genesis time {found} does not match the network genesis time {expected}")]
+    GenesisTimeMismatch { expected: u64, found: u64 },
+
+    #[error(
+        "Interchange genesis validators root {found} does not match the network genesis validators root {expected}"
+    )]
+    GenesisValidatorsRootMismatch { expected: B256, found: B256 },
+
+    #[error("Invalid public key in the slashing protection database: {0}")]
+    InvalidPublicKey(String),
+
+    #[error("Redb error: {0}")]
+    Redb(#[from] Box<redb::Error>),
+}
+
+impl From<redb::Error> for SlashingProtectionError {
+    fn from(err: redb::Error) -> Self {
+        SlashingProtectionError::Redb(Box::new(err))
+    }
+}
+
+impl From<redb::SetDurabilityError> for SlashingProtectionError {
+    fn from(err: redb::SetDurabilityError) -> Self {
+        SlashingProtectionError::Redb(Box::new(err.into()))
+    }
+}
+
+impl From<redb::TransactionError> for SlashingProtectionError {
+    fn from(err: redb::TransactionError) -> Self {
+        SlashingProtectionError::Redb(Box::new(err.into()))
+    }
+}
+
+impl From<redb::TableError> for SlashingProtectionError {
+    fn from(err: redb::TableError) -> Self {
+        SlashingProtectionError::Redb(Box::new(err.into()))
+    }
+}
+
+impl From<redb::CommitError> for SlashingProtectionError {
+    fn from(err: redb::CommitError) -> Self {
+        SlashingProtectionError::Redb(Box::new(err.into()))
+    }
+}
+
+impl From<redb::StorageError> for SlashingProtectionError {
+    fn from(err: redb::StorageError) -> Self {
+        SlashingProtectionError::Redb(Box::new(err.into()))
+    }
+}
+
+impl From<redb::DatabaseError> for SlashingProtectionError {
+    fn from(err: redb::DatabaseError) -> Self {
+        SlashingProtectionError::Redb(Box::new(err.into()))
+    }
+}
@@ -0,0 +1,444 @@
+pub mod error;
+
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use alloy_primitives::B256;
+use redb::{Database, Durability, ReadableDatabase, ReadableTable, TableDefinition};
+
+use crate::error::SlashingProtectionError;
+
+/// The EIP-3076 interchange format version this implementation reads and writes.
+pub const INTERCHANGE_FORMAT_VERSION: u64 = 5;
+
+/// A block signed by a validator, the root is `None` if it is unknown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedBlockRecord {
+    pub slot: u64,
+    pub signing_root: Option<B256>,
+}
+
+/// An attestation signed by a validator, the root is `None` if it is unknown.
+///
+/// A validator may sign a single attestation per `vote`: per slot for lean validators, per target
+/// epoch for beacon validators. `source` and `target` are the slots or epochs of the attestation's
+/// checkpoints, and `vote` is never below `target`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedAttestationRecord {
+    pub vote: u64,
+    pub source: u64,
+    pub target: u64,
+    pub signing_root: Option<B256>,
+}
+
+/// Everything a single validator has signed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SigningHistory {
+    pub signed_blocks: Vec<SignedBlockRecord>,
+    pub signed_attestations: Vec<SignedAttestationRecord>,
+}
+
+/// Persistent record of every block and attestation signed by the local validators, which are
+/// identified by `N` bytes.
+///
+/// Every signature must be checked against, and recorded in, this database before it is released,
+/// so a restart or a second instance sharing the same database can never produce a conflicting
+/// message. Checks and inserts happen in one write transaction.
+#[derive(Clone, Debug)]
+pub struct SigningHistoryDB<const N: usize> {
+    db: Arc<Database>,
+    blocks_table: &'static str,
+    attestations_table: &'static str,
+}
+
+impl<const N: usize> SigningHistoryDB<N> {
+    /// Open the database at `path`, creating it and its tables if they don't exist.
+    ///
+    /// Blocks are stored in `blocks_table` keyed by (validator, slot) with the signing root as
+    /// value. Attestations are stored in `attestations_table` keyed by (validator, vote) with
+    /// (source, target, signing_root) as value. Unknown roots are stored as zero.
+    pub fn open(
+        path: &Path,
+        blocks_table: &'static str,
+        attestations_table: &'static str,
+    ) -> Result<Self, SlashingProtectionError> {
+        let db = Self {
+            db: Arc::new(Database::create(path)?),
+            blocks_table,
+            attestations_table,
+        };
+
+        let write_txn = db.db.begin_write()?;
+        write_txn.open_table(db.blocks_table())?;
+        write_txn.open_table(db.attestations_table())?;
+        write_txn.commit()?;
+
+        Ok(db)
+    }
+
+    fn blocks_table(&self) -> TableDefinition<'static, ([u8; N], u64), [u8; 32]> {
+        TableDefinition::new(self.blocks_table)
+    }
+
+    fn attestations_table(&self) -> TableDefinition<'static, ([u8; N], u64), (u64, u64, [u8; 32])> {
+        TableDefinition::new(self.attestations_table)
+    }
+
+    /// Check that signing a block at `slot` with `signing_root` is safe and record it.
+    ///
+    /// Refuses a different block at an already signed slot, and any block below the highest
+    /// signed slot. Re-signing the exact same block is allowed.
+    pub fn check_and_insert_block(
+        &self,
+        validator: [u8; N],
+        slot: u64,
+        signing_root: B256,
+    ) -> Result<(), SlashingProtectionError> {
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate)?;
+        {
+            let mut table = write_txn.open_table(self.blocks_table())?;
+
+            if let Some(existing_root) = table
+                .get((validator, slot))?
+                .map(|guard| B256::from(guard.value()))
+            {
+                if !existing_root.is_zero() && existing_root == signing_root {
+                    return Ok(());
+                }
+                return Err(SlashingProtectionError::DoubleBlockProposal { slot });
+            }
+
+            if let Some((key, _)) = table
+                .range((validator, 0)..=(validator, u64::MAX))?
+                .next_back()
+                .transpose()?
+            {
+                let (_, highest_slot) = key.value();
+                if slot < highest_slot {
+                    return Err(SlashingProtectionError::BlockSlotNotAboveHighest {
+                        slot,
+                        highest_slot,
+                    });
+                }
+            }
+
+            table.insert((validator, slot), signing_root.0)?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// Check that signing an attestation for `vote` from `source` to `target` is safe and record
+    /// it.
+    ///
+    /// Refuses a different attestation for an already signed vote, and any attestation that
+    /// surrounds or is surrounded by a previously signed one. Re-signing the exact same attestation
+    /// is allowed.
+    pub fn check_and_insert_attestation(
+        &self,
+        validator: [u8; N],
+        vote: u64,
+        source: u64,
+        target: u64,
+        signing_root: B256,
+    ) -> Result<(), SlashingProtectionError> {
+        if source > target {
+            return Err(SlashingProtectionError::SourceAfterTarget {
+                source_checkpoint: source,
+                target_checkpoint: target,
+            });
+        }
+
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate)?;
+        {
+            let mut table = write_txn.open_table(self.attestations_table())?;
+
+            if let Some((_, _, existing_root)) =
+                table.get((validator, vote))?.map(|guard| guard.value())
+            {
+                let existing_root = B256::from(existing_root);
+                if !existing_root.is_zero() && existing_root == signing_root {
+                    return Ok(());
+                }
+                return Err(SlashingProtectionError::DoubleVote { vote });
+            }
+
+            // A vote is never below its target, so any attestation that surrounds or is surrounded
+            // by this one was signed for a vote after this source.
+            for entry in
+                table.range((validator, source.saturating_add(1))..=(validator, u64::MAX))?
+            {
+                let (_, value) = entry?;
+                let (previous_source, previous_target, _) = value.value();
+                if source < previous_source && target > previous_target {
+                    return Err(SlashingProtectionError::SurroundingVote {
+                        previous_source_checkpoint: previous_source,
+                        previous_target_checkpoint: previous_target,
+                    });
+                }
+                if source > previous_source && target < previous_target {
+                    return Err(SlashingProtectionError::SurroundedVote {
+                        previous_source_checkpoint: previous_source,
+                        previous_target_checkpoint: previous_target,
+                    });
+                }
+            }
+
+            table.insert((validator, vote), (source, target, signing_root.0))?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// Whether anything signed by `validator` has been recorded.
+    pub fn has_records(&self, validator: [u8; N]) -> Result<bool, SlashingProtectionError> {
+        let read_txn = self.db.begin_read()?;
+        let range = (validator, 0)..=(validator, u64::MAX);
+
+        Ok(read_txn
+            .open_table(self.blocks_table())?
+            .range(range.clone())?
+            .next()
+            .is_some()
+            || read_txn
+                .open_table(self.attestations_table())?
+                .range(range)?
+                .next()
+                .is_some())
+    }
+
+    /// Read the signing history of every validator accepted by `is_selected`.
+    pub fn export(
+        &self,
+        is_selected: impl Fn(&[u8; N]) -> bool,
+    ) -> Result<BTreeMap<[u8; N], SigningHistory>, SlashingProtectionError> {
+        let mut histories = BTreeMap::<[u8; N], SigningHistory>::new();
+
+        let read_txn = self.db.begin_read()?;
+
+        let blocks_table = read_txn.open_table(self.blocks_table())?;
+        for entry in blocks_table.iter()? {
+            let (key, value) = entry?;
+            let (validator, slot) = key.value();
+            if !is_selected(&validator) {
+                continue;
+            }
+            histories
+                .entry(validator)
+                .or_default()
+                .signed_blocks
+                .push(SignedBlockRecord {
+                    slot,
+                    signing_root: known_root(value.value()),
+                });
+        }
+
+        let attestations_table = read_txn.open_table(self.attestations_table())?;
+        for entry in attestations_table.iter()? {
+            let (key, value) = entry?;
+            let (validator, vote) = key.value();
+            if !is_selected(&validator) {
+                continue;
+            }
+            let (source, target, signing_root) = value.value();
+            histories
+                .entry(validator)
+                .or_default()
+                .signed_attestations
+                .push(SignedAttestationRecord {
+                    vote,
+                    source,
+                    target,
+                    signing_root: known_root(signing_root),
+                });
+        }
+
+        Ok(histories)
+    }
+
+    /// Merge signing histories into the database.
+    ///
+    /// Imported records are added next to the existing history. If an imported record conflicts
+    /// with an existing one at the same slot or vote, its root is recorded as unknown, so nothing
+    /// can be signed there anymore.
+    pub fn import<'a>(
+        &self,
+        histories: impl IntoIterator<Item = ([u8; N], &'a SigningHistory)>,
+    ) -> Result<(), SlashingProtectionError> {
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate)?;
+        {
+            let mut blocks_table = write_txn.open_table(self.blocks_table())?;
+            let mut attestations_table = write_txn.open_table(self.attestations_table())?;
+
+            for (validator, history) in histories {
+                for block in &history.signed_blocks {
+                    let key = (validator, block.slot);
+                    let imported_root = block.signing_root.unwrap_or_default().0;
+                    let signing_root = match blocks_table.get(key)?.map(|guard| guard.value()) {
+                        Some(existing_root) if existing_root != imported_root => [0; 32],
+                        _ => imported_root,
+                    };
+                    blocks_table.insert(key, signing_root)?;
+                }
+
+                for attestation in &history.signed_attestations {
+                    let key = (validator, attestation.vote);
+                    let imported = (
+                        attestation.source,
+                        attestation.target,
+                        attestation.signing_root.unwrap_or_default().0,
+                    );
+                    let record = match attestations_table.get(key)?.map(|guard| guard.value()) {
+                        Some(existing) if existing != imported => (existing.0, existing.1, [0; 32]),
+                        _ => imported,
+                    };
+                    attestations_table.insert(key, record)?;
+                }
+            }
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+}
+
+fn known_root(signing_root: [u8; 32]) -> Option<B256> {
+    let signing_root = B256::from(signing_root);
+    (!signing_root.is_zero()).then_some(signing_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use tempdir::TempDir;
+
+    use super::SigningHistoryDB;
+    use crate::error::SlashingProtectionError;
+
+    const VALIDATOR: [u8; 8] = [0; 8];
+
+    fn open_db(name: &str) -> (TempDir, SigningHistoryDB<8>) {
+        let tmp_dir = TempDir::new(name).unwrap();
+        let db = SigningHistoryDB::open(
+            &tmp_dir.path().join("slashing_protection.redb"),
+            "signed_blocks",
+            "signed_attestations",
+        )
+        .unwrap();
+        (tmp_dir, db)
+    }
+
+    #[test]
+    fn test_block_proposals() {
+        let (_tmp_dir, db) = open_db("test_block_proposals");
+
+        db.check_and_insert_block(VALIDATOR, 10, B256::repeat_byte(1))
+            .unwrap();
+        // Re-signing the same block is allowed.
+        db.check_and_insert_block(VALIDATOR, 10, B256::repeat_byte(1))
+            .unwrap();
+
+        assert!(matches!(
+            db.check_and_insert_block(VALIDATOR, 10, B256::repeat_byte(2)),
+            Err(SlashingProtectionError::DoubleBlockProposal { .. })
+        ));
+        assert!(matches!(
+            db.check_and_insert_block(VALIDATOR, 9, B256::repeat_byte(3)),
+            Err(SlashingProtectionError::BlockSlotNotAboveHighest {
+                highest_slot: 10,
+                ..
+            })
+        ));
+
+        // Other validators are tracked separately.
+        db.check_and_insert_block([1; 8], 9, B256::repeat_byte(3))
+            .unwrap();
+        db.check_and_insert_block(VALIDATOR, 11, B256::repeat_byte(4))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_attestations() {
+        let (_tmp_dir, db) = open_db("test_attestations");
+
+        db.check_and_insert_attestation(VALIDATOR, 10, 4, 8, B256::repeat_byte(1))
+            .unwrap();
+        db.check_and_insert_attestation(VALIDATOR, 10, 4, 8, B256::repeat_byte(1))
+            .unwrap();
+
+        assert!(matches!(
+            db.check_and_insert_attestation(VALIDATOR, 10, 4, 8, B256::repeat_byte(2)),
+            Err(SlashingProtectionError::DoubleVote { .. })
+        ));
+        assert!(matches!(
+            db.check_and_insert_attestation(VALIDATOR, 11, 3, 9, B256::repeat_byte(3)),
+            Err(SlashingProtectionError::SurroundingVote { .. })
+        ));
+        assert!(matches!(
+            db.check_and_insert_attestation(VALIDATOR, 11, 5, 7, B256::repeat_byte(4)),
+            Err(SlashingProtectionError::SurroundedVote { .. })
+        ));
+        assert!(matches!(
+            db.check_and_insert_attestation(VALIDATOR, 11, 9, 8, B256::repeat_byte(5)),
+            Err(SlashingProtectionError::SourceAfterTarget { .. })
+        ));
+
+        db.check_and_insert_attestation(VALIDATOR, 11, 4, 8, B256::repeat_byte(6))
+            .unwrap();
+        db.check_and_insert_attestation(VALIDATOR, 12, 8, 12, B256::repeat_byte(7))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_export_import() {
+        let (_source_dir, source) = open_db("test_export_source");
+        source
+            .check_and_insert_block(VALIDATOR, 10, B256::repeat_byte(1))
+            .unwrap();
+        source
+            .check_and_insert_attestation(VALIDATOR, 10, 4, 8, B256::repeat_byte(2))
+            .unwrap();
+        source
+            .check_and_insert_attestation([1; 8], 10, 4, 8, B256::repeat_byte(3))
+            .unwrap();
+
+        let histories = source.export(|_| true).unwrap();
+        assert_eq!(histories.len(), 2);
+
+        let (_destination_dir, destination) = open_db("test_export_destination");
+        destination
+            .check_and_insert_block(VALIDATOR, 10, B256::repeat_byte(4))
+            .unwrap();
+        destination
+            .import(
+                histories
+                    .iter()
+                    .map(|(validator, history)| (*validator, history)),
+            )
+            .unwrap();
+
+        assert!(destination.has_records([1; 8]).unwrap());
+        assert!(!destination.has_records([2; 8]).unwrap());
+        assert_eq!(
+            destination
+                .export(|validator| *validator == [1; 8])
+                .unwrap(),
+            source.export(|validator| *validator == [1; 8]).unwrap()
+        );
+
+        // The conflicting block at slot 10 blocks every root, including both known ones.
+        assert!(matches!(
+            destination.check_and_insert_block(VALIDATOR, 10, B256::repeat_byte(1)),
+            Err(SlashingProtectionError::DoubleBlockProposal { .. })
+        ));
+        assert!(matches!(
+            destination.check_and_insert_block(VALIDATOR, 10, B256::repeat_byte(4)),
+            Err(SlashingProtectionError::DoubleBlockProposal { .. })
+        ));
+        assert!(matches!(
+            destination.check_and_insert_attestation(VALIDATOR, 10, 4, 8, B256::repeat_byte(9)),
+            Err(SlashingProtectionError::DoubleVote { .. })
+        ));
+    }
+}
@@ -8,11 +8,13 @@ pub mod errors;
 pub mod private_key;
 pub mod public_key;
 pub mod signature;
+pub mod signature_set;
 pub mod traits;
 
 pub use private_key::PrivateKey;
 pub use public_key::PublicKey;
 pub use signature::BLSSignature;
+pub use signature_set::SignatureSet;
 
 #[cfg(feature = "supranational")]
 pub mod supranational;
@@ -6,3 +6,29 @@ use tree_hash_derive::TreeHash;
 pub struct PrivateKey {
     pub inner: B256,
 }
+
+/// Both backends must sign alike, so each is checked against the same signature produced by blst.
+#[cfg(all(test, any(feature = "supranational", feature = "zkcrypto")))]
+mod tests {
+    use alloy_primitives::{B256, hex};
+
+    use super::PrivateKey;
+    use crate::traits::Signable;
+
+    #[test]
+    fn test_sign_matches_blst() {
+        // Private keys are big-endian, as in EIP-2335 keystores
+        let private_key = PrivateKey {
+            inner: B256::from(hex!(
+                "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+            )),
+        };
+        let signature = private_key.sign(&[0x42; 32]).unwrap();
+        assert_eq!(
+            signature.inner.to_vec(),
+            hex!(
+                "9651a3d5eb3215e18141b04910b9a3a75312695fa9d12c379282c75f840081f11e122293bc1dc013a85d2fe3cf33ad1700b38e5aeb122bc0159f08084fba34a7c62c2714aaf9b3cc6fd9294705786f7db7a7c42d166bbca5199349f5ebd4c2be"
+            )
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use alloy_primitives::B256;
+
+use crate::{BLSSignature, PublicKey};
+
+/// A signature of `message` by the aggregate of `public_keys`.
+///
+/// Signature sets are collected so that many of them can be verified at once with
+/// [crate::traits::BatchVerifiable::verify_batch].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureSet {
+    pub signature: BLSSignature,
+    pub public_keys: Vec<PublicKey>,
+    pub message: B256,
+}
+
+impl SignatureSet {
+    /// A signature of `message` by a single `public_key`.
+    pub fn single(signature: BLSSignature, public_key: PublicKey, message: B256) -> Self {
+        Self {
+            signature,
+            public_keys: vec![public_key],
+            message,
+        }
+    }
+
+    /// A signature of `message` by the aggregate of `public_keys`.
+    pub fn aggregate(signature: BLSSignature, public_keys: Vec<PublicKey>, message: B256) -> Self {
+        Self {
+            signature,
+            public_keys,
+            message,
+        }
+    }
+}
+
+/// Run against whichever backend is enabled.
+#[cfg(all(test, any(feature = "supranational", feature = "zkcrypto")))]
+mod tests {
+    use alloy_primitives::B256;
+
+    use crate::{
+        BLSSignature, PrivateKey, PublicKey, SignatureSet,
+        traits::{Aggregatable, BatchVerifiable, PublicKeyDerivable, Signable},
+    };
+
+    fn private_key(index: u8) -> PrivateKey {
+        PrivateKey {
+            inner: B256::with_last_byte(index),
+        }
+    }
+
+    /// A signature set for each of the first three keys, and one signed by all three of them.
+    fn signature_sets() -> Vec<SignatureSet> {
+        let private_keys = (1..=3).map(private_key).collect::<Vec<_>>();
+        let mut signature_sets = private_keys
+            .iter()
+            .zip(1..)
+            .map(|(private_key, index)| {
+                let message = B256::repeat_byte(index);
+                SignatureSet::single(
+                    private_key.sign(message.as_slice()).unwrap(),
+                    private_key.public_key().unwrap(),
+                    message,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let message = B256::repeat_byte(0xaa);
+        let signatures = private_keys
+            .iter()
+            .map(|private_key| private_key.sign(message.as_slice()).unwrap())
+            .collect::<Vec<_>>();
+        signature_sets.push(SignatureSet::aggregate(
+            BLSSignature::aggregate(&signatures.iter().collect::<Vec<_>>()).unwrap(),
+            private_keys
+                .iter()
+                .map(|private_key| private_key.public_key().unwrap())
+                .collect::<Vec<PublicKey>>(),
+            message,
+        ));
+        signature_sets
+    }
+
+    fn verify_individually(signature_sets: &[SignatureSet]) -> bool {
+        signature_sets
+            .iter()
+            .all(|signature_set| signature_set.verify().unwrap())
+    }
+
+    #[test]
+    fn test_verify_batch_of_valid_signature_sets() {
+        let signature_sets = signature_sets();
+        assert!(verify_individually(&signature_sets));
+        assert!(SignatureSet::verify_batch(&signature_sets).unwrap());
+        assert!(SignatureSet::verify_batch(&signature_sets[..1]).unwrap());
+        assert!(SignatureSet::verify_batch(&[]).unwrap());
+    }
+
+    #[test]
+    fn test_verify_batch_rejects_one_bad_signature() {
+        for invalid_index in 0..signature_sets().len() {
+            let mut signature_sets = signature_sets();
+            signature_sets[invalid_index].message = B256::ZERO;
+
+            assert!(!signature_sets[invalid_index].verify().unwrap());
+            assert!(!SignatureSet::verify_batch(&signature_sets).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_verify_batch_matches_individual_verification() {
+        // Signatures swapped between two sets are valid in sum, but not individually
+        let mut swapped = signature_sets();
+        let signature = swapped[0].signature.clone();
+        swapped[0].signature = swapped[1].signature.clone();
+        swapped[1].signature = signature;
+
+        let mut without_public_keys = signature_sets();
+        without_public_keys[3].public_keys.clear();
+
+        for signature_sets in [signature_sets(), swapped, without_public_keys] {
+            assert_eq!(
+                SignatureSet::verify_batch(&signature_sets).unwrap(),
+                verify_individually(&signature_sets)
+            );
+        }
+    }
+}
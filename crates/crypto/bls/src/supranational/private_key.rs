@@ -3,10 +3,12 @@ use blst::min_pk::SecretKey as BlstSecretKey;
 use ssz_types::FixedVector;
 
 use crate::{
-    PrivateKey,
+    PrivateKey, PublicKey,
     constants::DST,
     signature::BLSSignature,
-    traits::{Signable, SupranationalSignable},
+    traits::{
+        PublicKeyDerivable, Signable, SupranationalPublicKeyDerivable, SupranationalSignable,
+    },
 };
 
 impl Signable for PrivateKey {
@@ -24,3 +26,15 @@ impl Signable for PrivateKey {
 }
 
 impl SupranationalSignable for PrivateKey {}
+
+impl PublicKeyDerivable for PrivateKey {
+    type Error = anyhow::Error;
+
+    fn public_key(&self) -> Result<PublicKey, Self::Error> {
+        let private_key = BlstSecretKey::from_bytes(self.inner.as_slice())
+            .map_err(|err| anyhow!("Failed to convert to BlstSecretKey: {err:?}"))?;
+        Ok(PublicKey::try_from(private_key.sk_to_pk())?)
+    }
+}
+
+impl SupranationalPublicKeyDerivable for PrivateKey {}
@@ -0,0 +1,87 @@
+use blst::{
+    BLST_ERROR, blst_scalar,
+    min_pk::{AggregatePublicKey as BlstAggregatePublicKey, Signature as BlstSignature},
+};
+use rand::Rng;
+
+use crate::{
+    constants::DST,
+    errors::BLSError,
+    signature_set::SignatureSet,
+    traits::{BatchVerifiable, SupranationalBatchVerifiable, Verifiable},
+};
+
+/// Number of random bits used for the scalar each signature set is multiplied with.
+const RANDOM_BITS: usize = 64;
+
+impl BatchVerifiable for SignatureSet {
+    type Error = BLSError;
+
+    fn verify(&self) -> Result<bool, BLSError> {
+        if self.public_keys.is_empty() {
+            return Ok(false);
+        }
+
+        self.signature.fast_aggregate_verify(
+            self.public_keys.iter().collect::<Vec<_>>(),
+            &self.message[..],
+        )
+    }
+
+    fn verify_batch(signature_sets: &[Self]) -> Result<bool, BLSError> {
+        if signature_sets.is_empty() {
+            return Ok(true);
+        }
+        if signature_sets
+            .iter()
+            .any(|signature_set| signature_set.public_keys.is_empty())
+        {
+            return Ok(false);
+        }
+
+        let signatures = signature_sets
+            .iter()
+            .map(|signature_set| signature_set.signature.to_blst_signature())
+            .collect::<Result<Vec<_>, _>>()?;
+        let public_keys = signature_sets
+            .iter()
+            .map(|signature_set| {
+                let public_keys = signature_set
+                    .public_keys
+                    .iter()
+                    .map(|public_key| public_key.to_blst_public_key())
+                    .collect::<Result<Vec<_>, _>>()?;
+                BlstAggregatePublicKey::aggregate(&public_keys.iter().collect::<Vec<_>>(), false)
+                    .map(|aggregate_public_key| aggregate_public_key.to_public_key())
+                    .map_err(|err| BLSError::BlstError(err.into()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let messages = signature_sets
+            .iter()
+            .map(|signature_set| &signature_set.message[..])
+            .collect::<Vec<_>>();
+
+        let mut rng = rand::rng();
+        let scalars = signature_sets
+            .iter()
+            .map(|_| {
+                let mut scalar = blst_scalar::default();
+                scalar.b[..8].copy_from_slice(&rng.random_range(1..u64::MAX).to_le_bytes());
+                scalar
+            })
+            .collect::<Vec<_>>();
+
+        Ok(BlstSignature::verify_multiple_aggregate_signatures(
+            &messages,
+            DST,
+            &public_keys.iter().collect::<Vec<_>>(),
+            false,
+            &signatures.iter().collect::<Vec<_>>(),
+            true,
+            &scalars,
+            RANDOM_BITS,
+        ) == BLST_ERROR::BLST_SUCCESS)
+    }
+}
+
+impl SupranationalBatchVerifiable for SignatureSet {}
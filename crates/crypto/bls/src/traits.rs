@@ -1,4 +1,4 @@
-use crate::{BLSSignature, PublicKey, errors::BLSError};
+use crate::{BLSSignature, PublicKey, SignatureSet, errors::BLSError};
 
 /// Trait for aggregating BLS public keys.
 ///
@@ -46,6 +46,23 @@ pub trait ZkcryptoSignable: Signable<Error = BLSError> {}
 /// Marker trait for supranational/blst BLS signing implementation
 pub trait SupranationalSignable: Signable<Error = anyhow::Error> {}
 
+/// Trait for deriving the BLS public key of a private key.
+pub trait PublicKeyDerivable {
+    type Error;
+
+    /// Derives the public key of the private key.
+    ///
+    /// # Returns
+    /// * `Result<PublicKey, Self::Error>` - The public key or an error
+    fn public_key(&self) -> Result<PublicKey, Self::Error>;
+}
+
+/// Marker trait for zkcrypto/bls12_381 public key derivation implementation
+pub trait ZkcryptoPublicKeyDerivable: PublicKeyDerivable<Error = BLSError> {}
+
+/// Marker trait for supranational/blst public key derivation implementation
+pub trait SupranationalPublicKeyDerivable: PublicKeyDerivable<Error = anyhow::Error> {}
+
 /// Trait for verifying BLS signatures.
 ///
 /// This trait provides functionality to verify both individual and aggregate BLS signatures
@@ -89,3 +106,32 @@ pub trait ZkcryptoVerifiable: Verifiable<Error = BLSError> {}
 
 /// Marker trait for supranational/blst BLS signature verification implementation
 pub trait SupranationalVerifiable: Verifiable<Error = BLSError> {}
+
+/// Trait for verifying [SignatureSet]s, either one at a time or many at once.
+pub trait BatchVerifiable: Sized {
+    type Error;
+
+    /// Verifies a single signature set, like [Verifiable::fast_aggregate_verify].
+    ///
+    /// # Returns
+    /// * `Result<bool, BLSError>` - Ok(true) if the signature is valid, Ok(false) if verification
+    ///   fails, or Err if there are issues with signature or public key bytes
+    fn verify(&self) -> Result<bool, Self::Error>;
+
+    /// Verifies all of `signature_sets` at once.
+    ///
+    /// Every set is multiplied by a random scalar before the sets are combined, so a batch
+    /// containing an invalid set fails verification, except with negligible probability. A failed
+    /// batch doesn't tell which sets are invalid, so callers check them one at a time to find out.
+    ///
+    /// # Returns
+    /// * `Result<bool, BLSError>` - Ok(true) if every signature set is valid, Ok(false) if any of
+    ///   them isn't, or Err if there are issues with signature or public key bytes
+    fn verify_batch(signature_sets: &[Self]) -> Result<bool, Self::Error>;
+}
+
+/// Marker trait for zkcrypto/bls12_381 BLS batch verification implementation
+pub trait ZkcryptoBatchVerifiable: BatchVerifiable<Error = BLSError> {}
+
+/// Marker trait for supranational/blst BLS batch verification implementation
+pub trait SupranationalBatchVerifiable: BatchVerifiable<Error = BLSError> {}
@@ -1,3 +1,4 @@
 pub mod private_key;
 pub mod public_key;
 pub mod signature;
+pub mod signature_set;
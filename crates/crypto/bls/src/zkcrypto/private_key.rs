@@ -1,19 +1,33 @@
 use anyhow::anyhow;
 use bls12_381::{
-    G2Projective, Scalar,
+    G1Projective, G2Projective, Scalar,
     hash_to_curve::{ExpandMsgXmd, HashToCurve},
 };
 use group::Curve;
 use ssz_types::FixedVector;
 
 use crate::{
-    PrivateKey,
+    PrivateKey, PublicKey,
     constants::DST,
     errors::BLSError,
     signature::BLSSignature,
-    traits::{Signable, ZkcryptoSignable},
+    traits::{PublicKeyDerivable, Signable, ZkcryptoPublicKeyDerivable, ZkcryptoSignable},
 };
 
+impl PrivateKey {
+    /// The scalar of the private key, which is stored big-endian as in EIP-2335 keystores while
+    /// [Scalar::from_bytes] reads little-endian bytes.
+    fn to_scalar(&self) -> Result<Scalar, BLSError> {
+        let mut bytes: [u8; 32] = self.inner.0;
+        bytes.reverse();
+        Scalar::from_bytes(&bytes)
+            .into_option()
+            .ok_or(BLSError::InvalidPrivateKey(anyhow!(
+                "Failed to convert inner to scalar"
+            )))
+    }
+}
+
 impl Signable for PrivateKey {
     type Error = BLSError;
 
@@ -23,12 +37,7 @@ impl Signable for PrivateKey {
             DST,
         );
 
-        let scalar = Scalar::from_bytes(self.inner.as_ref())
-            .into_option()
-            .ok_or(BLSError::InvalidPrivateKey(anyhow!(
-                "Failed to convert inner to scalar"
-            )))?;
-        let signature_point = hash_point * scalar;
+        let signature_point = hash_point * self.to_scalar()?;
         let signature_bytes = signature_point.to_affine().to_compressed();
 
         Ok(BLSSignature {
@@ -40,3 +49,15 @@ impl Signable for PrivateKey {
 }
 
 impl ZkcryptoSignable for PrivateKey {}
+
+impl PublicKeyDerivable for PrivateKey {
+    type Error = BLSError;
+
+    fn public_key(&self) -> Result<PublicKey, Self::Error> {
+        Ok(PublicKey::from(
+            G1Projective::generator() * self.to_scalar()?,
+        ))
+    }
+}
+
+impl ZkcryptoPublicKeyDerivable for PrivateKey {}
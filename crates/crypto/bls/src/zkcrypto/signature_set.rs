@@ -0,0 +1,75 @@
+use bls12_381::{
+    G1Affine, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
+    hash_to_curve::{ExpandMsgXmd, HashToCurve},
+    multi_miller_loop,
+};
+use rand::Rng;
+
+use crate::{
+    PublicKey,
+    constants::DST,
+    errors::BLSError,
+    signature_set::SignatureSet,
+    traits::{Aggregatable, BatchVerifiable, Verifiable, ZkcryptoBatchVerifiable},
+};
+
+impl BatchVerifiable for SignatureSet {
+    type Error = BLSError;
+
+    fn verify(&self) -> Result<bool, BLSError> {
+        if self.public_keys.is_empty() {
+            return Ok(false);
+        }
+
+        self.signature.fast_aggregate_verify(
+            self.public_keys.iter().collect::<Vec<_>>(),
+            &self.message[..],
+        )
+    }
+
+    /// Checks `e(g1, sum(r_i * sig_i)) == prod(e(r_i * pk_i, H(m_i)))` with a random `r_i` for
+    /// every signature set, using a single final exponentiation.
+    fn verify_batch(signature_sets: &[Self]) -> Result<bool, BLSError> {
+        if signature_sets.is_empty() {
+            return Ok(true);
+        }
+        if signature_sets
+            .iter()
+            .any(|signature_set| signature_set.public_keys.is_empty())
+        {
+            return Ok(false);
+        }
+
+        let mut rng = rand::rng();
+        let mut aggregate_signature = G2Projective::identity();
+        let mut terms = Vec::with_capacity(signature_sets.len() + 1);
+        for signature_set in signature_sets {
+            let scalar = Scalar::from(rng.random_range(1..u64::MAX));
+            let public_key =
+                PublicKey::aggregate(&signature_set.public_keys.iter().collect::<Vec<_>>())?;
+            let h = <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(
+                [&signature_set.message[..]],
+                DST,
+            );
+
+            aggregate_signature +=
+                G2Projective::from(G2Affine::try_from(&signature_set.signature)?) * scalar;
+            terms.push((
+                G1Affine::from(G1Affine::try_from(&public_key)? * scalar),
+                G2Prepared::from(G2Affine::from(h)),
+            ));
+        }
+        terms.push((
+            -G1Affine::generator(),
+            G2Prepared::from(G2Affine::from(aggregate_signature)),
+        ));
+
+        let terms = terms
+            .iter()
+            .map(|(public_key, message)| (public_key, message))
+            .collect::<Vec<_>>();
+        Ok(multi_miller_loop(&terms).final_exponentiation() == Gt::identity())
+    }
+}
+
+impl ZkcryptoBatchVerifiable for SignatureSet {}
@@ -0,0 +1,57 @@
+use anyhow::anyhow;
+use argon2::{Algorithm, Argon2, Params, Version};
+
+/// Length of the derived key, which is used as an AES-256 key.
+pub const ARGON2ID_KEY_LENGTH: usize = 32;
+
+// Based on https://www.rfc-editor.org/rfc/rfc9106
+pub fn argon2id(
+    password: &[u8],
+    salt: &[u8],
+    memory_cost: u32,
+    time_cost: u32,
+    parallelism: u32,
+) -> anyhow::Result<Vec<u8>> {
+    let params = Params::new(
+        memory_cost,
+        time_cost,
+        parallelism,
+        Some(ARGON2ID_KEY_LENGTH),
+    )
+    .map_err(|err| anyhow!("Invalid argon2id parameters: {err}"))?;
+
+    let mut derived_key = vec![0; ARGON2ID_KEY_LENGTH];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password, salt, &mut derived_key)
+        .map_err(|err| anyhow!("Failed to derive argon2id key: {err}"))?;
+    Ok(derived_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ARGON2ID_KEY_LENGTH, argon2id};
+
+    #[test]
+    fn test_argon2id_depends_on_password_and_salt() {
+        let derived_key = argon2id(b"password", &[0x02; 16], 32, 3, 4).unwrap();
+
+        assert_eq!(derived_key.len(), ARGON2ID_KEY_LENGTH);
+        assert_eq!(
+            derived_key,
+            argon2id(b"password", &[0x02; 16], 32, 3, 4).unwrap()
+        );
+        assert_ne!(
+            derived_key,
+            argon2id(b"password", &[0x03; 16], 32, 3, 4).unwrap()
+        );
+        assert_ne!(
+            derived_key,
+            argon2id(b"passwort", &[0x02; 16], 32, 3, 4).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_argon2id_rejects_invalid_parameters() {
+        argon2id(b"password", &[0x02; 16], 32, 0, 4).unwrap_err();
+    }
+}
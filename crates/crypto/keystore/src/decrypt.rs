@@ -2,6 +2,11 @@ use aes::{
     Aes128,
     cipher::{BlockEncrypt, KeyInit, generic_array::GenericArray},
 };
+use aes_gcm::{Aes256Gcm, Nonce, Tag, aead::AeadInPlace};
+use anyhow::{anyhow, ensure};
+
+/// Length of the AES-256-GCM initialization vector.
+pub const AES256_GCM_IV_LENGTH: usize = 12;
 
 pub fn aes128_ctr(buffer: &mut [u8], key: [u8; 16], initial_vector: &[u8; 16]) {
     let cipher = Aes128::new(&key.into());
@@ -16,3 +21,41 @@ pub fn aes128_ctr(buffer: &mut [u8], key: [u8; 16], initial_vector: &[u8; 16]) {
         counter = counter.wrapping_add(1);
     }
 }
+
+/// Encrypt `buffer` in place, returning the authentication tag.
+pub fn aes256_gcm_encrypt(
+    buffer: &mut [u8],
+    key: [u8; 32],
+    initial_vector: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    ensure!(
+        initial_vector.len() == AES256_GCM_IV_LENGTH,
+        "AES-256-GCM IV must be {AES256_GCM_IV_LENGTH} bytes"
+    );
+    let tag = Aes256Gcm::new(&key.into())
+        .encrypt_in_place_detached(Nonce::from_slice(initial_vector), &[], buffer)
+        .map_err(|err| anyhow!("Failed to encrypt: {err}"))?;
+    Ok(tag.to_vec())
+}
+
+/// Decrypt `buffer` in place, failing if it doesn't match the authentication `tag`.
+pub fn aes256_gcm_decrypt(
+    buffer: &mut [u8],
+    key: [u8; 32],
+    initial_vector: &[u8],
+    tag: &[u8],
+) -> anyhow::Result<()> {
+    ensure!(
+        initial_vector.len() == AES256_GCM_IV_LENGTH,
+        "AES-256-GCM IV must be {AES256_GCM_IV_LENGTH} bytes"
+    );
+    ensure!(tag.len() == 16, "AES-256-GCM tag must be 16 bytes");
+    Aes256Gcm::new(&key.into())
+        .decrypt_in_place_detached(
+            Nonce::from_slice(initial_vector),
+            &[],
+            buffer,
+            Tag::from_slice(tag),
+        )
+        .map_err(|_| anyhow!("Password provided is invalid or the keystore is corrupted!"))
+}
@@ -0,0 +1,176 @@
+use alloy_primitives::{B256, U512, hex};
+use anyhow::{anyhow, ensure};
+use sha2::{Digest, Sha256};
+
+use crate::hmac::hmac_sha_256;
+
+/// Order of the BLS12-381 curve, which private keys are reduced by.
+const CURVE_ORDER: [u8; 32] =
+    hex!("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001");
+
+/// Purpose of the EIP-2334 paths of validator keys.
+const PURPOSE: u32 = 12381;
+/// Coin type of the EIP-2334 paths of validator keys.
+const COIN_TYPE: u32 = 3600;
+
+/// The EIP-2334 path of the signing key of validator `index`.
+pub fn signing_key_path(index: u32) -> String {
+    format!("m/{PURPOSE}/{COIN_TYPE}/{index}/0/0")
+}
+
+/// The EIP-2334 path of the withdrawal key of validator `index`.
+pub fn withdrawal_key_path(index: u32) -> String {
+    format!("m/{PURPOSE}/{COIN_TYPE}/{index}/0")
+}
+
+/// Derive the private key at `path` from the BIP39 `seed`, as specified in EIP-2333.
+pub fn derive_private_key(seed: &[u8], path: &str) -> anyhow::Result<B256> {
+    let mut indices = path.split('/');
+    ensure!(
+        indices.next() == Some("m"),
+        "Key path must start with 'm': {path}"
+    );
+
+    indices.try_fold(derive_master_private_key(seed)?, |parent, index| {
+        let index = index
+            .parse::<u32>()
+            .map_err(|err| anyhow!("Invalid index {index} in key path {path}: {err}"))?;
+        Ok(derive_child_private_key(parent, index))
+    })
+}
+
+/// Derive the master private key from the BIP39 `seed`.
+pub fn derive_master_private_key(seed: &[u8]) -> anyhow::Result<B256> {
+    ensure!(
+        seed.len() >= 32,
+        "Seed must be at least 32 bytes, got {}",
+        seed.len()
+    );
+    Ok(hkdf_mod_r(seed))
+}
+
+/// Derive the child private key at `index` of the `parent` private key.
+pub fn derive_child_private_key(parent: B256, index: u32) -> B256 {
+    hkdf_mod_r(parent_to_lamport_public_key(parent, index).as_slice())
+}
+
+fn hkdf_extract(salt: &[u8], input_key_material: &[u8]) -> B256 {
+    hmac_sha_256(salt, input_key_material)
+}
+
+fn hkdf_expand(pseudo_random_key: B256, info: &[u8], length: usize) -> Vec<u8> {
+    let mut output_key_material = Vec::with_capacity(length + 32);
+    let mut block: Vec<u8> = vec![];
+    let mut counter = 1u8;
+    while output_key_material.len() < length {
+        block = hmac_sha_256(
+            pseudo_random_key.as_slice(),
+            &[block.as_slice(), info, &[counter]].concat(),
+        )
+        .to_vec();
+        output_key_material.extend_from_slice(&block);
+        counter += 1;
+    }
+    output_key_material.truncate(length);
+    output_key_material
+}
+
+fn hkdf_mod_r(input_key_material: &[u8]) -> B256 {
+    // ceil((3 * ceil(log2(r))) / 16)
+    const LENGTH: usize = 48;
+
+    let curve_order = U512::from_be_slice(&CURVE_ORDER);
+    let mut salt = b"BLS-SIG-KEYGEN-SALT-".to_vec();
+    loop {
+        salt = Sha256::digest(&salt).to_vec();
+        let pseudo_random_key = hkdf_extract(&salt, &[input_key_material, &[0]].concat());
+        let output_key_material =
+            hkdf_expand(pseudo_random_key, &(LENGTH as u16).to_be_bytes(), LENGTH);
+        let private_key = U512::from_be_slice(&output_key_material) % curve_order;
+        if !private_key.is_zero() {
+            return B256::from_slice(&private_key.to_be_bytes::<64>()[32..]);
+        }
+    }
+}
+
+fn input_key_material_to_lamport_private_key(
+    input_key_material: &[u8],
+    salt: &[u8],
+) -> Vec<[u8; 32]> {
+    let pseudo_random_key = hkdf_extract(salt, input_key_material);
+    hkdf_expand(pseudo_random_key, &[], 32 * 255)
+        .chunks_exact(32)
+        .map(|chunk| chunk.try_into().expect("Chunks are 32 bytes"))
+        .collect()
+}
+
+fn parent_to_lamport_public_key(parent: B256, index: u32) -> B256 {
+    let salt = index.to_be_bytes();
+    let flipped_parent = parent.0.map(|byte| !byte);
+    let lamport_public_key = input_key_material_to_lamport_private_key(parent.as_slice(), &salt)
+        .into_iter()
+        .chain(input_key_material_to_lamport_private_key(
+            flipped_parent.as_slice(),
+            &salt,
+        ))
+        .flat_map(|lamport_private_key| Sha256::digest(lamport_private_key))
+        .collect::<Vec<_>>();
+    B256::from_slice(&Sha256::digest(lamport_public_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{U256, hex};
+
+    use super::{derive_child_private_key, derive_master_private_key, derive_private_key};
+
+    // Test vectors of EIP-2333
+    #[test]
+    fn test_eip2333_vectors() {
+        let cases = [
+            (
+                hex!(
+                    "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+                )
+                .to_vec(),
+                "6083874454709270928345386274498605044986640685124978867557563392430687146096",
+                0,
+                "20397789859736650942317412262472558107875392172444076792671091975210932703118",
+            ),
+            (
+                hex!("3141592653589793238462643383279502884197169399375105820974944592").to_vec(),
+                "29757020647961307431480504535336562678282505419141012933316116377660817309383",
+                3141592653,
+                "25457201688850691947727629385191704516744796114925897962676248250929345014287",
+            ),
+        ];
+
+        for (seed, master_private_key, child_index, child_private_key) in cases {
+            let master = derive_master_private_key(&seed).unwrap();
+            assert_eq!(
+                U256::from_be_bytes(master.0),
+                master_private_key.parse::<U256>().unwrap()
+            );
+
+            let child = derive_child_private_key(master, child_index);
+            assert_eq!(
+                U256::from_be_bytes(child.0),
+                child_private_key.parse::<U256>().unwrap()
+            );
+
+            assert_eq!(
+                derive_private_key(&seed, &format!("m/{child_index}")).unwrap(),
+                child
+            );
+        }
+    }
+
+    #[test]
+    fn test_derive_private_key_rejects_invalid_paths() {
+        let seed = [0x42; 32];
+
+        derive_private_key(&seed, "12381/3600/0/0/0").unwrap_err();
+        derive_private_key(&seed, "m/12381/x/0").unwrap_err();
+        derive_master_private_key(&seed[..16]).unwrap_err();
+    }
+}
@@ -1,14 +1,32 @@
 use std::fs;
 
 use alloy_primitives::B256;
-use anyhow::{Result, anyhow, ensure};
+use anyhow::{Result, anyhow, bail, ensure};
 use rand;
 use ream_bls::{PrivateKey, PublicKey};
+use ream_post_quantum_crypto::leansig::{
+    private_key::PrivateKey as PostQuantumPrivateKey, public_key::PublicKey as PostQuantumPublicKey,
+};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-use crate::{decrypt::aes128_ctr, hex_serde, pbkdf2::pbkdf2, scrypt::scrypt};
+use crate::{
+    argon2id::argon2id,
+    decrypt::{AES256_GCM_IV_LENGTH, aes128_ctr, aes256_gcm_decrypt, aes256_gcm_encrypt},
+    hex_serde,
+    pbkdf2::pbkdf2,
+    scrypt::scrypt,
+};
+
+/// Argon2id parameters of newly created version 5 keystores: 64 MiB of memory, 4 passes and 2
+/// lanes.
+pub const ARGON2ID_MEMORY_COST: u32 = 65536;
+pub const ARGON2ID_TIME_COST: u32 = 4;
+pub const ARGON2ID_PARALLELISM: u32 = 2;
+
+/// Number of active epochs of keys generated by the account manager.
+pub const DEFAULT_NUM_ACTIVE_EPOCHS: u32 = 1 << 18;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct EncryptedKeystore<P = PublicKey, C = CryptoV4> {
@@ -42,6 +60,51 @@ where
 }
 
 impl EncryptedKeystore {
+    /// Create a version 4 keystore holding `private_key` encrypted with AES-128-CTR, using a key
+    /// derived from `password` with `kdf_params`.
+    pub fn encrypt(
+        private_key: &PrivateKey,
+        public_key: PublicKey,
+        password: &[u8],
+        kdf_params: KdfParams,
+        path: String,
+    ) -> Result<Self> {
+        let derived_key = kdf_params.derive_key(password)?;
+        ensure!(
+            derived_key.len() >= 32,
+            "Derived key must be at least 32 bytes"
+        );
+        let key: [u8; 16] = derived_key[0..16]
+            .try_into()
+            .map_err(|err| anyhow!("Failed to convert derived key into 16 byte array: {err:?}"))?;
+        let iv = rand::random::<[u8; 16]>();
+        let mut message = private_key.inner.to_vec();
+        aes128_ctr(&mut message, key, &iv);
+        let checksum = Sha256::digest([&derived_key[16..32], &message].concat());
+
+        Ok(EncryptedKeystore {
+            crypto: CryptoV4 {
+                kdf: FunctionBlock {
+                    params: kdf_params,
+                    message: vec![],
+                },
+                checksum: FunctionBlock {
+                    params: ChecksumParams::Sha256 {},
+                    message: checksum.to_vec(),
+                },
+                cipher: FunctionBlock {
+                    params: CipherParams::Aes128Ctr { iv: iv.to_vec() },
+                    message,
+                },
+            },
+            description: String::new(),
+            public_key,
+            path,
+            uuid: Uuid::new_v4().to_string(),
+            version: 4,
+        })
+    }
+
     pub fn validate_password(&self, password: &[u8]) -> anyhow::Result<bool> {
         let derived_key = self.crypto.kdf.params.derive_key(password)?;
         let derived_key_slice = &derived_key[16..32];
@@ -74,7 +137,9 @@ impl EncryptedKeystore {
                 })?;
                 aes128_ctr(private_key.inner.as_mut_slice(), key_param, iv_param);
             }
-            CipherParams::Aes256Gcm { .. } => todo!(),
+            CipherParams::Aes256Gcm { .. } => {
+                bail!("AES-256-GCM is only supported by version 5 keystores")
+            }
         };
         Ok(Keystore {
             public_key: self.public_key.clone(),
@@ -83,49 +148,60 @@ impl EncryptedKeystore {
     }
 }
 
-impl EncryptedKeystore<PublicKey, CryptoV5> {
-    /// Create a new keystore from seed phrase and key parameters
-    pub fn from_seed_phrase(
-        seed_phrase: &str,
-        lifetime: u32,
-        activation_epoch: u32,
+impl EncryptedKeystore<PostQuantumPublicKey, CryptoV5> {
+    /// Create a keystore holding `seed` encrypted with `password`. `public_key` is the public key
+    /// generated from the seed with the activation range of `key_type`.
+    pub fn from_seed(
+        seed: [u8; 32],
+        public_key: PostQuantumPublicKey,
+        password: &[u8],
+        key_type: KeyTypeParams,
         description: Option<String>,
         path: Option<String>,
-    ) -> Self {
-        EncryptedKeystore {
-            crypto: CryptoV5 {
-                kdf: FunctionBlock {
-                    params: KdfParams::Argon2Id {
-                        m: 65536,
-                        t: 4,
-                        p: 2,
-                        salt: rand::random::<[u8; 32]>().to_vec(),
-                    },
-                    message: vec![], // Empty message
-                },
-                cipher: FunctionBlock {
-                    params: CipherParams::Aes256Gcm {
-                        iv: rand::random::<[u8; 12]>().to_vec(),
-                        tag: rand::random::<[u8; 16]>().to_vec(),
-                    },
-                    // TODO: actually encrypt the seed phrase
-                    message: seed_phrase.as_bytes().to_vec(),
-                },
-                keytype: FunctionBlock {
-                    params: KeyTypeParams::XmssPoseidon2OtsSeed {
-                        lifetime,
-                        activation_epoch,
-                    },
-                    message: vec![],
-                },
-            },
+    ) -> Result<Self> {
+        let kdf_params = KdfParams::Argon2Id {
+            m: ARGON2ID_MEMORY_COST,
+            t: ARGON2ID_TIME_COST,
+            p: ARGON2ID_PARALLELISM,
+            salt: rand::random::<[u8; 32]>().to_vec(),
+        };
+        Ok(EncryptedKeystore {
+            crypto: CryptoV5::encrypt(&seed, password, kdf_params, key_type)?,
             description: description.unwrap_or_default(),
-            // TODO: derive the public key from the seed phrase
-            public_key: PublicKey::default(),
+            public_key,
             path: path.unwrap_or_default(),
             uuid: Uuid::new_v4().to_string(),
             version: 5,
-        }
+        })
+    }
+
+    /// Decrypt the seed of the keystore.
+    pub fn decrypt_seed(&self, password: &[u8]) -> Result<[u8; 32]> {
+        self.crypto
+            .decrypt(password)?
+            .try_into()
+            .map_err(|seed: Vec<u8>| anyhow!("Seed must be 32 bytes, got {}", seed.len()))
+    }
+
+    /// Decrypt the seed and regenerate the private key from it, checking that it belongs to the
+    /// public key of the keystore.
+    pub fn decrypt(&self, password: &[u8]) -> Result<PostQuantumPrivateKey> {
+        let seed = self.decrypt_seed(password)?;
+        let KeyTypeParams::XmssPoseidon2OtsSeed {
+            activation_epoch,
+            num_active_epochs,
+            ..
+        } = self.crypto.keytype.params;
+        let (public_key, private_key) = PostQuantumPrivateKey::generate_key_pair_from_seed(
+            seed,
+            activation_epoch as usize,
+            num_active_epochs as usize,
+        );
+        ensure!(
+            public_key == self.public_key,
+            "Key generated from the keystore seed doesn't match its public key"
+        );
+        Ok(private_key)
     }
 }
 
@@ -143,6 +219,48 @@ pub struct CryptoV5 {
     pub keytype: FunctionBlock<KeyTypeParams>,
 }
 
+impl CryptoV5 {
+    /// Encrypt `secret` with AES-256-GCM, using a key derived from `password` with `kdf_params`.
+    pub fn encrypt(
+        secret: &[u8],
+        password: &[u8],
+        kdf_params: KdfParams,
+        key_type: KeyTypeParams,
+    ) -> Result<Self> {
+        let key = kdf_params.derive_aes256_key(password)?;
+        let iv = rand::random::<[u8; AES256_GCM_IV_LENGTH]>().to_vec();
+        let mut message = secret.to_vec();
+        let tag = aes256_gcm_encrypt(&mut message, key, &iv)?;
+
+        Ok(CryptoV5 {
+            kdf: FunctionBlock {
+                params: kdf_params,
+                message: vec![],
+            },
+            cipher: FunctionBlock {
+                params: CipherParams::Aes256Gcm { iv, tag },
+                message,
+            },
+            keytype: FunctionBlock {
+                params: key_type,
+                message: vec![],
+            },
+        })
+    }
+
+    /// Decrypt the secret. The authentication tag rejects a wrong password or a modified
+    /// keystore.
+    pub fn decrypt(&self, password: &[u8]) -> Result<Vec<u8>> {
+        let CipherParams::Aes256Gcm { iv, tag } = &self.cipher.params else {
+            bail!("Version 5 keystores must use AES-256-GCM");
+        };
+        let key = self.kdf.params.derive_aes256_key(password)?;
+        let mut secret = self.cipher.message.clone();
+        aes256_gcm_decrypt(&mut secret, key, iv, tag)?;
+        Ok(secret)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct FunctionBlock<ParamType> {
     #[serde(flatten)]
@@ -179,6 +297,27 @@ pub enum KdfParams {
 }
 
 impl KdfParams {
+    /// Scrypt with the parameters recommended by EIP-2335 and a random salt.
+    pub fn new_scrypt() -> Self {
+        KdfParams::Scrypt {
+            dklen: 32,
+            n: 262144,
+            p: 1,
+            r: 8,
+            salt: rand::random::<[u8; 32]>().to_vec(),
+        }
+    }
+
+    /// PBKDF2 with the parameters recommended by EIP-2335 and a random salt.
+    pub fn new_pbkdf2() -> Self {
+        KdfParams::Pbkdf2 {
+            c: 262144,
+            dklen: 32,
+            prf: Prf::HmacSha256,
+            salt: rand::random::<[u8; 32]>().to_vec(),
+        }
+    }
+
     pub fn derive_key(&self, password: &[u8]) -> anyhow::Result<Vec<u8>> {
         match self {
             KdfParams::Pbkdf2 {
@@ -194,9 +333,17 @@ impl KdfParams {
                 dklen,
                 salt,
             } => scrypt(password, salt, *n, *p, *r, *dklen),
-            KdfParams::Argon2Id { .. } => todo!(),
+            KdfParams::Argon2Id { m, t, p, salt } => argon2id(password, salt, *m, *t, *p),
         }
     }
+
+    fn derive_aes256_key(&self, password: &[u8]) -> anyhow::Result<[u8; 32]> {
+        let derived_key = self.derive_key(password)?;
+        derived_key
+            .get(..32)
+            .and_then(|key| key.try_into().ok())
+            .ok_or_else(|| anyhow!("Derived key must be at least 32 bytes for AES-256"))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -234,12 +381,21 @@ pub enum KeyTypeParams {
     XmssPoseidon2OtsSeed {
         lifetime: u32,
         activation_epoch: u32,
+        /// Keystores written before the active range was recorded were generated with the
+        /// default of the account manager
+        #[serde(default = "default_num_active_epochs")]
+        num_active_epochs: u32,
     },
 }
 
+fn default_num_active_epochs() -> u32 {
+    DEFAULT_NUM_ACTIVE_EPOCHS
+}
+
 #[cfg(test)]
 mod tests {
     use alloy_primitives::hex;
+    use ream_bls::traits::PublicKeyDerivable;
     use serde_json;
     use ssz_types::FixedVector;
 
@@ -309,6 +465,7 @@ mod tests {
                     params: KeyTypeParams::XmssPoseidon2OtsSeed {
                         lifetime: 100000,
                         activation_epoch: 10,
+                        num_active_epochs: 1024,
                     },
                     message: vec![],
                 },
@@ -322,7 +479,7 @@ mod tests {
             version: 5,
         };
 
-        let keystore_as_string = r#"{"crypto":{"kdf":{"function":"argon2id","params":{"m":65536,"t":4,"p":2,"salt":"12345678"},"message":"90abcdef"},"cipher":{"function":"aes-256-gcm","params":{"iv":"aabbccdd","tag":"deadbeef"},"message":"11223344"},"keytype":{"function":"xmss-poseidon2-ots-seed","params":{"lifetime":100000,"activation_epoch":10},"message":""}},"description":"","pubkey":"0x121212121212121212121212121212121212121212121212121212121212121212121212121212121212121212121212","path":"","uuid":"123e4567-e89b-12d3-a456-426614174000","version":5}"#;
+        let keystore_as_string = r#"{"crypto":{"kdf":{"function":"argon2id","params":{"m":65536,"t":4,"p":2,"salt":"12345678"},"message":"90abcdef"},"cipher":{"function":"aes-256-gcm","params":{"iv":"aabbccdd","tag":"deadbeef"},"message":"11223344"},"keytype":{"function":"xmss-poseidon2-ots-seed","params":{"lifetime":100000,"activation_epoch":10,"num_active_epochs":1024},"message":""}},"description":"","pubkey":"0x121212121212121212121212121212121212121212121212121212121212121212121212121212121212121212121212","path":"","uuid":"123e4567-e89b-12d3-a456-426614174000","version":5}"#;
 
         let serialized = serde_json::to_string(&keystore).expect("Failed to serialize keystore");
         assert_eq!(serialized, keystore_as_string);
@@ -410,6 +567,8 @@ mod tests {
                     params: KeyTypeParams::XmssPoseidon2OtsSeed {
                         lifetime: 32,
                         activation_epoch: 28999934,
+                        // The fixture predates `num_active_epochs`, so it takes the default
+                        num_active_epochs: DEFAULT_NUM_ACTIVE_EPOCHS,
                     },
                     message: vec![], // Empty message
                 },
@@ -492,4 +651,114 @@ mod tests {
             private_key
         );
     }
+
+    fn test_key_type() -> KeyTypeParams {
+        KeyTypeParams::XmssPoseidon2OtsSeed {
+            lifetime: 32,
+            activation_epoch: 0,
+            num_active_epochs: 10,
+        }
+    }
+
+    #[test]
+    fn encrypt_decrypt_v5() {
+        let secret = [0x07; 32];
+        let kdf_params = KdfParams::Argon2Id {
+            m: 256,
+            t: 1,
+            p: 1,
+            salt: vec![0x42; 32],
+        };
+        let mut crypto =
+            CryptoV5::encrypt(&secret, b"password123", kdf_params, test_key_type()).unwrap();
+
+        assert_ne!(crypto.cipher.message, secret);
+        assert_eq!(crypto.decrypt(b"password123").unwrap(), secret);
+        assert!(crypto.decrypt(b"password124").is_err());
+
+        crypto.cipher.message[0] ^= 1;
+        assert!(crypto.decrypt(b"password123").is_err());
+    }
+
+    #[test]
+    fn decrypt_v5_from_seed() {
+        let seed = [0x07; 32];
+        let (public_key, _) = PostQuantumPrivateKey::generate_key_pair_from_seed(seed, 0, 10);
+        let keystore = EncryptedKeystore::from_seed(
+            seed,
+            public_key,
+            b"password123",
+            test_key_type(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let keystore_as_string = serde_json::to_string(&keystore).unwrap();
+        let mut keystore: EncryptedKeystore<PostQuantumPublicKey, CryptoV5> =
+            serde_json::from_str(&keystore_as_string).unwrap();
+
+        assert_eq!(keystore.decrypt_seed(b"password123").unwrap(), seed);
+        keystore.decrypt(b"password123").unwrap();
+        assert!(keystore.decrypt(b"password124").is_err());
+
+        keystore.public_key =
+            PostQuantumPrivateKey::generate_key_pair_from_seed([0x08; 32], 0, 10).0;
+        assert!(keystore.decrypt(b"password123").is_err());
+    }
+
+    #[test]
+    fn encrypt_decrypt_v4() {
+        let encrypted_keystore =
+            EncryptedKeystore::load_from_file("./assets/Pbkdf2TestKeystore.json").unwrap();
+        let password = hex!("7465737470617373776f7264f09f9491");
+        let keystore = encrypted_keystore.decrypt(&password).unwrap();
+        assert_eq!(
+            keystore.private_key.public_key().unwrap(),
+            encrypted_keystore.public_key
+        );
+
+        for kdf_params in [
+            KdfParams::Pbkdf2 {
+                c: 16,
+                dklen: 32,
+                prf: Prf::HmacSha256,
+                salt: vec![0x42; 32],
+            },
+            KdfParams::Scrypt {
+                dklen: 32,
+                n: 16,
+                p: 1,
+                r: 8,
+                salt: vec![0x42; 32],
+            },
+        ] {
+            let reencrypted_keystore = EncryptedKeystore::encrypt(
+                &keystore.private_key,
+                keystore.public_key.clone(),
+                b"password123",
+                kdf_params,
+                "m/12381/3600/0/0/0".to_string(),
+            )
+            .unwrap();
+
+            assert!(
+                reencrypted_keystore
+                    .validate_password(b"password123")
+                    .unwrap()
+            );
+            assert!(
+                !reencrypted_keystore
+                    .validate_password(b"password124")
+                    .unwrap()
+            );
+            assert_eq!(
+                reencrypted_keystore
+                    .decrypt(b"password123")
+                    .unwrap()
+                    .private_key,
+                keystore.private_key
+            );
+        }
+    }
 }
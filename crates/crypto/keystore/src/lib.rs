@@ -1,6 +1,8 @@
+pub mod argon2id;
 pub mod decrypt;
 pub mod hex_serde;
 pub mod hmac;
+pub mod key_derivation;
 pub mod keystore;
 pub mod lean_keystore;
 pub mod pbkdf2;
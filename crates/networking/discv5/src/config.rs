@@ -1,5 +1,6 @@
 use std::net::{IpAddr, Ipv4Addr};
 
+use alloy_primitives::aliases::B32;
 use discv5::{ConfigBuilder, Enr, ListenConfig};
 
 use crate::subnet::{AttestationSubnets, CustodyGroupCount, SyncCommitteeSubnets};
@@ -50,3 +51,35 @@ impl Default for DiscoveryConfig {
         }
     }
 }
+
+#[derive(Clone)]
+pub struct LeanDiscoveryConfig {
+    pub discv5_config: discv5::Config,
+    pub bootnodes: Vec<Enr>,
+    pub socket_address: IpAddr,
+    /// QUIC port of libp2p, advertised in the ENR
+    pub socket_port: u16,
+    pub discovery_port: u16,
+    pub disable_discovery: bool,
+    pub fork_digest: B32,
+    pub attestation_subnets: AttestationSubnets,
+}
+
+impl Default for LeanDiscoveryConfig {
+    fn default() -> Self {
+        let socket_address = Ipv4Addr::UNSPECIFIED;
+        let discovery_port = 9001;
+        let listen_config = ListenConfig::from_ip(socket_address.into(), discovery_port);
+
+        Self {
+            discv5_config: ConfigBuilder::new(listen_config).build(),
+            bootnodes: Vec::new(),
+            socket_address: socket_address.into(),
+            socket_port: 9000,
+            discovery_port,
+            disable_discovery: false,
+            fork_digest: B32::ZERO,
+            attestation_subnets: AttestationSubnets::new(),
+        }
+    }
+}
@@ -29,8 +29,8 @@ use tokio::sync::mpsc;
 use tracing::{error, info, trace, warn};
 
 use crate::{
-    config::DiscoveryConfig,
-    eth2::{ENR_ETH2_KEY, EnrForkId},
+    config::{DiscoveryConfig, LeanDiscoveryConfig},
+    eth2::{ENR_ETH2_KEY, EnrForkId, QUIC_ENR_KEY},
     subnet::{
         ATTESTATION_BITFIELD_ENR_KEY, AttestationSubnets, CUSTODY_GROUP_COUNT_ENR_KEY,
         EPOCHS_PER_SUBNET_SUBSCRIPTION, NEXT_FORK_DIGEST_ENR_KEY, SYNC_COMMITTEE_BITFIELD_ENR_KEY,
@@ -81,13 +81,20 @@ pub struct Discovery {
     subscription_epoch: u64,
     /// Last slot we checked for subnet rotation
     last_checked_slot: u64,
+    /// Whether a peer advertises the transport this node dials: TCP for the beacon chain and
+    /// QUIC for the lean chain
+    is_dialable: fn(&Enr) -> bool,
 }
 
 impl Discovery {
+    /// Create the discovery behaviour. If `previous_enr` is the ENR of the same key from an
+    /// earlier run, its sequence number is kept if the record is unchanged and incremented
+    /// otherwise, so peers always pick up the latest record.
     pub async fn new(
         local_key: Keypair,
         config: &DiscoveryConfig,
         current_slot: u64,
+        previous_enr: Option<Enr>,
     ) -> anyhow::Result<Self> {
         let enr_local =
             convert_to_enr(local_key).map_err(|err| anyhow!("Failed to convert key: {err:?}"))?;
@@ -98,7 +105,7 @@ impl Discovery {
         enr_builder.tcp4(config.socket_port);
         enr_builder.udp4(config.discovery_port);
 
-        let enr = enr_builder
+        let mut enr = enr_builder
             .add_value(
                 ENR_ETH2_KEY,
                 &EnrForkId::current(genesis_validators_root(), current_epoch),
@@ -113,20 +120,16 @@ impl Discovery {
             .build(&enr_local)
             .map_err(|err| anyhow!("Failed to build ENR: {err}"))?;
 
-        let node_local_id = enr.node_id();
-
-        let mut discv5 = Discv5::new(enr.clone(), enr_local, config.discv5_config.clone())
-            .map_err(|err| anyhow!("Failed to create discv5: {err:?}"))?;
-
-        // adding bootnodes to discv5
-        for enr in config.bootnodes.clone() {
-            // Skip adding ourselves to the routing table if we are a bootnode
-            if enr.node_id() == node_local_id {
-                continue;
-            }
-            if let Err(err) = discv5.add_enr(enr) {
-                error!("Failed to add bootnode to Discv5 {err:?}");
-            }
+        if let Some(previous_enr) = previous_enr
+            && previous_enr.node_id() == enr.node_id()
+        {
+            let seq = if previous_enr.iter().eq(enr.iter()) {
+                previous_enr.seq()
+            } else {
+                previous_enr.seq() + 1
+            };
+            enr.set_seq(seq, &enr_local)
+                .map_err(|err| anyhow!("Failed to set ENR sequence number: {err}"))?;
         }
 
         // Compute and set attestation subnets
@@ -141,16 +144,14 @@ impl Discovery {
 
         let subscription_epoch = current_epoch;
 
-        let event_stream = if !config.disable_discovery {
-            discv5
-                .start()
-                .await
-                .map_err(|err| anyhow!("Failed to start discv5: {err:?}"))?;
-            info!("Started discovery with ENR: {:?}", discv5.local_enr());
-            EventStream::Awaiting(Box::pin(discv5.event_stream()))
-        } else {
-            EventStream::Inactive
-        };
+        let (discv5, event_stream) = start_discv5(
+            enr,
+            enr_local,
+            config.discv5_config.clone(),
+            &config.bootnodes,
+            config.disable_discovery,
+        )
+        .await?;
 
         Ok(Self {
             discv5,
@@ -161,6 +162,50 @@ impl Discovery {
             current_attestation_subnets: config.attestation_subnets.clone(),
             subscription_epoch,
             last_checked_slot: current_slot,
+            is_dialable: has_tcp,
+        })
+    }
+
+    /// Create the discovery behaviour of the lean chain. Its ENR advertises the QUIC port of the
+    /// node, the lean fork digest and the attestation subnets the node subscribes to. Lean
+    /// subnet subscriptions are fixed at startup, so they are never rotated.
+    pub async fn new_lean(
+        local_key: Keypair,
+        config: &LeanDiscoveryConfig,
+    ) -> anyhow::Result<Self> {
+        let enr_local =
+            convert_to_enr(local_key).map_err(|err| anyhow!("Failed to convert key: {err:?}"))?;
+
+        let mut enr_builder = Enr::builder();
+        enr_builder.ip(config.socket_address);
+        enr_builder.udp4(config.discovery_port);
+
+        let enr = enr_builder
+            .add_value(QUIC_ENR_KEY, &config.socket_port)
+            .add_value(ENR_ETH2_KEY, &EnrForkId::lean(config.fork_digest))
+            .add_value(ATTESTATION_BITFIELD_ENR_KEY, &config.attestation_subnets)
+            .build(&enr_local)
+            .map_err(|err| anyhow!("Failed to build ENR: {err}"))?;
+
+        let (discv5, event_stream) = start_discv5(
+            enr,
+            enr_local,
+            config.discv5_config.clone(),
+            &config.bootnodes,
+            config.disable_discovery,
+        )
+        .await?;
+
+        Ok(Self {
+            discv5,
+            event_stream,
+            discovery_queries: FuturesUnordered::new(),
+            find_peer_active: false,
+            started: !config.disable_discovery,
+            current_attestation_subnets: config.attestation_subnets.clone(),
+            subscription_epoch: 0,
+            last_checked_slot: 0,
+            is_dialable: has_quic,
         })
     }
 
@@ -235,35 +280,24 @@ impl Discovery {
     }
 
     fn start_query(&mut self, query: QueryType, target_peers: usize) {
+        let Some(peer_predicate) = self.peer_predicate() else {
+            warn!("ENR missing or invalid ENR_ETH2_KEY, skipping peer query");
+            self.find_peer_active = false;
+            return;
+        };
         let query_future = self
             .discv5
             .find_node_predicate(
                 NodeId::random(),
                 match query.clone() {
-                    QueryType::Peers => {
-                        let Some(Ok(fork_id)) = self
-                            .discv5
-                            .local_enr()
-                            .get_decodable::<EnrForkId>(ENR_ETH2_KEY)
-                        else {
-                            warn!("ENR missing or invalid ENR_ETH2_KEY, skipping peer query");
-                            return;
-                        };
-                        let fork_digest = fork_id.fork_digest;
-
-                        Box::new(move |enr: &Enr| {
-                            enr.get_decodable::<EnrForkId>(ENR_ETH2_KEY)
-                                .and_then(Result::ok)
-                                .map(|id| id.fork_digest == fork_digest)
-                                .unwrap_or(false)
-                                && (enr.tcp4().is_some() || enr.tcp6().is_some())
-                        })
-                    }
+                    QueryType::Peers => Box::new(peer_predicate),
                     QueryType::AttestationSubnetPeers(subnet_ids) => {
-                        Box::new(attestation_subnet_predicate(subnet_ids))
+                        let subnet_predicate = attestation_subnet_predicate(subnet_ids);
+                        Box::new(move |enr: &Enr| peer_predicate(enr) && subnet_predicate(enr))
                     }
                     QueryType::SyncCommitteeSubnetPeers(subnet_ids) => {
-                        Box::new(sync_committee_subnet_predicate(subnet_ids))
+                        let subnet_predicate = sync_committee_subnet_predicate(subnet_ids);
+                        Box::new(move |enr: &Enr| peer_predicate(enr) && subnet_predicate(enr))
                     }
                 },
                 target_peers,
@@ -276,6 +310,28 @@ impl Discovery {
         self.discovery_queries.push(Box::pin(query_future));
     }
 
+    /// Whether a peer is on the same fork as the local node and advertises a transport this node
+    /// can dial. Every query, including subnet queries, only returns peers which match it.
+    fn peer_predicate(&self) -> Option<impl Fn(&Enr) -> bool + Send + Sync + 'static> {
+        let Some(Ok(fork_id)) = self
+            .discv5
+            .local_enr()
+            .get_decodable::<EnrForkId>(ENR_ETH2_KEY)
+        else {
+            return None;
+        };
+        let fork_digest = fork_id.fork_digest;
+        let is_dialable = self.is_dialable;
+
+        Some(move |enr: &Enr| {
+            enr.get_decodable::<EnrForkId>(ENR_ETH2_KEY)
+                .and_then(Result::ok)
+                .map(|id| id.fork_digest == fork_digest)
+                .unwrap_or(false)
+                && is_dialable(enr)
+        })
+    }
+
     fn process_queries(&mut self, cx: &mut Context) -> Option<HashMap<Enr, Option<Instant>>> {
         while let Poll::Ready(Some(query)) = self.discovery_queries.poll_next_unpin(cx) {
             let result = match query.query_type {
@@ -300,10 +356,16 @@ impl Discovery {
                     self.find_peer_active = false;
                     match query.result {
                         Ok(peers) => {
-                            let predicate = attestation_subnet_predicate(subnet_ids);
+                            let peer_predicate = self.peer_predicate();
+                            let subnet_predicate = attestation_subnet_predicate(subnet_ids);
                             let filtered_peers = peers
                                 .into_iter()
-                                .filter(|enr| predicate(enr))
+                                .filter(|enr| {
+                                    peer_predicate
+                                        .as_ref()
+                                        .is_some_and(|peer_predicate| peer_predicate(enr))
+                                        && subnet_predicate(enr)
+                                })
                                 .collect::<Vec<_>>();
                             info!("Found {} peers for subnets", filtered_peers.len());
                             let mut peer_map = HashMap::new();
@@ -322,10 +384,16 @@ impl Discovery {
                     self.find_peer_active = false;
                     match query.result {
                         Ok(peers) => {
-                            let predicate = sync_committee_subnet_predicate(subnet_ids);
+                            let peer_predicate = self.peer_predicate();
+                            let subnet_predicate = sync_committee_subnet_predicate(subnet_ids);
                             let filtered_peers = peers
                                 .into_iter()
-                                .filter(|enr| predicate(enr))
+                                .filter(|enr| {
+                                    peer_predicate
+                                        .as_ref()
+                                        .is_some_and(|peer_predicate| peer_predicate(enr))
+                                        && subnet_predicate(enr)
+                                })
                                 .collect::<Vec<_>>();
                             info!(
                                 "Found {} peers for sync committee subnets",
@@ -456,6 +524,54 @@ pub fn empty_predicate() -> impl Fn(&Enr) -> bool + Send + Sync {
     move |_enr: &Enr| true
 }
 
+fn has_tcp(enr: &Enr) -> bool {
+    enr.tcp4().is_some() || enr.tcp6().is_some()
+}
+
+fn has_quic(enr: &Enr) -> bool {
+    enr.get_decodable::<u16>(QUIC_ENR_KEY)
+        .is_some_and(|port| port.is_ok())
+}
+
+/// Create discv5 with the local `enr` and add the `bootnodes` to its routing table. Unless
+/// discovery is disabled, the discv5 service is started as well.
+async fn start_discv5(
+    enr: Enr,
+    enr_local: CombinedKey,
+    discv5_config: discv5::Config,
+    bootnodes: &[Enr],
+    disable_discovery: bool,
+) -> anyhow::Result<(Discv5, EventStream)> {
+    let node_local_id = enr.node_id();
+
+    let mut discv5 = Discv5::new(enr, enr_local, discv5_config)
+        .map_err(|err| anyhow!("Failed to create discv5: {err:?}"))?;
+
+    // adding bootnodes to discv5
+    for enr in bootnodes.iter().cloned() {
+        // Skip adding ourselves to the routing table if we are a bootnode
+        if enr.node_id() == node_local_id {
+            continue;
+        }
+        if let Err(err) = discv5.add_enr(enr) {
+            error!("Failed to add bootnode to Discv5 {err:?}");
+        }
+    }
+
+    let event_stream = if !disable_discovery {
+        discv5
+            .start()
+            .await
+            .map_err(|err| anyhow!("Failed to start discv5: {err:?}"))?;
+        info!("Started discovery with ENR: {:?}", discv5.local_enr());
+        EventStream::Awaiting(Box::pin(discv5.event_stream()))
+    } else {
+        EventStream::Inactive
+    };
+
+    Ok((discv5, event_stream))
+}
+
 fn convert_to_enr(key: Keypair) -> anyhow::Result<CombinedKey> {
     let key = key
         .try_into_secp256k1()
@@ -469,14 +585,14 @@ fn convert_to_enr(key: Keypair) -> anyhow::Result<CombinedKey> {
 mod tests {
     use std::net::Ipv4Addr;
 
-    use alloy_primitives::B256;
+    use alloy_primitives::{B256, aliases::B32};
     use libp2p::identity::Keypair;
     use ream_consensus_misc::constants::beacon::GENESIS_VALIDATORS_ROOT;
     use ream_network_spec::networks::initialize_test_network_spec;
 
     use super::*;
     use crate::{
-        config::DiscoveryConfig,
+        config::{DiscoveryConfig, LeanDiscoveryConfig},
         subnet::{AttestationSubnets, EPOCHS_PER_SUBNET_SUBSCRIPTION, SyncCommitteeSubnets},
     };
 
@@ -492,7 +608,7 @@ mod tests {
         config.attestation_subnets.enable_attestation_subnet(0)?; // Set subnet 0
         config.attestation_subnets.disable_attestation_subnet(1)?; // Set subnet 1
 
-        let discovery = Discovery::new(key, &config, 0).await.unwrap();
+        let discovery = Discovery::new(key, &config, 0, None).await.unwrap();
         // Check ENR reflects config.subnets
         let enr_subnets = discovery
             .discv5
@@ -514,7 +630,7 @@ mod tests {
         config.attestation_subnets.disable_attestation_subnet(1)?;
         config.disable_discovery = true;
 
-        let discovery = Discovery::new(key, &config, 0).await.unwrap();
+        let discovery = Discovery::new(key, &config, 0, None).await.unwrap();
         let local_enr = discovery.local_enr();
 
         // Predicate for subnet 0 should match
@@ -543,7 +659,7 @@ mod tests {
 
         config.attestation_subnets.enable_attestation_subnet(0)?; // Local node on subnet 0
         config.disable_discovery = false;
-        let mut discovery = Discovery::new(key, &config, 0).await.unwrap();
+        let mut discovery = Discovery::new(key, &config, 0, None).await.unwrap();
 
         // Simulate a peer with another Discovery instance
         let peer_key = Keypair::generate_secp256k1();
@@ -562,7 +678,9 @@ mod tests {
         peer_config.socket_port = 9001; // Different port
         peer_config.disable_discovery = true;
 
-        let peer_discovery = Discovery::new(peer_key, &peer_config, 0).await.unwrap();
+        let peer_discovery = Discovery::new(peer_key, &peer_config, 0, None)
+            .await
+            .unwrap();
         let peer_enr = peer_discovery.local_enr().clone();
 
         // Add peer to discv5
@@ -591,6 +709,91 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_lean_enr_fields() -> anyhow::Result<()> {
+        let fork_digest = B32::from_slice(&[0x12, 0x34, 0x56, 0x78]);
+        let mut config = LeanDiscoveryConfig {
+            socket_port: 9100,
+            disable_discovery: true,
+            fork_digest,
+            ..LeanDiscoveryConfig::default()
+        };
+        config.attestation_subnets.enable_attestation_subnet(3)?;
+
+        let discovery = Discovery::new_lean(Keypair::generate_secp256k1(), &config).await?;
+        let enr = discovery.local_enr();
+
+        // Lean nodes are dialed over QUIC only
+        assert!(has_quic(&enr));
+        assert!(!has_tcp(&enr));
+        let quic_port = enr
+            .get_decodable::<u16>(QUIC_ENR_KEY)
+            .ok_or_else(|| anyhow!("Missing quic field"))?
+            .map_err(|err| anyhow!("Failed to decode quic port: {err:?}"))?;
+        assert_eq!(quic_port, 9100);
+
+        let fork_id = enr
+            .get_decodable::<EnrForkId>(ENR_ETH2_KEY)
+            .ok_or_else(|| anyhow!("Missing eth2 field"))?
+            .map_err(|err| anyhow!("Failed to decode fork id: {err:?}"))?;
+        assert_eq!(fork_id.fork_digest, fork_digest);
+
+        assert!(attestation_subnet_predicate(vec![3])(&enr));
+        assert!(!attestation_subnet_predicate(vec![4])(&enr));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lean_peer_predicate() -> anyhow::Result<()> {
+        let fork_digest = B32::from_slice(&[0x12, 0x34, 0x56, 0x78]);
+        let lean_config = |fork_digest| -> anyhow::Result<LeanDiscoveryConfig> {
+            let mut config = LeanDiscoveryConfig {
+                disable_discovery: true,
+                fork_digest,
+                ..LeanDiscoveryConfig::default()
+            };
+            config.attestation_subnets.enable_attestation_subnet(3)?;
+            Ok(config)
+        };
+
+        let discovery =
+            Discovery::new_lean(Keypair::generate_secp256k1(), &lean_config(fork_digest)?).await?;
+        let peer_predicate = discovery
+            .peer_predicate()
+            .ok_or_else(|| anyhow!("Missing peer predicate"))?;
+
+        let same_fork_peer =
+            Discovery::new_lean(Keypair::generate_secp256k1(), &lean_config(fork_digest)?).await?;
+        assert!(peer_predicate(&same_fork_peer.local_enr()));
+
+        // A peer on the subnet, but on another fork, is not returned by subnet queries
+        let other_fork_peer = Discovery::new_lean(
+            Keypair::generate_secp256k1(),
+            &lean_config(B32::from_slice(&[0x87, 0x65, 0x43, 0x21]))?,
+        )
+        .await?;
+        assert!(attestation_subnet_predicate(vec![3])(
+            &other_fork_peer.local_enr()
+        ));
+        assert!(!peer_predicate(&other_fork_peer.local_enr()));
+
+        // A peer without a QUIC port can't be dialed by a lean node
+        let mut enr_builder = Enr::builder();
+        enr_builder.ip4(Ipv4Addr::new(192, 168, 1, 100));
+        enr_builder.tcp4(9000);
+        let tcp_peer = enr_builder
+            .add_value(ENR_ETH2_KEY, &EnrForkId::lean(fork_digest))
+            .add_value(
+                ATTESTATION_BITFIELD_ENR_KEY,
+                &lean_config(fork_digest)?.attestation_subnets,
+            )
+            .build(&CombinedKey::generate_secp256k1())
+            .map_err(|err| anyhow!("Failed to build ENR: {err}"))?;
+        assert!(attestation_subnet_predicate(vec![3])(&tcp_peer));
+        assert!(!peer_predicate(&tcp_peer));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_subnet_subscription_update() -> anyhow::Result<()> {
         let _ = GENESIS_VALIDATORS_ROOT.set(B256::ZERO);
@@ -604,7 +807,7 @@ mod tests {
 
         // Start at epoch 0
         let initial_slot = 0;
-        let mut discovery = Discovery::new(key, &config, initial_slot).await?;
+        let mut discovery = Discovery::new(key, &config, initial_slot, None).await?;
 
         // Get initial subscriptions
         let initial_subnets = discovery.current_attestation_subnets().clone();
@@ -646,11 +849,11 @@ mod tests {
         };
 
         let initial_slot = 0;
-        let discovery = Discovery::new(key.clone(), &config, initial_slot).await?;
+        let discovery = Discovery::new(key.clone(), &config, initial_slot, None).await?;
         let subnets1 = discovery.current_attestation_subnets().clone();
 
         // Create another discovery instance with the same key
-        let discovery2 = Discovery::new(key, &config, initial_slot).await?;
+        let discovery2 = Discovery::new(key, &config, initial_slot, None).await?;
         let subnets2 = discovery2.current_attestation_subnets().clone();
 
         // Should have the same subnets since they have the same node_id
@@ -662,6 +865,47 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_enr_sequence_number_continues_previous_enr() -> anyhow::Result<()> {
+        let _ = GENESIS_VALIDATORS_ROOT.set(B256::ZERO);
+        initialize_test_network_spec();
+
+        let key = Keypair::generate_secp256k1();
+        let config = DiscoveryConfig {
+            disable_discovery: true,
+            ..DiscoveryConfig::default()
+        };
+
+        let mut previous_enr = Discovery::new(key.clone(), &config, 0, None)
+            .await?
+            .local_enr();
+        previous_enr.set_seq(5, &convert_to_enr(key.clone())?)?;
+
+        // An unchanged record keeps its sequence number
+        let discovery = Discovery::new(key.clone(), &config, 0, Some(previous_enr.clone())).await?;
+        assert_eq!(discovery.local_enr().seq(), 5);
+
+        // A changed record gets the next one
+        let changed_config = DiscoveryConfig {
+            socket_port: config.socket_port + 1,
+            ..config.clone()
+        };
+        let discovery = Discovery::new(key.clone(), &changed_config, 0, Some(previous_enr)).await?;
+        assert_eq!(discovery.local_enr().seq(), 6);
+
+        // The ENR of another key is ignored
+        let discovery = Discovery::new(
+            Keypair::generate_secp256k1(),
+            &config,
+            0,
+            Some(discovery.local_enr()),
+        )
+        .await?;
+        assert_eq!(discovery.local_enr().seq(), 1);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_subnet_subscription_rotation() -> anyhow::Result<()> {
         let _ = GENESIS_VALIDATORS_ROOT.set(B256::ZERO);
@@ -674,7 +918,7 @@ mod tests {
         };
 
         let initial_slot = 0;
-        let mut discovery = Discovery::new(key, &config, initial_slot).await?;
+        let mut discovery = Discovery::new(key, &config, initial_slot, None).await?;
 
         let initial_subnets = discovery.current_attestation_subnets().clone();
         let _initial_epoch = discovery.subscription_epoch();
@@ -715,7 +959,7 @@ mod tests {
         };
 
         let initial_slot = 0;
-        let mut discovery = Discovery::new(key, &config, initial_slot).await?;
+        let mut discovery = Discovery::new(key, &config, initial_slot, None).await?;
 
         // Get initial ENR subnets
         let initial_enr_subnets = discovery
@@ -7,6 +7,7 @@ use ssz_derive::{Decode, Encode};
 use tracing::warn;
 
 pub const ENR_ETH2_KEY: &str = "eth2";
+pub const QUIC_ENR_KEY: &str = "quic";
 
 #[derive(Default, Debug, Encode, Decode)]
 pub struct EnrForkId {
@@ -51,6 +52,18 @@ impl EnrForkId {
     }
 }
 
+impl EnrForkId {
+    /// The fork id of a lean chain node. Lean networks don't schedule forks, so no next fork is
+    /// advertised.
+    pub fn lean(fork_digest: B32) -> Self {
+        Self {
+            fork_digest,
+            next_fork_version: B32::ZERO,
+            next_fork_epoch: FAR_FUTURE_EPOCH,
+        }
+    }
+}
+
 impl Encodable for EnrForkId {
     fn encode(&self, out: &mut dyn BufMut) {
         let ssz_bytes = self.as_ssz_bytes();
@@ -1,6 +1,7 @@
 use std::{net::IpAddr, path::PathBuf};
 
 use ream_p2p::bootnodes::Bootnodes;
+use ream_req_resp::rate_limiter::ProtocolQuota;
 use url::Url;
 
 pub struct ManagerConfig {
@@ -12,10 +13,12 @@ pub struct ManagerConfig {
     pub discovery_port: u16,
     pub disable_discovery: bool,
     pub bootnodes: Bootnodes,
+    pub private_key_path: Option<PathBuf>,
     pub checkpoint_sync_url: Option<Url>,
     pub execution_endpoint: Option<Url>,
     pub execution_jwt_secret: Option<PathBuf>,
     pub enable_builder: bool,
     pub mev_relay_url: Option<Url>,
     pub blob_retention_epochs: Option<u64>,
+    pub inbound_rate_limits: Vec<ProtocolQuota>,
 }
@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use anyhow::anyhow;
+use ream_bls::{SignatureSet, traits::BatchVerifiable};
+use ream_executor::ReamExecutor;
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::sleep,
+};
+use tracing::warn;
+
+/// How long to wait for more signatures after the first one of a batch arrives.
+const BATCH_WINDOW: Duration = Duration::from_millis(10);
+
+/// Maximum number of signature sets verified in one batch.
+const MAX_BATCH_SIZE: usize = 64;
+
+type PendingSignature = (SignatureSet, oneshot::Sender<bool>);
+
+/// Work queue which collects the signature sets of gossip messages arriving within
+/// [BATCH_WINDOW] of each other and verifies them as one batch.
+///
+/// If a batch fails, its signature sets are verified one at a time, so a single invalid signature
+/// only rejects the message it belongs to.
+#[derive(Debug, Clone)]
+pub struct SignatureBatchVerifier {
+    sender: mpsc::UnboundedSender<PendingSignature>,
+}
+
+impl SignatureBatchVerifier {
+    pub fn new(executor: &ReamExecutor) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        executor.spawn(run(receiver));
+        Self { sender }
+    }
+
+    /// Queues `signature_set` for verification and waits for the batch it ends up in.
+    pub async fn verify(&self, signature_set: SignatureSet) -> anyhow::Result<bool> {
+        let (sender, receiver) = oneshot::channel();
+        self.sender
+            .send((signature_set, sender))
+            .map_err(|err| anyhow!("Signature batch verifier is not running: {err}"))?;
+        receiver
+            .await
+            .map_err(|err| anyhow!("Signature batch verifier dropped the signature: {err}"))
+    }
+}
+
+async fn run(mut receiver: mpsc::UnboundedReceiver<PendingSignature>) {
+    while let Some(pending_signature) = receiver.recv().await {
+        let mut batch = vec![pending_signature];
+        let window = sleep(BATCH_WINDOW);
+        tokio::pin!(window);
+        while batch.len() < MAX_BATCH_SIZE {
+            tokio::select! {
+                _ = &mut window => break,
+                pending_signature = receiver.recv() => match pending_signature {
+                    Some(pending_signature) => batch.push(pending_signature),
+                    None => break,
+                },
+            }
+        }
+
+        // Signatures arriving while this batch is verified are queued up for the next one
+        if let Err(err) = tokio::task::spawn_blocking(move || verify_batch(batch)).await {
+            warn!("Failed to verify signature batch: {err}");
+        }
+    }
+}
+
+fn verify_batch(batch: Vec<PendingSignature>) {
+    let (signature_sets, senders): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+
+    let results = if signature_sets.len() > 1
+        && matches!(SignatureSet::verify_batch(&signature_sets), Ok(true))
+    {
+        vec![true; signature_sets.len()]
+    } else {
+        signature_sets
+            .iter()
+            .map(|signature_set| signature_set.verify().unwrap_or(false))
+            .collect()
+    };
+
+    for (sender, is_valid) in senders.into_iter().zip(results) {
+        // The receiver is gone if the message stopped being validated, nothing to report then
+        let _ = sender.send(is_valid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_bls::{
+        PrivateKey, SignatureSet,
+        traits::{PublicKeyDerivable, Signable},
+    };
+    use ream_executor::ReamExecutor;
+    use tokio::{runtime::Runtime, sync::oneshot};
+
+    use super::{SignatureBatchVerifier, verify_batch};
+
+    /// A signature by key `index`, over another message than the signed one if `!is_valid`.
+    fn signature_set(index: u8, is_valid: bool) -> SignatureSet {
+        let private_key = PrivateKey {
+            inner: B256::with_last_byte(index),
+        };
+        let message = B256::repeat_byte(index);
+        SignatureSet::single(
+            private_key.sign(message.as_slice()).unwrap(),
+            private_key.public_key().unwrap(),
+            if is_valid { message } else { B256::ZERO },
+        )
+    }
+
+    #[test]
+    fn test_failed_batch_only_rejects_invalid_signature() {
+        let validity = [true, true, false, true];
+        let (batch, receivers): (Vec<_>, Vec<_>) = validity
+            .iter()
+            .zip(1..)
+            .map(|(&is_valid, index)| {
+                let (sender, receiver) = oneshot::channel();
+                ((signature_set(index, is_valid), sender), receiver)
+            })
+            .unzip();
+
+        verify_batch(batch);
+
+        let results = receivers
+            .into_iter()
+            .map(|mut receiver| receiver.try_recv().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(results, validity);
+    }
+
+    #[test]
+    fn test_concurrent_signatures_are_verified() {
+        let executor = ReamExecutor::new().unwrap();
+        let batch_verifier = SignatureBatchVerifier::new(&executor);
+
+        let results = Runtime::new().unwrap().block_on(async {
+            let handles = (1..=8)
+                .map(|index| {
+                    let batch_verifier = batch_verifier.clone();
+                    tokio::spawn(async move {
+                        batch_verifier
+                            .verify(signature_set(index, index != 5))
+                            .await
+                            .unwrap()
+                    })
+                })
+                .collect::<Vec<_>>();
+            let mut results = vec![];
+            for handle in handles {
+                results.push(handle.await.unwrap());
+            }
+            results
+        });
+
+        assert_eq!(results, (1..=8).map(|index| index != 5).collect::<Vec<_>>());
+    }
+}
@@ -1,44 +1,60 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use libp2p::gossipsub::Message;
+use libp2p::{
+    PeerId,
+    gossipsub::{Message, MessageId},
+};
 use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_consensus_beacon::{
     blob_sidecar::BlobIdentifier,
     data_column_sidecar::{ColumnIdentifier, DATA_COLUMN_SIDECAR_SUBNET_COUNT},
+    electra::beacon_state::fork_name_at_epoch,
+};
+use ream_consensus_misc::{
+    constants::beacon::{FULU_FORK_EPOCH, genesis_validators_root},
+    misc::compute_epoch_at_slot,
+};
+use ream_events_beacon::{
+    BeaconEvent, BeaconEventSender,
+    event::{
+        attestation::{AttestationEvent, SingleAttestationEvent},
+        blob::{BlobSidecarEvent, DataColumnSidecarEvent},
+        chain::BlockGossipEvent,
+        light_client::{LightClientFinalityUpdateEvent, LightClientOptimisticUpdateEvent},
+        sync_committee::ContributionAndProofEvent,
+    },
 };
-use ream_consensus_misc::constants::beacon::{FULU_FORK_EPOCH, genesis_validators_root};
 use ream_execution_rpc_types::get_blobs::BlobAndProofV1;
 use ream_network_spec::networks::beacon_network_spec;
-use ream_p2p::{
-    gossipsub::beacon::{
-        configurations::GossipsubConfig,
-        message::GossipsubMessage,
-        topics::{GossipTopic, GossipTopicKind},
-    },
-    network::beacon::channel::GossipMessage,
+use ream_p2p::gossipsub::beacon::{
+    configurations::GossipsubConfig,
+    message::GossipsubMessage,
+    topics::{GossipTopic, GossipTopicKind},
 };
 use ream_storage::{cache::BeaconCacheDB, tables::table::CustomTable};
 use ream_validator_beacon::{
     blob_sidecars::compute_subnet_for_blob_sidecar, constants::SYNC_COMMITTEE_SUBNET_COUNT,
 };
-use ssz::Encode;
 use tracing::{error, info, trace, warn};
 use tree_hash::TreeHash;
 
 use crate::{
-    gossipsub::validate::{
-        aggregate_and_proof::validate_aggregate_and_proof,
-        attester_slashing::validate_attester_slashing,
-        beacon_attestation::validate_beacon_attestation,
-        beacon_block::validate_gossip_beacon_block, blob_sidecar::validate_blob_sidecar,
-        bls_to_execution_change::validate_bls_to_execution_change,
-        data_column_sidecar::validate_data_column_sidecar_full,
-        light_client_finality_update::validate_light_client_finality_update,
-        light_client_optimistic_update::validate_light_client_optimistic_update,
-        proposer_slashing::validate_proposer_slashing, result::ValidationResult,
-        sync_committee::validate_sync_committee,
-        sync_committee_contribution_and_proof::validate_sync_committee_contribution_and_proof,
-        voluntary_exit::validate_voluntary_exit,
+    gossipsub::{
+        batch_verifier::SignatureBatchVerifier,
+        validate::{
+            aggregate_and_proof::validate_aggregate_and_proof,
+            attester_slashing::validate_attester_slashing,
+            beacon_attestation::validate_beacon_attestation,
+            beacon_block::validate_gossip_beacon_block, blob_sidecar::validate_blob_sidecar,
+            bls_to_execution_change::validate_bls_to_execution_change,
+            data_column_sidecar::validate_data_column_sidecar_full,
+            light_client_finality_update::validate_light_client_finality_update,
+            light_client_optimistic_update::validate_light_client_optimistic_update,
+            proposer_slashing::validate_proposer_slashing, result::ValidationResult,
+            sync_committee::validate_sync_committee,
+            sync_committee_contribution_and_proof::validate_sync_committee_contribution_and_proof,
+            voluntary_exit::validate_voluntary_exit,
+        },
     },
     p2p_sender::P2PSender,
 };
@@ -120,515 +136,647 @@ pub fn init_gossipsub_config_with_topics() -> GossipsubConfig {
     gossipsub_config
 }
 
-/// Dispatches a gossipsub message to its appropriate handler.
+/// Dispatches a gossipsub message to its appropriate handler, and reports the validation result
+/// back to gossipsub, which forwards accepted messages and penalizes the senders of rejected
+/// ones.
 pub async fn handle_gossipsub_message(
     message: Message,
+    message_id: MessageId,
+    propagation_source: PeerId,
     beacon_chain: &BeaconChain,
     cached_db: &BeaconCacheDB,
     p2p_sender: &P2PSender,
+    batch_verifier: &SignatureBatchVerifier,
 ) {
-    match GossipsubMessage::decode(&message.topic, &message.data) {
-        Ok(gossip_message) => match gossip_message {
-            GossipsubMessage::BeaconBlock(signed_block) => {
-                info!(
-                    "Beacon block received over gossipsub: slot: {}, root: {}",
-                    signed_block.message.slot,
-                    signed_block.message.block_root()
-                );
-
-                let validation_result = match validate_gossip_beacon_block(
-                    beacon_chain,
-                    cached_db,
-                    &signed_block,
-                )
-                .await
-                {
+    let validation_result =
+        process_gossipsub_message(&message, beacon_chain, cached_db, batch_verifier).await;
+    p2p_sender.report_validation_result(message_id, propagation_source, &validation_result);
+}
+
+/// Validates a gossipsub message and imports it if it is accepted.
+///
+/// Messages that can't be validated because of a local error are ignored.
+async fn process_gossipsub_message(
+    message: &Message,
+    beacon_chain: &BeaconChain,
+    cached_db: &BeaconCacheDB,
+    batch_verifier: &SignatureBatchVerifier,
+) -> ValidationResult {
+    let gossip_message = match GossipsubMessage::decode(&message.topic, &message.data) {
+        Ok(gossip_message) => gossip_message,
+        Err(err) => {
+            trace!("Failed to decode gossip message: {err:?}");
+            return ValidationResult::Reject(format!("Failed to decode gossip message: {err:?}"));
+        }
+    };
+
+    match gossip_message {
+        GossipsubMessage::BeaconBlock(signed_block) => {
+            info!(
+                "Beacon block received over gossipsub: slot: {}, root: {}",
+                signed_block.message.slot,
+                signed_block.message.block_root()
+            );
+
+            let validation_result =
+                match validate_gossip_beacon_block(beacon_chain, cached_db, &signed_block).await {
                     Ok(result) => result,
                     Err(err) => {
                         warn!("Failed to validate gossipsub beacon block: {err}");
-                        return;
+                        return ValidationResult::Ignore(format!(
+                            "Failed to validate beacon block: {err}"
+                        ));
                     }
                 };
 
-                match validation_result {
-                    ValidationResult::Accept => {
-                        let signed_block_bytes = signed_block.as_ssz_bytes();
-                        if let Err(err) = beacon_chain.process_block(*signed_block).await {
-                            error!("Failed to process gossipsub beacon block: {err}");
-                        }
-                        p2p_sender.send_gossip(GossipMessage {
-                            topic: GossipTopic::from_topic_hash(&message.topic)
-                                .expect("invalid topic hash"),
-                            data: signed_block_bytes,
-                        });
-                    }
-                    ValidationResult::Ignore(reason) => {
-                        warn!("Ignoring gossipsub beacon block: {reason}");
-                    }
-                    ValidationResult::Reject(reason) => {
-                        warn!("Rejecting gossipsub beacon block: {reason}");
+            match &validation_result {
+                ValidationResult::Accept => {
+                    beacon_chain
+                        .event_sender
+                        .send_event(BeaconEvent::BlockGossip(BlockGossipEvent {
+                            slot: signed_block.message.slot,
+                            block: signed_block.message.block_root(),
+                        }));
+                    if let Err(err) = beacon_chain.process_block(*signed_block).await {
+                        error!("Failed to process gossipsub beacon block: {err}");
                     }
                 }
+                ValidationResult::Ignore(reason) => {
+                    warn!("Ignoring gossipsub beacon block: {reason}");
+                }
+                ValidationResult::Reject(reason) => {
+                    warn!("Rejecting gossipsub beacon block: {reason}");
+                }
             }
-            GossipsubMessage::BeaconAttestation((single_attestation, subnet_id)) => {
-                trace!(
-                    "Beacon Attestation received over gossipsub: root: {}",
-                    single_attestation.tree_hash_root()
-                );
-
-                match validate_beacon_attestation(
-                    &single_attestation,
-                    beacon_chain,
-                    subnet_id,
-                    cached_db,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: single_attestation.as_ssz_bytes(),
-                            });
-                        }
-                        ValidationResult::Reject(reason) => {
-                            info!("Attestation rejected: {reason}");
-                        }
-                        ValidationResult::Ignore(reason) => {
-                            info!("Attestation ignored: {reason}");
-                        }
-                    },
-                    Err(err) => {
-                        trace!("Could not validate attestation: {err}");
+
+            validation_result
+        }
+        GossipsubMessage::BeaconAttestation((single_attestation, subnet_id)) => {
+            trace!(
+                "Beacon Attestation received over gossipsub: root: {}",
+                single_attestation.tree_hash_root()
+            );
+
+            let validation_result = match validate_beacon_attestation(
+                &single_attestation,
+                beacon_chain,
+                subnet_id,
+                cached_db,
+                batch_verifier,
+            )
+            .await
+            {
+                Ok(validation_result) => validation_result,
+                Err(err) => {
+                    trace!("Could not validate attestation: {err}");
+                    return ValidationResult::Ignore(format!(
+                        "Could not validate attestation: {err}"
+                    ));
+                }
+            };
+
+            match &validation_result {
+                ValidationResult::Accept => {
+                    if let Err(err) = beacon_chain
+                        .process_single_attestation(&single_attestation)
+                        .await
+                    {
+                        warn!("Failed to process gossipsub attestation: {err}");
                     }
+                    beacon_chain
+                        .event_sender
+                        .send_event(BeaconEvent::SingleAttestation(
+                            SingleAttestationEvent::from(*single_attestation),
+                        ));
+                }
+                ValidationResult::Reject(reason) => {
+                    info!("Attestation rejected: {reason}");
+                }
+                ValidationResult::Ignore(reason) => {
+                    info!("Attestation ignored: {reason}");
                 }
             }
-            GossipsubMessage::BlsToExecutionChange(signed_bls_to_execution_change) => {
-                info!(
-                    "BLS to Execution Change received over gossipsub: root: {}",
-                    signed_bls_to_execution_change.tree_hash_root()
-                );
-
-                match validate_bls_to_execution_change(
-                    &signed_bls_to_execution_change,
-                    beacon_chain,
-                    cached_db,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: signed_bls_to_execution_change.as_ssz_bytes(),
-                            });
-                        }
-                        ValidationResult::Reject(reason) => {
-                            info!("BLS to Execution Change rejected: {reason}");
-                        }
-                        ValidationResult::Ignore(reason) => {
-                            info!("BLS to Execution Change ignored: {reason}");
-                        }
-                    },
-                    Err(err) => {
-                        error!("Could not validate BLS to Execution Change: {err}");
-                    }
+
+            validation_result
+        }
+        GossipsubMessage::BlsToExecutionChange(signed_bls_to_execution_change) => {
+            info!(
+                "BLS to Execution Change received over gossipsub: root: {}",
+                signed_bls_to_execution_change.tree_hash_root()
+            );
+
+            let validation_result = match validate_bls_to_execution_change(
+                &signed_bls_to_execution_change,
+                beacon_chain,
+                cached_db,
+            )
+            .await
+            {
+                Ok(validation_result) => validation_result,
+                Err(err) => {
+                    error!("Could not validate BLS to Execution Change: {err}");
+                    return ValidationResult::Ignore(format!(
+                        "Could not validate BLS to Execution Change: {err}"
+                    ));
+                }
+            };
+
+            match &validation_result {
+                ValidationResult::Accept => {
+                    beacon_chain
+                        .store
+                        .lock()
+                        .await
+                        .operation_pool
+                        .insert_signed_bls_to_execution_change(*signed_bls_to_execution_change);
+                }
+                ValidationResult::Reject(reason) => {
+                    info!("BLS to Execution Change rejected: {reason}");
+                }
+                ValidationResult::Ignore(reason) => {
+                    info!("BLS to Execution Change ignored: {reason}");
                 }
             }
-            GossipsubMessage::AggregateAndProof(aggregate_and_proof) => {
-                info!(
-                    "Aggregate And Proof received over gossipsub: root: {}",
-                    aggregate_and_proof.tree_hash_root()
-                );
 
+            validation_result
+        }
+        GossipsubMessage::AggregateAndProof(aggregate_and_proof) => {
+            info!(
+                "Aggregate And Proof received over gossipsub: root: {}",
+                aggregate_and_proof.tree_hash_root()
+            );
+
+            let validation_result =
                 match validate_aggregate_and_proof(&aggregate_and_proof, beacon_chain, cached_db)
                     .await
                 {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: aggregate_and_proof.as_ssz_bytes(),
-                            });
-                        }
-                        ValidationResult::Reject(reason) => {
-                            info!("Aggregate and proof rejected: {reason}");
-                        }
-                        ValidationResult::Ignore(reason) => {
-                            info!("Aggregate and proof ignored: {reason}");
-                        }
-                    },
+                    Ok(validation_result) => validation_result,
                     Err(err) => {
                         error!("Could not validate aggregate and proof: {err}");
+                        return ValidationResult::Ignore(format!(
+                            "Could not validate aggregate and proof: {err}"
+                        ));
+                    }
+                };
+
+            match &validation_result {
+                ValidationResult::Accept => {
+                    if let Err(err) = beacon_chain
+                        .process_aggregate(aggregate_and_proof.message.aggregate.clone())
+                        .await
+                    {
+                        warn!("Failed to process gossipsub aggregate: {err}");
                     }
+                    beacon_chain
+                        .event_sender
+                        .send_event(BeaconEvent::Attestation(AttestationEvent::from(
+                            aggregate_and_proof.message.aggregate,
+                        )));
+                }
+                ValidationResult::Reject(reason) => {
+                    info!("Aggregate and proof rejected: {reason}");
+                }
+                ValidationResult::Ignore(reason) => {
+                    info!("Aggregate and proof ignored: {reason}");
                 }
             }
-            GossipsubMessage::SyncCommittee((sync_committee, subnet_id)) => {
-                info!(
-                    "Sync Committee received over gossipsub: root: {}",
-                    sync_committee.tree_hash_root()
-                );
 
+            validation_result
+        }
+        GossipsubMessage::SyncCommittee((sync_committee, subnet_id)) => {
+            info!(
+                "Sync Committee received over gossipsub: root: {}",
+                sync_committee.tree_hash_root()
+            );
+
+            let validation_result =
                 match validate_sync_committee(&sync_committee, beacon_chain, subnet_id, cached_db)
                     .await
                 {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: sync_committee.as_ssz_bytes(),
-                            });
-                        }
-                        ValidationResult::Reject(reason) => {
-                            info!("Sync committee message rejected: {reason}");
-                        }
-                        ValidationResult::Ignore(reason) => {
-                            info!("Sync committee message ignored: {reason}");
-                        }
-                    },
+                    Ok(validation_result) => validation_result,
                     Err(err) => {
                         error!("Could not validate sync committee message: {err}");
+                        return ValidationResult::Ignore(format!(
+                            "Could not validate sync committee message: {err}"
+                        ));
                     }
+                };
+
+            match &validation_result {
+                ValidationResult::Accept => {}
+                ValidationResult::Reject(reason) => {
+                    info!("Sync committee message rejected: {reason}");
+                }
+                ValidationResult::Ignore(reason) => {
+                    info!("Sync committee message ignored: {reason}");
                 }
             }
-            GossipsubMessage::SyncCommitteeContributionAndProof(signed_contribution_and_proof) => {
-                info!(
-                    "Sync Committee Contribution And Proof received over gossipsub: root: {}",
-                    signed_contribution_and_proof.tree_hash_root()
-                );
-
-                match validate_sync_committee_contribution_and_proof(
-                    beacon_chain,
-                    cached_db,
-                    &signed_contribution_and_proof,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: signed_contribution_and_proof.as_ssz_bytes(),
-                            });
-                        }
-
-                        ValidationResult::Reject(reason) => {
-                            info!("Sync committee contribution and proof rejected: {reason}");
-                        }
-                        ValidationResult::Ignore(reason) => {
-                            info!("Sync committee contribution and proof ignored: {reason}");
-                        }
-                    },
-                    Err(err) => {
-                        error!("Could not validate sync committee contribution and proof: {err}");
-                    }
+
+            validation_result
+        }
+        GossipsubMessage::SyncCommitteeContributionAndProof(signed_contribution_and_proof) => {
+            info!(
+                "Sync Committee Contribution And Proof received over gossipsub: root: {}",
+                signed_contribution_and_proof.tree_hash_root()
+            );
+
+            let validation_result = match validate_sync_committee_contribution_and_proof(
+                beacon_chain,
+                cached_db,
+                &signed_contribution_and_proof,
+            )
+            .await
+            {
+                Ok(validation_result) => validation_result,
+                Err(err) => {
+                    error!("Could not validate sync committee contribution and proof: {err}");
+                    return ValidationResult::Ignore(format!(
+                        "Could not validate sync committee contribution and proof: {err}"
+                    ));
+                }
+            };
+
+            match &validation_result {
+                ValidationResult::Accept => {
+                    beacon_chain
+                        .event_sender
+                        .send_event(BeaconEvent::ContributionAndProof(
+                            ContributionAndProofEvent {
+                                message: signed_contribution_and_proof.message,
+                                signature: signed_contribution_and_proof.signature,
+                            },
+                        ));
+                }
+                ValidationResult::Reject(reason) => {
+                    info!("Sync committee contribution and proof rejected: {reason}");
+                }
+                ValidationResult::Ignore(reason) => {
+                    info!("Sync committee contribution and proof ignored: {reason}");
                 }
             }
-            GossipsubMessage::AttesterSlashing(attester_slashing) => {
-                info!(
-                    "Attester Slashing received over gossipsub: root: {}",
-                    attester_slashing.tree_hash_root()
-                );
 
+            validation_result
+        }
+        GossipsubMessage::AttesterSlashing(attester_slashing) => {
+            info!(
+                "Attester Slashing received over gossipsub: root: {}",
+                attester_slashing.tree_hash_root()
+            );
+
+            let validation_result =
                 match validate_attester_slashing(&attester_slashing, beacon_chain, cached_db).await
                 {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: attester_slashing.as_ssz_bytes(),
-                            });
-                            if let Err(err) = beacon_chain
-                                .process_attester_slashing(*attester_slashing)
-                                .await
-                            {
-                                error!("Failed to process gossipsub attester slashing: {err}");
-                            }
-                        }
-                        ValidationResult::Reject(reason) => {
-                            info!("Attester slashing rejected: {reason}");
-                        }
-                        ValidationResult::Ignore(reason) => {
-                            info!("Attester slashing ignored: {reason}");
-                        }
-                    },
+                    Ok(validation_result) => validation_result,
                     Err(err) => {
                         error!("Could not validate attester slashing: {err}");
+                        return ValidationResult::Ignore(format!(
+                            "Could not validate attester slashing: {err}"
+                        ));
                     }
+                };
+
+            match &validation_result {
+                ValidationResult::Accept => {
+                    beacon_chain
+                        .store
+                        .lock()
+                        .await
+                        .operation_pool
+                        .insert_attester_slashing(*attester_slashing.clone());
+                    if let Err(err) = beacon_chain
+                        .process_attester_slashing(*attester_slashing)
+                        .await
+                    {
+                        error!("Failed to process gossipsub attester slashing: {err}");
+                    }
+                }
+                ValidationResult::Reject(reason) => {
+                    info!("Attester slashing rejected: {reason}");
+                }
+                ValidationResult::Ignore(reason) => {
+                    info!("Attester slashing ignored: {reason}");
                 }
             }
-            GossipsubMessage::ProposerSlashing(proposer_slashing) => {
-                info!(
-                    "Proposer Slashing received over gossipsub: root: {}",
-                    proposer_slashing.tree_hash_root()
-                );
 
+            validation_result
+        }
+        GossipsubMessage::ProposerSlashing(proposer_slashing) => {
+            info!(
+                "Proposer Slashing received over gossipsub: root: {}",
+                proposer_slashing.tree_hash_root()
+            );
+
+            let validation_result =
                 match validate_proposer_slashing(&proposer_slashing, beacon_chain, cached_db).await
                 {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: proposer_slashing.as_ssz_bytes(),
-                            });
-                        }
-                        ValidationResult::Reject(reason) => {
-                            info!("Proposer slashing rejected: {reason}");
-                        }
-                        ValidationResult::Ignore(reason) => {
-                            info!("Proposer slashing ignored: {reason}");
-                        }
-                    },
+                    Ok(validation_result) => validation_result,
                     Err(err) => {
                         error!("Could not validate proposer slashing: {err}");
+                        return ValidationResult::Ignore(format!(
+                            "Could not validate proposer slashing: {err}"
+                        ));
                     }
+                };
+
+            match &validation_result {
+                ValidationResult::Accept => {
+                    beacon_chain
+                        .store
+                        .lock()
+                        .await
+                        .operation_pool
+                        .insert_proposer_slashing(*proposer_slashing);
+                }
+                ValidationResult::Reject(reason) => {
+                    info!("Proposer slashing rejected: {reason}");
+                }
+                ValidationResult::Ignore(reason) => {
+                    info!("Proposer slashing ignored: {reason}");
                 }
             }
-            GossipsubMessage::BlobSidecar(blob_sidecar) => {
-                info!(
-                    "Blob Sidecar received over gossipsub: root: {}",
-                    blob_sidecar.tree_hash_root()
-                );
-                match validate_blob_sidecar(
-                    beacon_chain,
-                    &blob_sidecar,
-                    compute_subnet_for_blob_sidecar(blob_sidecar.index),
-                    cached_db,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            let blob_sidecar_bytes = blob_sidecar.as_ssz_bytes();
-                            if let Err(err) = beacon_chain
-                                .store
-                                .lock()
-                                .await
-                                .db
-                                .blobs_and_proofs_provider()
-                                .insert(
-                                    BlobIdentifier::new(
-                                        blob_sidecar.signed_block_header.message.tree_hash_root(),
-                                        blob_sidecar.index,
-                                    ),
-                                    BlobAndProofV1 {
-                                        blob: blob_sidecar.blob,
-                                        proof: blob_sidecar.kzg_proof,
-                                    },
-                                )
-                            {
-                                error!("Failed to insert blob_sidecar: {err}");
-                            }
-
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: blob_sidecar_bytes,
-                            });
-                        }
-                        ValidationResult::Reject(reason) => {
-                            info!("Blob_sidecar rejected: {reason}");
-                        }
-                        ValidationResult::Ignore(reason) => {
-                            info!("Blob_sidecar ignored: {reason}");
-                        }
-                    },
-                    Err(err) => {
-                        error!("Could not validate blob_sidecar: {err}");
+
+            validation_result
+        }
+        GossipsubMessage::BlobSidecar(blob_sidecar) => {
+            info!(
+                "Blob Sidecar received over gossipsub: root: {}",
+                blob_sidecar.tree_hash_root()
+            );
+
+            let validation_result = match validate_blob_sidecar(
+                beacon_chain,
+                &blob_sidecar,
+                compute_subnet_for_blob_sidecar(blob_sidecar.index),
+                cached_db,
+            )
+            .await
+            {
+                Ok(validation_result) => validation_result,
+                Err(err) => {
+                    error!("Could not validate blob_sidecar: {err}");
+                    return ValidationResult::Ignore(format!(
+                        "Could not validate blob_sidecar: {err}"
+                    ));
+                }
+            };
+
+            match &validation_result {
+                ValidationResult::Accept => {
+                    beacon_chain
+                        .event_sender
+                        .send_event(BeaconEvent::BlobSidecar(BlobSidecarEvent::from(
+                            blob_sidecar.as_ref(),
+                        )));
+                    if let Err(err) = beacon_chain
+                        .store
+                        .lock()
+                        .await
+                        .db
+                        .blobs_and_proofs_provider()
+                        .insert(
+                            BlobIdentifier::new(
+                                blob_sidecar.signed_block_header.message.tree_hash_root(),
+                                blob_sidecar.index,
+                            ),
+                            BlobAndProofV1 {
+                                blob: blob_sidecar.blob,
+                                proof: blob_sidecar.kzg_proof,
+                            },
+                        )
+                    {
+                        error!("Failed to insert blob_sidecar: {err}");
                     }
                 }
+                ValidationResult::Reject(reason) => {
+                    info!("Blob_sidecar rejected: {reason}");
+                }
+                ValidationResult::Ignore(reason) => {
+                    info!("Blob_sidecar ignored: {reason}");
+                }
             }
-            GossipsubMessage::DataColumnSidecar(data_column_sidecar) => {
-                info!(
-                    "Data Column Sidecar received over gossipsub: index: {}, root: {}",
-                    data_column_sidecar.index,
-                    data_column_sidecar
-                        .signed_block_header
-                        .message
-                        .tree_hash_root()
-                );
-
-                // Extract subnet_id from the gossip topic
-                let subnet_id = match GossipTopic::from_topic_hash(&message.topic) {
-                    Ok(topic) => match topic.kind {
-                        GossipTopicKind::DataColumnSidecar(id) => id,
-                        _ => {
-                            error!("Unexpected topic kind for data column sidecar");
-                            return;
-                        }
-                    },
-                    Err(err) => {
-                        error!("Failed to parse topic for data column sidecar: {err}");
-                        return;
-                    }
-                };
 
-                let current_time_ms = match SystemTime::now().duration_since(UNIX_EPOCH) {
-                    Ok(duration) => u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
-                    Err(err) => {
-                        error!("Failed to get current time for data column validation: {err}");
-                        return;
-                    }
-                };
+            validation_result
+        }
+        GossipsubMessage::DataColumnSidecar(data_column_sidecar) => {
+            info!(
+                "Data Column Sidecar received over gossipsub: index: {}, root: {}",
+                data_column_sidecar.index,
+                data_column_sidecar
+                    .signed_block_header
+                    .message
+                    .tree_hash_root()
+            );
 
-                let validation_result = match validate_data_column_sidecar_full(
-                    &data_column_sidecar,
-                    beacon_chain,
-                    current_time_ms,
-                    subnet_id,
-                    cached_db,
-                )
-                .await
-                {
-                    Ok(validation_result) => validation_result,
-                    Err(err) => {
-                        error!("Could not validate data_column_sidecar: {err}");
-                        return;
+            // Extract subnet_id from the gossip topic
+            let subnet_id = match GossipTopic::from_topic_hash(&message.topic) {
+                Ok(topic) => match topic.kind {
+                    GossipTopicKind::DataColumnSidecar(id) => id,
+                    _ => {
+                        error!("Unexpected topic kind for data column sidecar");
+                        return ValidationResult::Reject(
+                            "Unexpected topic kind for data column sidecar".to_string(),
+                        );
                     }
-                };
+                },
+                Err(err) => {
+                    error!("Failed to parse topic for data column sidecar: {err}");
+                    return ValidationResult::Reject(format!(
+                        "Failed to parse topic for data column sidecar: {err}"
+                    ));
+                }
+            };
 
-                match validation_result {
-                    ValidationResult::Accept => {
-                        let data_column_sidecar_bytes = data_column_sidecar.as_ssz_bytes();
-                        if let Err(err) = beacon_chain
-                            .store
-                            .lock()
-                            .await
-                            .db
-                            .column_sidecars_provider()
-                            .insert(
-                                ColumnIdentifier::new(
-                                    data_column_sidecar
-                                        .signed_block_header
-                                        .message
-                                        .tree_hash_root(),
-                                    data_column_sidecar.index,
-                                ),
-                                *data_column_sidecar,
-                            )
-                        {
-                            error!("Failed to insert data_column_sidecar: {err}");
-                        }
-
-                        p2p_sender.send_gossip(GossipMessage {
-                            topic: GossipTopic::from_topic_hash(&message.topic)
-                                .expect("invalid topic hash"),
-                            data: data_column_sidecar_bytes,
-                        });
-                    }
-                    ValidationResult::Reject(reason) => {
-                        info!("Data column sidecar rejected: {reason}");
-                    }
-                    ValidationResult::Ignore(reason) => {
-                        info!("Data column sidecar ignored: {reason}");
+            let current_time_ms = match SystemTime::now().duration_since(UNIX_EPOCH) {
+                Ok(duration) => u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+                Err(err) => {
+                    error!("Failed to get current time for data column validation: {err}");
+                    return ValidationResult::Ignore(format!(
+                        "Failed to get current time for data column validation: {err}"
+                    ));
+                }
+            };
+
+            let validation_result = match validate_data_column_sidecar_full(
+                &data_column_sidecar,
+                beacon_chain,
+                current_time_ms,
+                subnet_id,
+                cached_db,
+            )
+            .await
+            {
+                Ok(validation_result) => validation_result,
+                Err(err) => {
+                    error!("Could not validate data_column_sidecar: {err}");
+                    return ValidationResult::Ignore(format!(
+                        "Could not validate data_column_sidecar: {err}"
+                    ));
+                }
+            };
+
+            match &validation_result {
+                ValidationResult::Accept => {
+                    beacon_chain
+                        .event_sender
+                        .send_event(BeaconEvent::DataColumnSidecar(
+                            DataColumnSidecarEvent::from(data_column_sidecar.as_ref()),
+                        ));
+                    if let Err(err) = beacon_chain
+                        .store
+                        .lock()
+                        .await
+                        .db
+                        .column_sidecars_provider()
+                        .insert(
+                            ColumnIdentifier::new(
+                                data_column_sidecar
+                                    .signed_block_header
+                                    .message
+                                    .tree_hash_root(),
+                                data_column_sidecar.index,
+                            ),
+                            *data_column_sidecar,
+                        )
+                    {
+                        error!("Failed to insert data_column_sidecar: {err}");
                     }
                 }
+                ValidationResult::Reject(reason) => {
+                    info!("Data column sidecar rejected: {reason}");
+                }
+                ValidationResult::Ignore(reason) => {
+                    info!("Data column sidecar ignored: {reason}");
+                }
             }
-            GossipsubMessage::LightClientFinalityUpdate(light_client_finality_update) => {
-                info!(
-                    "Light Client Finality Update received over gossipsub: root: {}",
-                    light_client_finality_update.tree_hash_root()
-                );
-
-                match validate_light_client_finality_update(
-                    &light_client_finality_update,
-                    cached_db,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: light_client_finality_update.as_ssz_bytes(),
-                            });
-                        }
-                        ValidationResult::Reject(reason) => {
-                            info!("Light client finality update rejected: {reason}");
-                        }
-                        ValidationResult::Ignore(reason) => {
-                            info!("Light client finality update ignored: {reason}");
-                        }
-                    },
-                    Err(err) => {
-                        error!("Could not validate light client finality update: {err}");
-                    }
+
+            validation_result
+        }
+        GossipsubMessage::LightClientFinalityUpdate(light_client_finality_update) => {
+            info!(
+                "Light Client Finality Update received over gossipsub: root: {}",
+                light_client_finality_update.tree_hash_root()
+            );
+
+            let validation_result = match validate_light_client_finality_update(
+                &light_client_finality_update,
+                cached_db,
+            )
+            .await
+            {
+                Ok(validation_result) => validation_result,
+                Err(err) => {
+                    error!("Could not validate light client finality update: {err}");
+                    return ValidationResult::Ignore(format!(
+                        "Could not validate light client finality update: {err}"
+                    ));
+                }
+            };
+
+            match &validation_result {
+                ValidationResult::Accept => {
+                    beacon_chain
+                        .event_sender
+                        .send_event(BeaconEvent::LightClientFinalityUpdate(Box::new(
+                            LightClientFinalityUpdateEvent {
+                                version: fork_name_at_epoch(compute_epoch_at_slot(
+                                    light_client_finality_update.attested_header.beacon.slot,
+                                ))
+                                .to_string(),
+                                data: *light_client_finality_update,
+                            },
+                        )));
+                }
+                ValidationResult::Reject(reason) => {
+                    info!("Light client finality update rejected: {reason}");
+                }
+                ValidationResult::Ignore(reason) => {
+                    info!("Light client finality update ignored: {reason}");
                 }
             }
-            GossipsubMessage::LightClientOptimisticUpdate(light_client_optimistic_update) => {
-                info!(
-                    "Light Client Optimistic Update received over gossipsub: root: {}",
-                    light_client_optimistic_update.tree_hash_root()
-                );
-
-                match validate_light_client_optimistic_update(
-                    &light_client_optimistic_update,
-                    beacon_chain,
-                    cached_db,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("Invalid topic hash"),
-                                data: light_client_optimistic_update.as_ssz_bytes(),
-                            });
-
-                            *cached_db.forwarded_optimistic_update_slot.write().await =
-                                Some(light_client_optimistic_update.attested_header.beacon.slot);
-                        }
-                        ValidationResult::Ignore(reason) => {
-                            info!("Light client optimistic update ignored: {reason}");
-                        }
-                        ValidationResult::Reject(reason) => {
-                            info!("Light client optimistic update rejected: {reason}");
-                        }
-                    },
-                    Err(err) => {
-                        error!("Could not validate light client optimistic update: {err}");
-                    }
+
+            validation_result
+        }
+        GossipsubMessage::LightClientOptimisticUpdate(light_client_optimistic_update) => {
+            info!(
+                "Light Client Optimistic Update received over gossipsub: root: {}",
+                light_client_optimistic_update.tree_hash_root()
+            );
+
+            let validation_result = match validate_light_client_optimistic_update(
+                &light_client_optimistic_update,
+                beacon_chain,
+                cached_db,
+            )
+            .await
+            {
+                Ok(validation_result) => validation_result,
+                Err(err) => {
+                    error!("Could not validate light client optimistic update: {err}");
+                    return ValidationResult::Ignore(format!(
+                        "Could not validate light client optimistic update: {err}"
+                    ));
+                }
+            };
+
+            match &validation_result {
+                ValidationResult::Accept => {
+                    let attested_slot = light_client_optimistic_update.attested_header.beacon.slot;
+                    *cached_db.forwarded_optimistic_update_slot.write().await = Some(attested_slot);
+                    beacon_chain
+                        .event_sender
+                        .send_event(BeaconEvent::LightClientOptimisticUpdate(Box::new(
+                            LightClientOptimisticUpdateEvent {
+                                version: fork_name_at_epoch(compute_epoch_at_slot(attested_slot))
+                                    .to_string(),
+                                data: *light_client_optimistic_update,
+                            },
+                        )));
+                }
+                ValidationResult::Ignore(reason) => {
+                    info!("Light client optimistic update ignored: {reason}");
+                }
+                ValidationResult::Reject(reason) => {
+                    info!("Light client optimistic update rejected: {reason}");
                 }
             }
-            GossipsubMessage::VoluntaryExit(voluntary_exit) => {
-                info!(
-                    "Voluntary Exit received over gossipsub: root: {}",
-                    voluntary_exit.tree_hash_root()
-                );
 
+            validation_result
+        }
+        GossipsubMessage::VoluntaryExit(voluntary_exit) => {
+            info!(
+                "Voluntary Exit received over gossipsub: root: {}",
+                voluntary_exit.tree_hash_root()
+            );
+
+            let validation_result =
                 match validate_voluntary_exit(&voluntary_exit, beacon_chain, cached_db).await {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: voluntary_exit.as_ssz_bytes(),
-                            });
-                        }
-                        ValidationResult::Reject(reason) => {
-                            info!("voluntary_exit rejected: {reason}");
-                        }
-                        ValidationResult::Ignore(reason) => {
-                            info!("voluntary_exit ignored: {reason}");
-                        }
-                    },
+                    Ok(validation_result) => validation_result,
                     Err(err) => {
                         error!("Could not validate voluntary_exit: {err}");
+                        return ValidationResult::Ignore(format!(
+                            "Could not validate voluntary_exit: {err}"
+                        ));
                     }
+                };
+
+            match &validation_result {
+                ValidationResult::Accept => {
+                    beacon_chain
+                        .store
+                        .lock()
+                        .await
+                        .operation_pool
+                        .insert_signed_voluntary_exit(*voluntary_exit);
+                }
+                ValidationResult::Reject(reason) => {
+                    info!("voluntary_exit rejected: {reason}");
+                }
+                ValidationResult::Ignore(reason) => {
+                    info!("voluntary_exit ignored: {reason}");
                 }
             }
-        },
-        Err(err) => {
-            trace!("Failed to decode gossip message: {err:?}");
+
+            validation_result
         }
-    };
+    }
 }
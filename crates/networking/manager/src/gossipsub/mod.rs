@@ -1,2 +1,3 @@
+pub mod batch_verifier;
 pub mod handle;
 pub mod validate;
@@ -1,5 +1,5 @@
 use anyhow::anyhow;
-use ream_bls::traits::Verifiable;
+use ream_bls::SignatureSet;
 use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_consensus_beacon::{
     electra::beacon_state::BeaconState, single_attestation::SingleAttestation,
@@ -15,12 +15,18 @@ use ream_storage::{
 use ream_validator_beacon::attestation::compute_subnet_for_attestation;
 
 use super::result::ValidationResult;
+use crate::gossipsub::batch_verifier::SignatureBatchVerifier;
 
+/// Validates a gossip attestation.
+///
+/// The signature is checked last, after the fork choice store is released, through
+/// `batch_verifier` so attestations arriving together are verified as one batch.
 pub async fn validate_beacon_attestation(
     attestation: &SingleAttestation,
     beacon_chain: &BeaconChain,
     attestation_subnet_id: u64,
     cached_db: &BeaconCacheDB,
+    batch_verifier: &SignatureBatchVerifier,
 ) -> anyhow::Result<ValidationResult> {
     let store = beacon_chain.store.lock().await;
 
@@ -120,25 +126,6 @@ pub async fn validate_beacon_attestation(
         ));
     }
 
-    // [REJECT] The signature of attestation is valid.
-    let validator = state
-        .validators
-        .get(attestation.attester_index as usize)
-        .ok_or_else(|| anyhow!("Could not get validator"))?;
-
-    let domain = state.get_domain(DOMAIN_BEACON_ATTESTER, Some(attestation.data.target.epoch));
-    let signing_root = compute_signing_root(&attestation.data, domain);
-
-    let signature_valid = attestation
-        .signature
-        .verify(&validator.public_key, signing_root.as_slice())?;
-
-    if !signature_valid {
-        return Ok(ValidationResult::Reject(
-            "Invalid attestation signature".to_string(),
-        ));
-    }
-
     // [IGNORE] The block being voted for (aggregate.data.beacon_block_root) has been seen (via
     // gossip or non-gossip sources) (a client MAY queue aggregates for processing once block is
     // retrieved).
@@ -180,10 +167,33 @@ pub async fn validate_beacon_attestation(
         ));
     }
 
-    cached_db
-        .seen_attestations
-        .write()
-        .await
-        .put(attestation_key, ());
+    let validator = state
+        .validators
+        .get(attestation.attester_index as usize)
+        .ok_or_else(|| anyhow!("Could not get validator"))?;
+    let domain = state.get_domain(DOMAIN_BEACON_ATTESTER, Some(attestation.data.target.epoch));
+    let signature_set = SignatureSet::single(
+        attestation.signature.clone(),
+        validator.public_key.clone(),
+        compute_signing_root(&attestation.data, domain),
+    );
+    drop(store);
+
+    // [REJECT] The signature of attestation is valid.
+    if !batch_verifier.verify(signature_set).await? {
+        return Ok(ValidationResult::Reject(
+            "Invalid attestation signature".to_string(),
+        ));
+    }
+
+    // Another copy of the attestation may have been accepted while the signature was verified
+    let mut seen_attestations = cached_db.seen_attestations.write().await;
+    if seen_attestations.contains(&attestation_key) {
+        return Ok(ValidationResult::Ignore(
+            "There has been no other valid attestation seen".to_string(),
+        ));
+    }
+    seen_attestations.put(attestation_key, ());
+
     Ok(ValidationResult::Accept)
 }
@@ -1,6 +1,18 @@
+use libp2p::gossipsub::MessageAcceptance;
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ValidationResult {
     Accept,
     Ignore(String),
     Reject(String),
 }
+
+impl From<&ValidationResult> for MessageAcceptance {
+    fn from(result: &ValidationResult) -> Self {
+        match result {
+            ValidationResult::Accept => MessageAcceptance::Accept,
+            ValidationResult::Ignore(_) => MessageAcceptance::Ignore,
+            ValidationResult::Reject(_) => MessageAcceptance::Reject,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use anyhow::anyhow;
-use libp2p::{PeerId, swarm::ConnectionId};
-use ream_p2p::network::beacon::channel::{GossipMessage, P2PMessage, P2PResponse};
+use libp2p::{PeerId, gossipsub::MessageId, swarm::ConnectionId};
+use ream_p2p::network::beacon::channel::{
+    GossipMessage, GossipValidationResult, P2PMessage, P2PResponse,
+};
 use ream_req_resp::{
     beacon::messages::BeaconResponseMessage, error::ReqRespError, handler::RespMessage,
     messages::ResponseMessage,
@@ -8,6 +10,8 @@ use ream_req_resp::{
 use tokio::sync::mpsc;
 use tracing::warn;
 
+use crate::gossipsub::validate::result::ValidationResult;
+
 #[derive(Clone)]
 pub struct P2PSender(pub mpsc::UnboundedSender<P2PMessage>);
 
@@ -18,6 +22,26 @@ impl P2PSender {
         }
     }
 
+    /// Report the validation result of a received gossip message to gossipsub, which forwards
+    /// accepted messages and penalizes the peers of rejected ones.
+    pub fn report_validation_result(
+        &self,
+        message_id: MessageId,
+        propagation_source: PeerId,
+        validation_result: &ValidationResult,
+    ) {
+        if let Err(err) = self
+            .0
+            .send(P2PMessage::GossipValidationResult(GossipValidationResult {
+                message_id,
+                propagation_source,
+                acceptance: validation_result.into(),
+            }))
+        {
+            warn!("Failed to send gossip validation result: {err}");
+        }
+    }
+
     pub fn send_response(
         &self,
         peer_id: PeerId,
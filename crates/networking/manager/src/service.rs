@@ -13,21 +13,34 @@ use ream_executor::ReamExecutor;
 use ream_network_spec::networks::beacon_network_spec;
 use ream_p2p::{
     config::NetworkConfig,
+    gossipsub::beacon::topics::{GossipTopic, GossipTopicKind},
     network::beacon::{Network, ReamNetworkEvent, network_state::NetworkState},
 };
+use ream_req_resp::{Chain, rate_limiter::RateLimiterConfig};
 use ream_storage::{cache::BeaconCacheDB, db::beacon::BeaconDB};
 use ream_sync_committee_pool::SyncCommitteePool;
 use ream_syncer::block_range::BlockRangeSyncer;
-use tokio::{sync::mpsc, time::interval};
+use tokio::{
+    sync::{Semaphore, mpsc},
+    time::interval,
+};
 use tracing::{error, info};
 
 use crate::{
     config::ManagerConfig,
-    gossipsub::handle::{handle_gossipsub_message, init_gossipsub_config_with_topics},
+    gossipsub::{
+        batch_verifier::SignatureBatchVerifier,
+        handle::{handle_gossipsub_message, init_gossipsub_config_with_topics},
+        validate::result::ValidationResult,
+    },
     p2p_sender::P2PSender,
     req_resp::handle_req_resp_message,
 };
 
+/// Maximum number of gossip attestations validated at the same time. Attestations arriving while
+/// all of them are in flight are ignored.
+const MAX_CONCURRENT_ATTESTATIONS: usize = 1024;
+
 pub struct NetworkManagerService {
     pub beacon_chain: Arc<BeaconChain>,
     manager_receiver: mpsc::UnboundedReceiver<ReamNetworkEvent>,
@@ -37,6 +50,8 @@ pub struct NetworkManagerService {
     pub ream_db: BeaconDB,
     pub cached_db: Arc<BeaconCacheDB>,
     pub sync_committee_pool: Arc<SyncCommitteePool>,
+    pub batch_verifier: SignatureBatchVerifier,
+    executor: ReamExecutor,
 }
 
 /// The `NetworkManagerService` acts as the manager for all networking activities in Ream.
@@ -89,6 +104,9 @@ impl NetworkManagerService {
             discv5_config,
             gossipsub_config,
             data_dir: ream_directory,
+            private_key_path: config.private_key_path,
+            rate_limiter_config: RateLimiterConfig::new(Chain::Beacon)
+                .with_quotas(&config.inbound_rate_limits),
         };
 
         let (manager_sender, manager_receiver) = mpsc::unbounded_channel();
@@ -110,6 +128,7 @@ impl NetworkManagerService {
             network_state.clone(),
             executor.clone(),
         );
+        let batch_verifier = SignatureBatchVerifier::new(&executor);
 
         Ok(Self {
             beacon_chain,
@@ -120,6 +139,8 @@ impl NetworkManagerService {
             ream_db,
             cached_db,
             sync_committee_pool,
+            batch_verifier,
+            executor,
         })
     }
 
@@ -136,6 +157,8 @@ impl NetworkManagerService {
             cached_db,
             network_state,
             block_range_syncer,
+            batch_verifier,
+            executor,
             ..
         } = self;
 
@@ -143,6 +166,7 @@ impl NetworkManagerService {
             beacon_network_spec().seconds_per_slot(),
         ));
         let mut syncer_handle = block_range_syncer.start();
+        let attestation_permits = Arc::new(Semaphore::new(MAX_CONCURRENT_ATTESTATIONS));
         loop {
             tokio::select! {
                 result = &mut syncer_handle => {
@@ -186,9 +210,34 @@ impl NetworkManagerService {
                 }
                 Some(event) = manager_receiver.recv() => {
                     match event {
+                        // Attestations are handled concurrently, so the ones arriving together have
+                        // their signatures verified as one batch.
+                        ReamNetworkEvent::GossipsubMessage { message_id, propagation_source, message }
+                            if matches!(
+                                GossipTopic::from_topic_hash(&message.topic),
+                                Ok(GossipTopic { kind: GossipTopicKind::BeaconAttestation(_), .. })
+                            ) =>
+                        {
+                            let Ok(permit) = attestation_permits.clone().try_acquire_owned() else {
+                                p2p_sender.report_validation_result(
+                                    message_id,
+                                    propagation_source,
+                                    &ValidationResult::Ignore("Too many attestations being validated".to_string()),
+                                );
+                                continue;
+                            };
+                            let beacon_chain = beacon_chain.clone();
+                            let cached_db = cached_db.clone();
+                            let p2p_sender = p2p_sender.clone();
+                            let batch_verifier = batch_verifier.clone();
+                            executor.spawn(async move {
+                                handle_gossipsub_message(message, message_id, propagation_source, &beacon_chain, &cached_db, &p2p_sender, &batch_verifier).await;
+                                drop(permit);
+                            });
+                        }
                         // Handles Gossipsub messages from other peers.
-                        ReamNetworkEvent::GossipsubMessage { message } =>
-                            handle_gossipsub_message(message, &beacon_chain, &cached_db, &p2p_sender).await,
+                        ReamNetworkEvent::GossipsubMessage { message_id, propagation_source, message } =>
+                            handle_gossipsub_message(message, message_id, propagation_source, &beacon_chain, &cached_db, &p2p_sender, &batch_verifier).await,
                         // Handles Req/Resp messages from other peers.
                         ReamNetworkEvent::RequestMessage { peer_id, stream_id, connection_id, message } =>
                             handle_req_resp_message(peer_id, stream_id, connection_id, message, &p2p_sender, &ream_db, network_state.clone()).await,
@@ -300,6 +300,12 @@ impl NetworkState {
             cached_peer.peer_score = cached_peer.peer_score.saturating_sub(20);
         }
     }
+
+    pub fn rate_limited_request_from_peer(&self, peer_id: PeerId) {
+        if let Some(cached_peer) = self.peer_table.lock().get_mut(&peer_id) {
+            cached_peer.peer_score = cached_peer.peer_score.saturating_sub(10);
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -86,6 +86,15 @@ impl Bootnodes {
         }
     }
 
+    /// ENRs to seed the discovery routing table of the lean node with. The default lean peers
+    /// and multiaddr bootnodes have no ENR, so they are only dialed directly.
+    pub fn to_enrs_lean(&self) -> Vec<Enr> {
+        match self {
+            Bootnodes::Custom(enrs) => enrs.clone(),
+            Bootnodes::Default | Bootnodes::None | Bootnodes::Multiaddr(_) => vec![],
+        }
+    }
+
     pub fn to_multiaddrs_lean(&self) -> Vec<Multiaddr> {
         match self {
             Bootnodes::Default => {
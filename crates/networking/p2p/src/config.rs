@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use ream_discv5::config::DiscoveryConfig;
+use ream_req_resp::rate_limiter::RateLimiterConfig;
 
 use crate::gossipsub::beacon::configurations::GossipsubConfig;
 
@@ -10,4 +11,10 @@ pub struct NetworkConfig {
     pub gossipsub_config: GossipsubConfig,
 
     pub data_dir: PathBuf,
+
+    /// Hex encoded secp256k1 network key to use instead of the one in `data_dir`.
+    pub private_key_path: Option<PathBuf>,
+
+    /// Quotas of the inbound req/resp requests of every peer.
+    pub rate_limiter_config: RateLimiterConfig,
 }
@@ -1,7 +1,11 @@
 use std::sync::Arc;
 
 use alloy_primitives::B256;
-use libp2p::{PeerId, swarm::ConnectionId};
+use libp2p::{
+    PeerId,
+    gossipsub::{MessageAcceptance, MessageId},
+    swarm::ConnectionId,
+};
 use ream_consensus_beacon::blob_sidecar::BlobIdentifier;
 use ream_req_resp::{
     beacon::messages::{BeaconResponseMessage, status::Status},
@@ -22,6 +26,7 @@ pub enum P2PMessage {
     Request(P2PRequest),
     Response(P2PResponse),
     Gossip(GossipMessage),
+    GossipValidationResult(GossipValidationResult),
 }
 
 pub enum P2PRequest {
@@ -54,6 +59,15 @@ pub struct P2PResponse {
     pub message: Box<RespMessage>,
 }
 
+/// The outcome of validating a received gossip message, which gossipsub uses to forward it or
+/// penalize the peer that sent it.
+#[derive(Debug, Clone)]
+pub struct GossipValidationResult {
+    pub message_id: MessageId,
+    pub propagation_source: PeerId,
+    pub acceptance: MessageAcceptance,
+}
+
 #[derive(Debug, Clone)]
 pub struct GossipMessage {
     pub topic: GossipTopic,
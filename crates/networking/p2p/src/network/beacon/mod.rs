@@ -12,7 +12,7 @@ use std::{
 };
 
 use anyhow::anyhow;
-use channel::{P2PCallbackResponse, P2PMessage, P2PRequest, P2PResponse};
+use channel::{GossipValidationResult, P2PCallbackResponse, P2PMessage, P2PRequest, P2PResponse};
 use delay_map::{HashMapDelay, HashSetDelay};
 use discv5::Enr;
 use libp2p::{
@@ -20,12 +20,14 @@ use libp2p::{
     connection_limits::{self, ConnectionLimits},
     core::ConnectedPoint,
     futures::StreamExt,
-    gossipsub::{Event as GossipsubEvent, IdentTopic as Topic, Message, MessageAuthenticity},
+    gossipsub::{
+        Event as GossipsubEvent, IdentTopic as Topic, Message, MessageAuthenticity, MessageId,
+    },
     identify,
     multiaddr::Protocol,
     swarm::{self, ConnectionId, NetworkBehaviour, SwarmEvent},
 };
-use libp2p_identity::{Keypair, PublicKey, secp256k1};
+use libp2p_identity::{Keypair, PublicKey};
 use network_state::NetworkState;
 use parking_lot::{Mutex, RwLock};
 use peer::CachedPeer;
@@ -53,13 +55,16 @@ use tokio::{
     time::interval,
 };
 use tracing::{error, info, trace, warn};
-use utils::read_meta_data_from_disk;
+use utils::{load_or_create_network_key, read_enr_from_disk, read_meta_data_from_disk};
 
 use crate::{
     config::NetworkConfig,
     constants::{PING_INTERVAL_DURATION, TARGET_PEER_COUNT},
     gossipsub::{GossipsubBehaviour, beacon::topics::GossipTopic, snappy::SnappyTransform},
-    network::misc::{Executor, build_transport, peer_id_from_enr},
+    network::{
+        misc::{Executor, build_transport, peer_id_from_enr},
+        peer_store::{PEER_STORE_FLUSH_INTERVAL, PeerStore},
+    },
 };
 
 #[derive(NetworkBehaviour)]
@@ -92,6 +97,8 @@ pub enum ReamNetworkEvent {
         message: BeaconRequestMessage,
     },
     GossipsubMessage {
+        message_id: MessageId,
+        propagation_source: PeerId,
         message: Message,
     },
 }
@@ -104,14 +111,15 @@ pub struct Network {
     request_id: u64,
     network_state: Arc<NetworkState>,
     peers_to_ping: HashSetDelay<PeerId>,
+    peer_store: PeerStore,
 }
 
 impl Network {
     /// Initializes the network by:
-    /// - Creating a local keypair
+    /// - Loading the local keypair, or creating and saving one on first start
     /// - Setting up the discovery, req_resp and gossipsub behaviours
     /// - Starting P2P listening and discovery
-    /// - Connecting to the configured bootnodes
+    /// - Connecting to the peers of the previous run, then to the configured bootnodes
     /// - Subscribing to the configured gossipsub topics
     ///
     /// Note that this function starts P2P listening, but not handling network events yet.
@@ -121,20 +129,26 @@ impl Network {
         config: &NetworkConfig,
         status: Status,
     ) -> anyhow::Result<Self> {
-        let local_key = secp256k1::Keypair::generate();
+        let local_key =
+            load_or_create_network_key(&config.data_dir, config.private_key_path.as_deref())?;
+        let previous_enr = read_enr_from_disk(&config.data_dir).unwrap_or_else(|err| {
+            error!("Failed to read ENR from disk: {err:?}");
+            None
+        });
 
         let discovery = {
             let mut discovery = Discovery::new(
                 Keypair::from(local_key.clone()),
                 &config.discv5_config,
                 status.head_slot,
+                previous_enr,
             )
             .await?;
             discovery.discover_peers(QueryType::Peers, 16);
             discovery
         };
 
-        let req_resp = ReqResp::new(Chain::Beacon);
+        let req_resp = ReqResp::new(Chain::Beacon, config.rate_limiter_config.clone());
 
         let gossipsub = {
             let snappy_transform =
@@ -212,6 +226,18 @@ impl Network {
             status: RwLock::new(status),
             data_dir: config.data_dir.clone(),
         });
+        if let Err(err) = network_state.write_enr_to_disk() {
+            error!("Failed to write ENR to disk: {err:?}");
+        }
+
+        let peer_store = PeerStore::load(&config.data_dir).unwrap_or_else(|err| {
+            error!("Failed to load the peer store, starting without known peers: {err:?}");
+            PeerStore::empty(&config.data_dir)
+        });
+        info!(
+            "Loaded {} known peers from the peer store",
+            peer_store.len()
+        );
 
         let mut network = Network {
             peer_id: PeerId::from_public_key(&PublicKey::from(local_key.public().clone())),
@@ -221,6 +247,7 @@ impl Network {
             request_id: 0,
             network_state,
             peers_to_ping: HashSetDelay::new(PING_INTERVAL_DURATION),
+            peer_store,
         };
 
         network.start_network_worker(config).await?;
@@ -246,6 +273,18 @@ impl Network {
             }
         }
 
+        // Redial the peers of the previous run first, best peers first
+        let stored_peer_enrs = self
+            .peer_store
+            .peers_to_dial()
+            .into_iter()
+            .filter_map(|peer| peer.enr.clone())
+            .take(TARGET_PEER_COUNT)
+            .collect::<Vec<_>>();
+        for enr in stored_peer_enrs {
+            self.dial_enr(enr);
+        }
+
         let mut bootnodes = HashMap::new();
         for bootnode in config.discv5_config.bootnodes.clone() {
             bootnodes.insert(bootnode, None);
@@ -305,6 +344,8 @@ impl Network {
         mut p2p_receiver: UnboundedReceiver<P2PMessage>,
     ) {
         let mut status_interval = interval(Duration::from_secs(30));
+        let mut peer_store_flush_interval = interval(PEER_STORE_FLUSH_INTERVAL);
+        peer_store_flush_interval.reset();
         loop {
             tokio::select! {
                 Some(event) = self.swarm.next() => {
@@ -348,6 +389,9 @@ impl Network {
                                 warn!("Failed to publish gossip message: {err}");
                             }
                         }
+                        P2PMessage::GossipValidationResult(GossipValidationResult { message_id, propagation_source, acceptance }) => {
+                            self.swarm.behaviour_mut().gossipsub.report_message_validation_result(&message_id, &propagation_source, acceptance);
+                        }
                     }
                 }
                 Some(Ok(peer_id)) = self.peers_to_ping.next() => {
@@ -367,6 +411,11 @@ impl Network {
                         warn!("Failed to send timeout response: {err:?}");
                     }
                 }
+                _ = peer_store_flush_interval.tick() => {
+                    if let Err(err) = self.peer_store.flush() {
+                        warn!("Failed to write the peer store: {err:?}");
+                    }
+                }
                 _ = status_interval.tick() => {
                     let now = Instant::now();
                     let mut peer_table = self.network_state.peer_table.write();
@@ -466,6 +515,15 @@ impl Network {
             SwarmEvent::ConnectionEstablished {
                 peer_id, endpoint, ..
             } => {
+                // Bans are kept in the peer store, so they outlast restarts
+                if self.peer_store.is_banned(&peer_id) {
+                    trace!("Disconnecting banned peer {peer_id}");
+                    if let Err(err) = self.swarm.disconnect_peer_id(peer_id) {
+                        warn!("Failed to disconnect banned peer {peer_id}: {err:?}");
+                    }
+                    return None;
+                }
+
                 if let ConnectedPoint::Listener { send_back_addr, .. } = &endpoint {
                     self.network_state.upsert_peer(
                         peer_id,
@@ -474,7 +532,21 @@ impl Network {
                         Direction::Inbound,
                         None,
                     );
+                    // The port an inbound peer connects from can't be dialed back
+                    self.peer_store.record_connection(peer_id, None);
                 } else {
+                    self.peer_store
+                        .record_connection(peer_id, Some(endpoint.get_remote_address().clone()));
+                    if let Some(enr) = self
+                        .network_state
+                        .peer_table
+                        .read()
+                        .get(&peer_id)
+                        .and_then(|peer| peer.enr.clone())
+                    {
+                        self.peer_store.record_enr(peer_id, enr);
+                    }
+
                     // send status request to the peer
                     let status_message =
                         BeaconRequestMessage::Status(self.network_state.status.read().clone());
@@ -511,6 +583,9 @@ impl Network {
                     }
                     DiscoveryOutEvent::UpdatedEnr { enr } => {
                         *self.network_state.local_enr.write() = enr;
+                        if let Err(err) = self.network_state.write_enr_to_disk() {
+                            error!("Failed to write ENR to disk: {err:?}");
+                        }
                         None
                     }
                 },
@@ -533,45 +608,71 @@ impl Network {
     fn handle_discovered_peers(&mut self, peers: HashMap<Enr, Option<Instant>>) {
         trace!("Discovered peers: {peers:?}");
         for (enr, _) in peers {
-            let mut multiaddrs: Vec<Multiaddr> = Vec::new();
-            if let Some(ip) = enr.ip4()
-                && let Some(tcp) = enr.tcp4()
-            {
-                let mut multiaddr: Multiaddr = ip.into();
-                multiaddr.push(Protocol::Tcp(tcp));
-                multiaddrs.push(multiaddr);
+            self.dial_enr(enr);
+        }
+    }
+
+    /// Dial the TCP addresses of `enr`, tracking the peer as connecting if any dial started. Banned
+    /// peers, peers which are connected or being connected to, and any peer once the node has
+    /// reached its target peer count are skipped.
+    fn dial_enr(&mut self, enr: Enr) {
+        if let Some(peer_id) = peer_id_from_enr(&enr) {
+            if self.peer_store.is_banned(&peer_id) {
+                trace!("Skipping banned peer: {peer_id}");
+                return;
             }
-            if let Some(ip6) = enr.ip6()
-                && let Some(tcp6) = enr.tcp6()
+            if self.swarm.is_connected(&peer_id)
+                || self
+                    .cached_peer(&peer_id)
+                    .is_some_and(|peer| peer.state == ConnectionState::Connecting)
             {
-                let mut multiaddr: Multiaddr = ip6.into();
-                multiaddr.push(Protocol::Tcp(tcp6));
-                multiaddrs.push(multiaddr);
+                trace!("Skipping peer which is already connected or connecting: {peer_id}");
+                return;
             }
+        }
+        if self.network_state.connected_peer_count() >= TARGET_PEER_COUNT {
+            trace!("Peer count is at target, not dialing peer: {enr:?}");
+            return;
+        }
 
-            let mut successfully_dialed = false;
-            for multiaddr in multiaddrs {
-                if let Err(err) = self.swarm.dial(multiaddr) {
-                    warn!("Failed to dial peer: {err:?}");
-                } else {
-                    successfully_dialed = true;
-                }
-            }
+        let mut multiaddrs: Vec<Multiaddr> = Vec::new();
+        if let Some(ip) = enr.ip4()
+            && let Some(tcp) = enr.tcp4()
+        {
+            let mut multiaddr: Multiaddr = ip.into();
+            multiaddr.push(Protocol::Tcp(tcp));
+            multiaddrs.push(multiaddr);
+        }
+        if let Some(ip6) = enr.ip6()
+            && let Some(tcp6) = enr.tcp6()
+        {
+            let mut multiaddr: Multiaddr = ip6.into();
+            multiaddr.push(Protocol::Tcp(tcp6));
+            multiaddrs.push(multiaddr);
+        }
 
-            if !successfully_dialed {
-                trace!("Failed to dial any multiaddr for peer: {:?}", enr);
-                continue;
+        let mut successfully_dialed = false;
+        for multiaddr in multiaddrs {
+            if let Err(err) = self.swarm.dial(multiaddr) {
+                warn!("Failed to dial peer: {err:?}");
+            } else {
+                successfully_dialed = true;
             }
+        }
 
-            if let Some(peer_id) = peer_id_from_enr(&enr) {
-                self.network_state.upsert_peer(
-                    peer_id,
-                    None,
-                    ConnectionState::Connecting,
-                    Direction::Outbound,
-                    Some(enr.clone()),
-                );
-            }
+        if !successfully_dialed {
+            trace!("Failed to dial any multiaddr for peer: {:?}", enr);
+            return;
+        }
+
+        if let Some(peer_id) = peer_id_from_enr(&enr) {
+            self.network_state.upsert_peer(
+                peer_id,
+                None,
+                ConnectionState::Connecting,
+                Direction::Outbound,
+                Some(enr.clone()),
+            );
         }
     }
 
@@ -596,6 +697,11 @@ impl Network {
 
         let message = match message {
             Ok(message) => message,
+            Err(ReqRespMessageError::RateLimitBan { duration }) => {
+                // Kept in the peer store, which refuses the peer's connections until it expires
+                self.peer_store.ban(peer_id, duration);
+                return None;
+            }
             Err(err) => {
                 if let ReqRespMessageError::Outbound { request_id, .. } = &err
                     && let Some(callback) = self.callbacks.get(request_id)
@@ -801,10 +907,14 @@ impl Network {
     fn handle_gossipsub_event(&mut self, event: GossipsubEvent) -> Option<ReamNetworkEvent> {
         match event {
             GossipsubEvent::Message {
-                propagation_source: _,
-                message_id: _,
+                propagation_source,
+                message_id,
+                message,
+            } => Some(ReamNetworkEvent::GossipsubMessage {
+                message_id,
+                propagation_source,
                 message,
-            } => Some(ReamNetworkEvent::GossipsubMessage { message }),
+            }),
             GossipsubEvent::Subscribed { peer_id, topic } => {
                 trace!("Peer {peer_id} subscribed to topic: {topic:?}");
                 None
@@ -838,6 +948,14 @@ impl Network {
     }
 }
 
+impl Drop for Network {
+    fn drop(&mut self) {
+        if let Err(err) = self.peer_store.flush() {
+            warn!("Failed to write the peer store: {err:?}");
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::net::IpAddr;
@@ -853,6 +971,7 @@ mod tests {
     };
     use ream_executor::ReamExecutor;
     use ream_network_spec::networks::beacon::initialize_test_network_spec;
+    use ream_req_resp::rate_limiter::RateLimiterConfig;
     use tokio::{runtime::Runtime, time::sleep};
 
     use super::*;
@@ -893,7 +1012,10 @@ mod tests {
                 topics,
                 ..Default::default()
             },
-            data_dir: std::env::temp_dir().join("ream_network_test"),
+            // Every network gets its own data directory, so it doesn't reuse another's key
+            data_dir: std::env::temp_dir().join(format!("ream_network_test_{}", PeerId::random())),
+            private_key_path: None,
+            rate_limiter_config: RateLimiterConfig::new(Chain::Beacon),
         };
 
         Network::init(
@@ -8,7 +8,10 @@ use ream_peer::{ConnectionState, Direction};
 use ream_req_resp::beacon::messages::{meta_data::GetMetaDataV3, status::Status};
 use ssz::Encode;
 
-use super::{peer::CachedPeer, utils::META_DATA_FILE_NAME};
+use super::{
+    peer::CachedPeer,
+    utils::{ENR_FILE_NAME, META_DATA_FILE_NAME},
+};
 
 pub struct NetworkState {
     pub local_enr: RwLock<Enr>,
@@ -59,6 +62,22 @@ impl NetworkState {
         Ok(())
     }
 
+    /// Save the local ENR, so its sequence number keeps increasing across restarts.
+    pub fn write_enr_to_disk(&self) -> anyhow::Result<()> {
+        let enr_path = self.data_dir.join(ENR_FILE_NAME);
+        fs::write(enr_path, self.local_enr.read().to_base64())
+            .map_err(|err| anyhow!("Failed to write ENR to disk: {err:?}"))?;
+        Ok(())
+    }
+
+    pub fn connected_peer_count(&self) -> usize {
+        self.peer_table
+            .read()
+            .values()
+            .filter(|peer| peer.state == ConnectionState::Connected)
+            .count()
+    }
+
     /// Gets a vector of all connected peers.
     pub fn connected_peers(&self) -> Vec<CachedPeer> {
         self.peer_table
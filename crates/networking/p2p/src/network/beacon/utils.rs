@@ -1,10 +1,24 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
+use alloy_primitives::hex;
 use anyhow::anyhow;
+use discv5::Enr;
+use libp2p_identity::secp256k1;
 use ream_req_resp::beacon::messages::meta_data::GetMetaDataV3;
 use ssz::Decode;
+use tracing::info;
+
+use crate::network::misc::read_private_key_from_file;
 
 pub const META_DATA_FILE_NAME: &str = "meta_data.ssz";
+pub const NETWORK_KEY_FILE_NAME: &str = "network_key";
+pub const ENR_FILE_NAME: &str = "enr.dat";
 
 pub fn read_meta_data_from_disk(path: PathBuf) -> anyhow::Result<GetMetaDataV3> {
     let meta_data_path = path.join(META_DATA_FILE_NAME);
@@ -15,3 +29,91 @@ pub fn read_meta_data_from_disk(path: PathBuf) -> anyhow::Result<GetMetaDataV3>
     GetMetaDataV3::from_ssz_bytes(&fs::read(meta_data_path)?)
         .map_err(|err| anyhow!("Failed to decode meta data: {err:?}"))
 }
+
+/// Load the node's network key from `private_key_path` if given, otherwise from the data
+/// directory, generating and saving a new key there on first start.
+pub fn load_or_create_network_key(
+    data_dir: &Path,
+    private_key_path: Option<&Path>,
+) -> anyhow::Result<secp256k1::Keypair> {
+    if let Some(private_key_path) = private_key_path {
+        return read_private_key_from_file(private_key_path);
+    }
+
+    let network_key_path = data_dir.join(NETWORK_KEY_FILE_NAME);
+    if network_key_path.exists() {
+        return read_private_key_from_file(&network_key_path);
+    }
+
+    let local_key = secp256k1::Keypair::generate();
+    fs::create_dir_all(data_dir)
+        .map_err(|err| anyhow!("Failed to create data directory: {err:?}"))?;
+    // Only the owner may read the secret key
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&network_key_path)
+        .and_then(|mut file| file.write_all(hex::encode(local_key.secret().to_bytes()).as_bytes()))
+        .map_err(|err| anyhow!("Failed to write network key to disk: {err:?}"))?;
+    info!(
+        "Generated new network key at {}",
+        network_key_path.display()
+    );
+
+    Ok(local_key)
+}
+
+/// Read the ENR the node advertised on its last run, if any.
+pub fn read_enr_from_disk(path: &Path) -> anyhow::Result<Option<Enr>> {
+    let enr_path = path.join(ENR_FILE_NAME);
+    if !enr_path.exists() {
+        return Ok(None);
+    }
+
+    Enr::from_str(fs::read_to_string(enr_path)?.trim())
+        .map(Some)
+        .map_err(|err| anyhow!("Failed to decode ENR: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_key_is_persisted() {
+        let data_dir = std::env::temp_dir().join(format!(
+            "ream_network_key_test_{}",
+            libp2p::PeerId::random()
+        ));
+
+        let local_key = load_or_create_network_key(&data_dir, None).unwrap();
+        let permissions = fs::metadata(data_dir.join(NETWORK_KEY_FILE_NAME))
+            .unwrap()
+            .permissions();
+        assert_eq!(
+            std::os::unix::fs::PermissionsExt::mode(&permissions) & 0o777,
+            0o600
+        );
+        let reloaded_key = load_or_create_network_key(&data_dir, None).unwrap();
+        assert_eq!(
+            local_key.secret().to_bytes(),
+            reloaded_key.secret().to_bytes()
+        );
+
+        let override_path = data_dir.join("override_key");
+        let override_key = secp256k1::Keypair::generate();
+        fs::write(
+            &override_path,
+            hex::encode(override_key.secret().to_bytes()),
+        )
+        .unwrap();
+        let loaded_key = load_or_create_network_key(&data_dir, Some(&override_path)).unwrap();
+        assert_eq!(
+            loaded_key.secret().to_bytes(),
+            override_key.secret().to_bytes()
+        );
+
+        fs::remove_dir_all(data_dir).unwrap();
+    }
+}
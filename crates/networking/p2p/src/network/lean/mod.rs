@@ -1,7 +1,7 @@
 use std::{
     collections::HashMap,
     error::Error,
-    fmt, fs,
+    fmt,
     net::IpAddr,
     num::{NonZeroU8, NonZeroUsize},
     path::PathBuf,
@@ -13,17 +13,17 @@ use std::{
     time::Instant,
 };
 
-use alloy_primitives::hex;
 use anyhow::anyhow;
 use delay_map::{HashMapDelay, HashSetDelay};
-use discv5::multiaddr::Protocol;
+use discv5::{Enr, multiaddr::Protocol};
 use futures::{StreamExt, stream::FuturesUnordered};
 use libp2p::{
     Multiaddr, SwarmBuilder,
     connection_limits::{self, ConnectionLimits},
     core::{ConnectedPoint, Endpoint, transport::PortUse, util::unreachable},
     gossipsub::{
-        Event as GossipsubEvent, FailedMessages, IdentTopic, MessageAuthenticity, PublishError,
+        Event as GossipsubEvent, FailedMessages, IdentTopic, MessageAcceptance,
+        MessageAuthenticity, PublishError, TopicHash,
     },
     identify,
     swarm::{
@@ -33,11 +33,15 @@ use libp2p::{
         dummy,
     },
 };
-use libp2p_identity::{Keypair, PeerId, secp256k1};
+use libp2p_identity::{Keypair, PeerId};
 use ream_chain_lean::{
-    messages::{LeanChainServiceMessage, RequestResult},
+    messages::{LeanChainServiceMessage, MessageOrigin, RequestResult},
     p2p_request::{LeanP2PRequest, P2PCallbackRequest},
 };
+use ream_discv5::{
+    config::LeanDiscoveryConfig,
+    discovery::{Discovery, DiscoveryOutEvent, QueryType},
+};
 use ream_executor::ReamExecutor;
 use ream_metrics::{
     GOSSIP_AGGREGATION_SIZE_BYTES, GOSSIP_ATTESTATION_SIZE_BYTES, GOSSIP_BLOCK_SIZE_BYTES,
@@ -49,7 +53,7 @@ use ream_peer::{ConnectionState, Direction};
 use ream_req_resp::{
     Chain, ReqResp, ReqRespMessage,
     error::ReqRespError,
-    handler::{ReqRespMessageReceived, RespMessage},
+    handler::{ReqRespMessageError, ReqRespMessageReceived, RespMessage},
     lean::{
         NetworkEvent, ReamNetworkEvent, ResponseCallback,
         messages::{
@@ -57,6 +61,7 @@ use ream_req_resp::{
         },
     },
     messages::{RequestMessage, ResponseMessage},
+    rate_limiter::RateLimiterConfig,
 };
 use ssz::Encode;
 use tokio::{
@@ -69,7 +74,8 @@ use tokio::{
 use tracing::{error, info, trace, warn};
 
 use crate::{
-    bootnodes::Bootnodes,
+    bootnodes::{Bootnodes, to_multiaddrs},
+    constants::TARGET_PEER_COUNT,
     gossipsub::{
         GossipsubBehaviour,
         lean::{
@@ -81,13 +87,16 @@ use crate::{
     network::{
         lean::LeanP2PRequest::{
             EndOfStream, GossipAggregatedAttestation, GossipAttestation, GossipBlock,
-            InvalidRequest, Request, Response,
+            GossipValidationResult, InvalidRequest, Request, Response,
         },
-        misc::Executor,
+        misc::{Executor, peer_id_from_enr, read_private_key_from_file},
+        peer_store::{PEER_STORE_FLUSH_INTERVAL, PeerStore},
     },
 };
 
 const BOOTNODE_RETRY_TIMEOUT: Duration = Duration::from_secs(30);
+const PEER_DISCOVERY_INTERVAL: Duration = Duration::from_secs(30);
+const PEER_DISCOVERY_QUERY_TARGET: usize = 16;
 const SLOW_PEER_CONSECUTIVE_WINDOW: Duration = Duration::from_secs(5);
 const SLOW_PEER_DISCONNECT_THRESHOLD: u32 = 5;
 const SLOW_PEER_MIN_CONNECTED_PEERS_FOR_DISCONNECT: usize = 6;
@@ -206,6 +215,10 @@ impl SlowPeerBanBehaviour {
         self.banned_peers.insert(peer_id);
     }
 
+    fn ban_peer_for(&mut self, peer_id: PeerId, duration: Duration) {
+        self.banned_peers.insert_at(peer_id, duration);
+    }
+
     fn unban_peer(&mut self, peer_id: &PeerId) {
         self.banned_peers.remove(peer_id);
     }
@@ -364,6 +377,9 @@ impl BootnodeRetry {
 struct ReamBehaviour {
     pub identify: identify::Behaviour,
 
+    /// The discovery domain: discv5
+    pub discovery: Discovery,
+
     /// The request-response domain
     pub req_resp: ReqResp,
 
@@ -376,10 +392,13 @@ struct ReamBehaviour {
 }
 
 pub struct LeanNetworkConfig {
+    pub discv5_config: LeanDiscoveryConfig,
     pub gossipsub_config: LeanGossipsubConfig,
     pub socket_address: IpAddr,
     pub socket_port: u16,
     pub private_key_path: Option<PathBuf>,
+    pub data_dir: PathBuf,
+    pub rate_limiter_config: RateLimiterConfig,
 }
 
 pub struct LeanNetworkService {
@@ -393,6 +412,7 @@ pub struct LeanNetworkService {
     pub network_state: Arc<NetworkState>,
     check_canonical_futures: FuturesUnordered<oneshot::Receiver<(PeerId, bool)>>,
     slow_peer_trackers: HashMap<PeerId, SlowPeerTracker>,
+    peer_store: PeerStore,
     pub multi_addr: Multiaddr,
 }
 
@@ -414,21 +434,7 @@ impl LeanNetworkService {
         };
 
         let local_key = if let Some(ref path) = network_config.private_key_path {
-            let private_key_hex = fs::read_to_string(path).map_err(|err| {
-                anyhow!("failed to read secret key file {}: {err}", path.display())
-            })?;
-            let private_key_bytes = hex::decode(private_key_hex.trim()).map_err(|err| {
-                anyhow!(
-                    "failed to decode hex from private key file {}: {err}",
-                    path.display()
-                )
-            })?;
-            let private_key =
-                secp256k1::SecretKey::try_from_bytes(private_key_bytes).map_err(|err| {
-                    anyhow!("failed to decode secp256k1 secret key from bytes: {err}")
-                })?;
-
-            Keypair::from(secp256k1::Keypair::from(private_key))
+            Keypair::from(read_private_key_from_file(path)?)
         } else {
             Keypair::generate_secp256k1()
         };
@@ -444,6 +450,13 @@ impl LeanNetworkService {
             .map_err(|err| anyhow!("Failed to create gossipsub behaviour: {err:?}"))?
         };
 
+        let discovery = {
+            let mut discovery =
+                Discovery::new_lean(local_key.clone(), &network_config.discv5_config).await?;
+            discovery.discover_peers(QueryType::Peers, PEER_DISCOVERY_QUERY_TARGET);
+            discovery
+        };
+
         let identify = {
             let local_public_key = local_key.public();
             let identify_config =
@@ -454,12 +467,27 @@ impl LeanNetworkService {
             identify::Behaviour::new(identify_config)
         };
 
+        let peer_store = PeerStore::load(&network_config.data_dir).unwrap_or_else(|err| {
+            warn!("Failed to load the peer store, starting without known peers: {err:?}");
+            PeerStore::empty(&network_config.data_dir)
+        });
+        info!(
+            "Loaded {} known peers from the peer store",
+            peer_store.len()
+        );
+
+        let mut slow_peer_banlist = SlowPeerBanBehaviour::new();
+        for (peer_id, remaining) in peer_store.banned_peers() {
+            slow_peer_banlist.ban_peer_for(peer_id, remaining);
+        }
+
         let behaviour = {
             ReamBehaviour {
-                req_resp: ReqResp::new(Chain::Lean),
+                req_resp: ReqResp::new(Chain::Lean, network_config.rate_limiter_config.clone()),
                 gossipsub,
                 identify,
-                slow_peer_banlist: SlowPeerBanBehaviour::new(),
+                discovery,
+                slow_peer_banlist,
                 connection_limits,
             }
         };
@@ -494,6 +522,7 @@ impl LeanNetworkService {
             network_state,
             check_canonical_futures: FuturesUnordered::new(),
             slow_peer_trackers: HashMap::new(),
+            peer_store,
             multi_addr: multi_addr.clone(),
             chain_callback_requests: HashMap::new(),
         };
@@ -524,12 +553,30 @@ impl LeanNetworkService {
         let bootnode_addresses = bootnodes.to_multiaddrs_lean();
         let mut bootnode_redial_interval = interval(Duration::from_secs(20));
         let mut status_interval = interval(Duration::from_secs(4));
+        let mut peer_discovery_interval = interval(PEER_DISCOVERY_INTERVAL);
+        let mut peer_store_flush_interval = interval(PEER_STORE_FLUSH_INTERVAL);
+        peer_store_flush_interval.reset();
+
+        // Redial the peers of the previous run first, best peers first
+        let stored_peer_addresses = self
+            .peer_store
+            .peers_to_dial()
+            .into_iter()
+            .flat_map(|peer| peer.addresses.clone())
+            .collect();
+        self.connect_to_multinodes(stored_peer_addresses).await;
 
         loop {
             tokio::select! {
                 _ = bootnode_redial_interval.tick() => {
                     self.connect_to_multinodes(bootnode_addresses.clone()).await;
                 }
+                _ = peer_discovery_interval.tick() => {
+                    self.discover_peers();
+                }
+                _ = peer_store_flush_interval.tick() => {
+                    self.flush_peer_store();
+                }
                 Some(Ok((peer_id, mut retry_state))) = self.bootnode_retry_state.next() => {
                     if self.is_slow_peer_banned(&peer_id) {
                         trace!(
@@ -614,6 +661,13 @@ impl LeanNetworkService {
                                 "aggregated_attestation"
                             );
                         }
+                        GossipValidationResult { message_id, propagation_source, acceptance } => {
+                            self.swarm.behaviour_mut().gossipsub.report_message_validation_result(
+                                &message_id,
+                                &propagation_source,
+                                acceptance,
+                            );
+                        }
                         Request { peer_id, callback, message } => {
                             let message = match message {
                                 P2PCallbackRequest::BlocksByRoot { roots } => {
@@ -785,6 +839,18 @@ impl LeanNetworkService {
                 );
                 None
             }
+            SwarmEvent::Behaviour(ReamBehaviourEvent::Discovery(discovery_event)) => {
+                match discovery_event {
+                    DiscoveryOutEvent::DiscoveredPeers { peers } => {
+                        self.handle_discovered_peers(peers.into_keys().collect())
+                            .await;
+                    }
+                    DiscoveryOutEvent::UpdatedEnr { enr } => {
+                        info!("Local ENR updated: {enr}");
+                    }
+                }
+                None
+            }
             SwarmEvent::Behaviour(ReamBehaviourEvent::Gossipsub(gossipsub_event)) => {
                 self.handle_gossipsub_event(gossipsub_event)
             }
@@ -797,6 +863,8 @@ impl LeanNetworkService {
                 let (address, direction) = match endpoint {
                     ConnectedPoint::Dialer { address, .. } => {
                         self.bootnode_retry_state.remove(&peer_id);
+                        self.peer_store
+                            .record_connection(peer_id, Some(address.clone()));
 
                         // send status request to the peer
                         let status_message = LeanRequestMessage::Status(self.our_status());
@@ -805,6 +873,8 @@ impl LeanNetworkService {
                         (address, Direction::Outbound)
                     }
                     ConnectedPoint::Listener { send_back_addr, .. } => {
+                        // The port an inbound peer connects from can't be dialed back
+                        self.peer_store.record_connection(peer_id, None);
                         (send_back_addr, Direction::Inbound)
                     }
                 };
@@ -877,7 +947,15 @@ impl LeanNetworkService {
                     .with_label_values(&["total"])
                     .set(self.swarm.behaviour().gossipsub.all_mesh_peers().count() as i64);
             }
-            GossipsubEvent::Message { message, .. } => {
+            GossipsubEvent::Message {
+                propagation_source,
+                message_id,
+                message,
+            } => {
+                let origin = MessageOrigin::Gossip {
+                    message_id: message_id.clone(),
+                    propagation_source,
+                };
                 match LeanGossipsubMessage::decode(&message.topic, &message.data) {
                     Ok(LeanGossipsubMessage::Block(signed_block)) => {
                         observe_histogram_vec(
@@ -891,7 +969,7 @@ impl LeanNetworkService {
                             self.chain_message_sender
                                 .send(LeanChainServiceMessage::ProcessBlock {
                                     signed_block,
-                                    need_gossip: false,
+                                    origin,
                                 })
                         {
                             warn!("failed to send block for slot {slot} item to chain: {err:?}");
@@ -912,7 +990,7 @@ impl LeanNetworkService {
                             LeanChainServiceMessage::ProcessAttestation {
                                 signed_attestation,
                                 subnet_id,
-                                need_gossip: false,
+                                origin,
                             },
                         ) {
                             warn!(
@@ -931,7 +1009,7 @@ impl LeanNetworkService {
                         if let Err(err) = self.chain_message_sender.send(
                             LeanChainServiceMessage::ProcessAggregatedAttestation {
                                 aggregated_attestation,
-                                need_gossip: false,
+                                origin,
                             },
                         ) {
                             warn!(
@@ -939,7 +1017,17 @@ impl LeanNetworkService {
                             );
                         }
                     }
-                    Err(err) => warn!("Failed to decode {:?} gossip topic: {err:?}", message.topic),
+                    Err(err) => {
+                        warn!("Failed to decode {:?} gossip topic: {err:?}", message.topic);
+                        self.swarm
+                            .behaviour_mut()
+                            .gossipsub
+                            .report_message_validation_result(
+                                &message_id,
+                                &propagation_source,
+                                MessageAcceptance::Reject,
+                            );
+                    }
                 }
             }
             GossipsubEvent::SlowPeer {
@@ -1065,7 +1153,18 @@ impl LeanNetworkService {
 
         let message = match message {
             Ok(message) => message,
+            Err(ReqRespMessageError::RateLimitBan { duration }) => {
+                self.ban_peer_for(peer_id, duration);
+                return None;
+            }
             Err(err) => {
+                if let ReqRespMessageError::Inbound {
+                    err: ReqRespError::RateLimited(_),
+                    ..
+                } = &err
+                {
+                    self.network_state.rate_limited_request_from_peer(peer_id);
+                }
                 warn!(
                     ?peer_id,
                     ?connection_id,
@@ -1217,6 +1316,64 @@ impl LeanNetworkService {
         }
     }
 
+    /// Dial the peers found by discovery over QUIC, the only transport of the lean node.
+    async fn handle_discovered_peers(&mut self, enrs: Vec<Enr>) {
+        for enr in &enrs {
+            if let Some(peer_id) = peer_id_from_enr(enr) {
+                self.peer_store.record_enr(peer_id, enr.clone());
+            }
+        }
+        let addresses = to_multiaddrs(&enrs)
+            .into_iter()
+            .filter(|address| {
+                address
+                    .iter()
+                    .any(|protocol| matches!(protocol, Protocol::QuicV1))
+            })
+            .collect();
+        self.connect_to_multinodes(addresses).await;
+    }
+
+    /// Look for peers of the subscribed attestation subnets which have no mesh peers, or for any
+    /// peers of the lean network while the node is below its target peer count.
+    fn discover_peers(&mut self) {
+        let subnets_without_peers = self
+            .network_config
+            .gossipsub_config
+            .topics
+            .iter()
+            .filter_map(|topic| match topic.kind {
+                LeanGossipTopicKind::AttestationSubnet(subnet_id) => {
+                    let topic_hash = TopicHash::from(topic.clone());
+                    let has_mesh_peers = self
+                        .swarm
+                        .behaviour()
+                        .gossipsub
+                        .mesh_peers(&topic_hash)
+                        .next()
+                        .is_some();
+                    (!has_mesh_peers).then_some(subnet_id)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let peer_count = self.network_state.connected_peer_count();
+
+        let discovery = &mut self.swarm.behaviour_mut().discovery;
+        if !subnets_without_peers.is_empty() {
+            info!(
+                "No mesh peers on attestation subnets {subnets_without_peers:?}, discovering subnet peers"
+            );
+            discovery.discover_peers(
+                QueryType::AttestationSubnetPeers(subnets_without_peers),
+                PEER_DISCOVERY_QUERY_TARGET,
+            );
+        } else if peer_count < TARGET_PEER_COUNT {
+            info!("Peer count is below target: {peer_count}, discovering more peers");
+            discovery.discover_peers(QueryType::Peers, PEER_DISCOVERY_QUERY_TARGET);
+        }
+    }
+
     pub fn handle_status_response(&mut self, peer_id: PeerId, status: &Status) {
         info!(
             ?peer_id,
@@ -1254,6 +1411,16 @@ impl LeanNetworkService {
             .behaviour_mut()
             .slow_peer_banlist
             .ban_peer(peer_id);
+        self.peer_store.ban(peer_id, SLOW_PEER_BAN_DURATION);
+        self.bootnode_retry_state.remove(&peer_id);
+    }
+
+    fn ban_peer_for(&mut self, peer_id: PeerId, duration: Duration) {
+        self.swarm
+            .behaviour_mut()
+            .slow_peer_banlist
+            .ban_peer_for(peer_id, duration);
+        self.peer_store.ban(peer_id, duration);
         self.bootnode_retry_state.remove(&peer_id);
     }
 
@@ -1262,6 +1429,20 @@ impl LeanNetworkService {
             .behaviour_mut()
             .slow_peer_banlist
             .unban_peer(peer_id);
+        self.peer_store.unban(peer_id);
+    }
+
+    /// Write the known peers to disk, with the scores of the peers in the peer table.
+    fn flush_peer_store(&mut self) {
+        for (peer_id, cached_peer) in self.network_state.peer_table.lock().iter() {
+            if self.peer_store.get(peer_id).is_some() {
+                self.peer_store
+                    .record_score(*peer_id, cached_peer.peer_score);
+            }
+        }
+        if let Err(err) = self.peer_store.flush() {
+            warn!("Failed to write the peer store: {err:?}");
+        }
     }
 
     fn is_slow_peer_banned(&self, peer_id: &PeerId) -> bool {
@@ -1404,6 +1585,12 @@ impl LeanNetworkService {
     }
 }
 
+impl Drop for LeanNetworkService {
+    fn drop(&mut self) {
+        self.flush_peer_store();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -1430,11 +1617,20 @@ mod tests {
         initialize_lean_test_network_spec();
 
         let executor = ReamExecutor::new().expect("Failed to create executor");
+        let data_dir =
+            std::env::temp_dir().join(format!("ream_lean_network_test_{}", PeerId::random()));
+        std::fs::create_dir_all(&data_dir)?;
         let config = Arc::new(LeanNetworkConfig {
+            discv5_config: LeanDiscoveryConfig {
+                disable_discovery: true,
+                ..LeanDiscoveryConfig::default()
+            },
             gossipsub_config: LeanGossipsubConfig::default(),
             socket_address: Ipv4Addr::new(127, 0, 0, 1).into(),
             socket_port,
             private_key_path: None,
+            data_dir,
+            rate_limiter_config: RateLimiterConfig::new(Chain::Lean),
         });
 
         let (chain_sender, chain_receiver) = mpsc::unbounded_channel::<LeanChainServiceMessage>();
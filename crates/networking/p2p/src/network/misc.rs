@@ -1,5 +1,7 @@
-use std::{io, pin::Pin, time::Duration};
+use std::{fs, io, path::Path, pin::Pin, time::Duration};
 
+use alloy_primitives::hex;
+use anyhow::anyhow;
 use discv5::Enr;
 use enr::CombinedPublicKey;
 use libp2p::{
@@ -14,7 +16,10 @@ use libp2p::{
     tcp::{Config as TcpConfig, tokio::Transport as TcpTransport},
     yamux,
 };
-use libp2p_identity::{Keypair, PeerId, secp256k1::PublicKey as Secp256k1PublicKey};
+use libp2p_identity::{
+    Keypair, PeerId,
+    secp256k1::{self, PublicKey as Secp256k1PublicKey},
+};
 use libp2p_mplex::{Config, MaxBufferBehaviour};
 use ream_executor::ReamExecutor;
 use yamux::Config as YamuxConfig;
@@ -27,6 +32,22 @@ impl libp2p::swarm::Executor for Executor {
     }
 }
 
+/// Read a hex encoded secp256k1 private key, as written by `ream generate_private_key`.
+pub fn read_private_key_from_file(path: &Path) -> anyhow::Result<secp256k1::Keypair> {
+    let private_key_hex = fs::read_to_string(path)
+        .map_err(|err| anyhow!("failed to read secret key file {}: {err}", path.display()))?;
+    let private_key_bytes = hex::decode(private_key_hex.trim()).map_err(|err| {
+        anyhow!(
+            "failed to decode hex from private key file {}: {err}",
+            path.display()
+        )
+    })?;
+    let private_key = secp256k1::SecretKey::try_from_bytes(private_key_bytes)
+        .map_err(|err| anyhow!("failed to decode secp256k1 secret key from bytes: {err}"))?;
+
+    Ok(secp256k1::Keypair::from(private_key))
+}
+
 pub fn build_transport(local_private_key: Keypair) -> io::Result<Boxed<(PeerId, StreamMuxerBox)>> {
     // mplex config
     let mut mplex_config = Config::new();
@@ -1,3 +1,4 @@
 pub mod beacon;
 pub mod lean;
 pub mod misc;
+pub mod peer_store;
@@ -0,0 +1,295 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::anyhow;
+use discv5::Enr;
+use libp2p::{Multiaddr, PeerId};
+use serde::{Deserialize, Serialize};
+
+pub const PEER_STORE_FILE_NAME: &str = "peers.json";
+
+/// How often the peer store is written to disk while the node is running. It is also written on
+/// shutdown.
+pub const PEER_STORE_FLUSH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Score of a peer the node has no opinion of yet, matching the lean peer table.
+pub const DEFAULT_PEER_SCORE: u8 = u8::MAX / 2;
+
+/// Number of peers kept on disk. Banned peers are kept first, then the peers connected to most
+/// recently.
+const MAX_STORED_PEERS: usize = 1024;
+
+/// Number of addresses kept per peer, most recently used first.
+const MAX_ADDRESSES_PER_PEER: usize = 4;
+
+/// A peer remembered across restarts. Times are in seconds since the Unix epoch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredPeer {
+    pub peer_id: PeerId,
+    /// Addresses the peer was dialed on, ending with its `/p2p` peer id.
+    pub addresses: Vec<Multiaddr>,
+    pub enr: Option<Enr>,
+    pub score: u8,
+    pub banned_until: Option<u64>,
+    pub last_connected: Option<u64>,
+}
+
+impl StoredPeer {
+    fn new(peer_id: PeerId) -> Self {
+        Self {
+            peer_id,
+            addresses: vec![],
+            enr: None,
+            score: DEFAULT_PEER_SCORE,
+            banned_until: None,
+            last_connected: None,
+        }
+    }
+
+    fn is_banned(&self, now: u64) -> bool {
+        self.banned_until.is_some_and(|until| until > now)
+    }
+}
+
+/// The known peers of the node, kept in [PEER_STORE_FILE_NAME] in the data directory so a
+/// restarted node redials its good peers before the bootnodes, and keeps its bans.
+#[derive(Debug)]
+pub struct PeerStore {
+    path: PathBuf,
+    peers: HashMap<PeerId, StoredPeer>,
+}
+
+impl PeerStore {
+    /// Load the peer store of `data_dir`, which is empty if it was never written.
+    pub fn load(data_dir: &Path) -> anyhow::Result<Self> {
+        let path = data_dir.join(PEER_STORE_FILE_NAME);
+        let peers = if path.exists() {
+            serde_json::from_str::<Vec<StoredPeer>>(&fs::read_to_string(&path)?)
+                .map_err(|err| anyhow!("Failed to decode {}: {err}", path.display()))?
+                .into_iter()
+                .map(|peer| (peer.peer_id, peer))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self { path, peers })
+    }
+
+    /// An empty peer store of `data_dir`, replacing the stored peers on the next flush.
+    pub fn empty(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join(PEER_STORE_FILE_NAME),
+            peers: HashMap::new(),
+        }
+    }
+
+    /// Write the peer store to disk, forgetting expired bans and the peers beyond
+    /// [MAX_STORED_PEERS]. The file is replaced atomically, so a crash never leaves a partial one.
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        let now = unix_timestamp();
+        let mut peers = self
+            .peers
+            .drain()
+            .map(|(_, mut peer)| {
+                if !peer.is_banned(now) {
+                    peer.banned_until = None;
+                }
+                peer
+            })
+            .collect::<Vec<_>>();
+        peers.sort_by_key(|peer| Reverse((peer.banned_until.is_some(), peer.last_connected)));
+        peers.truncate(MAX_STORED_PEERS);
+
+        let temporary_path = self.path.with_extension("json.tmp");
+        fs::write(&temporary_path, serde_json::to_string(&peers)?)?;
+        fs::rename(&temporary_path, &self.path)?;
+
+        self.peers = peers.into_iter().map(|peer| (peer.peer_id, peer)).collect();
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    pub fn get(&self, peer_id: &PeerId) -> Option<&StoredPeer> {
+        self.peers.get(peer_id)
+    }
+
+    /// Record a successful connection to `peer_id`, along with the address it was dialed on.
+    pub fn record_connection(&mut self, peer_id: PeerId, address: Option<Multiaddr>) {
+        let peer = self.entry(peer_id);
+        peer.last_connected = Some(unix_timestamp());
+        if let Some(address) = address {
+            let address = address.with_p2p(peer_id).unwrap_or_else(|address| address);
+            peer.addresses
+                .retain(|known_address| *known_address != address);
+            peer.addresses.insert(0, address);
+            peer.addresses.truncate(MAX_ADDRESSES_PER_PEER);
+        }
+    }
+
+    pub fn record_enr(&mut self, peer_id: PeerId, enr: Enr) {
+        self.entry(peer_id).enr = Some(enr);
+    }
+
+    pub fn record_score(&mut self, peer_id: PeerId, score: u8) {
+        self.entry(peer_id).score = score;
+    }
+
+    pub fn ban(&mut self, peer_id: PeerId, duration: Duration) {
+        self.entry(peer_id).banned_until = Some(unix_timestamp() + duration.as_secs());
+    }
+
+    pub fn unban(&mut self, peer_id: &PeerId) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.banned_until = None;
+        }
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.peers
+            .get(peer_id)
+            .is_some_and(|peer| peer.is_banned(unix_timestamp()))
+    }
+
+    /// The peers whose ban has not expired yet, with the time left on their ban.
+    pub fn banned_peers(&self) -> Vec<(PeerId, Duration)> {
+        let now = unix_timestamp();
+        self.peers
+            .values()
+            .filter_map(|peer| {
+                let banned_until = peer.banned_until.filter(|until| *until > now)?;
+                Some((peer.peer_id, Duration::from_secs(banned_until - now)))
+            })
+            .collect()
+    }
+
+    /// The peers to redial at startup, best first: peers which aren't banned and were connected
+    /// to before, ordered by score and then by the time of their last connection.
+    pub fn peers_to_dial(&self) -> Vec<&StoredPeer> {
+        let now = unix_timestamp();
+        let mut peers = self
+            .peers
+            .values()
+            .filter(|peer| {
+                peer.last_connected.is_some()
+                    && !peer.is_banned(now)
+                    && (!peer.addresses.is_empty() || peer.enr.is_some())
+            })
+            .collect::<Vec<_>>();
+        peers.sort_by_key(|peer| Reverse((peer.score, peer.last_connected)));
+        peers
+    }
+
+    fn entry(&mut self, peer_id: PeerId) -> &mut StoredPeer {
+        self.peers
+            .entry(peer_id)
+            .or_insert_with(|| StoredPeer::new(peer_id))
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, time::Duration};
+
+    use libp2p::{Multiaddr, PeerId};
+
+    use super::PeerStore;
+
+    fn temporary_data_dir() -> std::path::PathBuf {
+        let data_dir = std::env::temp_dir().join(format!("ream_peer_store_{}", PeerId::random()));
+        fs::create_dir_all(&data_dir).expect("Failed to create data dir");
+        data_dir
+    }
+
+    #[test]
+    fn test_peer_store_round_trip() -> anyhow::Result<()> {
+        let data_dir = temporary_data_dir();
+        let peer_id = PeerId::random();
+        let address: Multiaddr = "/ip4/127.0.0.1/udp/9000/quic-v1".parse()?;
+
+        let mut peer_store = PeerStore::load(&data_dir)?;
+        assert!(peer_store.is_empty());
+        peer_store.record_connection(peer_id, Some(address.clone()));
+        peer_store.record_score(peer_id, 200);
+        peer_store.flush()?;
+
+        let peer_store = PeerStore::load(&data_dir)?;
+        let stored_peer = peer_store.get(&peer_id).expect("Peer should be stored");
+        assert_eq!(
+            stored_peer.addresses,
+            vec![address.with_p2p(peer_id).unwrap()]
+        );
+        assert_eq!(stored_peer.score, 200);
+        assert!(stored_peer.last_connected.is_some());
+
+        fs::remove_dir_all(data_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_peers_to_dial_order_and_bans() -> anyhow::Result<()> {
+        let data_dir = temporary_data_dir();
+        let address: Multiaddr = "/ip4/127.0.0.1/udp/9000/quic-v1".parse()?;
+        let (good_peer, average_peer, banned_peer, unknown_peer) = (
+            PeerId::random(),
+            PeerId::random(),
+            PeerId::random(),
+            PeerId::random(),
+        );
+
+        let mut peer_store = PeerStore::load(&data_dir)?;
+        for peer_id in [good_peer, average_peer, banned_peer] {
+            peer_store.record_connection(peer_id, Some(address.clone()));
+        }
+        peer_store.record_score(good_peer, u8::MAX);
+        peer_store.record_score(unknown_peer, u8::MAX);
+        peer_store.ban(banned_peer, Duration::from_secs(600));
+
+        let peers_to_dial = peer_store
+            .peers_to_dial()
+            .into_iter()
+            .map(|peer| peer.peer_id)
+            .collect::<Vec<_>>();
+        assert_eq!(peers_to_dial, vec![good_peer, average_peer]);
+        assert!(peer_store.is_banned(&banned_peer));
+        assert!(!peer_store.is_banned(&good_peer));
+
+        peer_store.flush()?;
+        let mut peer_store = PeerStore::load(&data_dir)?;
+        let banned_peers = peer_store.banned_peers();
+        assert_eq!(banned_peers.len(), 1);
+        assert_eq!(banned_peers[0].0, banned_peer);
+        assert!(banned_peers[0].1 <= Duration::from_secs(600));
+
+        peer_store.unban(&banned_peer);
+        assert!(peer_store.banned_peers().is_empty());
+        assert!(
+            peer_store
+                .peers_to_dial()
+                .iter()
+                .any(|peer| peer.peer_id == banned_peer)
+        );
+
+        fs::remove_dir_all(data_dir)?;
+        Ok(())
+    }
+}
@@ -1,6 +1,5 @@
 use discv5::Enr;
-
-use crate::constants::QUIC_ENR_KEY;
+use ream_discv5::eth2::QUIC_ENR_KEY;
 
 /// The QUIC port of ENR record if it is defined.
 pub fn quic_from_enr(enr: &Enr) -> Option<u16> {
@@ -27,6 +27,9 @@ pub enum ReqRespError {
 
     #[error("Raw error message {0}")]
     RawError(String),
+
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
 }
 
 impl From<ssz::DecodeError> for ReqRespError {
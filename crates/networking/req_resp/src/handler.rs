@@ -5,6 +5,7 @@ use std::{
     collections::{HashMap, VecDeque, hash_map::Entry},
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use delay_map::HashSetDelay;
@@ -69,6 +70,7 @@ impl RespMessage {
                 ReqRespError::Disconnected
                 | ReqRespError::StreamTimedOut(_)
                 | ReqRespError::TokioTimedOut(_) => Some(ResponseCode::ResourceUnavailable),
+                ReqRespError::RateLimited(_) => Some(ResponseCode::RateLimited),
             },
             RespMessage::EndOfStream => None,
         }
@@ -253,7 +255,12 @@ impl ReqRespConnectionHandler {
             self.behaviour_events
                 .push(HandlerEvent::Err(ReqRespMessageError::Inbound {
                     stream_id,
-                    err: ReqRespError::RawError(err.to_string()),
+                    err: match err {
+                        ReqRespError::RateLimited(reason) => {
+                            ReqRespError::RateLimited(reason.clone())
+                        }
+                        err => ReqRespError::RawError(err.to_string()),
+                    },
                 }));
         }
 
@@ -652,8 +659,18 @@ async fn send_response_message_to_inbound_stream(
 
 #[derive(Debug)]
 pub enum ReqRespMessageError {
-    Inbound { stream_id: u64, err: ReqRespError },
-    Outbound { request_id: u64, err: ReqRespError },
+    Inbound {
+        stream_id: u64,
+        err: ReqRespError,
+    },
+    Outbound {
+        request_id: u64,
+        err: ReqRespError,
+    },
+    /// The peer kept exceeding its inbound rate limits, and is to be banned for `duration`.
+    RateLimitBan {
+        duration: Duration,
+    },
 }
 
 #[derive(Debug)]
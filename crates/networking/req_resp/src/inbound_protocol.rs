@@ -301,12 +301,17 @@ impl Decoder for InboundSSZSnappyCodec {
     }
 }
 
+/// Response code for rate limited requests, from the range the spec leaves to clients.
+pub const RATE_LIMITED_RESPONSE_CODE: u8 = 139;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ResponseCode {
     Success,
     InvalidRequest,
     ServerError,
     ResourceUnavailable,
+    /// Client specific code for requests beyond the rate limits of the server.
+    RateLimited,
     ReservedCode(u8),
     ErroneousCode(u8),
 }
@@ -319,6 +324,7 @@ impl From<u8> for ResponseCode {
             2 => ResponseCode::ServerError,
             3 => ResponseCode::ResourceUnavailable,
             4..=127 => ResponseCode::ReservedCode(byte),
+            RATE_LIMITED_RESPONSE_CODE => ResponseCode::RateLimited,
             _ => ResponseCode::ErroneousCode(byte),
         }
     }
@@ -331,6 +337,7 @@ impl From<ResponseCode> for u8 {
             ResponseCode::InvalidRequest => 1,
             ResponseCode::ServerError => 2,
             ResponseCode::ResourceUnavailable => 3,
+            ResponseCode::RateLimited => RATE_LIMITED_RESPONSE_CODE,
             ResponseCode::ReservedCode(byte) => byte,
             ResponseCode::ErroneousCode(byte) => byte,
         }
@@ -8,10 +8,12 @@ pub mod lean;
 pub mod messages;
 pub mod outbound_protocol;
 pub mod protocol_id;
+pub mod rate_limiter;
 
 use std::{
     cmp::max,
     task::{Context, Poll},
+    time::Instant,
 };
 
 use handler::{
@@ -29,9 +31,14 @@ use libp2p::{
     },
 };
 use messages::RequestMessage;
-use tracing::{debug, trace};
+use rate_limiter::{RATE_LIMIT_BAN_DURATION, RateLimiter, RateLimiterConfig, request_protocol};
+use ream_metrics::{
+    REQ_RESP_INBOUND_REQUEST_COST_TOTAL, REQ_RESP_RATE_LIMITED_PEERS_BANNED_TOTAL,
+    REQ_RESP_RATE_LIMITED_REQUESTS_TOTAL, inc_int_counter_vec, inc_int_counter_vec_by,
+};
+use tracing::{debug, trace, warn};
 
-use crate::constants::MAX_PAYLOAD_SIZE;
+use crate::{constants::MAX_PAYLOAD_SIZE, error::ReqRespError};
 
 /// Maximum number of concurrent requests per protocol ID that a client may issue.
 pub const MAX_CONCURRENT_REQUESTS: usize = 2;
@@ -64,13 +71,15 @@ pub enum ConnectionRequest {
 pub struct ReqResp {
     pub events: Vec<ToSwarm<ReqRespMessage, ConnectionRequest>>,
     pub chain: Chain,
+    rate_limiter: RateLimiter,
 }
 
 impl ReqResp {
-    pub fn new(chain: Chain) -> Self {
+    pub fn new(chain: Chain, rate_limiter_config: RateLimiterConfig) -> Self {
         ReqResp {
             events: vec![],
             chain,
+            rate_limiter: RateLimiter::new(rate_limiter_config),
         }
     }
 
@@ -101,6 +110,59 @@ impl ReqResp {
             },
         });
     }
+
+    /// Charge an inbound request to the quota of its protocol. A rate limited request gets a
+    /// `RateLimited` error response instead of reaching the node. Peers which keep exceeding their
+    /// quotas are disconnected, and a `RateLimitBan` error asks the node to ban them. Returns
+    /// whether the request was allowed.
+    fn rate_limit_request(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        request: &RequestMessage,
+    ) -> bool {
+        let Some(protocol) = request_protocol(request) else {
+            return true;
+        };
+        let cost = request.max_response_chunks().max(1);
+        inc_int_counter_vec_by(&REQ_RESP_INBOUND_REQUEST_COST_TOTAL, cost, &[&protocol]);
+
+        let now = Instant::now();
+        let Err(retry_after) = self.rate_limiter.allows(peer_id, &protocol, cost, now) else {
+            return true;
+        };
+
+        inc_int_counter_vec(&REQ_RESP_RATE_LIMITED_REQUESTS_TOTAL, &[&protocol]);
+        debug!("REQRESP: Rate limited {protocol} request of cost {cost} from peer {peer_id}");
+        self.send_response(
+            peer_id,
+            connection_id,
+            stream_id,
+            RespMessage::Error(ReqRespError::RateLimited(format!(
+                "{protocol} quota exceeded, retry in {}ms",
+                retry_after.as_millis()
+            ))),
+        );
+
+        if self.rate_limiter.record_violation(peer_id, now) {
+            warn!("REQRESP: Banning peer {peer_id} for repeatedly exceeding its rate limits");
+            inc_int_counter_vec(&REQ_RESP_RATE_LIMITED_PEERS_BANNED_TOTAL, &[]);
+            self.events.push(ToSwarm::GenerateEvent(ReqRespMessage {
+                peer_id,
+                connection_id,
+                message: Err(ReqRespMessageError::RateLimitBan {
+                    duration: RATE_LIMIT_BAN_DURATION,
+                }),
+            }));
+            self.events.push(ToSwarm::CloseConnection {
+                peer_id,
+                connection: CloseConnection::All,
+            });
+        }
+
+        false
+    }
 }
 
 impl NetworkBehaviour for ReqResp {
@@ -151,6 +213,7 @@ impl NetworkBehaviour for ReqResp {
             trace!(
                 "REQRESP: Connection closed for peer {peer_id} with connection ID {connection_id} due to {cause:?}"
             );
+            self.rate_limiter.prune(Instant::now());
         }
     }
 
@@ -161,11 +224,22 @@ impl NetworkBehaviour for ReqResp {
         event: <Self::ConnectionHandler as ConnectionHandler>::ToBehaviour,
     ) {
         match event {
-            HandlerEvent::Ok(message) => self.events.push(ToSwarm::GenerateEvent(ReqRespMessage {
-                peer_id,
-                connection_id,
-                message: Ok(*message),
-            })),
+            HandlerEvent::Ok(message) => {
+                if let ReqRespMessageReceived::Request {
+                    stream_id,
+                    message: request,
+                } = message.as_ref()
+                    && !self.rate_limit_request(peer_id, connection_id, *stream_id, request)
+                {
+                    return;
+                }
+
+                self.events.push(ToSwarm::GenerateEvent(ReqRespMessage {
+                    peer_id,
+                    connection_id,
+                    message: Ok(*message),
+                }))
+            }
             HandlerEvent::Err(err) => self.events.push(ToSwarm::GenerateEvent(ReqRespMessage {
                 peer_id,
                 connection_id,
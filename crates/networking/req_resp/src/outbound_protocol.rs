@@ -286,7 +286,10 @@ impl Decoder for OutboundSSZSnappyCodec {
                     }
                 } else {
                     Ok(Some(RespMessage::Error(
-                        VariableList::<u8, U256>::from_ssz_bytes(&buf).map(ReqRespError::from).map_err(|err| anyhow!("OutboundSSZSnappyCodec::decode: protocol: {:?}, response_code: {response_code:?}, err: {err:?}", self.protocol.protocol))?,
+                        VariableList::<u8, U256>::from_ssz_bytes(&buf).map(|message| match response_code {
+                            ResponseCode::RateLimited => ReqRespError::RateLimited(String::from_utf8_lossy(&message).to_string()),
+                            _ => ReqRespError::from(message),
+                        }).map_err(|err| anyhow!("OutboundSSZSnappyCodec::decode: protocol: {:?}, response_code: {response_code:?}, err: {err:?}", self.protocol.protocol))?,
                     )))
                 }
             }
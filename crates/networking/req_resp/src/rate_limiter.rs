@@ -0,0 +1,413 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, ensure};
+use libp2p::PeerId;
+use ream_consensus_beacon::data_column_sidecar::NUMBER_OF_COLUMNS;
+
+use crate::{
+    Chain,
+    constants::{
+        MAX_REQUEST_BLOB_SIDECARS, MAX_REQUEST_BLOCKS, MAX_REQUEST_BLOCKS_DENEB,
+        MAX_REQUEST_DATA_COLUMN_SIDECARS_PER_COLUMN,
+    },
+    messages::RequestMessage,
+    protocol_id::SupportedProtocol,
+};
+
+/// Number of rate limited requests after which a peer is banned, if they are all made within
+/// [RATE_LIMIT_VIOLATION_WINDOW].
+pub const MAX_RATE_LIMIT_VIOLATIONS: u32 = 5;
+
+pub const RATE_LIMIT_VIOLATION_WINDOW: Duration = Duration::from_secs(60);
+
+/// How long a peer which exceeded its quotas [MAX_RATE_LIMIT_VIOLATIONS] times is banned for.
+pub const RATE_LIMIT_BAN_DURATION: Duration = Duration::from_secs(30 * 60);
+
+/// Period over which the default quotas of the data serving protocols replenish.
+const DEFAULT_REPLENISH_PERIOD: Duration = Duration::from_secs(10);
+
+/// A token bucket holding up to `max_tokens`, which refills completely over `replenish_period`.
+/// Every request takes one token per requested item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    pub max_tokens: u64,
+    pub replenish_period: Duration,
+}
+
+impl Quota {
+    pub const fn new(max_tokens: u64, replenish_period: Duration) -> Self {
+        Self {
+            max_tokens,
+            replenish_period,
+        }
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        self.max_tokens as f64 / self.replenish_period.as_secs_f64()
+    }
+}
+
+/// The quota of a single protocol, written as `<protocol>:<max tokens>/<seconds>`, e.g.
+/// `blocks_by_range:512/10`. The protocol is the message name of its protocol ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolQuota {
+    pub protocol: String,
+    pub quota: Quota,
+}
+
+impl FromStr for ProtocolQuota {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (protocol, quota) = value
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Expected <protocol>:<max tokens>/<seconds>, got {value}"))?;
+        let (max_tokens, seconds) = quota
+            .split_once('/')
+            .ok_or_else(|| anyhow!("Expected <max tokens>/<seconds>, got {quota}"))?;
+        let max_tokens = max_tokens
+            .parse::<u64>()
+            .map_err(|err| anyhow!("Invalid max tokens {max_tokens}: {err}"))?;
+        let seconds = seconds
+            .parse::<u64>()
+            .map_err(|err| anyhow!("Invalid replenish period {seconds}: {err}"))?;
+        ensure!(
+            max_tokens > 0 && seconds > 0,
+            "The quota of {protocol} needs a positive number of tokens and seconds"
+        );
+
+        Ok(Self {
+            protocol: protocol.to_string(),
+            quota: Quota::new(max_tokens, Duration::from_secs(seconds)),
+        })
+    }
+}
+
+impl fmt::Display for ProtocolQuota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}/{}",
+            self.protocol,
+            self.quota.max_tokens,
+            self.quota.replenish_period.as_secs()
+        )
+    }
+}
+
+/// The inbound request quotas of every protocol of a chain. Protocols without a quota are not
+/// limited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimiterConfig {
+    pub chain: Chain,
+    pub quotas: HashMap<String, Quota>,
+}
+
+impl RateLimiterConfig {
+    /// The default quotas of `chain`. The data serving protocols allow a peer one request of the
+    /// largest allowed size every ten seconds.
+    pub fn new(chain: Chain) -> Self {
+        let quotas = match chain {
+            Chain::Beacon => vec![
+                ("status", Quota::new(5, Duration::from_secs(15))),
+                ("ping", Quota::new(2, Duration::from_secs(10))),
+                ("metadata", Quota::new(2, Duration::from_secs(5))),
+                ("goodbye", Quota::new(1, Duration::from_secs(10))),
+                (
+                    "beacon_blocks_by_range",
+                    Quota::new(MAX_REQUEST_BLOCKS, DEFAULT_REPLENISH_PERIOD),
+                ),
+                (
+                    "beacon_blocks_by_root",
+                    Quota::new(MAX_REQUEST_BLOCKS_DENEB, DEFAULT_REPLENISH_PERIOD),
+                ),
+                (
+                    "blob_sidecars_by_range",
+                    Quota::new(MAX_REQUEST_BLOB_SIDECARS, DEFAULT_REPLENISH_PERIOD),
+                ),
+                (
+                    "blob_sidecars_by_root",
+                    Quota::new(MAX_REQUEST_BLOB_SIDECARS, DEFAULT_REPLENISH_PERIOD),
+                ),
+                (
+                    "data_column_sidecars_by_range",
+                    Quota::new(
+                        MAX_REQUEST_DATA_COLUMN_SIDECARS_PER_COLUMN * NUMBER_OF_COLUMNS,
+                        DEFAULT_REPLENISH_PERIOD,
+                    ),
+                ),
+                (
+                    "data_column_sidecars_by_root",
+                    Quota::new(
+                        MAX_REQUEST_DATA_COLUMN_SIDECARS_PER_COLUMN * NUMBER_OF_COLUMNS,
+                        DEFAULT_REPLENISH_PERIOD,
+                    ),
+                ),
+            ],
+            Chain::Lean => vec![
+                ("status", Quota::new(5, Duration::from_secs(15))),
+                (
+                    "blocks_by_root",
+                    Quota::new(MAX_REQUEST_BLOCKS, DEFAULT_REPLENISH_PERIOD),
+                ),
+                (
+                    "blocks_by_range",
+                    Quota::new(MAX_REQUEST_BLOCKS, DEFAULT_REPLENISH_PERIOD),
+                ),
+            ],
+        };
+
+        Self {
+            chain,
+            quotas: quotas
+                .into_iter()
+                .map(|(protocol, quota)| (protocol.to_string(), quota))
+                .collect(),
+        }
+    }
+
+    /// Replace the default quotas of the protocols in `quotas`.
+    pub fn with_quotas(mut self, quotas: &[ProtocolQuota]) -> Self {
+        for ProtocolQuota { protocol, quota } in quotas {
+            self.quotas.insert(protocol.clone(), *quota);
+        }
+        self
+    }
+}
+
+/// Check that `quota` names a req/resp protocol of `chain`.
+pub fn validate_protocol_quota(chain: Chain, quota: &ProtocolQuota) -> anyhow::Result<()> {
+    let protocols = SupportedProtocol::supported_protocols(chain)
+        .iter()
+        .map(|protocol_id| protocol_id.protocol.message_name().to_string())
+        .collect::<Vec<_>>();
+    ensure!(
+        protocols.contains(&quota.protocol),
+        "Unknown req/resp protocol {}, expected one of {}",
+        quota.protocol,
+        protocols.join(", ")
+    );
+    Ok(())
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_update: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, quota: &Quota, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_update)
+            .as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * quota.tokens_per_second()).min(quota.max_tokens as f64);
+        self.last_update = now;
+    }
+}
+
+#[derive(Debug)]
+struct Violations {
+    count: u32,
+    window_start: Instant,
+}
+
+/// Per peer and per protocol token buckets for inbound requests, shared by all connections of a
+/// peer so reconnecting doesn't refill them.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimiterConfig,
+    buckets: HashMap<(PeerId, String), TokenBucket>,
+    violations: HashMap<PeerId, Violations>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimiterConfig) -> Self {
+        Self {
+            config,
+            buckets: HashMap::new(),
+            violations: HashMap::new(),
+        }
+    }
+
+    /// Take the tokens for `request` from the bucket of `peer_id`. The cost of a request is the
+    /// number of items it asks for, and requests larger than the bucket cost a full bucket.
+    /// Returns how long the peer has to wait if the bucket holds too few tokens.
+    pub fn allows(
+        &mut self,
+        peer_id: PeerId,
+        protocol: &str,
+        cost: u64,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let Some(quota) = self.config.quotas.get(protocol) else {
+            return Ok(());
+        };
+
+        let cost = cost.clamp(1, quota.max_tokens) as f64;
+        let bucket = self
+            .buckets
+            .entry((peer_id, protocol.to_string()))
+            .or_insert(TokenBucket {
+                tokens: quota.max_tokens as f64,
+                last_update: now,
+            });
+        bucket.refill(quota, now);
+
+        if bucket.tokens >= cost {
+            bucket.tokens -= cost;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (cost - bucket.tokens) / quota.tokens_per_second(),
+            ))
+        }
+    }
+
+    /// Record a rate limited request of `peer_id`. Returns whether the peer has exceeded its
+    /// quotas [MAX_RATE_LIMIT_VIOLATIONS] times within [RATE_LIMIT_VIOLATION_WINDOW].
+    pub fn record_violation(&mut self, peer_id: PeerId, now: Instant) -> bool {
+        let violations = self.violations.entry(peer_id).or_insert(Violations {
+            count: 0,
+            window_start: now,
+        });
+        if now.saturating_duration_since(violations.window_start) > RATE_LIMIT_VIOLATION_WINDOW {
+            violations.count = 0;
+            violations.window_start = now;
+        }
+        violations.count += 1;
+        violations.count >= MAX_RATE_LIMIT_VIOLATIONS
+    }
+
+    /// Forget the buckets which have refilled completely and the expired violations.
+    pub fn prune(&mut self, now: Instant) {
+        let quotas = &self.config.quotas;
+        self.buckets.retain(|(_, protocol), bucket| {
+            let Some(quota) = quotas.get(protocol) else {
+                return false;
+            };
+            bucket.refill(quota, now);
+            bucket.tokens < quota.max_tokens as f64
+        });
+        self.violations.retain(|_, violations| {
+            now.saturating_duration_since(violations.window_start) <= RATE_LIMIT_VIOLATION_WINDOW
+        });
+    }
+}
+
+/// The protocol `request` is rate limited under, the message name of its protocol ID.
+pub fn request_protocol(request: &RequestMessage) -> Option<String> {
+    request
+        .supported_protocols()
+        .first()
+        .map(|protocol_id| protocol_id.protocol.message_name().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use libp2p::PeerId;
+
+    use super::{
+        MAX_RATE_LIMIT_VIOLATIONS, ProtocolQuota, Quota, RateLimiter, RateLimiterConfig,
+        validate_protocol_quota,
+    };
+    use crate::Chain;
+
+    #[test]
+    fn test_rate_limiter_weights_requests_by_count() {
+        let quota = "blocks_by_range:100/10".parse::<ProtocolQuota>().unwrap();
+        let mut rate_limiter =
+            RateLimiter::new(RateLimiterConfig::new(Chain::Lean).with_quotas(&[quota]));
+        let peer_id = PeerId::random();
+        let other_peer_id = PeerId::random();
+        let now = Instant::now();
+
+        assert!(
+            rate_limiter
+                .allows(peer_id, "blocks_by_range", 60, now)
+                .is_ok()
+        );
+        let retry_after = rate_limiter
+            .allows(peer_id, "blocks_by_range", 60, now)
+            .unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(2));
+
+        // Buckets are per peer and per protocol
+        assert!(
+            rate_limiter
+                .allows(other_peer_id, "blocks_by_range", 60, now)
+                .is_ok()
+        );
+        assert!(
+            rate_limiter
+                .allows(peer_id, "blocks_by_root", 60, now)
+                .is_ok()
+        );
+
+        // 20 tokens are replenished after two seconds
+        let later = now + Duration::from_secs(2);
+        assert!(
+            rate_limiter
+                .allows(peer_id, "blocks_by_range", 60, later)
+                .is_ok()
+        );
+
+        // Requests larger than the bucket cost a full bucket
+        let refilled = later + Duration::from_secs(10);
+        assert!(
+            rate_limiter
+                .allows(peer_id, "blocks_by_range", 1000, refilled)
+                .is_ok()
+        );
+        assert!(
+            rate_limiter
+                .allows(peer_id, "blocks_by_range", 1, refilled)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rate_limiter_violations() {
+        let mut rate_limiter = RateLimiter::new(RateLimiterConfig::new(Chain::Beacon));
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        for _ in 1..MAX_RATE_LIMIT_VIOLATIONS {
+            assert!(!rate_limiter.record_violation(peer_id, now));
+        }
+        assert!(rate_limiter.record_violation(peer_id, now));
+
+        // Violations outside of the window are forgotten
+        let later = now + Duration::from_secs(61);
+        rate_limiter.prune(later);
+        assert!(!rate_limiter.record_violation(peer_id, later));
+    }
+
+    #[test]
+    fn test_parse_protocol_quota() {
+        let quota = "beacon_blocks_by_range:512/10"
+            .parse::<ProtocolQuota>()
+            .unwrap();
+        assert_eq!(
+            quota,
+            ProtocolQuota {
+                protocol: "beacon_blocks_by_range".to_string(),
+                quota: Quota::new(512, Duration::from_secs(10)),
+            }
+        );
+        assert_eq!(quota.to_string(), "beacon_blocks_by_range:512/10");
+        assert!(validate_protocol_quota(Chain::Beacon, &quota).is_ok());
+        assert!(validate_protocol_quota(Chain::Lean, &quota).is_err());
+
+        assert!("blocks_by_range:512".parse::<ProtocolQuota>().is_err());
+        assert!("blocks_by_range:0/10".parse::<ProtocolQuota>().is_err());
+    }
+}
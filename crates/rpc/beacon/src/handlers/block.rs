@@ -23,6 +23,7 @@ use ream_consensus_beacon::{
         beacon_state::BeaconState,
         blinded_beacon_block::SignedBlindedBeaconBlock,
     },
+    fork_choice::execution_status::ExecutionStatus,
     genesis::Genesis,
 };
 use ream_consensus_misc::constants::beacon::{
@@ -107,6 +108,15 @@ pub async fn get_block_root_from_id(block_id: ID, db: &BeaconDB) -> Result<B256,
     Ok(block_root)
 }
 
+/// Whether `block_root` was imported without its execution payload being verified.
+pub fn is_execution_optimistic(db: &BeaconDB, block_root: B256) -> Result<bool, ApiError> {
+    let execution_status = db.get_execution_status(block_root).map_err(|err| {
+        ApiError::InternalError(format!("Failed to get execution status, error: {err:?}"))
+    })?;
+
+    Ok(execution_status == ExecutionStatus::Optimistic)
+}
+
 fn get_attestations_rewards(beacon_state: &BeaconState, beacon_block: &SignedBeaconBlock) -> u64 {
     let mut attester_reward = 0;
     let attestations = &beacon_block.message.body.attestations;
@@ -192,11 +202,13 @@ pub async fn get_block_attestations(
     db: Data<BeaconDB>,
     block_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    let beacon_block = get_beacon_block_from_id(block_id.into_inner(), &db).await?;
+    let block_root = get_block_root_from_id(block_id.into_inner(), &db).await?;
+    let beacon_block = get_beacon_block_from_id(ID::Root(block_root), &db).await?;
 
-    Ok(HttpResponse::Ok().json(BeaconVersionedResponse::new(
-        beacon_block.message.body.attestations,
-    )))
+    Ok(HttpResponse::Ok().json(
+        BeaconVersionedResponse::new(beacon_block.message.body.attestations)
+            .with_execution_optimistic(is_execution_optimistic(&db, block_root)?),
+    ))
 }
 
 /// Called by `/blocks/<block_id>/root` to get the Tree hash of the Block.
@@ -207,7 +219,10 @@ pub async fn get_block_root(
 ) -> Result<impl Responder, ApiError> {
     let block_root = get_block_root_from_id(block_id.into_inner(), &db).await?;
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(RootResponse::new(block_root))))
+    Ok(HttpResponse::Ok().json(
+        BeaconResponse::new(RootResponse::new(block_root))
+            .with_execution_optimistic(is_execution_optimistic(&db, block_root)?),
+    ))
 }
 
 /// Called by `/beacon/blocks/{block_id}/rewards` to get the block rewards response
@@ -217,7 +232,8 @@ pub async fn get_block_rewards(
     block_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
     let block_id_value = block_id.into_inner();
-    let beacon_block = get_beacon_block_from_id(block_id_value.clone(), &db).await?;
+    let block_root = get_block_root_from_id(block_id_value.clone(), &db).await?;
+    let beacon_block = get_beacon_block_from_id(ID::Root(block_root), &db).await?;
     let beacon_state = get_state_from_id(block_id_value.clone(), &db).await?;
 
     let attestation_reward = get_attestations_rewards(&beacon_state, &beacon_block);
@@ -247,7 +263,10 @@ pub async fn get_block_rewards(
         attester_slashings: attester_slashing_reward,
     };
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(response)))
+    Ok(HttpResponse::Ok().json(
+        BeaconResponse::new(response)
+            .with_execution_optimistic(is_execution_optimistic(&db, block_root)?),
+    ))
 }
 
 /// Called by `/blocks/<block_id>` to get the Beacon Block.
@@ -256,9 +275,13 @@ pub async fn get_block_from_id(
     db: Data<BeaconDB>,
     block_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    let beacon_block = get_beacon_block_from_id(block_id.into_inner(), &db).await?;
+    let block_root = get_block_root_from_id(block_id.into_inner(), &db).await?;
+    let beacon_block = get_beacon_block_from_id(ID::Root(block_root), &db).await?;
 
-    Ok(HttpResponse::Ok().json(BeaconVersionedResponse::new(beacon_block)))
+    Ok(HttpResponse::Ok().json(
+        BeaconVersionedResponse::new(beacon_block)
+            .with_execution_optimistic(is_execution_optimistic(&db, block_root)?),
+    ))
 }
 
 #[post("/beacon/rewards/sync_committee/{block_id}")]
@@ -321,7 +344,8 @@ pub async fn get_blind_block(
     db: Data<BeaconDB>,
     block_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    let beacon_block = get_beacon_block_from_id(block_id.into_inner(), &db).await?;
+    let block_root = get_block_root_from_id(block_id.into_inner(), &db).await?;
+    let beacon_block = get_beacon_block_from_id(ID::Root(block_root), &db).await?;
     let blinded_beacon_block = beacon_block.as_signed_blinded_beacon_block();
     match http_request
         .headers()
@@ -331,7 +355,10 @@ pub async fn get_blind_block(
         Some(SSZ_CONTENT_TYPE) => Ok(HttpResponse::Ok()
             .content_type(SSZ_CONTENT_TYPE)
             .body(blinded_beacon_block.as_ssz_bytes())),
-        _ => Ok(HttpResponse::Ok().json(BeaconVersionedResponse::new(blinded_beacon_block))),
+        _ => Ok(HttpResponse::Ok().json(
+            BeaconVersionedResponse::new(blinded_beacon_block)
+                .with_execution_optimistic(is_execution_optimistic(&db, block_root)?),
+        )),
     }
 }
 
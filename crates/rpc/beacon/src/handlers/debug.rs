@@ -10,12 +10,13 @@ use ream_api_types_beacon::responses::{
     ForkChoiceValidity,
 };
 use ream_api_types_common::{error::ApiError, id::ID};
-use ream_fork_choice_beacon::store::{BlockWithEpochInfo, Store};
-use ream_operation_pool::OperationPool;
+use ream_chain_beacon::beacon_chain::BeaconChain;
+use ream_consensus_beacon::fork_choice::execution_status::ExecutionStatus;
+use ream_fork_choice_beacon::store::BlockWithEpochInfo;
 use ream_storage::{db::beacon::BeaconDB, tables::field::REDBField};
 use serde_json::json;
 
-use crate::handlers::state::get_state_from_id;
+use crate::handlers::{block::is_execution_optimistic, state::get_state_from_id};
 
 #[get("/debug/beacon/states/{state_id}")]
 pub async fn get_debug_beacon_state(
@@ -28,7 +29,10 @@ pub async fn get_debug_beacon_state(
 }
 
 #[get("/debug/beacon/heads")]
-pub async fn get_debug_beacon_heads(db: Data<BeaconDB>) -> Result<impl Responder, ApiError> {
+pub async fn get_debug_beacon_heads(
+    db: Data<BeaconDB>,
+    beacon_chain: Data<Arc<BeaconChain>>,
+) -> Result<impl Responder, ApiError> {
     let justified_checkpoint = db.justified_checkpoint_provider().get().map_err(|err| {
         ApiError::InternalError(format!(
             "Failed to get justified_checkpoint, error: {err:?}"
@@ -36,13 +40,10 @@ pub async fn get_debug_beacon_heads(db: Data<BeaconDB>) -> Result<impl Responder
     })?;
 
     let mut blocks = HashMap::new();
-    let store = Store::new(
-        db.get_ref().clone(),
-        Arc::new(OperationPool::default()),
-        None,
-    );
-
-    store
+    beacon_chain
+        .store
+        .lock()
+        .await
         .filter_block_tree(justified_checkpoint.root, &mut blocks)
         .map_err(|err| {
             ApiError::InternalError(format!("Failed to filter block tree, error: {err:?}"))
@@ -60,7 +61,7 @@ pub async fn get_debug_beacon_heads(db: Data<BeaconDB>) -> Result<impl Responder
             leaves.push(BeaconHeadResponse {
                 root: block.block_root(),
                 slot: block.slot,
-                execution_optimistic: false,
+                execution_optimistic: is_execution_optimistic(&db, *block_root)?,
             });
         }
     }
@@ -69,7 +70,10 @@ pub async fn get_debug_beacon_heads(db: Data<BeaconDB>) -> Result<impl Responder
 }
 
 #[get("/debug/fork_choice")]
-pub async fn get_debug_fork_choice(db: Data<BeaconDB>) -> Result<impl Responder, ApiError> {
+pub async fn get_debug_fork_choice(
+    db: Data<BeaconDB>,
+    beacon_chain: Data<Arc<BeaconChain>>,
+) -> Result<impl Responder, ApiError> {
     let justified_checkpoint = db.justified_checkpoint_provider().get().map_err(|err| {
         ApiError::InternalError(format!(
             "Failed to get justified_checkpoint, error: {err:?}"
@@ -81,11 +85,7 @@ pub async fn get_debug_fork_choice(db: Data<BeaconDB>) -> Result<impl Responder,
         ))
     })?;
 
-    let store = Store::new(
-        db.get_ref().clone(),
-        Arc::new(OperationPool::default()),
-        None,
-    );
+    let store = beacon_chain.store.lock().await;
     let blocks = store.get_filtered_block_tree().map_err(|err| {
         ApiError::InternalError(format!("Failed to get filtered block tree, error: {err:?}"))
     })?;
@@ -105,6 +105,17 @@ pub async fn get_debug_fork_choice(db: Data<BeaconDB>) -> Result<impl Responder,
             ))
         })?;
 
+        let validity = match store.db.get_execution_status(block_root) {
+            Ok(ExecutionStatus::Valid | ExecutionStatus::Irrelevant) => ForkChoiceValidity::Valid,
+            Ok(ExecutionStatus::Optimistic) => ForkChoiceValidity::Optimistic,
+            Ok(ExecutionStatus::Invalid) => ForkChoiceValidity::Invalid,
+            Err(err) => {
+                return Err(ApiError::InternalError(format!(
+                    "Failed to get execution status for block {block_root:?}, error: {err:?}"
+                )));
+            }
+        };
+
         fork_choice_nodes.push(ForkChoiceNode {
             slot: block.slot,
             block_root,
@@ -112,9 +123,7 @@ pub async fn get_debug_fork_choice(db: Data<BeaconDB>) -> Result<impl Responder,
             justified_epoch,
             finalized_epoch,
             weight,
-            // NOTE: As `EXECUTION_OPTIMISTIC` is default to false, validity will be always "valid"
-            // in this context.
-            validity: ForkChoiceValidity::Valid,
+            validity,
             execution_block_hash: block.body.execution_payload.block_hash,
             extra_data: json!({}),
         });
@@ -10,7 +10,7 @@ use ream_api_types_common::{error::ApiError, id::ID};
 use ream_consensus_misc::{constants::beacon::SLOTS_PER_EPOCH, misc::compute_start_slot_at_epoch};
 use ream_storage::db::beacon::BeaconDB;
 
-use crate::handlers::state::get_state_from_id;
+use crate::handlers::state::{get_state_from_id, is_state_execution_optimistic};
 
 #[get("/validator/duties/proposer/{epoch}")]
 pub async fn get_proposer_duties(
@@ -18,7 +18,9 @@ pub async fn get_proposer_duties(
     epoch: Path<u64>,
 ) -> Result<impl Responder, ApiError> {
     let epoch = epoch.into_inner();
-    let state = get_state_from_id(ID::Slot(compute_start_slot_at_epoch(epoch)), &db).await?;
+    let state_id = ID::Slot(compute_start_slot_at_epoch(epoch));
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
     let dependent_root = state
         .get_block_root_at_slot(compute_start_slot_at_epoch(epoch) - 1)
         .map_err(|err| ApiError::BadRequest(format!("Failed to get dependent root {err:?}")))?;
@@ -39,7 +41,10 @@ pub async fn get_proposer_duties(
             slot,
         });
     }
-    Ok(HttpResponse::Ok().json(DutiesResponse::new(Some(dependent_root), duties)))
+    Ok(HttpResponse::Ok().json(
+        DutiesResponse::new(Some(dependent_root), duties)
+            .with_execution_optimistic(execution_optimistic),
+    ))
 }
 
 #[post("/validator/duties/attester/{epoch}")]
@@ -49,7 +54,9 @@ pub async fn get_attester_duties(
     validator_indices: Json<Vec<u64>>,
 ) -> Result<impl Responder, ApiError> {
     let epoch = epoch.into_inner();
-    let state = get_state_from_id(ID::Slot(compute_start_slot_at_epoch(epoch)), &db).await?;
+    let state_id = ID::Slot(compute_start_slot_at_epoch(epoch));
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
     let dependent_root = state
         .get_block_root_at_slot(compute_start_slot_at_epoch(epoch) - 1)
         .map_err(|err| ApiError::BadRequest(format!("Failed to get dependent root {err:?}")))?;
@@ -90,7 +97,10 @@ pub async fn get_attester_duties(
             });
         }
     }
-    Ok(HttpResponse::Ok().json(DutiesResponse::new(Some(dependent_root), duties)))
+    Ok(HttpResponse::Ok().json(
+        DutiesResponse::new(Some(dependent_root), duties)
+            .with_execution_optimistic(execution_optimistic),
+    ))
 }
 
 #[post("/validator/duties/sync/{epoch}")]
@@ -100,7 +110,9 @@ pub async fn get_sync_committee_duties(
     validator_indices: Json<Vec<u64>>,
 ) -> Result<impl Responder, ApiError> {
     let epoch = epoch.into_inner();
-    let state = get_state_from_id(ID::Slot(compute_start_slot_at_epoch(epoch)), &db).await?;
+    let state_id = ID::Slot(compute_start_slot_at_epoch(epoch));
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
     let validator_indices = validator_indices.into_inner();
 
     let mut duties = vec![];
@@ -135,5 +147,6 @@ pub async fn get_sync_committee_duties(
             validator_sync_committee_indices,
         });
     }
-    Ok(HttpResponse::Ok().json(DutiesResponse::new(None, duties)))
+    Ok(HttpResponse::Ok()
+        .json(DutiesResponse::new(None, duties).with_execution_optimistic(execution_optimistic)))
 }
@@ -40,8 +40,8 @@ pub async fn get_light_client_bootstrap(
         })?;
 
     let beacon_state = db
-        .state_provider()
-        .get(block_root)
+        .get_state(block_root)
+        .await
         .map_err(|err| {
             ApiError::InternalError(format!(
                 "Failed to get beacon_state from block_root, error: {err:?}"
@@ -101,8 +101,8 @@ pub async fn get_light_client_updates(
             )))?;
 
         let state = db
-            .state_provider()
-            .get(block_root)
+            .get_state(block_root)
+            .await
             .map_err(|err| {
                 ApiError::InternalError(format!(
                     "Failed to get state from block_root, error: {err:?}"
@@ -127,8 +127,8 @@ pub async fn get_light_client_updates(
 
         let attested_block_root = attested_block.message.tree_hash_root();
         let attested_state = db
-            .state_provider()
-            .get(attested_block_root)
+            .get_state(attested_block_root)
+            .await
             .map_err(|err| {
                 ApiError::InternalError(format!(
                     "Failed to get attested_state from attested_block_root, error: {err:?}"
@@ -224,8 +224,8 @@ pub async fn get_light_client_finality_update(
 
     let attested_block_root = attested_block.message.tree_hash_root();
     let attested_state = db
-        .state_provider()
-        .get(attested_block_root)
+        .get_state(attested_block_root)
+        .await
         .map_err(|err| {
             ApiError::InternalError(format!("Failed to get attested state, error: {err:?}"))
         })?
@@ -12,15 +12,17 @@ use ream_api_types_common::{error::ApiError, id::ID};
 use ream_bls::traits::Verifiable;
 use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_consensus_beacon::{
-    attestation::Attestation, attester_slashing::AttesterSlashing,
-    bls_to_execution_change::SignedBLSToExecutionChange, electra::beacon_state::BeaconState,
-    proposer_slashing::ProposerSlashing, single_attestation::SingleAttestation,
-    voluntary_exit::SignedVoluntaryExit,
+    attester_slashing::AttesterSlashing, bls_to_execution_change::SignedBLSToExecutionChange,
+    electra::beacon_state::BeaconState, proposer_slashing::ProposerSlashing,
+    single_attestation::SingleAttestation, voluntary_exit::SignedVoluntaryExit,
 };
 use ream_consensus_misc::{
     constants::beacon::DOMAIN_SYNC_COMMITTEE,
     misc::{compute_epoch_at_slot, compute_signing_root},
 };
+use ream_events_beacon::{
+    BeaconEvent, BeaconEventSender, event::attestation::SingleAttestationEvent,
+};
 use ream_network_manager::{
     gossipsub::validate::{
         beacon_attestation::validate_beacon_attestation, result::ValidationResult,
@@ -38,10 +40,6 @@ use ream_validator_beacon::{
     sync_committee::{SyncCommitteeMessage, is_assigned_to_sync_committee},
 };
 use ssz::Encode;
-use ssz_types::{
-    BitList, BitVector,
-    typenum::{U64, U131072},
-};
 
 use crate::handlers::state::get_state_from_id;
 
@@ -259,7 +257,6 @@ pub async fn post_proposer_slashings(
 /// POST /eth/v2/beacon/pool/attestations
 #[post("/beacon/pool/attestations")]
 pub async fn post_attestations(
-    operation_pool: Data<Arc<OperationPool>>,
     network_manager: Data<Arc<NetworkManagerService>>,
     beacon_chain: Data<Arc<BeaconChain>>,
     attestations: Json<Vec<SingleAttestation>>,
@@ -283,6 +280,7 @@ pub async fn post_attestations(
             beacon_chain.get_ref().as_ref(),
             subnet_id,
             &network_manager.cached_db,
+            &network_manager.batch_verifier,
         )
         .await
         {
@@ -302,13 +300,8 @@ pub async fn post_attestations(
             }
         }
 
-        let attestation = convert_single_to_attestation(&single_attestation, &beacon_state)
-            .map_err(|err| ApiError::BadRequest(format!("Invalid attestation: {err:?}")))?;
-
-        operation_pool.insert_attestation(attestation.clone(), single_attestation.committee_index);
-
         beacon_chain
-            .process_attestation(attestation.clone(), false)
+            .process_single_attestation(&single_attestation)
             .await
             .map_err(|err| {
                 ApiError::BadRequest(format!("Attestation failed processing: {err:?}"))
@@ -321,6 +314,12 @@ pub async fn post_attestations(
             },
             data: single_attestation.as_ssz_bytes(),
         });
+
+        beacon_chain
+            .event_sender
+            .send_event(BeaconEvent::SingleAttestation(
+                SingleAttestationEvent::from(single_attestation),
+            ));
     }
 
     Ok(HttpResponse::Ok())
@@ -342,38 +341,6 @@ pub async fn get_attestations(
     Ok(HttpResponse::Ok().json(DataVersionedResponse::new(all_attestations)))
 }
 
-fn convert_single_to_attestation(
-    single: &SingleAttestation,
-    state: &ream_consensus_beacon::electra::beacon_state::BeaconState,
-) -> Result<Attestation, String> {
-    let committee = state
-        .get_beacon_committee(single.data.slot, single.committee_index)
-        .map_err(|err| format!("Failed to get committee: {err:?}"))?;
-
-    let validator_index_in_committee = committee
-        .iter()
-        .position(|&idx| idx == single.attester_index)
-        .ok_or_else(|| format!("Validator {} not found in committee", single.attester_index))?;
-
-    let mut aggregation_bits = BitList::<U131072>::with_capacity(committee.len())
-        .map_err(|err| format!("Failed to create aggregation_bits: {err:?}"))?;
-    aggregation_bits
-        .set(validator_index_in_committee, true)
-        .map_err(|err| format!("Failed to set aggregation bit: {err:?}"))?;
-
-    let mut committee_bits = BitVector::<U64>::new();
-    committee_bits
-        .set(single.committee_index as usize, true)
-        .map_err(|err| format!("Failed to set committee bit: {err:?}"))?;
-
-    Ok(Attestation {
-        aggregation_bits,
-        data: single.data.clone(),
-        signature: single.signature.clone(),
-        committee_bits,
-    })
-}
-
 async fn get_head_state(beacon_chain: &BeaconChain) -> Result<BeaconState, ApiError> {
     let store = beacon_chain.store.lock().await;
 
@@ -6,14 +6,13 @@ use actix_web::{
 };
 use ream_api_types_beacon::request::PrepareBeaconProposerItem;
 use ream_api_types_common::error::ApiError;
-use ream_fork_choice_beacon::store::Store;
+use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_operation_pool::OperationPool;
-use ream_storage::db::beacon::BeaconDB;
 
 #[post("/validator/prepare_beacon_proposer")]
 pub async fn prepare_beacon_proposer(
-    db: Data<BeaconDB>,
     operation_pool: Data<Arc<OperationPool>>,
+    beacon_chain: Data<Arc<BeaconChain>>,
     prepare_beacon_proposer_items: Json<Vec<PrepareBeaconProposerItem>>,
 ) -> Result<impl Responder, ApiError> {
     let items = prepare_beacon_proposer_items.into_inner();
@@ -22,9 +21,10 @@ pub async fn prepare_beacon_proposer(
         return Err(ApiError::BadRequest("Empty request body".to_string()));
     }
 
-    // Create a store instance to get the current epoch
-    let store = Store::new(db.get_ref().clone(), operation_pool.get_ref().clone(), None);
-    let current_epoch = store
+    let current_epoch = beacon_chain
+        .store
+        .lock()
+        .await
         .get_current_store_epoch()
         .map_err(|err| ApiError::InternalError(format!("Failed to get current epoch: {err}")))?;
 
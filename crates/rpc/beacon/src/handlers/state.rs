@@ -20,6 +20,8 @@ use ream_storage::{
 use serde::{Deserialize, Serialize};
 use tree_hash::TreeHash;
 
+use super::block::is_execution_optimistic;
+
 pub const SYNC_COMMITTEE_SUBNET_COUNT: u64 = 4;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -65,8 +67,9 @@ struct SyncCommitteeResponse {
     pub validator_aggregates: Vec<QuotedU64Vec>,
 }
 
-pub async fn get_state_from_id(state_id: ID, db: &BeaconDB) -> Result<BeaconState, ApiError> {
-    let block_root = match state_id {
+/// Resolve `state_id` to the root of the block whose post-state it is.
+pub async fn get_state_block_root_from_id(state_id: &ID, db: &BeaconDB) -> Result<B256, ApiError> {
+    match state_id {
         ID::Finalized => {
             let finalized_checkpoint = db.finalized_checkpoint_provider().get().map_err(|err| {
                 ApiError::InternalError(format!(
@@ -90,18 +93,41 @@ pub async fn get_state_from_id(state_id: ID, db: &BeaconDB) -> Result<BeaconStat
                 "This ID type is currently not supported: {state_id:?}"
             )));
         }
-        ID::Slot(slot) => db.slot_index_provider().get(slot),
-        ID::Root(root) => db.state_root_index_provider().get(root),
+        ID::Slot(slot) => db.slot_index_provider().get(*slot),
+        ID::Root(root) => db.state_root_index_provider().get(*root),
     }
     .map_err(|err| ApiError::InternalError(format!("Failed to get headers, error: {err:?}")))?
-    .ok_or_else(|| ApiError::NotFound(format!("Failed to find `block_root` from {state_id:?}")))?;
-
-    db.state_provider()
-        .get(block_root)
-        .map_err(|err| {
-            ApiError::InternalError(format!("Failed to get block by block_root, error: {err:?}"))
-        })?
-        .ok_or_else(|| ApiError::NotFound(format!("Failed to find `block_root` from {state_id:?}")))
+    .ok_or_else(|| ApiError::NotFound(format!("Failed to find `block_root` from {state_id:?}")))
+}
+
+/// Whether the state `state_id` resolves to is the post-state of a block that was imported
+/// without its execution payload being verified.
+pub async fn is_state_execution_optimistic(state_id: &ID, db: &BeaconDB) -> Result<bool, ApiError> {
+    // An empty slot has no block of its own, only finalized empty slots have a state
+    if let ID::Slot(slot) = state_id
+        && db
+            .slot_index_provider()
+            .get(*slot)
+            .map_err(|err| ApiError::InternalError(format!("Failed to get slot index: {err:?}")))?
+            .is_none()
+    {
+        return Ok(false);
+    }
+
+    is_execution_optimistic(db, get_state_block_root_from_id(state_id, db).await?)
+}
+
+/// Get the state `state_id` resolves to. Historical states are regenerated on demand.
+pub async fn get_state_from_id(state_id: ID, db: &BeaconDB) -> Result<BeaconState, ApiError> {
+    match state_id {
+        ID::Slot(slot) => db.get_state_at_slot(slot).await,
+        _ => {
+            db.get_state(get_state_block_root_from_id(&state_id, db).await?)
+                .await
+        }
+    }
+    .map_err(|err| ApiError::InternalError(format!("Failed to get state, error: {err:?}")))?
+    .ok_or_else(|| ApiError::NotFound(format!("Failed to find state from {state_id:?}")))
 }
 
 #[get("/beacon/states/{state_id}/root")]
@@ -109,11 +135,14 @@ pub async fn get_state_root(
     db: Data<BeaconDB>,
     state_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
 
     let state_root = state.tree_hash_root();
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(state_root)))
+    Ok(HttpResponse::Ok()
+        .json(BeaconResponse::new(state_root).with_execution_optimistic(execution_optimistic)))
 }
 
 /// Called by `/eth/v1/beacon/states/{state_id}/fork` to get fork of state.
@@ -122,9 +151,12 @@ pub async fn get_state_fork(
     db: Data<BeaconDB>,
     state_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(state.fork)))
+    Ok(HttpResponse::Ok()
+        .json(BeaconResponse::new(state.fork).with_execution_optimistic(execution_optimistic)))
 }
 
 /// Called by `/states/<state_id>/finality_checkpoints` to get the Checkpoint Data of state.
@@ -133,15 +165,18 @@ pub async fn get_state_finality_checkpoint(
     db: Data<BeaconDB>,
     state_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
 
-    Ok(
-        HttpResponse::Ok().json(BeaconResponse::new(CheckpointData::new(
+    Ok(HttpResponse::Ok().json(
+        BeaconResponse::new(CheckpointData::new(
             state.previous_justified_checkpoint,
             state.current_justified_checkpoint,
             state.finalized_checkpoint,
-        ))),
-    )
+        ))
+        .with_execution_optimistic(execution_optimistic),
+    ))
 }
 
 /// Called by `/states/<state_id>/randao` to get the Randao mix of state.
@@ -153,14 +188,19 @@ pub async fn get_state_randao(
     state_id: Path<ID>,
     query: Query<EpochQuery>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
 
     let randao_mix = match query.epoch {
         Some(epoch) => state.get_randao_mix(epoch),
         None => state.get_randao_mix(state.get_current_epoch()),
     };
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(RandaoResponse::new(randao_mix))))
+    Ok(HttpResponse::Ok().json(
+        BeaconResponse::new(RandaoResponse::new(randao_mix))
+            .with_execution_optimistic(execution_optimistic),
+    ))
 }
 
 /// Called by `/eth/v1/beacon/states/{state_id}/pending_consolidations` to get pending
@@ -170,13 +210,14 @@ pub async fn get_pending_consolidations(
     db: Data<BeaconDB>,
     state_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
-
-    Ok(
-        HttpResponse::Ok().json(BeaconVersionedResponse::new(Vec::from(
-            state.pending_consolidations,
-        ))),
-    )
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
+
+    Ok(HttpResponse::Ok().json(
+        BeaconVersionedResponse::new(Vec::from(state.pending_consolidations))
+            .with_execution_optimistic(execution_optimistic),
+    ))
 }
 
 /// Called by `/eth/v1/beacon/states/{state_id}/pending_deposits` to get pending deposits
@@ -186,13 +227,14 @@ pub async fn get_pending_deposits(
     db: Data<BeaconDB>,
     state_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
-
-    Ok(
-        HttpResponse::Ok().json(BeaconVersionedResponse::new(Vec::from(
-            state.pending_deposits,
-        ))),
-    )
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
+
+    Ok(HttpResponse::Ok().json(
+        BeaconVersionedResponse::new(Vec::from(state.pending_deposits))
+            .with_execution_optimistic(execution_optimistic),
+    ))
 }
 
 /// Called by `/states/{state_id}/pending_partial_withdrawals` to get pending partial withdrawals
@@ -202,13 +244,14 @@ pub async fn get_pending_partial_withdrawals(
     db: Data<BeaconDB>,
     state_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
-
-    Ok(
-        HttpResponse::Ok().json(BeaconVersionedResponse::new(Vec::from(
-            state.pending_partial_withdrawals,
-        ))),
-    )
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
+
+    Ok(HttpResponse::Ok().json(
+        BeaconVersionedResponse::new(Vec::from(state.pending_partial_withdrawals))
+            .with_execution_optimistic(execution_optimistic),
+    ))
 }
 
 /// Called by `/states/{state_id}/sync_committees` to get sync_committees
@@ -220,7 +263,9 @@ pub async fn get_sync_committees(
     state_id: Path<ID>,
     epoch: Query<EpochQuery>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
     let current_epoch = state.get_current_epoch();
     let epoch = epoch.epoch.unwrap_or(current_epoch);
     let sync_committee_period = compute_sync_committee_period(epoch);
@@ -253,10 +298,11 @@ pub async fn get_sync_committees(
         .map(|chunk| QuotedU64Vec(chunk.to_vec()))
         .collect::<Vec<QuotedU64Vec>>();
 
-    Ok(
-        HttpResponse::Ok().json(BeaconVersionedResponse::new(SyncCommitteeResponse {
+    Ok(HttpResponse::Ok().json(
+        BeaconVersionedResponse::new(SyncCommitteeResponse {
             validators,
             validator_aggregates,
-        })),
-    )
+        })
+        .with_execution_optimistic(execution_optimistic),
+    ))
 }
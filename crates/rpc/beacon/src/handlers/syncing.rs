@@ -6,10 +6,9 @@ use ream_api_types_beacon::{
     sync::SyncStatus,
 };
 use ream_api_types_common::error::ApiError;
+use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_execution_engine::ExecutionEngine;
-use ream_fork_choice_beacon::store::Store;
-use ream_operation_pool::OperationPool;
-use ream_storage::{db::beacon::BeaconDB, tables::table::REDBTable};
+use ream_storage::tables::table::REDBTable;
 use serde::{Deserialize, Serialize};
 use tracing::error;
 
@@ -33,30 +32,39 @@ impl Syncing {
 }
 
 pub async fn calculate_sync_status(
-    db: &BeaconDB,
-    operation_pool: &Arc<OperationPool>,
+    beacon_chain: &BeaconChain,
     execution_engine: &Option<ExecutionEngine>,
 ) -> Result<SyncStatus, ApiError> {
-    let store = Store::new(db.clone(), operation_pool.clone(), None);
+    let (head_slot, current_slot, is_optimistic) = {
+        let store = beacon_chain.store.lock().await;
 
-    // get head_slot
-    let head = store.get_head().map_err(|err| {
-        ApiError::InternalError(format!("Failed to get current slot, error: {err:?}"))
-    })?;
+        // get head_slot
+        let head = store.get_head().map_err(|err| {
+            ApiError::InternalError(format!("Failed to get current slot, error: {err:?}"))
+        })?;
 
-    let head_slot = match db.block_provider().get(head) {
-        Ok(Some(block)) => block.message.slot,
-        err => {
-            return Err(ApiError::InternalError(format!(
-                "Failed to get head slot, error: {err:?}"
-            )));
-        }
-    };
+        let head_slot = match store.db.block_provider().get(head) {
+            Ok(Some(block)) => block.message.slot,
+            err => {
+                return Err(ApiError::InternalError(format!(
+                    "Failed to get head slot, error: {err:?}"
+                )));
+            }
+        };
 
-    // calculate sync_distance
-    let current_slot = store.get_current_slot().map_err(|err| {
-        ApiError::InternalError(format!("Failed to get current slot, error: {err:?}"))
-    })?;
+        // calculate sync_distance
+        let current_slot = store.get_current_slot().map_err(|err| {
+            ApiError::InternalError(format!("Failed to get current slot, error: {err:?}"))
+        })?;
+
+        let is_optimistic = store.is_optimistic(head).map_err(|err| {
+            ApiError::InternalError(format!(
+                "Failed to get head execution status, error: {err:?}"
+            ))
+        })?;
+
+        (head_slot, current_slot, is_optimistic)
+    };
 
     let sync_distance = current_slot.saturating_sub(head_slot);
 
@@ -77,18 +85,17 @@ pub async fn calculate_sync_status(
         sync_distance,
         is_syncing: sync_distance > 1,
         el_offline,
-        is_optimistic: EXECUTION_OPTIMISTIC,
+        is_optimistic,
     })
 }
 
 /// Called by `eth/v1/node/syncing` to get the Node Version.
 #[get("/node/syncing")]
 pub async fn get_syncing_status(
-    db: Data<BeaconDB>,
-    operation_pool: Data<Arc<OperationPool>>,
+    beacon_chain: Data<Arc<BeaconChain>>,
     execution_engine: Data<Option<ExecutionEngine>>,
 ) -> Result<impl Responder, ApiError> {
-    let sync_status = calculate_sync_status(&db, &operation_pool, &execution_engine).await?;
+    let sync_status = calculate_sync_status(&beacon_chain, &execution_engine).await?;
 
     Ok(HttpResponse::Ok().json(DataResponse::new(Syncing { sync_status })))
 }
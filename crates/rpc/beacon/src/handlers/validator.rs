@@ -4,7 +4,7 @@ use actix_web::{
     HttpResponse, Responder, get, post,
     web::{Data, Json, Path, Query},
 };
-use alloy_primitives::{Address, B256, U256, aliases::B32};
+use alloy_primitives::{Address, B64, B256, U256, aliases::B32};
 use ream_api_types_beacon::{
     block::{FullBlockData, ProduceBlockData, ProduceBlockResponse},
     committee::{BeaconCommitteeSubscription, SyncCommitteeSubscription},
@@ -16,6 +16,7 @@ use ream_api_types_beacon::{
 };
 use ream_api_types_common::{error::ApiError, id::ID};
 use ream_bls::{BLSSignature, PublicKey, traits::Verifiable};
+use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_consensus_beacon::{
     attestation::Attestation,
     attester_slashing::AttesterSlashing,
@@ -47,15 +48,18 @@ use ream_consensus_misc::{
     validator::Validator,
 };
 use ream_events_beacon::{
-    BeaconEvent, contribution_and_proof::SignedContributionAndProof,
-    event::sync_committee::ContributionAndProofEvent,
+    BeaconEvent, BeaconEventSender,
+    contribution_and_proof::SignedContributionAndProof,
+    event::{
+        execution::{PayloadAttributes, PayloadAttributesData, PayloadAttributesEvent},
+        sync_committee::ContributionAndProofEvent,
+    },
 };
 use ream_execution_engine::{ExecutionEngine, engine_trait::ExecutionApi};
 use ream_execution_rpc_types::{
     forkchoice_update::{ForkchoiceStateV1, PayloadAttributesV3},
     get_payload::Payload,
 };
-use ream_fork_choice_beacon::store::Store;
 use ream_network_manager::gossipsub::validate::sync_committee_contribution_and_proof::get_sync_subcommittee_pubkeys;
 use ream_operation_pool::OperationPool;
 use ream_p2p::{
@@ -92,7 +96,10 @@ use ssz_types::{
 use tokio::sync::{Mutex, broadcast};
 use tree_hash::TreeHash;
 
-use super::state::get_state_from_id;
+use super::{
+    block::is_execution_optimistic,
+    state::{get_state_from_id, is_state_execution_optimistic},
+};
 
 ///  For slots in Electra and later, this AttestationData must have a committee_index of 0.
 const ELECTRA_COMMITTEE_INDEX: u64 = 0;
@@ -128,7 +135,8 @@ pub async fn get_validator_from_state(
     param: Path<(ID, ValidatorID)>,
 ) -> Result<impl Responder, ApiError> {
     let (state_id, validator_id) = param.into_inner();
-    let state = get_state_from_id(state_id, &db).await?;
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
 
     let (index, validator) = {
         match &validator_id {
@@ -159,14 +167,15 @@ pub async fn get_validator_from_state(
 
     let status = validator_status(&validator, &db).await?;
 
-    Ok(
-        HttpResponse::Ok().json(BeaconResponse::new(ValidatorData::new(
+    Ok(HttpResponse::Ok().json(
+        BeaconResponse::new(ValidatorData::new(
             index as u64,
             *balance,
             status,
             validator,
-        ))),
-    )
+        ))
+        .with_execution_optimistic(execution_optimistic),
+    ))
 }
 
 pub async fn validator_status(
@@ -224,7 +233,9 @@ pub async fn get_validators_from_state(
         return Err(ApiError::TooManyValidatorsIds);
     }
 
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
     let mut validators_data = Vec::new();
     let mut validator_indices_to_process = Vec::new();
 
@@ -280,7 +291,8 @@ pub async fn get_validators_from_state(
         ));
     }
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(validators_data)))
+    Ok(HttpResponse::Ok()
+        .json(BeaconResponse::new(validators_data).with_execution_optimistic(execution_optimistic)))
 }
 
 #[post("/beacon/states/{state_id}/validators")]
@@ -293,7 +305,9 @@ pub async fn post_validators_from_state(
     let ValidatorsPostRequest { ids, statuses, .. } = request.into_inner();
     let status_query = StatusQuery { status: statuses };
 
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
     let mut validators_data = Vec::new();
     let mut validator_indices_to_process = Vec::new();
 
@@ -349,7 +363,8 @@ pub async fn post_validators_from_state(
         ));
     }
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(validators_data)))
+    Ok(HttpResponse::Ok()
+        .json(BeaconResponse::new(validators_data).with_execution_optimistic(execution_optimistic)))
 }
 
 #[derive(Debug, Serialize)]
@@ -367,7 +382,9 @@ pub async fn post_validator_identities_from_state(
     state_id: Path<ID>,
     validator_ids: Json<Vec<ValidatorID>>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
 
     let validator_ids_set: HashSet<ValidatorID> = validator_ids.into_inner().into_iter().collect();
 
@@ -390,7 +407,9 @@ pub async fn post_validator_identities_from_state(
         })
         .collect();
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(validator_identities)))
+    Ok(HttpResponse::Ok().json(
+        BeaconResponse::new(validator_identities).with_execution_optimistic(execution_optimistic),
+    ))
 }
 
 #[get("/beacon/states/{state_id}/validator_balances")]
@@ -399,17 +418,20 @@ pub async fn get_validator_balances_from_state(
     query: Query<IdQuery>,
     db: Data<BeaconDB>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
-    Ok(
-        HttpResponse::Ok().json(BeaconResponse::new(build_validator_balances(
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
+    Ok(HttpResponse::Ok().json(
+        BeaconResponse::new(build_validator_balances(
             &state
                 .validators
                 .into_iter()
                 .zip(state.balances.into_iter())
                 .collect::<Vec<_>>(),
             query.id.as_ref(),
-        ))),
-    )
+        ))
+        .with_execution_optimistic(execution_optimistic),
+    ))
 }
 
 #[post("/beacon/states/{state_id}/validator_balances")]
@@ -418,17 +440,20 @@ pub async fn post_validator_balances_from_state(
     body: Json<IdQuery>,
     db: Data<BeaconDB>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
-    Ok(
-        HttpResponse::Ok().json(BeaconResponse::new(build_validator_balances(
+    let state_id = state_id.into_inner();
+    let state = get_state_from_id(state_id.clone(), &db).await?;
+    let execution_optimistic = is_state_execution_optimistic(&state_id, &db).await?;
+    Ok(HttpResponse::Ok().json(
+        BeaconResponse::new(build_validator_balances(
             &state
                 .validators
                 .into_iter()
                 .zip(state.balances.into_iter())
                 .collect::<Vec<_>>(),
             body.id.as_ref(),
-        ))),
-    )
+        ))
+        .with_execution_optimistic(execution_optimistic),
+    ))
 }
 
 #[derive(Debug, Serialize)]
@@ -507,26 +532,24 @@ fn check_validator_participation(
 #[get("/validator/attestation_data")]
 pub async fn get_attestation_data(
     db: Data<BeaconDB>,
-    opertation_pool: Data<Arc<OperationPool>>,
+    beacon_chain: Data<Arc<BeaconChain>>,
     query: Query<AttestationQuery>,
 ) -> Result<impl Responder, ApiError> {
-    let store = Store::new(
-        db.get_ref().clone(),
-        opertation_pool.get_ref().clone(),
-        None,
-    );
+    let current_slot = {
+        let store = beacon_chain.store.lock().await;
 
-    if store.is_syncing().map_err(|err| {
-        ApiError::InternalError(format!("Failed to check syncing status, err: {err:?}"))
-    })? {
-        return Err(ApiError::UnderSyncing);
-    }
+        if store.is_syncing().map_err(|err| {
+            ApiError::InternalError(format!("Failed to check syncing status, err: {err:?}"))
+        })? {
+            return Err(ApiError::UnderSyncing);
+        }
 
-    let slot = query.slot;
+        store.get_current_slot().map_err(|err| {
+            ApiError::InternalError(format!("Failed to slot_index, error: {err:?}"))
+        })?
+    };
 
-    let current_slot = store
-        .get_current_slot()
-        .map_err(|err| ApiError::InternalError(format!("Failed to slot_index, error: {err:?}")))?;
+    let slot = query.slot;
 
     if slot > current_slot + 1 {
         return Err(ApiError::InvalidParameter(format!(
@@ -542,6 +565,11 @@ pub async fn get_attestation_data(
             "Failed to find highest block root".to_string(),
         ))?;
 
+    // Attestations must not vote for blocks whose payload isn't verified yet
+    if is_execution_optimistic(&db, beacon_block_root)? {
+        return Err(ApiError::UnderSyncing);
+    }
+
     let source_checkpoint = db.justified_checkpoint_provider().get().map_err(|err| {
         ApiError::InternalError(format!("Failed to get source checkpoint, error: {err:?}"))
     })?;
@@ -581,17 +609,23 @@ pub async fn post_beacon_committee_selections(
 #[get("/validator/sync_committee_contribution")]
 pub async fn get_sync_committee_contribution(
     db: Data<BeaconDB>,
-    operation_pool: Data<Arc<OperationPool>>,
     sync_committee_pool: Data<Arc<SyncCommitteePool>>,
+    beacon_chain: Data<Arc<BeaconChain>>,
     query: Query<SyncCommitteeContributionQuery>,
 ) -> Result<impl Responder, ApiError> {
-    let store = Store::new(db.get_ref().clone(), operation_pool.get_ref().clone(), None);
+    let current_slot = {
+        let store = beacon_chain.store.lock().await;
 
-    if store.is_syncing().map_err(|err| {
-        ApiError::InternalError(format!("Failed to check syncing status, err: {err:?}"))
-    })? {
-        return Err(ApiError::UnderSyncing);
-    }
+        if store.is_syncing().map_err(|err| {
+            ApiError::InternalError(format!("Failed to check syncing status, err: {err:?}"))
+        })? {
+            return Err(ApiError::UnderSyncing);
+        }
+
+        store.get_current_slot().map_err(|err| {
+            ApiError::InternalError(format!("Failed to get current slot, err: {err:?}"))
+        })?
+    };
 
     let slot = query.slot;
     let subcommittee_index = query.subcommittee_index;
@@ -604,10 +638,6 @@ pub async fn get_sync_committee_contribution(
         )));
     }
 
-    let current_slot = store.get_current_slot().map_err(|err| {
-        ApiError::InternalError(format!("Failed to get current slot, err: {err:?}"))
-    })?;
-
     // Validate slot is not too far in the future
     if slot > current_slot + 1 {
         return Err(ApiError::InvalidParameter(format!(
@@ -623,9 +653,10 @@ pub async fn get_sync_committee_contribution(
             ApiError::NotFound(format!("Beacon block root {beacon_block_root:?} not found"))
         })?;
 
-    // Check if block is fully verified (not optimistic)
-    // TODO: Add optimistic sync check when execution layer integration is complete
-    // For now, we assume all blocks in fork choice are fully verified
+    // Contributions must not be produced for blocks whose payload isn't verified yet
+    if is_execution_optimistic(&db, beacon_block_root)? {
+        return Err(ApiError::UnderSyncing);
+    }
 
     // Try to get the best (highest-participation) sync committee contribution from the pool
     // Per spec: return 404 if no contribution is available
@@ -1117,15 +1148,19 @@ fn validate_signed_contribution_and_proof(
 #[post("/validator/contribution_and_proofs")]
 pub async fn post_contribution_and_proofs(
     db: Data<BeaconDB>,
-    operation_pool: Data<Arc<OperationPool>>,
     event_sender: Data<broadcast::Sender<BeaconEvent>>,
+    beacon_chain: Data<Arc<BeaconChain>>,
     contributions: Json<Vec<SignedContributionAndProof>>,
 ) -> Result<impl Responder, ApiError> {
-    let store = Store::new(db.get_ref().clone(), operation_pool.get_ref().clone(), None);
-
-    if store.is_syncing().map_err(|err| {
-        ApiError::InternalError(format!("Failed to check syncing status: {err:?}"))
-    })? {
+    if beacon_chain
+        .store
+        .lock()
+        .await
+        .is_syncing()
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to check syncing status: {err:?}"))
+        })?
+    {
         return Err(ApiError::UnderSyncing);
     }
 
@@ -1290,28 +1325,38 @@ async fn get_local_execution_payload(
     fork_name: ForkName,
     forkchoice_state: ForkchoiceStateV1,
     payload_attribute: PayloadAttributesV3,
+    prepared_payload_id: Option<B64>,
 ) -> Result<(Payload, u64), ApiError> {
-    let result = execution_engine
-        .engine_forkchoice_updated_v3(
-            ForkchoiceStateV1 {
-                head_block_hash: forkchoice_state.head_block_hash,
-                safe_block_hash: forkchoice_state.safe_block_hash,
-                finalized_block_hash: forkchoice_state.finalized_block_hash,
-            },
-            Some(PayloadAttributesV3 {
-                timestamp: payload_attribute.timestamp,
-                prev_randao: payload_attribute.prev_randao,
-                suggested_fee_recipient: payload_attribute.suggested_fee_recipient,
-                withdrawals: payload_attribute.withdrawals.clone(),
-                parent_beacon_block_root: payload_attribute.parent_beacon_block_root,
-            }),
-        )
-        .await
-        .map_err(|err| ApiError::InternalError(format!("Failed to update forkchoice: {err}")))?;
+    // Use the payload the execution engine has been building since the beacon chain prepared
+    // this proposal, if any
+    let payload_id = match prepared_payload_id {
+        Some(payload_id) => payload_id,
+        None => {
+            let result = execution_engine
+                .engine_forkchoice_updated_v3(
+                    ForkchoiceStateV1 {
+                        head_block_hash: forkchoice_state.head_block_hash,
+                        safe_block_hash: forkchoice_state.safe_block_hash,
+                        finalized_block_hash: forkchoice_state.finalized_block_hash,
+                    },
+                    Some(PayloadAttributesV3 {
+                        timestamp: payload_attribute.timestamp,
+                        prev_randao: payload_attribute.prev_randao,
+                        suggested_fee_recipient: payload_attribute.suggested_fee_recipient,
+                        withdrawals: payload_attribute.withdrawals.clone(),
+                        parent_beacon_block_root: payload_attribute.parent_beacon_block_root,
+                    }),
+                )
+                .await
+                .map_err(|err| {
+                    ApiError::InternalError(format!("Failed to update forkchoice: {err}"))
+                })?;
 
-    let payload_id = result.payload_id.ok_or_else(|| {
-        ApiError::InternalError("No payload id returned from forkchoice update".into())
-    })?;
+            result.payload_id.ok_or_else(|| {
+                ApiError::InternalError("No payload id returned from forkchoice update".into())
+            })?
+        }
+    };
 
     let payload = execution_engine
         .engine_get_payload(&fork_name, payload_id)
@@ -1376,6 +1421,7 @@ pub async fn get_blocks_v3(
     operation_pool: Data<Arc<OperationPool>>,
     execution_engine: Option<Data<ExecutionEngine>>,
     builder_client: Option<Data<Arc<BuilderClient>>>,
+    beacon_chain: Data<Arc<BeaconChain>>,
 ) -> Result<impl Responder, ApiError> {
     let slot = path.into_inner();
     let query_params = query.into_inner();
@@ -1449,17 +1495,50 @@ pub async fn get_blocks_v3(
         parent_beacon_block_root: state.latest_block_header.tree_hash_root(),
     };
 
+    // Blocks must not be built on top of a block whose payload isn't verified yet
+    if is_execution_optimistic(&db, payload_attribute.parent_beacon_block_root)? {
+        return Err(ApiError::UnderSyncing);
+    }
+
+    beacon_chain
+        .event_sender
+        .send_event(BeaconEvent::PayloadAttributes(PayloadAttributesEvent {
+            version: fork_name.to_string(),
+            data: PayloadAttributesData {
+                proposal_slot: slot,
+                parent_block_root: payload_attribute.parent_beacon_block_root,
+                parent_block_number: state.latest_execution_payload_header.block_number,
+                parent_block_hash: state.latest_execution_payload_header.block_hash,
+                proposer_index,
+                payload_attributes: PayloadAttributes {
+                    timestamp: payload_attribute.timestamp,
+                    prev_randao: payload_attribute.prev_randao,
+                    suggested_fee_recipient: payload_attribute.suggested_fee_recipient,
+                    withdrawals: payload_attribute.withdrawals.to_vec(),
+                    parent_beacon_block_root: payload_attribute.parent_beacon_block_root,
+                },
+            },
+        }));
+
     let Some(execution_engine) = execution_engine else {
         return Err(ApiError::InternalError(
             "Execution engine not available".into(),
         ));
     };
 
+    let prepared_payload_id = beacon_chain
+        .get_prepared_payload_id(
+            slot,
+            payload_attribute.parent_beacon_block_root,
+            payload_attribute.suggested_fee_recipient,
+        )
+        .await;
     let (local_payload, local_execution_value) = get_local_execution_payload(
         &execution_engine,
         fork_name,
         forkchoice_state,
         payload_attribute,
+        prepared_payload_id,
     )
     .await?;
 
@@ -1483,7 +1562,18 @@ pub async fn get_blocks_v3(
         .try_into()
         .unwrap_or_default();
     let attestations: VariableList<Attestation, U8> = operation_pool
-        .get_all_attestations()
+        .get_attestations_for_block(&state)
+        .into_iter()
+        .filter_map(
+            |attestations| match compute_on_chain_aggregate(attestations) {
+                Ok(attestation) => Some(attestation),
+                Err(err) => {
+                    tracing::warn!("Failed to compute on-chain aggregate: {err}");
+                    None
+                }
+            },
+        )
+        .collect::<Vec<_>>()
         .try_into()
         .unwrap_or_default();
     let deposits: VariableList<Deposit, U16> = operation_pool
@@ -1673,21 +1763,25 @@ pub async fn get_blocks_v3(
 
 #[get("/validator/aggregate_attestation")]
 pub async fn get_aggregate_attestation(
-    opertation_pool: Data<Arc<OperationPool>>,
+    operation_pool: Data<Arc<OperationPool>>,
     attestation_query: Query<AttestationQuery>,
 ) -> Result<impl Responder, ApiError> {
-    let attestations = opertation_pool.get_attestations(
-        attestation_query.slot,
-        attestation_query.committee_index,
-        attestation_query.attestation_data_root,
-    );
-    if attestations.is_empty() {
-        return Err(ApiError::NotFound(String::from("No attestations found")));
-    }
-
-    let aggregated_attestation = compute_on_chain_aggregate(attestations).map_err(|err| {
-        ApiError::InternalError(format!("Failed to compute attestation aggregate {err}"))
+    let attestation_data_root = attestation_query.attestation_data_root.ok_or_else(|| {
+        ApiError::BadRequest(String::from(
+            "Missing attestation_data_root query parameter",
+        ))
+    })?;
+    let committee_index = attestation_query.committee_index.ok_or_else(|| {
+        ApiError::BadRequest(String::from("Missing committee_index query parameter"))
     })?;
 
+    let aggregated_attestation = operation_pool
+        .get_aggregate_attestation(
+            attestation_query.slot,
+            attestation_data_root,
+            committee_index,
+        )
+        .ok_or_else(|| ApiError::NotFound(String::from("No attestations found")))?;
+
     Ok(HttpResponse::Ok().json(DataVersionedResponse::new(aggregated_attestation)))
 }
@@ -0,0 +1,67 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    os::unix::fs::OpenOptionsExt,
+    path::Path,
+};
+
+use actix_web::{
+    Error,
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::AUTHORIZATION,
+    middleware::Next,
+    web::Data,
+};
+use alloy_primitives::hex;
+use ream_api_types_common::error::ApiError;
+use subtle::ConstantTimeEq;
+use tracing::info;
+
+pub const API_TOKEN_FILENAME: &str = "api-token.txt";
+
+/// The bearer token every key manager request has to present.
+#[derive(Clone)]
+pub struct ApiToken(String);
+
+impl ApiToken {
+    /// Read the token from `path`, or generate a random one and write it there if the file
+    /// doesn't exist yet. A generated token is only readable by the current user.
+    pub fn load_or_generate(path: &Path) -> anyhow::Result<Self> {
+        if path.exists() {
+            return Ok(Self(fs::read_to_string(path)?.trim().to_string()));
+        }
+
+        let token = format!("api-token-0x{}", hex::encode(rand::random::<[u8; 32]>()));
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(path)?
+            .write_all(token.as_bytes())?;
+        info!("Generated key manager API token at {}", path.display());
+        Ok(Self(token))
+    }
+}
+
+/// Reject requests without a matching `Authorization: Bearer <token>` header.
+pub async fn authorize(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let api_token = request
+        .app_data::<Data<ApiToken>>()
+        .ok_or_else(|| ApiError::InternalError("API token not available".to_string()))?;
+
+    let is_authorized = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| token.as_bytes().ct_eq(api_token.0.as_bytes()).into());
+    if !is_authorized {
+        return Err(ApiError::Unauthorized.into());
+    }
+
+    next.call(request).await
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use actix_web::{
+    HttpResponse, Responder, delete, get, post,
+    web::{Data, Json, Path},
+};
+use ream_api_types_beacon::{
+    keymanager::{FeeRecipient, SetFeeRecipientRequest},
+    responses::DataResponse,
+};
+use ream_api_types_common::error::ApiError;
+use ream_bls::PublicKey;
+use ream_validator_beacon::validator_store::ValidatorStore;
+
+use crate::handlers::validator_not_found;
+
+/// Called by `/eth/v1/validator/{pubkey}/feerecipient` to get the fee recipient of a validator.
+#[get("/validator/{pubkey}/feerecipient")]
+pub async fn get_fee_recipient(
+    validator_store: Data<Arc<ValidatorStore>>,
+    public_key: Path<PublicKey>,
+) -> Result<impl Responder, ApiError> {
+    let public_key = public_key.into_inner();
+    let ethaddress = validator_store
+        .fee_recipient(&public_key)
+        .ok_or_else(|| validator_not_found(&public_key))?;
+
+    Ok(HttpResponse::Ok().json(DataResponse::new(FeeRecipient {
+        pubkey: public_key,
+        ethaddress,
+    })))
+}
+
+/// Called by `/eth/v1/validator/{pubkey}/feerecipient` to set the fee recipient of a validator.
+#[post("/validator/{pubkey}/feerecipient")]
+pub async fn set_fee_recipient(
+    validator_store: Data<Arc<ValidatorStore>>,
+    public_key: Path<PublicKey>,
+    request: Json<SetFeeRecipientRequest>,
+) -> Result<impl Responder, ApiError> {
+    let public_key = public_key.into_inner();
+    let SetFeeRecipientRequest { ethaddress } = request.into_inner();
+
+    if !validator_store
+        .update_settings(&public_key, |settings| {
+            settings.fee_recipient = Some(ethaddress)
+        })
+        .map_err(|err| ApiError::InternalError(format!("Failed to set fee recipient: {err}")))?
+    {
+        return Err(validator_not_found(&public_key));
+    }
+
+    Ok(HttpResponse::Accepted().finish())
+}
+
+/// Called by `/eth/v1/validator/{pubkey}/feerecipient` to reset the fee recipient of a validator
+/// to the default.
+#[delete("/validator/{pubkey}/feerecipient")]
+pub async fn delete_fee_recipient(
+    validator_store: Data<Arc<ValidatorStore>>,
+    public_key: Path<PublicKey>,
+) -> Result<impl Responder, ApiError> {
+    let public_key = public_key.into_inner();
+
+    if !validator_store
+        .update_settings(&public_key, |settings| settings.fee_recipient = None)
+        .map_err(|err| ApiError::InternalError(format!("Failed to delete fee recipient: {err}")))?
+    {
+        return Err(validator_not_found(&public_key));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
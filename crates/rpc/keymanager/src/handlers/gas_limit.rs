@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use actix_web::{
+    HttpResponse, Responder, delete, get, post,
+    web::{Data, Json, Path},
+};
+use ream_api_types_beacon::{
+    keymanager::{GasLimit, SetGasLimitRequest},
+    responses::DataResponse,
+};
+use ream_api_types_common::error::ApiError;
+use ream_bls::PublicKey;
+use ream_validator_beacon::validator_store::ValidatorStore;
+
+use crate::handlers::validator_not_found;
+
+/// Called by `/eth/v1/validator/{pubkey}/gas_limit` to get the gas limit of a validator.
+#[get("/validator/{pubkey}/gas_limit")]
+pub async fn get_gas_limit(
+    validator_store: Data<Arc<ValidatorStore>>,
+    public_key: Path<PublicKey>,
+) -> Result<impl Responder, ApiError> {
+    let public_key = public_key.into_inner();
+    let gas_limit = validator_store
+        .gas_limit(&public_key)
+        .ok_or_else(|| validator_not_found(&public_key))?;
+
+    Ok(HttpResponse::Ok().json(DataResponse::new(GasLimit {
+        pubkey: public_key,
+        gas_limit,
+    })))
+}
+
+/// Called by `/eth/v1/validator/{pubkey}/gas_limit` to set the gas limit of a validator.
+#[post("/validator/{pubkey}/gas_limit")]
+pub async fn set_gas_limit(
+    validator_store: Data<Arc<ValidatorStore>>,
+    public_key: Path<PublicKey>,
+    request: Json<SetGasLimitRequest>,
+) -> Result<impl Responder, ApiError> {
+    let public_key = public_key.into_inner();
+    let SetGasLimitRequest { gas_limit } = request.into_inner();
+
+    if !validator_store
+        .update_settings(&public_key, |settings| settings.gas_limit = Some(gas_limit))
+        .map_err(|err| ApiError::InternalError(format!("Failed to set gas limit: {err}")))?
+    {
+        return Err(validator_not_found(&public_key));
+    }
+
+    Ok(HttpResponse::Accepted().finish())
+}
+
+/// Called by `/eth/v1/validator/{pubkey}/gas_limit` to reset the gas limit of a validator to the
+/// default.
+#[delete("/validator/{pubkey}/gas_limit")]
+pub async fn delete_gas_limit(
+    validator_store: Data<Arc<ValidatorStore>>,
+    public_key: Path<PublicKey>,
+) -> Result<impl Responder, ApiError> {
+    let public_key = public_key.into_inner();
+
+    if !validator_store
+        .update_settings(&public_key, |settings| settings.gas_limit = None)
+        .map_err(|err| ApiError::InternalError(format!("Failed to delete gas limit: {err}")))?
+    {
+        return Err(validator_not_found(&public_key));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use actix_web::{
+    HttpResponse, Responder, delete, get, post,
+    web::{Data, Json, Path},
+};
+use ream_api_types_beacon::{
+    keymanager::{Graffiti, SetGraffitiRequest},
+    responses::DataResponse,
+};
+use ream_api_types_common::error::ApiError;
+use ream_bls::PublicKey;
+use ream_validator_beacon::validator_store::ValidatorStore;
+
+use crate::handlers::validator_not_found;
+
+/// Graffiti is included in blocks as 32 bytes.
+const MAX_GRAFFITI_LENGTH: usize = 32;
+
+/// Called by `/eth/v1/validator/{pubkey}/graffiti` to get the graffiti of a validator.
+#[get("/validator/{pubkey}/graffiti")]
+pub async fn get_graffiti(
+    validator_store: Data<Arc<ValidatorStore>>,
+    public_key: Path<PublicKey>,
+) -> Result<impl Responder, ApiError> {
+    let public_key = public_key.into_inner();
+    let graffiti = validator_store
+        .graffiti(&public_key)
+        .ok_or_else(|| validator_not_found(&public_key))?;
+
+    Ok(HttpResponse::Ok().json(DataResponse::new(Graffiti {
+        pubkey: public_key,
+        graffiti,
+    })))
+}
+
+/// Called by `/eth/v1/validator/{pubkey}/graffiti` to set the graffiti of a validator.
+#[post("/validator/{pubkey}/graffiti")]
+pub async fn set_graffiti(
+    validator_store: Data<Arc<ValidatorStore>>,
+    public_key: Path<PublicKey>,
+    request: Json<SetGraffitiRequest>,
+) -> Result<impl Responder, ApiError> {
+    let public_key = public_key.into_inner();
+    let SetGraffitiRequest { graffiti } = request.into_inner();
+
+    if graffiti.len() > MAX_GRAFFITI_LENGTH {
+        return Err(ApiError::BadRequest(format!(
+            "Graffiti is {} bytes, at most {MAX_GRAFFITI_LENGTH} are allowed",
+            graffiti.len()
+        )));
+    }
+
+    if !validator_store
+        .update_settings(&public_key, |settings| settings.graffiti = Some(graffiti))
+        .map_err(|err| ApiError::InternalError(format!("Failed to set graffiti: {err}")))?
+    {
+        return Err(validator_not_found(&public_key));
+    }
+
+    Ok(HttpResponse::Accepted().finish())
+}
+
+/// Called by `/eth/v1/validator/{pubkey}/graffiti` to remove the graffiti of a validator.
+#[delete("/validator/{pubkey}/graffiti")]
+pub async fn delete_graffiti(
+    validator_store: Data<Arc<ValidatorStore>>,
+    public_key: Path<PublicKey>,
+) -> Result<impl Responder, ApiError> {
+    let public_key = public_key.into_inner();
+
+    if !validator_store
+        .update_settings(&public_key, |settings| settings.graffiti = None)
+        .map_err(|err| ApiError::InternalError(format!("Failed to delete graffiti: {err}")))?
+    {
+        return Err(validator_not_found(&public_key));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
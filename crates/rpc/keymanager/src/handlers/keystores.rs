@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use actix_web::{
+    HttpResponse, Responder, delete, get, post,
+    web::{self, Data, Json},
+};
+use alloy_primitives::B256;
+use ream_api_types_beacon::{
+    keymanager::{
+        DeleteKeystoreResult, DeleteKeystoreStatus, DeleteKeystoresRequest,
+        DeleteKeystoresResponse, ImportKeystoreResult, ImportKeystoreStatus,
+        ImportKeystoresRequest, KeystoreInfo,
+    },
+    responses::DataResponse,
+};
+use ream_api_types_common::error::ApiError;
+use ream_validator_beacon::{
+    slashing_protection::{SlashingProtectionDB, interchange::Interchange},
+    validator_store::{ImportStatus, ValidatorStore},
+};
+
+/// Called by `/eth/v1/keystores` to list the validators the validator client signs for.
+#[get("/keystores")]
+pub async fn list_keystores(
+    validator_store: Data<Arc<ValidatorStore>>,
+) -> Result<impl Responder, ApiError> {
+    let keystores = validator_store
+        .list()
+        .into_iter()
+        .map(|(public_key, derivation_path)| KeystoreInfo {
+            validating_pubkey: public_key,
+            derivation_path: (!derivation_path.is_empty()).then_some(derivation_path),
+            readonly: false,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(DataResponse::new(keystores)))
+}
+
+/// Called by `/eth/v1/keystores` to import keystores, together with their slashing protection
+/// history.
+#[post("/keystores")]
+pub async fn import_keystores(
+    validator_store: Data<Arc<ValidatorStore>>,
+    slashing_protection: Data<SlashingProtectionDB>,
+    genesis_validators_root: Data<B256>,
+    request: Json<ImportKeystoresRequest>,
+) -> Result<impl Responder, ApiError> {
+    let ImportKeystoresRequest {
+        keystores,
+        passwords,
+        slashing_protection: interchange,
+    } = request.into_inner();
+
+    if keystores.len() != passwords.len() {
+        return Err(ApiError::BadRequest(format!(
+            "Got {} keystores but {} passwords",
+            keystores.len(),
+            passwords.len()
+        )));
+    }
+
+    // The history has to be imported before any key can sign.
+    if let Some(interchange) = interchange {
+        let interchange = serde_json::from_str::<Interchange>(&interchange).map_err(|err| {
+            ApiError::BadRequest(format!("Invalid slashing protection interchange: {err}"))
+        })?;
+        slashing_protection
+            .import_interchange(&interchange, *genesis_validators_root)
+            .map_err(|err| {
+                ApiError::BadRequest(format!("Failed to import slashing protection: {err}"))
+            })?;
+    }
+
+    // Decrypting a keystore runs its key derivation function, which mustn't block the worker
+    let validator_store = validator_store.get_ref().clone();
+    let results = web::block(move || {
+        keystores
+            .iter()
+            .zip(passwords.iter())
+            .map(
+                |(keystore, password)| match validator_store.import(keystore, password) {
+                    Ok(ImportStatus::Imported) => ImportKeystoreResult {
+                        status: ImportKeystoreStatus::Imported,
+                        message: None,
+                    },
+                    Ok(ImportStatus::Duplicate) => ImportKeystoreResult {
+                        status: ImportKeystoreStatus::Duplicate,
+                        message: None,
+                    },
+                    Err(err) => ImportKeystoreResult {
+                        status: ImportKeystoreStatus::Error,
+                        message: Some(err.to_string()),
+                    },
+                },
+            )
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|err| ApiError::InternalError(format!("Failed to import keystores: {err}")))?;
+
+    Ok(HttpResponse::Ok().json(DataResponse::new(results)))
+}
+
+/// Called by `/eth/v1/keystores` to delete keystores.
+///
+/// The slashing protection history of every requested key that has one is exported, so the keys
+/// can safely be moved to another validator client.
+#[delete("/keystores")]
+pub async fn delete_keystores(
+    validator_store: Data<Arc<ValidatorStore>>,
+    slashing_protection: Data<SlashingProtectionDB>,
+    genesis_validators_root: Data<B256>,
+    request: Json<DeleteKeystoresRequest>,
+) -> Result<impl Responder, ApiError> {
+    let DeleteKeystoresRequest { pubkeys } = request.into_inner();
+
+    let results = pubkeys
+        .iter()
+        .map(|public_key| match validator_store.delete(public_key) {
+            Ok(true) => DeleteKeystoreResult {
+                status: DeleteKeystoreStatus::Deleted,
+                message: None,
+            },
+            Ok(false) => match slashing_protection.has_records(public_key) {
+                Ok(true) => DeleteKeystoreResult {
+                    status: DeleteKeystoreStatus::NotActive,
+                    message: None,
+                },
+                Ok(false) => DeleteKeystoreResult {
+                    status: DeleteKeystoreStatus::NotFound,
+                    message: None,
+                },
+                Err(err) => DeleteKeystoreResult {
+                    status: DeleteKeystoreStatus::Error,
+                    message: Some(err.to_string()),
+                },
+            },
+            Err(err) => DeleteKeystoreResult {
+                status: DeleteKeystoreStatus::Error,
+                message: Some(err.to_string()),
+            },
+        })
+        .collect::<Vec<_>>();
+
+    let interchange = slashing_protection
+        .export_interchange_for(*genesis_validators_root, Some(&pubkeys))
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to export slashing protection: {err}"))
+        })?;
+    let slashing_protection = serde_json::to_string(&interchange).map_err(|err| {
+        ApiError::InternalError(format!("Failed to serialize slashing protection: {err}"))
+    })?;
+
+    Ok(HttpResponse::Ok().json(DeleteKeystoresResponse {
+        data: results,
+        slashing_protection,
+    }))
+}
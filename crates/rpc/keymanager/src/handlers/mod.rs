@@ -0,0 +1,12 @@
+pub mod fee_recipient;
+pub mod gas_limit;
+pub mod graffiti;
+pub mod keystores;
+
+use alloy_primitives::hex;
+use ream_api_types_common::error::ApiError;
+use ream_bls::PublicKey;
+
+fn validator_not_found(public_key: &PublicKey) -> ApiError {
+    ApiError::ValidatorNotFound(format!("0x{}", hex::encode(public_key.to_bytes())))
+}
@@ -0,0 +1,4 @@
+pub mod auth;
+pub mod handlers;
+pub mod routes;
+pub mod server;
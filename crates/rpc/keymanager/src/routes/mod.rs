@@ -0,0 +1,38 @@
+use actix_web::{
+    middleware::from_fn,
+    web::{ServiceConfig, scope},
+};
+
+use crate::{
+    auth::authorize,
+    handlers::{
+        fee_recipient::{delete_fee_recipient, get_fee_recipient, set_fee_recipient},
+        gas_limit::{delete_gas_limit, get_gas_limit, set_gas_limit},
+        graffiti::{delete_graffiti, get_graffiti, set_graffiti},
+        keystores::{delete_keystores, import_keystores, list_keystores},
+    },
+};
+
+/// Creates and returns all Keymanager API routes.
+pub fn register_keymanager_routes(cfg: &mut ServiceConfig) {
+    cfg.service(list_keystores)
+        .service(import_keystores)
+        .service(delete_keystores)
+        .service(get_fee_recipient)
+        .service(set_fee_recipient)
+        .service(delete_fee_recipient)
+        .service(get_gas_limit)
+        .service(set_gas_limit)
+        .service(delete_gas_limit)
+        .service(get_graffiti)
+        .service(set_graffiti)
+        .service(delete_graffiti);
+}
+
+pub fn register_routers(config: &mut ServiceConfig) {
+    config.service(
+        scope("/eth/v1")
+            .wrap(from_fn(authorize))
+            .configure(register_keymanager_routes),
+    );
+}
@@ -0,0 +1,28 @@
+use std::{io::Result, sync::Arc};
+
+use alloy_primitives::B256;
+use ream_rpc_common::{config::RpcServerConfig, server::RpcServerBuilder};
+use ream_validator_beacon::{
+    slashing_protection::SlashingProtectionDB, validator_store::ValidatorStore,
+};
+
+use crate::{auth::ApiToken, routes::register_routers};
+
+/// Start the Keymanager API server.
+pub async fn start(
+    server_config: RpcServerConfig,
+    api_token: ApiToken,
+    validator_store: Arc<ValidatorStore>,
+    slashing_protection: SlashingProtectionDB,
+    genesis_validators_root: B256,
+) -> Result<()> {
+    RpcServerBuilder::new(server_config.http_socket_address)
+        .allow_origin(server_config.http_allow_origin)
+        .with_data(api_token)
+        .with_data(validator_store)
+        .with_data(slashing_protection)
+        .with_data(genesis_validators_root)
+        .configure(register_routers)
+        .start()
+        .await
+}
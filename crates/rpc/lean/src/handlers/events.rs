@@ -0,0 +1,65 @@
+use std::{str::FromStr, time::Duration};
+
+use actix_web::{Responder, get, web};
+use actix_web_lab::sse;
+use futures::stream;
+use ream_api_types_common::error::ApiError;
+use ream_events_lean::{LeanEvent, LeanEventTopic};
+use serde::Deserialize;
+use tokio::sync::broadcast;
+
+#[derive(Deserialize)]
+pub struct EventQuery {
+    /// Comma separated list of topics, e.g. `?topics=head,finalized`.
+    topics: String,
+}
+
+// GET /lean/v0/events
+#[get("/events")]
+pub async fn get_events(
+    query: web::Query<EventQuery>,
+    event_sender: web::Data<broadcast::Sender<LeanEvent>>,
+) -> Result<impl Responder, ApiError> {
+    let topics = query
+        .topics
+        .split(',')
+        .map(|topic| LeanEventTopic::from_str(topic.trim()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+
+    let receiver = event_sender.subscribe();
+    let stream = stream::unfold(receiver, move |mut receiver| {
+        let topics = topics.clone();
+        async move {
+            loop {
+                let event = match receiver.recv().await {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Event stream lagged, skipped {skipped} events");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                };
+
+                let topic = event.topic();
+                if !topics.contains(&topic) {
+                    continue;
+                }
+
+                match event.serialize_data() {
+                    Ok(json_data) => {
+                        let sse_event =
+                            sse::Event::Data(sse::Data::new(json_data).event(topic.to_string()));
+                        return Some((Ok::<_, actix_web::Error>(sse_event), receiver));
+                    }
+                    Err(err) => {
+                        tracing::warn!("Failed to serialize {topic} event: {err}");
+                        continue;
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(sse::Sse::from_stream(stream).with_keep_alive(Duration::from_secs(10)))
+}
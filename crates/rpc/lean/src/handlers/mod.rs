@@ -2,6 +2,7 @@ pub mod aggregator;
 pub mod block;
 pub mod block_header;
 pub mod checkpoint;
+pub mod events;
 pub mod fork_choice;
 pub mod head;
 pub mod health;
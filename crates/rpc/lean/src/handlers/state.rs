@@ -1,7 +1,7 @@
 use actix_web::{
     HttpRequest, HttpResponse, Responder, get,
     http::header,
-    web::{Data, Path},
+    web::{self, Data, Path},
 };
 use ream_api_types_common::{
     content_type::{ContentType, JSON_CONTENT_TYPE, SSZ_CONTENT_TYPE},
@@ -70,10 +70,13 @@ pub async fn get_state(
         }
     };
 
-    let db = lean_chain.store.lock().await;
-    let state = db
-        .state_provider()
-        .get(block_root?)
+    // A pruned state is regenerated by replaying blocks, which mustn't hold up fork choice
+    let block_root = block_root?;
+    let db = lean_chain.store.lock().await.clone();
+    drop(lean_chain);
+    let state = web::block(move || db.get_state(block_root))
+        .await
+        .map_err(|err| ApiError::InternalError(format!("Failed to read state: {err}")))?
         .map_err(|err| ApiError::InternalError(format!("DB error: {err}")))?
         .ok_or_else(|| ApiError::NotFound("Lean state not found".to_string()))?;
 
@@ -5,6 +5,7 @@ use crate::handlers::{
     block::{get_block, get_finalized_signed_block},
     block_header::get_block_header,
     checkpoint::get_justified_checkpoint,
+    events::get_events,
     fork_choice::get_fork_choice_tree,
     head::get_head,
     health::get_health,
@@ -25,6 +26,7 @@ pub fn register_lean_routes(cfg: &mut ServiceConfig) {
         .service(get_justified_checkpoint)
         .service(get_state)
         .service(get_health)
+        .service(get_events)
         .service(handle_status)
         .service(handle_toggle)
         .service(handle_delete);
@@ -1,9 +1,11 @@
 use std::{io::Result, sync::Arc};
 
 use actix_web::web::Data;
+use ream_events_lean::LeanEvent;
 use ream_fork_choice_lean::store::{LeanStoreReader, LeanStoreWriter};
 use ream_network_state_lean::{AggregatorState, NetworkState};
 use ream_rpc_common::{config::RpcServerConfig, server::RpcServerBuilder};
+use tokio::sync::broadcast;
 
 use crate::routes::{register_routers, register_test_driver_routers};
 
@@ -13,12 +15,14 @@ pub async fn start(
     lean_chain: LeanStoreReader,
     network_state: Arc<NetworkState>,
     aggregator_state: Arc<AggregatorState>,
+    event_sender: broadcast::Sender<LeanEvent>,
 ) -> Result<()> {
     RpcServerBuilder::new(server_config.http_socket_address)
         .allow_origin(server_config.http_allow_origin)
         .with_data(lean_chain)
         .with_data(network_state)
         .with_data(aggregator_state)
+        .with_data(event_sender)
         .configure(register_routers)
         .start()
         .await
@@ -31,6 +35,7 @@ pub async fn start_test_driver(
     lean_chain_writer: LeanStoreWriter,
     network_state: Arc<NetworkState>,
     aggregator_state: Arc<AggregatorState>,
+    event_sender: broadcast::Sender<LeanEvent>,
 ) -> Result<()> {
     RpcServerBuilder::new(server_config.http_socket_address)
         .allow_origin(server_config.http_allow_origin)
@@ -38,6 +43,7 @@ pub async fn start_test_driver(
         .with_app_data(Data::new(lean_chain_writer))
         .with_data(network_state)
         .with_data(aggregator_state)
+        .with_data(event_sender)
         .configure(register_test_driver_routers)
         .start()
         .await
@@ -1,19 +1,29 @@
-use std::{collections::HashSet, path::PathBuf, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::PathBuf,
+    sync::Arc,
+};
 
-use anyhow::anyhow;
-use ream_consensus_beacon::electra::beacon_state::BeaconState;
+use alloy_primitives::B256;
+use anyhow::{anyhow, ensure};
+use ream_consensus_beacon::{
+    electra::beacon_state::BeaconState, fork_choice::execution_status::ExecutionStatus,
+};
 use ream_consensus_misc::constants::beacon::SLOTS_PER_EPOCH;
-use redb::{Database, ReadableDatabase};
+use redb::{Database, ReadableDatabase, ReadableTable};
+use tree_hash::TreeHash;
 
 use crate::{
     cache::BeaconCacheDB,
+    errors::StoreError,
     tables::{
         beacon::{
             beacon_block::BeaconBlockTable, beacon_state::BeaconStateTable,
             blobs_and_proofs::BlobsAndProofsTable, block_timeliness::BlockTimelinessTable,
-            checkpoint_states::CheckpointStatesTable, column_sidecars::ColumnSidecarsTable,
-            equivocating_indices::EquivocatingIndicesField,
-            finalized_checkpoint::FinalizedCheckpointField, genesis_time::GenesisTimeField,
+            checkpoint_states::CheckpointStatesTable, cold_state::BeaconColdStateTable,
+            column_sidecars::ColumnSidecarsTable, equivocating_indices::EquivocatingIndicesField,
+            execution_status::ExecutionStatusTable, finalized_checkpoint::FinalizedCheckpointField,
+            genesis_time::GenesisTimeField, justifications::JustificationsTable,
             justified_checkpoint::JustifiedCheckpointField, latest_messages::LatestMessagesTable,
             parent_root_index::ParentRootIndexMultimapTable,
             proposer_boost_root::ProposerBoostRootField, slot_index::BeaconSlotIndexTable,
@@ -22,10 +32,17 @@ use crate::{
             unrealized_justifications::UnrealizedJustificationsTable,
             unrealized_justified_checkpoint::UnrealizedJustifiedCheckpointField,
         },
-        table::REDBTable,
+        field::REDBField,
+        multimap_table::MultimapTable,
+        table::{CustomTable, REDBTable},
     },
+    write_batch::WriteBatch,
 };
 
+/// Number of slots between two snapshots of finalized states. A finalized state in between is
+/// regenerated by replaying the blocks since the previous snapshot.
+pub const SLOTS_PER_RESTORE_POINT: u64 = 2048;
+
 #[derive(Clone, Debug)]
 pub struct BeaconDB {
     pub db: Arc<Database>,
@@ -41,6 +58,11 @@ impl BeaconDB {
         self
     }
 
+    /// Start a batch of updates to be committed atomically.
+    pub fn write_batch(&self) -> Result<WriteBatch, StoreError> {
+        WriteBatch::new(&self.db)
+    }
+
     pub fn block_provider(&self) -> BeaconBlockTable {
         BeaconBlockTable {
             db: self.db.clone(),
@@ -54,6 +76,12 @@ impl BeaconDB {
         }
     }
 
+    pub fn cold_state_provider(&self) -> BeaconColdStateTable {
+        BeaconColdStateTable {
+            db: self.db.clone(),
+        }
+    }
+
     pub fn blobs_and_proofs_provider(&self) -> BlobsAndProofsTable {
         BlobsAndProofsTable {
             data_dir: self.data_dir.clone(),
@@ -72,6 +100,12 @@ impl BeaconDB {
         }
     }
 
+    pub fn execution_status_provider(&self) -> ExecutionStatusTable {
+        ExecutionStatusTable {
+            db: self.db.clone(),
+        }
+    }
+
     pub fn checkpoint_states_provider(&self) -> CheckpointStatesTable {
         CheckpointStatesTable {
             db: self.db.clone(),
@@ -84,6 +118,12 @@ impl BeaconDB {
         }
     }
 
+    pub fn justifications_provider(&self) -> JustificationsTable {
+        JustificationsTable {
+            db: self.db.clone(),
+        }
+    }
+
     pub fn unrealized_justifications_provider(&self) -> UnrealizedJustificationsTable {
         UnrealizedJustificationsTable {
             db: self.db.clone(),
@@ -163,6 +203,53 @@ impl BeaconDB {
         }
     }
 
+    /// Check that the tip of the slot index points at a stored block, and the checkpoints at
+    /// stored blocks and states. Block import commits all of its updates atomically, so this only
+    /// fails for a database written by an older version or damaged on disk.
+    pub fn check_consistency(&self) -> anyhow::Result<()> {
+        let Some(highest_root) = self.slot_index_provider().get_highest_root()? else {
+            return Ok(());
+        };
+
+        let block_provider = self.block_provider();
+        ensure!(
+            block_provider.get(highest_root)?.is_some(),
+            "Block of the highest slot index is missing: {highest_root}"
+        );
+
+        let state_provider = self.state_provider();
+        for (name, block_root) in [
+            (
+                "justified checkpoint",
+                self.justified_checkpoint_provider().get()?.root,
+            ),
+            (
+                "finalized checkpoint",
+                self.finalized_checkpoint_provider().get()?.root,
+            ),
+        ] {
+            ensure!(
+                block_provider.get(block_root)?.is_some(),
+                "Block of the {name} is missing: {block_root}"
+            );
+            ensure!(
+                state_provider.get(block_root)?.is_some(),
+                "State of the {name} is missing: {block_root}"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The execution status of `block_root`. Blocks without a recorded status were imported
+    /// before execution statuses were tracked, when only verified payloads were accepted.
+    pub fn get_execution_status(&self, block_root: B256) -> anyhow::Result<ExecutionStatus> {
+        Ok(self
+            .execution_status_provider()
+            .get(block_root)?
+            .unwrap_or(ExecutionStatus::Valid))
+    }
+
     pub fn get_latest_state(&self) -> anyhow::Result<BeaconState> {
         let highest_root = self
             .slot_index_provider()
@@ -177,6 +264,352 @@ impl BeaconDB {
         Ok(state)
     }
 
+    /// The post-state of `block_root`.
+    ///
+    /// Only the states since the latest finalized block are stored in full, the states of older
+    /// blocks of the canonical chain are regenerated from the closest snapshot before them.
+    pub async fn get_state(&self, block_root: B256) -> anyhow::Result<Option<BeaconState>> {
+        if let Some(state) = self.state_provider().get(block_root)? {
+            return Ok(Some(state));
+        }
+
+        let Some(block) = self.block_provider().get(block_root)? else {
+            return Ok(None);
+        };
+        let slot = block.message.slot;
+        if slot > self.get_finalized_slot()?
+            || self.slot_index_provider().get(slot)? != Some(block_root)
+        {
+            return Ok(None);
+        }
+
+        self.regenerate_state(slot).await
+    }
+
+    /// The state at `slot` of the canonical chain. If `slot` is empty, this is the state of the
+    /// latest block before it, advanced to `slot`.
+    pub async fn get_state_at_slot(&self, slot: u64) -> anyhow::Result<Option<BeaconState>> {
+        if let Some(block_root) = self.slot_index_provider().get(slot)? {
+            return self.get_state(block_root).await;
+        }
+
+        // Only empty slots up to the finalized block are known to be part of the canonical chain
+        if slot > self.get_finalized_slot()? {
+            return Ok(None);
+        }
+
+        self.regenerate_state(slot).await
+    }
+
+    fn get_finalized_slot(&self) -> anyhow::Result<u64> {
+        let finalized_root = self.finalized_checkpoint_provider().get()?.root;
+        Ok(self
+            .block_provider()
+            .get(finalized_root)?
+            .ok_or_else(|| anyhow!("Finalized block not found: {finalized_root}"))?
+            .message
+            .slot)
+    }
+
+    /// Regenerate the finalized state at `slot` by replaying the canonical blocks since the
+    /// latest snapshot at or before `slot`.
+    async fn regenerate_state(&self, slot: u64) -> anyhow::Result<Option<BeaconState>> {
+        let Some(mut state) = self.cold_state_provider().get_at_or_before(slot)? else {
+            return Ok(None);
+        };
+
+        let block_provider = self.block_provider();
+        for (_, block_root) in self
+            .slot_index_provider()
+            .get_range(state.slot + 1..=slot)?
+        {
+            let block = block_provider
+                .get(block_root)?
+                .ok_or_else(|| anyhow!("Block not found: {block_root}"))?;
+            state.replay_block(&block).await?;
+        }
+        if state.slot < slot {
+            state.process_slots(slot)?;
+        }
+
+        Ok(Some(state))
+    }
+
+    /// Move the states finalized by `finalized_root` out of the hot database.
+    ///
+    /// The canonical chain up to `finalized_root` is written to the slot index and its states
+    /// are kept as a snapshot every [SLOTS_PER_RESTORE_POINT] slots. Every other state which
+    /// isn't `finalized_root` or one of its descendants is deleted, including the states of
+    /// abandoned forks. Returns the number of deleted states.
+    pub fn migrate_finalized_states(&self, finalized_root: B256) -> anyhow::Result<usize> {
+        let block_provider = self.block_provider();
+        let state_provider = self.state_provider();
+        let slot_index_provider = self.slot_index_provider();
+        let cold_state_provider = self.cold_state_provider();
+        let hot_roots = state_provider
+            .iter_keys()?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        // Walk back the canonical chain until the blocks migrated by the previous finalization
+        let mut canonical_chain = vec![];
+        let mut block_root = finalized_root;
+        while hot_roots.contains(&block_root) {
+            let block = block_provider
+                .get(block_root)?
+                .ok_or_else(|| anyhow!("Block not found: {block_root}"))?;
+            canonical_chain.push((block.message.slot, block_root));
+            block_root = block.message.parent_root;
+        }
+        canonical_chain.reverse();
+        let (Some(&(oldest_slot, _)), Some(&(finalized_slot, _))) =
+            (canonical_chain.first(), canonical_chain.last())
+        else {
+            return Ok(0);
+        };
+
+        // Slots of the finalized range may still point to blocks of abandoned forks
+        let mut batch = self.write_batch()?;
+        let canonical_roots = canonical_chain.iter().copied().collect::<HashMap<_, _>>();
+        for (slot, block_root) in slot_index_provider.get_range(oldest_slot..=finalized_slot)? {
+            if canonical_roots.get(&slot) != Some(&block_root) {
+                slot_index_provider.remove_batch(&mut batch, slot)?;
+            }
+        }
+        for &(slot, block_root) in &canonical_chain {
+            slot_index_provider.insert_batch(&mut batch, slot, block_root)?;
+        }
+
+        let mut latest_snapshot_slot = cold_state_provider.get_highest_slot()?;
+        for &(slot, block_root) in &canonical_chain {
+            if latest_snapshot_slot.is_none_or(|snapshot_slot| {
+                slot / SLOTS_PER_RESTORE_POINT > snapshot_slot / SLOTS_PER_RESTORE_POINT
+            }) {
+                let state = state_provider
+                    .get(block_root)?
+                    .ok_or_else(|| anyhow!("State not found: {block_root}"))?;
+                cold_state_provider.insert_batch(&mut batch, slot, state)?;
+                latest_snapshot_slot = Some(slot);
+            }
+        }
+
+        // Keep the finalized state and the states of its descendants, which are still hot
+        let parent_root_index_provider = self.parent_root_index_multimap_provider();
+        let mut descendants = HashSet::from([finalized_root]);
+        let mut queue = VecDeque::from([finalized_root]);
+        while let Some(block_root) = queue.pop_front() {
+            for child_root in parent_root_index_provider
+                .get(block_root)?
+                .unwrap_or_default()
+            {
+                if descendants.insert(child_root) {
+                    queue.push_back(child_root);
+                }
+            }
+        }
+
+        let mut deleted_count = 0;
+        for block_root in hot_roots.difference(&descendants) {
+            state_provider.remove_batch(&mut batch, *block_root)?;
+            deleted_count += 1;
+        }
+        batch.commit()?;
+
+        Ok(deleted_count)
+    }
+
+    /// Delete the blocks which are neither ancestors nor descendants of the finalized block,
+    /// together with their states. These are the blocks of forks abandoned by finalization.
+    /// Returns the number of deleted blocks.
+    pub fn prune_non_canonical_blocks(&self) -> anyhow::Result<usize> {
+        let finalized_root = self.finalized_checkpoint_provider().get()?.root;
+        let block_provider = self.block_provider();
+
+        let mut canonical_roots = HashSet::new();
+        let mut block_root = finalized_root;
+        while let Some(block) = block_provider.get(block_root)? {
+            canonical_roots.insert(block_root);
+            block_root = block.message.parent_root;
+        }
+
+        let parent_root_index_provider = self.parent_root_index_multimap_provider();
+        let mut queue = VecDeque::from([finalized_root]);
+        while let Some(block_root) = queue.pop_front() {
+            for child_root in parent_root_index_provider
+                .get(block_root)?
+                .unwrap_or_default()
+            {
+                if canonical_roots.insert(child_root) {
+                    queue.push_back(child_root);
+                }
+            }
+        }
+
+        let state_provider = self.state_provider();
+        let execution_status_provider = self.execution_status_provider();
+        let block_timeliness_provider = self.block_timeliness_provider();
+        let justifications_provider = self.justifications_provider();
+        let mut batch = self.write_batch()?;
+        let mut deleted_count = 0;
+        for block_root in block_provider.iter_keys()? {
+            if canonical_roots.contains(&block_root) {
+                continue;
+            }
+            block_provider.remove_batch(&mut batch, block_root)?;
+            state_provider.remove_batch(&mut batch, block_root)?;
+            execution_status_provider.remove_batch(&mut batch, block_root)?;
+            block_timeliness_provider.remove_batch(&mut batch, block_root)?;
+            justifications_provider.remove_batch(&mut batch, block_root)?;
+            deleted_count += 1;
+        }
+        batch.commit()?;
+
+        Ok(deleted_count)
+    }
+
+    /// Delete the snapshots of finalized states before `slot`, after which states before `slot`
+    /// can no longer be regenerated. The states since the finalized block are always kept.
+    /// Returns the number of deleted states.
+    pub fn prune_states_before(&self, slot: u64) -> anyhow::Result<usize> {
+        let cold_state_provider = self.cold_state_provider();
+        let mut batch = self.write_batch()?;
+        let mut deleted_count = 0;
+        for snapshot_slot in cold_state_provider.iter_slots()? {
+            if snapshot_slot < slot {
+                cold_state_provider.remove_batch(&mut batch, snapshot_slot)?;
+                deleted_count += 1;
+            }
+        }
+        batch.commit()?;
+
+        Ok(deleted_count)
+    }
+
+    /// Check the links between the stored blocks, states, index tables and blob and column files.
+    /// Returns a description of every problem found.
+    pub fn verify(&self) -> anyhow::Result<Vec<String>> {
+        let block_provider = self.block_provider();
+        let slot_index_provider = self.slot_index_provider();
+        let mut problems = vec![];
+        if let Err(err) = self.check_consistency() {
+            problems.push(err.to_string());
+        }
+
+        let oldest_slot = slot_index_provider.get_oldest_slot()?.unwrap_or_default();
+        let mut block_state_roots = HashMap::new();
+        for block_root in block_provider.iter_keys()? {
+            let Some(block) = block_provider.get(block_root)? else {
+                continue;
+            };
+            if block.message.tree_hash_root() != block_root {
+                problems.push(format!("Block {block_root} is stored under the wrong root"));
+            }
+            // The anchor block of a checkpoint synced database has no parent
+            if block.message.slot > oldest_slot
+                && block_provider.get(block.message.parent_root)?.is_none()
+            {
+                problems.push(format!(
+                    "Parent {} of block {block_root} is missing",
+                    block.message.parent_root
+                ));
+            }
+            block_state_roots.insert(block_root, (block.message.slot, block.message.state_root));
+        }
+
+        for (slot, block_root) in slot_index_provider.get_range(..)? {
+            match block_state_roots.get(&block_root) {
+                Some((block_slot, _)) if *block_slot == slot => {}
+                Some((block_slot, _)) => problems.push(format!(
+                    "Slot index {slot} points at block {block_root} of slot {block_slot}"
+                )),
+                None => problems.push(format!(
+                    "Slot index {slot} points at missing block {block_root}"
+                )),
+            }
+        }
+
+        let read_txn = self.db.begin_read()?;
+        let state_root_index = read_txn.open_table(BeaconStateRootIndexTable::TABLE_DEFINITION)?;
+        for entry in state_root_index.iter()? {
+            let (state_root, block_root) = entry?;
+            let (state_root, block_root) = (state_root.value(), block_root.value());
+            if block_state_roots
+                .get(&block_root)
+                .is_none_or(|(_, block_state_root)| *block_state_root != state_root)
+            {
+                problems.push(format!(
+                    "State root index {state_root} points at block {block_root}, which has another \
+                     state root"
+                ));
+            }
+        }
+        drop(state_root_index);
+        drop(read_txn);
+
+        let state_provider = self.state_provider();
+        for block_root in state_provider.iter_keys()? {
+            let Some((_, state_root)) = block_state_roots.get(&block_root) else {
+                problems.push(format!("State of missing block {block_root} is stored"));
+                continue;
+            };
+            if let Some(state) = state_provider.get(block_root)?
+                && state.tree_hash_root() != *state_root
+            {
+                problems.push(format!(
+                    "State of block {block_root} doesn't match the block's state root"
+                ));
+            }
+        }
+
+        let cold_state_provider = self.cold_state_provider();
+        for slot in cold_state_provider.iter_slots()? {
+            let Some(block_root) = slot_index_provider.get(slot)? else {
+                continue;
+            };
+            if let (Some(state), Some((_, state_root))) = (
+                cold_state_provider.get(slot)?,
+                block_state_roots.get(&block_root),
+            ) && state.tree_hash_root() != *state_root
+            {
+                problems.push(format!(
+                    "Snapshot at slot {slot} doesn't match the state root of block {block_root}"
+                ));
+            }
+        }
+
+        let blobs_and_proofs_provider = self.blobs_and_proofs_provider();
+        for blob_identifier in blobs_and_proofs_provider.iter_keys()? {
+            if !block_state_roots.contains_key(&blob_identifier.block_root) {
+                problems.push(format!(
+                    "Blob {} of missing block {} is stored",
+                    blob_identifier.index, blob_identifier.block_root
+                ));
+            } else if let Err(err) = blobs_and_proofs_provider.get(blob_identifier) {
+                problems.push(format!(
+                    "Blob {} of block {} can't be read: {err}",
+                    blob_identifier.index, blob_identifier.block_root
+                ));
+            }
+        }
+
+        let column_sidecars_provider = self.column_sidecars_provider();
+        for column_identifier in column_sidecars_provider.iter_keys()? {
+            if !block_state_roots.contains_key(&column_identifier.block_root) {
+                problems.push(format!(
+                    "Column {} of missing block {} is stored",
+                    column_identifier.index, column_identifier.block_root
+                ));
+            } else if let Err(err) = column_sidecars_provider.get(column_identifier) {
+                problems.push(format!(
+                    "Column {} of block {} can't be read: {err}",
+                    column_identifier.index, column_identifier.block_root
+                ));
+            }
+        }
+
+        Ok(problems)
+    }
+
     /// Prune blobs older than the minimum retention period
     pub fn prune_old_blobs(
         &self,
@@ -211,3 +644,156 @@ impl BeaconDB {
         Ok(pruned_count)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use anyhow::anyhow;
+    use ream_consensus_beacon::electra::{
+        beacon_block::SignedBeaconBlock, beacon_state::BeaconState,
+    };
+    use ream_consensus_misc::checkpoint::Checkpoint;
+    use ream_network_spec::networks::initialize_test_network_spec;
+    use snap::raw::Decoder;
+    use ssz::Decode;
+    use tempdir::TempDir;
+    use tree_hash::TreeHash;
+
+    use super::BeaconDB;
+    use crate::{
+        db::ReamDB,
+        tables::{field::REDBField, table::REDBTable},
+    };
+
+    const PATH_TO_TEST_DATA_FOLDER: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../testing/gossip-validation/tests/assets/sepolia"
+    );
+
+    fn read_ssz_snappy_file<T: Decode>(path: &str) -> anyhow::Result<T> {
+        let ssz_snappy = std::fs::read(format!("{PATH_TO_TEST_DATA_FOLDER}/{path}"))?;
+        let ssz = Decoder::new().decompress_vec(&ssz_snappy)?;
+        T::from_ssz_bytes(&ssz).map_err(|err| anyhow!("Failed to decode SSZ: {err:?}"))
+    }
+
+    /// Three consecutive sepolia blocks with their states, and a competing block at the slot of
+    /// the second one.
+    struct Chain {
+        grandparent_root: B256,
+        parent_root: B256,
+        parent_state: BeaconState,
+        fork_root: B256,
+        child_root: B256,
+    }
+
+    fn setup_chain(db: &BeaconDB) -> anyhow::Result<Chain> {
+        let grandparent_block =
+            read_ssz_snappy_file::<SignedBeaconBlock>("blocks/grandparent_9552074.ssz_snappy")?;
+        let grandparent_state =
+            read_ssz_snappy_file::<BeaconState>("states/grandparent_state_9552074.ssz_snappy")?;
+        let parent_block =
+            read_ssz_snappy_file::<SignedBeaconBlock>("blocks/parent_9552075.ssz_snappy")?;
+        let parent_state =
+            read_ssz_snappy_file::<BeaconState>("states/parent_state_9552075.ssz_snappy")?;
+        let child_block =
+            read_ssz_snappy_file::<SignedBeaconBlock>("blocks/child_9552076.ssz_snappy")?;
+
+        let mut fork_block = parent_block.clone();
+        fork_block.message.body.graffiti = B256::repeat_byte(0xff);
+
+        let grandparent_root = grandparent_block.message.block_root();
+        let parent_root = parent_block.message.block_root();
+        let fork_root = fork_block.message.block_root();
+        let child_root = child_block.message.block_root();
+
+        db.block_provider()
+            .insert(grandparent_root, grandparent_block)?;
+        db.state_provider()
+            .insert(grandparent_root, grandparent_state)?;
+        db.block_provider().insert(parent_root, parent_block)?;
+        db.state_provider()
+            .insert(parent_root, parent_state.clone())?;
+        // The fork is imported last, so the slot index points at it
+        db.block_provider().insert(fork_root, fork_block)?;
+        db.state_provider()
+            .insert(fork_root, parent_state.clone())?;
+        db.block_provider().insert(child_root, child_block)?;
+        db.state_provider()
+            .insert(child_root, parent_state.clone())?;
+
+        Ok(Chain {
+            grandparent_root,
+            parent_root,
+            parent_state,
+            fork_root,
+            child_root,
+        })
+    }
+
+    fn init_db(tmp_dir: &TempDir) -> anyhow::Result<BeaconDB> {
+        initialize_test_network_spec();
+        Ok(ReamDB::new(tmp_dir.path().to_path_buf())?.init_beacon_db()?)
+    }
+
+    #[test]
+    fn test_migrate_finalized_states() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_migrate_finalized_states")?;
+        let db = init_db(&tmp_dir)?;
+        let chain = setup_chain(&db)?;
+
+        // Every state but the one of the finalized block is deleted, including the fork
+        assert_eq!(db.migrate_finalized_states(chain.child_root)?, 3);
+        let state_provider = db.state_provider();
+        assert!(state_provider.get(chain.child_root)?.is_some());
+        for block_root in [chain.grandparent_root, chain.parent_root, chain.fork_root] {
+            assert!(state_provider.get(block_root)?.is_none());
+        }
+
+        // The slot index no longer points at the abandoned fork
+        let slot_index_provider = db.slot_index_provider();
+        assert_eq!(
+            slot_index_provider.get(chain.parent_state.slot)?,
+            Some(chain.parent_root)
+        );
+
+        // Only the first state of the restore point is kept as a snapshot
+        let cold_state_provider = db.cold_state_provider();
+        assert_eq!(
+            cold_state_provider.get_highest_slot()?,
+            Some(chain.parent_state.slot - 1)
+        );
+
+        // Migrating again has nothing left to move
+        assert_eq!(db.migrate_finalized_states(chain.child_root)?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_regenerate_state_between_snapshots() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_regenerate_state")?;
+        let db = init_db(&tmp_dir)?;
+        let chain = setup_chain(&db)?;
+        db.finalized_checkpoint_provider().insert(Checkpoint {
+            epoch: chain.parent_state.get_current_epoch(),
+            root: chain.child_root,
+        })?;
+        db.migrate_finalized_states(chain.child_root)?;
+
+        // The parent state was deleted and is replayed on top of the grandparent snapshot
+        let expected_state_root = chain.parent_state.tree_hash_root();
+        let state = db
+            .get_state(chain.parent_root)
+            .await?
+            .ok_or_else(|| anyhow!("Parent state was not regenerated"))?;
+        assert_eq!(state.tree_hash_root(), expected_state_root);
+        let state = db
+            .get_state_at_slot(chain.parent_state.slot)
+            .await?
+            .ok_or_else(|| anyhow!("State at slot was not regenerated"))?;
+        assert_eq!(state.tree_hash_root(), expected_state_root);
+
+        // States of abandoned forks are gone for good
+        assert!(db.get_state(chain.fork_root).await?.is_none());
+        Ok(())
+    }
+}
@@ -1,10 +1,19 @@
-use std::{fmt::Write, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Write,
+    sync::Arc,
+};
 
-use redb::{Database, ReadableDatabase, ReadableTableMetadata};
+use alloy_primitives::B256;
+use anyhow::ensure;
+use ream_consensus_lean::state::LeanState;
+use redb::{Database, ReadableDatabase, ReadableTable, ReadableTableMetadata};
 use tracing::info;
+use tree_hash::TreeHash;
 
 use crate::{
     cache::LeanCacheDB,
+    errors::StoreError,
     tables::{
         field::REDBField,
         lean::{
@@ -24,6 +33,7 @@ use crate::{
         },
         table::REDBTable,
     },
+    write_batch::WriteBatch,
 };
 
 #[derive(Clone, Debug)]
@@ -40,6 +50,11 @@ impl LeanDB {
         self
     }
 
+    /// Start a batch of updates to be committed atomically.
+    pub fn write_batch(&self) -> Result<WriteBatch, StoreError> {
+        WriteBatch::new(&self.db)
+    }
+
     pub fn block_provider(&self) -> LeanBlockTable {
         LeanBlockTable {
             db: self.db.clone(),
@@ -159,6 +174,272 @@ impl LeanDB {
         }
     }
 
+    /// Check that the head, the checkpoints and the tip of the slot index point at stored
+    /// blocks, and that the head state is stored. Block import commits all of its updates
+    /// atomically, so this only fails for a database written by an older version or damaged on
+    /// disk.
+    pub fn check_consistency(&self) -> anyhow::Result<()> {
+        let head = match self.head_provider().get() {
+            Ok(head) => head,
+            Err(StoreError::FieldNotInitilized) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let block_provider = self.block_provider();
+        let mut block_roots = vec![
+            ("head", head),
+            (
+                "latest justified checkpoint",
+                self.latest_justified_provider().get()?.root,
+            ),
+            (
+                "latest finalized checkpoint",
+                self.latest_finalized_provider().get()?.root,
+            ),
+        ];
+        if let Some(highest_root) = self.slot_index_provider().get_highest_root()? {
+            block_roots.push(("highest slot index", highest_root));
+        }
+        for (name, block_root) in block_roots {
+            ensure!(
+                block_provider.get(block_root)?.is_some(),
+                "Block of the {name} is missing: {block_root}"
+            );
+        }
+        ensure!(
+            self.state_provider().get(head)?.is_some(),
+            "State of the head is missing: {head}"
+        );
+
+        Ok(())
+    }
+
+    /// Return the post-state of `block_root`. If it was pruned, it is regenerated by replaying
+    /// the blocks on top of the closest ancestor whose state is still stored.
+    pub fn get_state(&self, block_root: B256) -> anyhow::Result<Option<LeanState>> {
+        let state_provider = self.state_provider();
+        if let Some(state) = state_provider.get(block_root)? {
+            return Ok(Some(state));
+        }
+
+        let block_provider = self.block_provider();
+        let mut blocks = vec![];
+        let mut root = block_root;
+        let mut state = loop {
+            let Some(signed_block) = block_provider.get(root)? else {
+                return Ok(None);
+            };
+            root = signed_block.block.parent_root;
+            blocks.push(signed_block.block);
+            if let Some(state) = state_provider.get(root)? {
+                break state;
+            }
+        };
+
+        // Signatures were verified when the blocks were imported
+        for block in blocks.iter().rev() {
+            state.state_transition(block, true)?;
+        }
+
+        Ok(Some(state))
+    }
+
+    /// Delete the state of the last block at or before `slot`. With an `archive_snapshot_interval`
+    /// the state of the first block of every interval is kept, so the states in between can be
+    /// regenerated from it. Returns the slot and root of the block whose state was deleted.
+    pub fn prune_state_at_or_before(
+        &self,
+        slot: u64,
+        archive_snapshot_interval: Option<u64>,
+    ) -> anyhow::Result<Option<(u64, B256)>> {
+        let slot_index_provider = self.slot_index_provider();
+        let Some((prune_slot, block_root)) = slot_index_provider.get_at_or_before(slot)? else {
+            return Ok(None);
+        };
+
+        if let Some(interval) = archive_snapshot_interval {
+            let previous_slot = match prune_slot.checked_sub(1) {
+                Some(slot) => slot_index_provider
+                    .get_at_or_before(slot)?
+                    .map(|(slot, _)| slot),
+                None => None,
+            };
+            if previous_slot.is_none_or(|slot| slot / interval < prune_slot / interval) {
+                return Ok(None);
+            }
+        }
+
+        self.state_provider().remove(block_root)?;
+        Ok(Some((prune_slot, block_root)))
+    }
+
+    /// Delete the blocks which are neither ancestors nor descendants of the latest finalized
+    /// block, together with their states. Returns the number of deleted blocks.
+    pub fn prune_non_canonical_blocks(&self) -> anyhow::Result<usize> {
+        let finalized_root = self.latest_finalized_provider().get()?.root;
+        let block_provider = self.block_provider();
+
+        let mut canonical_roots = HashSet::new();
+        let mut block_root = finalized_root;
+        while let Some(signed_block) = block_provider.get(block_root)? {
+            canonical_roots.insert(block_root);
+            block_root = signed_block.block.parent_root;
+        }
+
+        // The children index covers every block since the latest finalized one
+        let mut children = HashMap::<B256, Vec<B256>>::new();
+        for (block_root, (parent_root, _)) in block_provider.get_index_map()? {
+            children.entry(parent_root).or_default().push(block_root);
+        }
+        let mut queue = VecDeque::from([finalized_root]);
+        while let Some(block_root) = queue.pop_front() {
+            for child_root in children.remove(&block_root).unwrap_or_default() {
+                if canonical_roots.insert(child_root) {
+                    queue.push_back(child_root);
+                }
+            }
+        }
+
+        let state_provider = self.state_provider();
+        let mut batch = self.write_batch()?;
+        let mut deleted_count = 0;
+        for block_root in block_provider.iter_keys()? {
+            if canonical_roots.contains(&block_root) {
+                continue;
+            }
+            block_provider.remove_batch(&mut batch, block_root)?;
+            state_provider.remove_batch(&mut batch, block_root)?;
+            deleted_count += 1;
+        }
+        batch.commit()?;
+
+        Ok(deleted_count)
+    }
+
+    /// Delete the states of the blocks before `slot`, except the states of the head and the
+    /// checkpoints. States before `slot` can no longer be regenerated afterwards. Returns the
+    /// number of deleted states.
+    pub fn prune_states_before(&self, slot: u64) -> anyhow::Result<usize> {
+        let kept_roots = HashSet::from([
+            self.head_provider().get()?,
+            self.latest_justified_provider().get()?.root,
+            self.latest_finalized_provider().get()?.root,
+        ]);
+
+        let block_provider = self.block_provider();
+        let state_provider = self.state_provider();
+        let mut batch = self.write_batch()?;
+        let mut deleted_count = 0;
+        for block_root in state_provider.iter_keys()? {
+            if kept_roots.contains(&block_root) {
+                continue;
+            }
+            if block_provider
+                .get(block_root)?
+                .is_none_or(|signed_block| signed_block.block.slot < slot)
+            {
+                state_provider.remove_batch(&mut batch, block_root)?;
+                deleted_count += 1;
+            }
+        }
+        batch.commit()?;
+
+        Ok(deleted_count)
+    }
+
+    /// Check the links between the stored blocks, states and index tables. Returns a description
+    /// of every problem found.
+    pub fn verify(&self) -> anyhow::Result<Vec<String>> {
+        let block_provider = self.block_provider();
+        let slot_index_provider = self.slot_index_provider();
+        let mut problems = vec![];
+        if let Err(err) = self.check_consistency() {
+            problems.push(err.to_string());
+        }
+
+        let oldest_slot = slot_index_provider.get_oldest_slot()?.unwrap_or_default();
+        let mut block_state_roots = HashMap::new();
+        for block_root in block_provider.iter_keys()? {
+            let Some(signed_block) = block_provider.get(block_root)? else {
+                continue;
+            };
+            let block = signed_block.block;
+            if block.tree_hash_root() != block_root {
+                problems.push(format!("Block {block_root} is stored under the wrong root"));
+            }
+            // The anchor block of a checkpoint synced database has no parent
+            if block.slot > oldest_slot && block_provider.get(block.parent_root)?.is_none() {
+                problems.push(format!(
+                    "Parent {} of block {block_root} is missing",
+                    block.parent_root
+                ));
+            }
+            block_state_roots.insert(block_root, (block.slot, block.state_root));
+        }
+
+        let read_txn = self.db.begin_read()?;
+        let slot_index = read_txn.open_table(LeanSlotIndexTable::TABLE_DEFINITION)?;
+        for entry in slot_index.iter()? {
+            let (slot, block_root) = entry?;
+            let (slot, block_root) = (slot.value(), block_root.value());
+            match block_state_roots.get(&block_root) {
+                Some((block_slot, _)) if *block_slot == slot => {}
+                Some((block_slot, _)) => problems.push(format!(
+                    "Slot index {slot} points at block {block_root} of slot {block_slot}"
+                )),
+                None => problems.push(format!(
+                    "Slot index {slot} points at missing block {block_root}"
+                )),
+            }
+        }
+
+        let state_root_index = read_txn.open_table(LeanStateRootIndexTable::TABLE_DEFINITION)?;
+        for entry in state_root_index.iter()? {
+            let (state_root, block_root) = entry?;
+            let (state_root, block_root) = (state_root.value(), block_root.value());
+            if block_state_roots
+                .get(&block_root)
+                .is_none_or(|(_, block_state_root)| *block_state_root != state_root)
+            {
+                problems.push(format!(
+                    "State root index {state_root} points at block {block_root}, which has another \
+                     state root"
+                ));
+            }
+        }
+        drop(slot_index);
+        drop(state_root_index);
+        drop(read_txn);
+
+        for (block_root, (_, slot)) in block_provider.get_index_map()? {
+            if block_state_roots
+                .get(&block_root)
+                .is_none_or(|(block_slot, _)| *block_slot != slot)
+            {
+                problems.push(format!(
+                    "Children index entry of block {block_root} doesn't match a stored block"
+                ));
+            }
+        }
+
+        let state_provider = self.state_provider();
+        for block_root in state_provider.iter_keys()? {
+            let Some((_, state_root)) = block_state_roots.get(&block_root) else {
+                problems.push(format!("State of missing block {block_root} is stored"));
+                continue;
+            };
+            if let Some(state) = state_provider.get(block_root)?
+                && state.tree_hash_root() != *state_root
+            {
+                problems.push(format!(
+                    "State of block {block_root} doesn't match the block's state root"
+                ));
+            }
+        }
+
+        Ok(problems)
+    }
+
     /// Checks the storage usage of all tables and reports metrics.
     ///
     /// # Arguments
@@ -244,3 +525,132 @@ impl LeanDB {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_consensus_lean::{
+        block::{Block, BlockBody, SignedBlock},
+        state::LeanState,
+        utils::generate_default_validators,
+    };
+    use tempdir::TempDir;
+    use tree_hash::TreeHash;
+
+    use super::LeanDB;
+    use crate::{db::ReamDB, tables::table::REDBTable};
+
+    const VALIDATOR_COUNT: u64 = 4;
+
+    /// Store a chain of empty blocks from slot 1 to `length` on top of a genesis state, together
+    /// with every post-state. Returns the genesis root and the root and post-state of every block.
+    fn store_chain(lean_db: &LeanDB, length: u64) -> (B256, Vec<(B256, LeanState)>) {
+        let mut state = LeanState::generate_genesis(
+            0,
+            Some(generate_default_validators(VALIDATOR_COUNT as usize)),
+        );
+        let mut genesis_root = None;
+        let mut chain = vec![];
+        for slot in 1..=length {
+            state.process_slots(slot).unwrap();
+            let parent_root = state.latest_block_header.tree_hash_root();
+            if genesis_root.is_none() {
+                genesis_root = Some(parent_root);
+                lean_db
+                    .state_provider()
+                    .insert(parent_root, state.clone())
+                    .unwrap();
+            }
+
+            let mut block = Block {
+                slot,
+                proposer_index: slot % VALIDATOR_COUNT,
+                parent_root,
+                state_root: B256::ZERO,
+                body: BlockBody {
+                    attestations: Default::default(),
+                },
+            };
+            state.process_block(&block).unwrap();
+            block.state_root = state.tree_hash_root();
+
+            let block_root = block.tree_hash_root();
+            lean_db
+                .block_provider()
+                .insert_ref(
+                    block_root,
+                    &SignedBlock {
+                        block,
+                        #[cfg(feature = "devnet5")]
+                        proof: Default::default(),
+                    },
+                )
+                .unwrap();
+            lean_db
+                .state_provider()
+                .insert(block_root, state.clone())
+                .unwrap();
+            chain.push((block_root, state.clone()));
+        }
+
+        (genesis_root.unwrap(), chain)
+    }
+
+    #[test]
+    fn test_get_state_regenerates_pruned_states() {
+        let tmp_dir = TempDir::new("test_get_state").unwrap();
+        let lean_db = ReamDB::new(tmp_dir.path().to_path_buf())
+            .unwrap()
+            .init_lean_db()
+            .unwrap();
+        let (genesis_root, chain) = store_chain(&lean_db, 6);
+
+        // Only the state of the block at slot 3 is kept as a snapshot
+        for (index, (block_root, _)) in chain.iter().enumerate() {
+            if index != 2 {
+                lean_db.state_provider().remove(*block_root).unwrap();
+            }
+        }
+        let (block_root, state) = &chain[5];
+        assert_eq!(
+            lean_db.get_state(*block_root).unwrap().as_ref(),
+            Some(state)
+        );
+        let (block_root, state) = &chain[1];
+        assert_eq!(
+            lean_db.get_state(*block_root).unwrap().as_ref(),
+            Some(state)
+        );
+
+        // Without any state to replay from, or for an unknown block, there is nothing to return
+        assert_eq!(lean_db.get_state(B256::repeat_byte(1)).unwrap(), None);
+        lean_db.state_provider().remove(genesis_root).unwrap();
+        lean_db.state_provider().remove(chain[2].0).unwrap();
+        assert_eq!(lean_db.get_state(chain[5].0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_prune_state_keeps_archive_snapshots() {
+        let tmp_dir = TempDir::new("test_prune_state").unwrap();
+        let lean_db = ReamDB::new(tmp_dir.path().to_path_buf())
+            .unwrap()
+            .init_lean_db()
+            .unwrap();
+        let (_, chain) = store_chain(&lean_db, 6);
+
+        // The block at slot 3 is the first one of its snapshot interval
+        assert_eq!(lean_db.prune_state_at_or_before(3, Some(3)).unwrap(), None);
+        assert!(lean_db.state_provider().get(chain[2].0).unwrap().is_some());
+        assert_eq!(
+            lean_db.prune_state_at_or_before(4, Some(3)).unwrap(),
+            Some((4, chain[3].0))
+        );
+        assert!(lean_db.state_provider().get(chain[3].0).unwrap().is_none());
+
+        assert_eq!(
+            lean_db.prune_state_at_or_before(3, None).unwrap(),
+            Some((3, chain[2].0))
+        );
+        assert!(lean_db.state_provider().get(chain[2].0).unwrap().is_none());
+    }
+}
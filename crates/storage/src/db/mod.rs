@@ -1,23 +1,31 @@
 pub mod beacon;
 pub mod lean;
 
-use std::{fs, io, path::PathBuf, sync::Arc};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use beacon::BeaconDB;
 use lean::LeanDB;
-use redb::{Builder, Database};
+use redb::{
+    Builder, Database, MultimapTableHandle, ReadableDatabase, ReadableTableMetadata, TableHandle,
+};
 use tracing::info;
 
 use crate::{
     errors::StoreError,
+    migrations::{SCHEMA_VERSION, get_schema_version, run_migrations},
     tables::{
         beacon::{
             beacon_block::BeaconBlockTable, beacon_state::BeaconStateTable,
             blobs_and_proofs::BLOB_FOLDER_NAME, block_timeliness::BlockTimelinessTable,
-            checkpoint_states::CheckpointStatesTable, column_sidecars::COLUMN_FOLDER_NAME,
-            equivocating_indices::EQUIVOCATING_INDICES_FIELD,
-            finalized_checkpoint::FinalizedCheckpointField, genesis_time::GenesisTimeField,
+            checkpoint_states::CheckpointStatesTable, cold_state::BeaconColdStateTable,
+            column_sidecars::COLUMN_FOLDER_NAME, equivocating_indices::EQUIVOCATING_INDICES_FIELD,
+            execution_status::ExecutionStatusTable, finalized_checkpoint::FinalizedCheckpointField,
+            genesis_time::GenesisTimeField, justifications::JustificationsTable,
             justified_checkpoint::JustifiedCheckpointField, latest_messages::LatestMessagesTable,
             parent_root_index::PARENT_ROOT_INDEX_MULTIMAP_TABLE,
             proposer_boost_root::ProposerBoostRootField, slot_index::BeaconSlotIndexTable,
@@ -26,7 +34,7 @@ use crate::{
             unrealized_justifications::UnrealizedJustificationsTable,
             unrealized_justified_checkpoint::UnrealizedJustifiedCheckpointField,
         },
-        field::REDBField,
+        field::{CustomField, REDBField},
         lean::{
             aggregated_payloads::AggregatedPayloadsTable,
             attestation_data_by_root::LeanAttestationDataByRootTable, block::LeanBlockTable,
@@ -41,6 +49,12 @@ use crate::{
             state_root_index::LeanStateRootIndexTable, time::LeanTimeField,
             validator_id::LeanValidatorIdField,
         },
+        metadata::{
+            chain::CHAIN_FIELD,
+            lean_encoding::{LEAN_ENCODING, LEAN_ENCODING_FIELD},
+            network::NETWORK_FIELD,
+            string_field::StringField,
+        },
         table::REDBTable,
     },
 };
@@ -52,6 +66,14 @@ pub const REDB_FILE: &str = "ream.redb";
 /// 1 GiB
 pub const REDB_CACHE_SIZE: usize = 1_024 * 1_024 * 1_024;
 
+/// Number of entries and bytes on disk of a table, as reported by `ream db info`.
+#[derive(Clone, Debug)]
+pub struct TableSize {
+    pub name: String,
+    pub entries: u64,
+    pub bytes: u64,
+}
+
 #[derive(Clone, Debug)]
 pub struct ReamDB {
     db: Arc<Database>,
@@ -70,16 +92,26 @@ impl ReamDB {
         })
     }
 
+    /// The directory holding the database and the files stored next to it.
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
     pub fn init_beacon_db(&self) -> Result<BeaconDB, StoreError> {
+        self.prepare("beacon")?;
+
         let write_txn = self.db.begin_write()?;
 
         write_txn.open_table(BeaconBlockTable::TABLE_DEFINITION)?;
         write_txn.open_table(BeaconStateTable::TABLE_DEFINITION)?;
         write_txn.open_table(BlockTimelinessTable::TABLE_DEFINITION)?;
         write_txn.open_table(CheckpointStatesTable::TABLE_DEFINITION)?;
+        write_txn.open_table(BeaconColdStateTable::TABLE_DEFINITION)?;
         write_txn.open_table(EQUIVOCATING_INDICES_FIELD)?;
+        write_txn.open_table(ExecutionStatusTable::TABLE_DEFINITION)?;
         write_txn.open_table(FinalizedCheckpointField::FIELD_DEFINITION)?;
         write_txn.open_table(GenesisTimeField::FIELD_DEFINITION)?;
+        write_txn.open_table(JustificationsTable::TABLE_DEFINITION)?;
         write_txn.open_table(JustifiedCheckpointField::FIELD_DEFINITION)?;
         write_txn.open_table(LatestMessagesTable::TABLE_DEFINITION)?;
         write_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
@@ -103,6 +135,9 @@ impl ReamDB {
     }
 
     pub fn init_lean_db(&self) -> Result<LeanDB, StoreError> {
+        let schema_version = get_schema_version(&self.db)?;
+        self.prepare("lean")?;
+
         let write_txn = self.db.begin_write()?;
 
         write_txn.open_table(LatestFinalizedField::FIELD_DEFINITION)?;
@@ -123,13 +158,179 @@ impl ReamDB {
         write_txn.open_table(LeanLatestNewAggregatedPayloadsTable::TABLE_DEFINITION)?;
         write_txn.open_table(LeanLatestKnownAggregatedPayloadsTable::TABLE_DEFINITION)?;
         write_txn.open_table(LeanValidatorIdField::FIELD_DEFINITION)?;
+        write_txn.open_table(LEAN_ENCODING_FIELD)?;
         write_txn.commit()?;
 
+        // Blocks and aggregated payloads are encoded differently depending on the `devnet5`
+        // feature, which the schema version doesn't capture. Lean data written before the
+        // encoding was recorded may be in either encoding, so only a fresh database is stamped.
+        let lean_encoding_field = StringField::lean_encoding(self.db.clone());
+        if !check_metadata(&lean_encoding_field, "encoding", LEAN_ENCODING)? {
+            let has_blocks = !self
+                .db
+                .begin_read()?
+                .open_table(LeanBlockTable::TABLE_DEFINITION)?
+                .is_empty()?;
+            if schema_version == Some(0) || has_blocks {
+                return Err(StoreError::LeanEncodingUnknown);
+            }
+            lean_encoding_field.insert(LEAN_ENCODING.to_string())?;
+        }
+
+        Ok(LeanDB {
+            db: self.db.clone(),
+            cache: None,
+        })
+    }
+
+    /// Open the beacon tables of a database written by a beacon node of `network`, without
+    /// migrating the database or recording anything in it.
+    pub fn open_beacon_db(&self, network: &str) -> Result<BeaconDB, StoreError> {
+        self.check_readable("beacon")?;
+        check_metadata(&StringField::network(self.db.clone()), "network", network)?;
+
+        Ok(BeaconDB {
+            db: self.db.clone(),
+            data_dir: self.data_dir.clone(),
+            cache: None,
+        })
+    }
+
+    /// Open the lean tables of a database written by a lean node, without migrating the database
+    /// or recording anything in it.
+    pub fn open_lean_db(&self) -> Result<LeanDB, StoreError> {
+        self.check_readable("lean")?;
+        check_metadata(
+            &StringField::lean_encoding(self.db.clone()),
+            "encoding",
+            LEAN_ENCODING,
+        )?;
+
         Ok(LeanDB {
             db: self.db.clone(),
             cache: None,
         })
     }
+
+    /// Check that the database belongs to `network`, recording it if the database is new.
+    pub fn check_network(&self, network: &str) -> Result<(), StoreError> {
+        record_or_check(StringField::network(self.db.clone()), "network", network)
+    }
+
+    /// Return the chain the database was created for, or `None` if it was never opened by a
+    /// node.
+    pub fn get_chain(&self) -> Result<Option<String>, StoreError> {
+        match StringField::chain(self.db.clone()).get() {
+            Ok(chain) => Ok(Some(chain)),
+            Err(StoreError::FieldNotInitilized) => Ok(None),
+            Err(StoreError::Redb(err)) if matches!(*err, redb::Error::TableDoesNotExist(_)) => {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Return the size of every table in the database, largest first.
+    pub fn table_sizes(&self) -> Result<Vec<TableSize>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let mut table_sizes = vec![];
+        for handle in read_txn.list_tables()? {
+            let table = read_txn.open_untyped_table(handle.clone())?;
+            let stats = table.stats()?;
+            table_sizes.push(TableSize {
+                name: handle.name().to_string(),
+                entries: table.len()?,
+                bytes: stats.stored_bytes() + stats.metadata_bytes() + stats.fragmented_bytes(),
+            });
+        }
+        for handle in read_txn.list_multimap_tables()? {
+            let table = read_txn.open_untyped_multimap_table(handle.clone())?;
+            let stats = table.stats()?;
+            table_sizes.push(TableSize {
+                name: handle.name().to_string(),
+                entries: table.len()?,
+                bytes: stats.stored_bytes() + stats.metadata_bytes() + stats.fragmented_bytes(),
+            });
+        }
+        table_sizes.sort_by_key(|table_size| std::cmp::Reverse(table_size.bytes));
+
+        Ok(table_sizes)
+    }
+
+    /// Compact the database file, returning the space freed by deleted entries to the file
+    /// system. No [BeaconDB] or [LeanDB] of this database may be alive. Returns whether anything
+    /// was compacted.
+    pub fn compact(self) -> anyhow::Result<bool> {
+        let mut db = Arc::into_inner(self.db)
+            .ok_or_else(|| anyhow!("Database can't be compacted while it is in use"))?;
+        Ok(db.compact().map_err(StoreError::from)?)
+    }
+
+    /// Check that the database is at the current schema version and belongs to `chain`.
+    fn check_readable(&self, chain: &str) -> Result<(), StoreError> {
+        let version = get_schema_version(&self.db)?.unwrap_or_default();
+        if version > SCHEMA_VERSION {
+            return Err(StoreError::SchemaVersionTooNew {
+                version,
+                supported: SCHEMA_VERSION,
+            });
+        }
+        if version < SCHEMA_VERSION {
+            return Err(StoreError::SchemaVersionTooOld {
+                version,
+                supported: SCHEMA_VERSION,
+            });
+        }
+
+        check_metadata(&StringField::chain(self.db.clone()), "chain", chain)?;
+        Ok(())
+    }
+
+    /// Upgrade the database to the current schema version and check that it belongs to `chain`.
+    fn prepare(&self, chain: &str) -> Result<(), StoreError> {
+        run_migrations(&self.db)?;
+
+        let write_txn = self.db.begin_write()?;
+        write_txn.open_table(CHAIN_FIELD)?;
+        write_txn.open_table(NETWORK_FIELD)?;
+        write_txn.commit()?;
+
+        record_or_check(StringField::chain(self.db.clone()), "chain", chain)
+    }
+}
+
+/// Record `expected` in `field` if it isn't set yet, otherwise check that it matches.
+fn record_or_check(
+    field: impl CustomField<Value = String>,
+    name: &'static str,
+    expected: &str,
+) -> Result<(), StoreError> {
+    if check_metadata(&field, name, expected)? {
+        Ok(())
+    } else {
+        field.insert(expected.to_string())
+    }
+}
+
+/// Check that `field` matches `expected`. Returns whether `field` is set.
+fn check_metadata(
+    field: &impl CustomField<Value = String>,
+    name: &'static str,
+    expected: &str,
+) -> Result<bool, StoreError> {
+    match field.get() {
+        Ok(found) if found == expected => Ok(true),
+        Ok(found) => Err(StoreError::MetadataMismatch {
+            field: name,
+            expected: expected.to_string(),
+            found,
+        }),
+        Err(StoreError::FieldNotInitilized) => Ok(false),
+        Err(StoreError::Redb(err)) if matches!(*err, redb::Error::TableDoesNotExist(_)) => {
+            Ok(false)
+        }
+        Err(err) => Err(err),
+    }
 }
 
 pub fn reset_db(db_path: &PathBuf) -> anyhow::Result<()> {
@@ -159,3 +360,157 @@ pub fn reset_db(db_path: &PathBuf) -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_consensus_lean::block::{Block, BlockBody, SignedBlock};
+    use tempdir::TempDir;
+
+    use super::ReamDB;
+    use crate::{
+        errors::StoreError,
+        migrations::{SCHEMA_VERSION, get_schema_version},
+        tables::{
+            field::{CustomField, REDBField},
+            metadata::{
+                lean_encoding::{LEAN_ENCODING, LEAN_ENCODING_FIELD},
+                schema_version::SchemaVersionField,
+                string_field::StringField,
+            },
+        },
+    };
+
+    #[test]
+    fn test_new_database_is_at_current_schema_version() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_schema_version")?;
+        let ream_db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+        assert_eq!(get_schema_version(&ream_db.db)?, None);
+
+        ream_db.init_lean_db()?;
+
+        assert_eq!(get_schema_version(&ream_db.db)?, Some(SCHEMA_VERSION));
+        Ok(())
+    }
+
+    #[test]
+    fn test_refuses_newer_schema_version() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_schema_version")?;
+        let ream_db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+        ream_db.init_beacon_db()?;
+
+        SchemaVersionField {
+            db: ream_db.db.clone(),
+        }
+        .insert(SCHEMA_VERSION + 1)?;
+
+        assert!(matches!(
+            ream_db.init_beacon_db(),
+            Err(StoreError::SchemaVersionTooNew { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_refuses_other_chain_and_network() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_chain")?;
+        let ream_db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+        ream_db.init_beacon_db()?;
+        ream_db.check_network("hoodi")?;
+
+        ream_db.init_beacon_db()?;
+        ream_db.check_network("hoodi")?;
+        assert!(matches!(
+            ream_db.init_lean_db(),
+            Err(StoreError::MetadataMismatch { field: "chain", .. })
+        ));
+        assert!(matches!(
+            ream_db.check_network("sepolia"),
+            Err(StoreError::MetadataMismatch {
+                field: "network",
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_refuses_other_lean_encoding() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_lean_encoding")?;
+        let ream_db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+        ream_db.init_lean_db()?;
+
+        let lean_encoding_field = StringField::lean_encoding(ream_db.db.clone());
+        assert_eq!(lean_encoding_field.get()?, LEAN_ENCODING);
+        lean_encoding_field.insert("other".to_string())?;
+
+        assert!(matches!(
+            ream_db.init_lean_db(),
+            Err(StoreError::MetadataMismatch {
+                field: "encoding",
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_leaves_database_untouched() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_open")?;
+        let ream_db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+        assert!(matches!(
+            ream_db.open_beacon_db("hoodi"),
+            Err(StoreError::SchemaVersionTooOld { .. })
+        ));
+        assert_eq!(get_schema_version(&ream_db.db)?, None);
+
+        ream_db.init_beacon_db()?;
+        ream_db.open_beacon_db("hoodi")?;
+        assert_eq!(ream_db.get_chain()?.as_deref(), Some("beacon"));
+        assert!(matches!(
+            StringField::network(ream_db.db.clone()).get(),
+            Err(StoreError::FieldNotInitilized)
+        ));
+        assert!(matches!(
+            ream_db.open_lean_db(),
+            Err(StoreError::MetadataMismatch { field: "chain", .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_refuses_unversioned_lean_database_with_blocks() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_lean_encoding")?;
+        let ream_db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+        let lean_db = ream_db.init_lean_db()?;
+        let signed_block = SignedBlock {
+            block: Block {
+                slot: 1,
+                proposer_index: 0,
+                parent_root: B256::ZERO,
+                state_root: B256::ZERO,
+                body: BlockBody {
+                    attestations: Default::default(),
+                },
+            },
+            #[cfg(feature = "devnet5")]
+            proof: Default::default(),
+        };
+        lean_db
+            .block_provider()
+            .insert_ref(B256::repeat_byte(1), &signed_block)?;
+
+        // Databases written before this binary recorded neither a schema version nor an encoding
+        let write_txn = ream_db.db.begin_write()?;
+        write_txn.delete_table(SchemaVersionField::FIELD_DEFINITION)?;
+        write_txn.delete_table(LEAN_ENCODING_FIELD)?;
+        write_txn.commit()?;
+        assert_eq!(get_schema_version(&ream_db.db)?, Some(0));
+
+        assert!(matches!(
+            ream_db.init_lean_db(),
+            Err(StoreError::LeanEncodingUnknown)
+        ));
+        Ok(())
+    }
+}
@@ -16,6 +16,28 @@ pub enum StoreError {
 
     #[error("SnappyError not found {0}")]
     SnappyError(#[from] snap::Error),
+
+    #[error(
+        "Database schema version {version} is newer than version {supported} supported by this binary"
+    )]
+    SchemaVersionTooNew { version: u64, supported: u64 },
+
+    #[error(
+        "Database schema version {version} is older than version {supported}, start the node to migrate it"
+    )]
+    SchemaVersionTooOld { version: u64, supported: u64 },
+
+    #[error(
+        "Lean database predates recording its encoding, its blocks may not decode with this binary, purge it with --purge-db and resync"
+    )]
+    LeanEncodingUnknown,
+
+    #[error("Database was created for {field} {found}, but {expected} was requested")]
+    MetadataMismatch {
+        field: &'static str,
+        expected: String,
+        found: String,
+    },
 }
 
 impl From<redb::Error> for StoreError {
@@ -60,6 +82,12 @@ impl From<redb::DatabaseError> for StoreError {
     }
 }
 
+impl From<redb::CompactionError> for StoreError {
+    fn from(err: redb::CompactionError) -> Self {
+        StoreError::Redb(Box::new(err.into()))
+    }
+}
+
 impl From<ssz::DecodeError> for StoreError {
     fn from(value: ssz::DecodeError) -> Self {
         StoreError::DecodeError(format!("{value:?}"))
@@ -2,4 +2,6 @@ pub mod cache;
 pub mod db;
 pub mod dir;
 pub mod errors;
+pub mod migrations;
 pub mod tables;
+pub mod write_batch;
@@ -0,0 +1,85 @@
+mod v1;
+
+use std::sync::Arc;
+
+use redb::{Database, ReadableDatabase, TableError};
+use tracing::info;
+
+use crate::{
+    errors::StoreError,
+    tables::{field::REDBField, metadata::schema_version::SchemaVersionField},
+    write_batch::WriteBatch,
+};
+
+/// The schema version of databases written by this binary. Bump it together with a new entry in
+/// [MIGRATIONS] whenever the layout or the encoding of a table changes. Encodings that depend on
+/// a cargo feature are recorded in the database instead, see
+/// [LEAN_ENCODING](crate::tables::metadata::lean_encoding::LEAN_ENCODING).
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// A step upgrading the database from `version - 1` to `version`.
+struct Migration {
+    version: u64,
+    description: &'static str,
+    migrate: fn(&mut WriteBatch) -> Result<(), StoreError>,
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "Drop the lean aggregated payload tables, whose encoding changed with devnet5",
+    migrate: v1::migrate,
+}];
+
+/// Return the schema version of the database, or `None` if it is empty.
+///
+/// Databases created before schema versions were recorded are at version 0.
+pub fn get_schema_version(db: &Database) -> Result<Option<u64>, StoreError> {
+    let read_txn = db.begin_read()?;
+    match read_txn.open_table(SchemaVersionField::FIELD_DEFINITION) {
+        Ok(table) => Ok(Some(
+            table
+                .get(SchemaVersionField::KEY)?
+                .map(|version| version.value())
+                .unwrap_or_default(),
+        )),
+        Err(TableError::TableDoesNotExist(_)) => {
+            let is_empty = read_txn.list_tables()?.next().is_none()
+                && read_txn.list_multimap_tables()?.next().is_none();
+            Ok((!is_empty).then_some(0))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Upgrade the database to [SCHEMA_VERSION] one migration at a time. Each migration is committed
+/// atomically together with the version it upgrades to, so an interrupted upgrade resumes from
+/// the last completed step.
+pub fn run_migrations(db: &Arc<Database>) -> Result<(), StoreError> {
+    let schema_version_field = SchemaVersionField { db: db.clone() };
+    let Some(version) = get_schema_version(db)? else {
+        return schema_version_field.insert(SCHEMA_VERSION);
+    };
+
+    if version > SCHEMA_VERSION {
+        return Err(StoreError::SchemaVersionTooNew {
+            version,
+            supported: SCHEMA_VERSION,
+        });
+    }
+
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > version)
+    {
+        info!(
+            "Migrating database to schema version {}: {}",
+            migration.version, migration.description
+        );
+        let mut batch = WriteBatch::new(db)?;
+        (migration.migrate)(&mut batch)?;
+        schema_version_field.insert_batch(&mut batch, migration.version)?;
+        batch.commit()?;
+    }
+
+    Ok(())
+}
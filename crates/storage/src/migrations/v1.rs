@@ -0,0 +1,21 @@
+use crate::{
+    errors::StoreError,
+    tables::{
+        lean::{
+            aggregated_payloads::AggregatedPayloadsTable,
+            latest_known_aggregated_payloads::LeanLatestKnownAggregatedPayloadsTable,
+            latest_new_aggregated_payloads::LeanLatestNewAggregatedPayloadsTable,
+        },
+        table::REDBTable,
+    },
+    write_batch::WriteBatch,
+};
+
+/// The aggregated payload tables only hold attestations of the non-finalized chain, which are
+/// gossiped again, so they are dropped rather than re-encoded. `init_lean_db` recreates them.
+pub fn migrate(batch: &mut WriteBatch) -> Result<(), StoreError> {
+    batch.delete_table(AggregatedPayloadsTable::TABLE_DEFINITION)?;
+    batch.delete_table(LeanLatestKnownAggregatedPayloadsTable::TABLE_DEFINITION)?;
+    batch.delete_table(LeanLatestNewAggregatedPayloadsTable::TABLE_DEFINITION)?;
+    Ok(())
+}
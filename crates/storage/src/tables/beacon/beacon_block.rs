@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use alloy_primitives::B256;
 use ream_consensus_beacon::electra::beacon_block::SignedBeaconBlock;
-use redb::{Database, Durability, ReadableDatabase, TableDefinition};
+use redb::{Database, ReadableDatabase, ReadableTable, TableDefinition};
 use tree_hash::TreeHash;
 
 use super::parent_root_index::ParentRootIndexMultimapTable;
@@ -15,6 +15,7 @@ use crate::{
         ssz_encoder::SSZEncoding,
         table::REDBTable,
     },
+    write_batch::WriteBatch,
 };
 
 pub struct BeaconBlockTable {
@@ -68,62 +69,90 @@ impl REDBTable for BeaconBlockTable {
         Ok(block)
     }
 
-    fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
+    fn insert_batch(
+        &self,
+        batch: &mut WriteBatch,
+        key: Self::Key,
+        value: Self::Value,
+    ) -> Result<(), StoreError> {
         // insert entry to slot_index table
         let block_root = value.message.tree_hash_root();
         let slot_index_table = BeaconSlotIndexTable {
             db: self.db.clone(),
         };
-        slot_index_table.insert(value.message.slot, block_root)?;
+        slot_index_table.insert_batch(batch, value.message.slot, block_root)?;
 
         // insert entry to state root index table
         let state_root_index_table = BeaconStateRootIndexTable {
             db: self.db.clone(),
         };
-        state_root_index_table.insert(value.message.state_root, block_root)?;
+        state_root_index_table.insert_batch(batch, value.message.state_root, block_root)?;
 
         let parent_root_index_table = ParentRootIndexMultimapTable {
             db: self.db.clone(),
         };
-        parent_root_index_table.insert(value.message.parent_root, block_root)?;
+        parent_root_index_table.insert_batch(batch, value.message.parent_root, block_root)?;
 
-        if let Some(cache) = &self.cache
-            && let Ok(mut cache_lock) = cache.blocks.lock()
-        {
-            cache_lock.put(key, value.clone());
-        }
-
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate)?;
-        let mut table = write_txn.open_table(Self::TABLE_DEFINITION)?;
-        table.insert(key, value)?;
+        let mut table = batch.open_table(Self::TABLE_DEFINITION)?;
+        table.insert(key, &value)?;
         drop(table);
-        write_txn.commit()?;
-        Ok(())
-    }
 
-    fn remove(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        if let Some(cache) = &self.cache
-            && let Ok(mut cache_lock) = cache.blocks.lock()
-        {
-            cache_lock.pop(&key);
+        if let Some(cache) = self.cache.clone() {
+            batch.on_commit(move || {
+                if let Ok(mut cache_lock) = cache.blocks.lock() {
+                    cache_lock.put(key, value);
+                }
+            });
         }
+        Ok(())
+    }
 
-        let write_txn = self.db.begin_write()?;
-        let mut table = write_txn.open_table(Self::TABLE_DEFINITION)?;
+    fn remove_batch(
+        &self,
+        batch: &mut WriteBatch,
+        key: Self::Key,
+    ) -> Result<Option<Self::Value>, StoreError> {
+        let mut table = batch.open_table(Self::TABLE_DEFINITION)?;
         let value = table.remove(key)?.map(|v| v.value());
+        drop(table);
+
         if let Some(block) = &value {
             let slot_index_table = BeaconSlotIndexTable {
                 db: self.db.clone(),
             };
-            slot_index_table.remove(block.message.slot)?;
+            slot_index_table.remove_block_batch(batch, block.message.slot, key)?;
             let state_root_index_table = BeaconStateRootIndexTable {
                 db: self.db.clone(),
             };
-            state_root_index_table.remove(block.message.state_root)?;
+            state_root_index_table.remove_batch(batch, block.message.state_root)?;
+            let parent_root_index_table = ParentRootIndexMultimapTable {
+                db: self.db.clone(),
+            };
+            parent_root_index_table.remove_batch(batch, block.message.parent_root, key)?;
+        }
+
+        if let Some(cache) = self.cache.clone() {
+            batch.on_commit(move || {
+                if let Ok(mut cache_lock) = cache.blocks.lock() {
+                    cache_lock.pop(&key);
+                }
+            });
         }
-        drop(table);
-        write_txn.commit()?;
         Ok(value)
     }
 }
+
+impl BeaconBlockTable {
+    /// The roots of all stored blocks.
+    pub fn iter_keys(&self) -> Result<Vec<B256>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(Self::TABLE_DEFINITION)?;
+
+        let mut keys = Vec::new();
+        for result in table.iter()? {
+            let (key_guard, _value_guard) = result?;
+            keys.push(key_guard.value());
+        }
+        Ok(keys)
+    }
+}
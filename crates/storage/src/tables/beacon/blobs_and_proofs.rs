@@ -27,6 +27,24 @@ impl BlobsAndProofsTable {
         ))
     }
 
+    /// The identifiers of all stored blobs, parsed from the file names.
+    pub fn iter_keys(&self) -> Result<Vec<BlobIdentifier>, StoreError> {
+        let mut keys = vec![];
+        for entry in read_dir(self.data_dir.join(BLOB_FOLDER_NAME))? {
+            let path = entry?.path();
+            if let Some(filename) = path.file_name().and_then(|name| name.to_str())
+                && let Some((block_root, index)) = filename
+                    .strip_suffix(".ssz_snappy")
+                    .and_then(|stem| stem.split_once('_'))
+                && let (Ok(block_root), Ok(index)) =
+                    (block_root.parse::<B256>(), index.parse::<u64>())
+            {
+                keys.push(BlobIdentifier::new(block_root, index));
+            }
+        }
+        Ok(keys)
+    }
+
     /// Prune blobs older than the specified slot by removing their associated block roots
     pub fn prune_old_blobs(
         &self,
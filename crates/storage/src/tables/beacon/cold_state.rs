@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use ream_consensus_beacon::electra::beacon_state::BeaconState;
+use redb::{Database, ReadableDatabase, ReadableTable, TableDefinition};
+
+use crate::{
+    errors::StoreError,
+    tables::{ssz_encoder::SSZEncoding, table::REDBTable},
+};
+
+pub struct BeaconColdStateTable {
+    pub db: Arc<Database>,
+}
+
+/// Table definition for the Cold State table, which holds periodic snapshots of finalized states.
+/// The states in between are regenerated by replaying blocks on top of the closest snapshot.
+///
+/// Key: slot of the snapshot
+/// Value: BeaconState
+impl REDBTable for BeaconColdStateTable {
+    const TABLE_DEFINITION: TableDefinition<'_, u64, SSZEncoding<BeaconState>> =
+        TableDefinition::new("beacon_cold_state");
+
+    type Key = u64;
+
+    type KeyTableDefinition = u64;
+
+    type Value = BeaconState;
+
+    type ValueTableDefinition = SSZEncoding<BeaconState>;
+
+    fn database(&self) -> Arc<Database> {
+        self.db.clone()
+    }
+}
+
+impl BeaconColdStateTable {
+    /// The latest snapshot at or before `slot`.
+    pub fn get_at_or_before(&self, slot: u64) -> Result<Option<BeaconState>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(Self::TABLE_DEFINITION)?;
+        Ok(table
+            .range(..=slot)?
+            .next_back()
+            .transpose()?
+            .map(|(_, state)| state.value()))
+    }
+
+    pub fn get_highest_slot(&self) -> Result<Option<u64>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(Self::TABLE_DEFINITION)?;
+        Ok(table.last()?.map(|result| result.0.value()))
+    }
+
+    /// The slots of all stored snapshots.
+    pub fn iter_slots(&self) -> Result<Vec<u64>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(Self::TABLE_DEFINITION)?;
+
+        let mut slots = Vec::new();
+        for result in table.iter()? {
+            let (key_guard, _value_guard) = result?;
+            slots.push(key_guard.value());
+        }
+        Ok(slots)
+    }
+}
@@ -1,9 +1,10 @@
 use std::{
-    fs::{File, remove_file},
+    fs::{File, read_dir, remove_file},
     io::{Read, Write},
     path::PathBuf,
 };
 
+use alloy_primitives::B256;
 use ream_consensus_beacon::data_column_sidecar::{ColumnIdentifier, DataColumnSidecar};
 use snap::raw::{Decoder, Encoder};
 use ssz::{Decode, Encode};
@@ -23,6 +24,24 @@ impl ColumnSidecarsTable {
             column_identifier.block_root, column_identifier.index
         ))
     }
+
+    /// The identifiers of all stored columns, parsed from the file names.
+    pub fn iter_keys(&self) -> Result<Vec<ColumnIdentifier>, StoreError> {
+        let mut keys = vec![];
+        for entry in read_dir(self.data_dir.join(COLUMN_FOLDER_NAME))? {
+            let path = entry?.path();
+            if let Some(filename) = path.file_name().and_then(|name| name.to_str())
+                && let Some((block_root, index)) = filename
+                    .strip_suffix(".ssz_snappy")
+                    .and_then(|stem| stem.split_once('_'))
+                && let (Ok(block_root), Ok(index)) =
+                    (block_root.parse::<B256>(), index.parse::<u64>())
+            {
+                keys.push(ColumnIdentifier::new(block_root, index));
+            }
+        }
+        Ok(keys)
+    }
 }
 
 impl CustomTable for ColumnSidecarsTable {
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+use ream_consensus_beacon::fork_choice::execution_status::ExecutionStatus;
+use redb::{Database, TableDefinition};
+
+use crate::tables::{ssz_encoder::SSZEncoding, table::REDBTable};
+
+pub struct ExecutionStatusTable {
+    pub db: Arc<Database>,
+}
+
+/// Table definition for the Execution Status table
+///
+/// Key: block_root
+/// Value: ExecutionStatus
+impl REDBTable for ExecutionStatusTable {
+    const TABLE_DEFINITION: TableDefinition<'_, SSZEncoding<B256>, SSZEncoding<ExecutionStatus>> =
+        TableDefinition::new("beacon_execution_status");
+
+    type Key = B256;
+
+    type KeyTableDefinition = SSZEncoding<B256>;
+
+    type Value = ExecutionStatus;
+
+    type ValueTableDefinition = SSZEncoding<ExecutionStatus>;
+
+    fn database(&self) -> Arc<Database> {
+        self.db.clone()
+    }
+}
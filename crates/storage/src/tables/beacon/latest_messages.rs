@@ -1,9 +1,12 @@
 use std::sync::Arc;
 
 use ream_consensus_beacon::fork_choice::latest_message::LatestMessage;
-use redb::{Database, TableDefinition};
+use redb::{Database, ReadableDatabase, ReadableTable, TableDefinition};
 
-use crate::tables::{ssz_encoder::SSZEncoding, table::REDBTable};
+use crate::{
+    errors::StoreError,
+    tables::{ssz_encoder::SSZEncoding, table::REDBTable},
+};
 
 pub struct LatestMessagesTable {
     pub db: Arc<Database>,
@@ -29,3 +32,18 @@ impl REDBTable for LatestMessagesTable {
         self.db.clone()
     }
 }
+
+impl LatestMessagesTable {
+    /// All latest messages, keyed by validator index.
+    pub fn iter(&self) -> Result<Vec<(u64, LatestMessage)>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(Self::TABLE_DEFINITION)?;
+
+        let mut entries = Vec::new();
+        for result in table.iter()? {
+            let (key_guard, value_guard) = result?;
+            entries.push((key_guard.value(), value_guard.value()));
+        }
+        Ok(entries)
+    }
+}
@@ -3,10 +3,13 @@ pub mod beacon_state;
 pub mod blobs_and_proofs;
 pub mod block_timeliness;
 pub mod checkpoint_states;
+pub mod cold_state;
 pub mod column_sidecars;
 pub mod equivocating_indices;
+pub mod execution_status;
 pub mod finalized_checkpoint;
 pub mod genesis_time;
+pub mod justifications;
 pub mod justified_checkpoint;
 pub mod latest_messages;
 pub mod parent_root_index;
@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
 use alloy_primitives::B256;
-use redb::{Database, Durability, MultimapTableDefinition, ReadableDatabase};
+use redb::{Database, MultimapTableDefinition, ReadableDatabase};
 
 use crate::{
     errors::StoreError,
     tables::{multimap_table::MultimapTable, ssz_encoder::SSZEncoding},
+    write_batch::WriteBatch,
 };
 
 /// Table definition for the Parent Root Index Multimap table
@@ -41,12 +42,29 @@ impl MultimapTable for ParentRootIndexMultimapTable {
     }
 
     fn insert(&self, key: Self::Key, value: Self::InsertValue) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate)?;
-        let mut table = write_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
+        let mut batch = WriteBatch::new(&self.db)?;
+        self.insert_batch(&mut batch, key, value)?;
+        batch.commit()
+    }
+
+    fn insert_batch(
+        &self,
+        batch: &mut WriteBatch,
+        key: Self::Key,
+        value: Self::InsertValue,
+    ) -> Result<(), StoreError> {
+        let mut table = batch.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
         table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
         Ok(())
     }
+
+    fn remove_batch(
+        &self,
+        batch: &mut WriteBatch,
+        key: Self::Key,
+        value: Self::InsertValue,
+    ) -> Result<bool, StoreError> {
+        let mut table = batch.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
+        Ok(table.remove(key, value)?)
+    }
 }
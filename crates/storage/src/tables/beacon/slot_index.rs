@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{ops::RangeBounds, sync::Arc};
 
 use alloy_primitives::B256;
 use redb::{Database, ReadableDatabase, ReadableTable, TableDefinition};
@@ -6,6 +6,7 @@ use redb::{Database, ReadableDatabase, ReadableTable, TableDefinition};
 use crate::{
     errors::StoreError,
     tables::{ssz_encoder::SSZEncoding, table::REDBTable},
+    write_batch::WriteBatch,
 };
 
 pub struct BeaconSlotIndexTable {
@@ -57,4 +58,32 @@ impl BeaconSlotIndexTable {
         let table = read_txn.open_table(Self::TABLE_DEFINITION)?;
         Ok(table.last()?.map(|result| result.1.value()))
     }
+
+    /// The `(slot, block_root)` entries within `range`, in ascending slot order.
+    pub fn get_range(&self, range: impl RangeBounds<u64>) -> Result<Vec<(u64, B256)>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(Self::TABLE_DEFINITION)?;
+        table
+            .range(range)?
+            .map(|result| {
+                let (slot, block_root) = result?;
+                Ok((slot.value(), block_root.value()))
+            })
+            .collect()
+    }
+
+    /// Stage the removal of the entry of `slot` in `batch`, if it still points at `block_root`.
+    /// The slot of a removed block may since have been taken over by a block of another fork.
+    pub fn remove_block_batch(
+        &self,
+        batch: &mut WriteBatch,
+        slot: u64,
+        block_root: B256,
+    ) -> Result<(), StoreError> {
+        let mut table = batch.open_table(Self::TABLE_DEFINITION)?;
+        if table.get(slot)?.map(|root| root.value()) == Some(block_root) {
+            table.remove(slot)?;
+        }
+        Ok(())
+    }
 }
@@ -1,9 +1,9 @@
 use std::{fmt::Debug, sync::Arc};
 
-use redb::{Database, Durability, ReadableDatabase, TableDefinition};
+use redb::{Database, ReadableDatabase, TableDefinition};
 use ssz::{Decode, Encode};
 
-use crate::errors::StoreError;
+use crate::{errors::StoreError, write_batch::WriteBatch};
 
 pub trait REDBField
 where
@@ -32,25 +32,35 @@ where
         &self,
         value: <Self::ValueFieldDefinition as redb::Value>::SelfType<'a>,
     ) -> Result<(), StoreError> {
-        let mut write_txn = self.database().begin_write()?;
-        write_txn.set_durability(Durability::Immediate)?;
-        {
-            let mut table = write_txn.open_table(Self::FIELD_DEFINITION)?;
-            table.insert(Self::KEY, value)?;
-        }
-        write_txn.commit()?;
-        Ok(())
+        let mut batch = WriteBatch::new(&self.database())?;
+        self.insert_batch(&mut batch, value)?;
+        batch.commit()
     }
 
     fn remove(&self) -> Result<Option<Self::Value>, StoreError> {
-        let write_txn = self.database().begin_write()?;
-        let value = {
-            let mut table = write_txn.open_table(Self::FIELD_DEFINITION)?;
-            table
-                .remove(Self::KEY)?
-                .map(|value| Self::Value::from(value.value()))
-        };
-        write_txn.commit()?;
+        let mut batch = WriteBatch::new(&self.database())?;
+        let value = self.remove_batch(&mut batch)?;
+        batch.commit()?;
+        Ok(value)
+    }
+
+    /// Stage an insert in `batch`, applied once the batch is committed.
+    fn insert_batch<'a>(
+        &self,
+        batch: &mut WriteBatch,
+        value: <Self::ValueFieldDefinition as redb::Value>::SelfType<'a>,
+    ) -> Result<(), StoreError> {
+        let mut table = batch.open_table(Self::FIELD_DEFINITION)?;
+        table.insert(Self::KEY, value)?;
+        Ok(())
+    }
+
+    /// Stage a removal in `batch`, applied once the batch is committed.
+    fn remove_batch(&self, batch: &mut WriteBatch) -> Result<Option<Self::Value>, StoreError> {
+        let mut table = batch.open_table(Self::FIELD_DEFINITION)?;
+        let value = table
+            .remove(Self::KEY)?
+            .map(|value| Self::Value::from(value.value()));
         Ok(value)
     }
 }
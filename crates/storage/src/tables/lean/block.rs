@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use alloy_primitives::B256;
 use ream_consensus_lean::block::SignedBlock;
-use redb::{Database, Durability, ReadableDatabase, ReadableTable, TableDefinition};
+use redb::{Database, ReadableDatabase, ReadableTable, TableDefinition};
 use tree_hash::TreeHash;
 
 use super::{
@@ -14,6 +14,7 @@ use crate::{
     cache::LeanCacheDB,
     errors::StoreError,
     tables::{ssz_encoder::SSZEncoding, table::REDBTable},
+    write_batch::WriteBatch,
 };
 
 pub struct LeanBlockTable {
@@ -67,38 +68,48 @@ impl REDBTable for LeanBlockTable {
         Ok(block)
     }
 
-    fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        self.insert_ref(key, &value)
+    fn insert_batch(
+        &self,
+        batch: &mut WriteBatch,
+        key: Self::Key,
+        value: Self::Value,
+    ) -> Result<(), StoreError> {
+        self.insert_ref_batch(batch, key, &value)
     }
 
-    fn remove(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        if let Some(cache) = &self.cache
-            && let Ok(mut cache_lock) = cache.blocks.lock()
-        {
-            cache_lock.pop(&key);
-        }
-
-        let write_txn = self.db.begin_write()?;
-        let mut table = write_txn.open_table(Self::TABLE_DEFINITION)?;
+    fn remove_batch(
+        &self,
+        batch: &mut WriteBatch,
+        key: Self::Key,
+    ) -> Result<Option<Self::Value>, StoreError> {
+        let mut table = batch.open_table(Self::TABLE_DEFINITION)?;
         let value = table.remove(key)?.map(|v| v.value());
+        drop(table);
+
         if let Some(block) = &value {
             let slot_index_table = LeanSlotIndexTable {
                 db: self.db.clone(),
             };
-            slot_index_table.remove(block.block.slot)?;
+            slot_index_table.remove_block_batch(batch, block.block.slot, key)?;
             let state_root_index_table = LeanStateRootIndexTable {
                 db: self.db.clone(),
             };
 
-            state_root_index_table.remove(block.block.state_root)?;
+            state_root_index_table.remove_batch(batch, block.block.state_root)?;
 
             let children_index_table = LeanChildrenIndexTable {
                 db: self.db.clone(),
             };
-            children_index_table.remove(key)?;
+            children_index_table.remove_batch(batch, key)?;
+        }
+
+        if let Some(cache) = self.cache.clone() {
+            batch.on_commit(move || {
+                if let Ok(mut cache_lock) = cache.blocks.lock() {
+                    cache_lock.pop(&key);
+                }
+            });
         }
-        drop(table);
-        write_txn.commit()?;
         Ok(value)
     }
 }
@@ -108,28 +119,54 @@ impl LeanBlockTable {
         matches!(self.get(key), Ok(Some(_)))
     }
 
+    /// The roots of all stored blocks.
+    pub fn iter_keys(&self) -> Result<Vec<B256>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(Self::TABLE_DEFINITION)?;
+
+        let mut keys = Vec::new();
+        for result in table.iter()? {
+            let (key_guard, _value_guard) = result?;
+            keys.push(key_guard.value());
+        }
+        Ok(keys)
+    }
+
     pub fn insert_ref(&self, key: B256, value: &SignedBlock) -> Result<(), StoreError> {
+        let mut batch = WriteBatch::new(&self.db)?;
+        self.insert_ref_batch(&mut batch, key, value)?;
+        batch.commit()
+    }
+
+    /// Stage an insert of a borrowed block in `batch`, applied once the batch is committed.
+    pub fn insert_ref_batch(
+        &self,
+        batch: &mut WriteBatch,
+        key: B256,
+        value: &SignedBlock,
+    ) -> Result<(), StoreError> {
         // insert entry to slot_index table
         let block_root = value.block.tree_hash_root();
         let slot_index_table = LeanSlotIndexTable {
             db: self.db.clone(),
         };
 
-        slot_index_table.insert(value.block.slot, block_root)?;
+        slot_index_table.insert_batch(batch, value.block.slot, block_root)?;
 
         // insert entry to state root index table
         let state_root_index_table = LeanStateRootIndexTable {
             db: self.db.clone(),
         };
 
-        state_root_index_table.insert(value.block.state_root, block_root)?;
+        state_root_index_table.insert_batch(batch, value.block.state_root, block_root)?;
 
         // insert entry to children index table, mirroring slot + parent_root so
         // fork choice can build the children map without decoding full blocks
         let children_index_table = LeanChildrenIndexTable {
             db: self.db.clone(),
         };
-        children_index_table.insert(
+        children_index_table.insert_batch(
+            batch,
             block_root,
             ChildIndexEntry {
                 slot: value.block.slot,
@@ -137,18 +174,18 @@ impl LeanBlockTable {
             },
         )?;
 
-        if let Some(cache) = &self.cache
-            && let Ok(mut cache_lock) = cache.blocks.lock()
-        {
-            cache_lock.put(key, value.clone());
-        }
-
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate)?;
-        let mut table = write_txn.open_table(Self::TABLE_DEFINITION)?;
+        let mut table = batch.open_table(Self::TABLE_DEFINITION)?;
         table.insert(key, value)?;
         drop(table);
-        write_txn.commit()?;
+
+        if let Some(cache) = self.cache.clone() {
+            let value = value.clone();
+            batch.on_commit(move || {
+                if let Ok(mut cache_lock) = cache.blocks.lock() {
+                    cache_lock.put(key, value);
+                }
+            });
+        }
         Ok(())
     }
 
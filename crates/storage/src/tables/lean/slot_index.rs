@@ -6,6 +6,7 @@ use redb::{Database, ReadableDatabase, ReadableTable, TableDefinition};
 use crate::{
     errors::StoreError,
     tables::{ssz_encoder::SSZEncoding, table::REDBTable},
+    write_batch::WriteBatch,
 };
 
 pub struct LeanSlotIndexTable {
@@ -52,9 +53,35 @@ impl LeanSlotIndexTable {
         Ok(table.last()?.map(|result| result.0.value()))
     }
 
+    /// Return the slot and root of the latest block at or before `slot`.
+    pub fn get_at_or_before(&self, slot: u64) -> Result<Option<(u64, B256)>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(Self::TABLE_DEFINITION)?;
+        Ok(table
+            .range(..=slot)?
+            .next_back()
+            .transpose()?
+            .map(|(slot, root)| (slot.value(), root.value())))
+    }
+
     pub fn get_highest_root(&self) -> Result<Option<B256>, StoreError> {
         let read_txn = self.db.begin_read()?;
         let table = read_txn.open_table(Self::TABLE_DEFINITION)?;
         Ok(table.last()?.map(|result| result.1.value()))
     }
+
+    /// Stage the removal of the entry of `slot` in `batch`, if it still points at `block_root`.
+    /// The slot of a removed block may since have been taken over by a block of another fork.
+    pub fn remove_block_batch(
+        &self,
+        batch: &mut WriteBatch,
+        slot: u64,
+        block_root: B256,
+    ) -> Result<(), StoreError> {
+        let mut table = batch.open_table(Self::TABLE_DEFINITION)?;
+        if table.get(slot)?.map(|root| root.value()) == Some(block_root) {
+            table.remove(slot)?;
+        }
+        Ok(())
+    }
 }
@@ -2,12 +2,13 @@ use std::sync::Arc;
 
 use alloy_primitives::B256;
 use ream_consensus_lean::state::LeanState;
-use redb::{Database, ReadableDatabase, TableDefinition};
+use redb::{Database, ReadableDatabase, ReadableTable, TableDefinition};
 
 use crate::{
     cache::LeanCacheDB,
     errors::StoreError,
     tables::{ssz_encoder::SSZEncoding, table::REDBTable},
+    write_batch::WriteBatch,
 };
 
 pub struct LeanStateTable {
@@ -61,48 +62,60 @@ impl REDBTable for LeanStateTable {
         Ok(state)
     }
 
-    fn insert<'a>(
+    fn insert_batch<'a>(
         &self,
+        batch: &mut WriteBatch,
         key: <Self::KeyTableDefinition as redb::Value>::SelfType<'a>,
-        value: <Self::ValueTableDefinition as redb::Value>::SelfType<'a>,
+        value: Self::Value,
     ) -> Result<(), StoreError> {
-        if let Some(cache) = &self.cache
-            && let Ok(mut cache_lock) = cache.states.lock()
-        {
-            cache_lock.put(key, value.clone());
-        }
-
-        let mut write_txn = self.database().begin_write()?;
-        write_txn.set_durability(redb::Durability::Immediate)?;
-        {
-            let mut table = write_txn.open_table(Self::TABLE_DEFINITION)?;
-            table.insert(key, value)?;
+        let mut table = batch.open_table(Self::TABLE_DEFINITION)?;
+        table.insert(key, &value)?;
+        drop(table);
+
+        if let Some(cache) = self.cache.clone() {
+            batch.on_commit(move || {
+                if let Ok(mut cache_lock) = cache.states.lock() {
+                    cache_lock.put(key, value);
+                }
+            });
         }
-        write_txn.commit()?;
         Ok(())
     }
 
-    fn remove<'a>(
+    fn remove_batch<'a>(
         &self,
+        batch: &mut WriteBatch,
         key: <Self::KeyTableDefinition as redb::Value>::SelfType<'a>,
     ) -> Result<Option<Self::Value>, StoreError> {
-        if let Some(cache) = &self.cache
-            && let Ok(mut cache_lock) = cache.states.lock()
-        {
-            cache_lock.pop(&key);
+        let mut table = batch.open_table(Self::TABLE_DEFINITION)?;
+        let value = table.remove(key)?.map(|value| value.value());
+        drop(table);
+
+        if let Some(cache) = self.cache.clone() {
+            batch.on_commit(move || {
+                if let Ok(mut cache_lock) = cache.states.lock() {
+                    cache_lock.pop(&key);
+                }
+            });
         }
-
-        let write_txn = self.database().begin_write()?;
-        let value = {
-            let mut table = write_txn.open_table(Self::TABLE_DEFINITION)?;
-            table.remove(key)?.map(|value| value.value())
-        };
-        write_txn.commit()?;
         Ok(value)
     }
 }
 
 impl LeanStateTable {
+    /// The block roots of all stored states.
+    pub fn iter_keys(&self) -> Result<Vec<B256>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(Self::TABLE_DEFINITION)?;
+
+        let mut keys = Vec::new();
+        for result in table.iter()? {
+            let (key_guard, _value_guard) = result?;
+            keys.push(key_guard.value());
+        }
+        Ok(keys)
+    }
+
     pub fn iter_values(
         &self,
     ) -> Result<impl Iterator<Item = anyhow::Result<LeanState>>, StoreError> {
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use redb::{Database, TableDefinition};
+
+use super::string_field::StringField;
+
+/// Table definition for the Chain table
+///
+/// Value: String
+pub(crate) const CHAIN_FIELD: TableDefinition<&str, &str> = TableDefinition::new("ream_chain");
+
+const CHAIN_KEY: &str = "chain_key";
+
+impl StringField {
+    /// The chain the database belongs to, either `beacon` or `lean`.
+    pub fn chain(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            definition: CHAIN_FIELD,
+            key: CHAIN_KEY,
+        }
+    }
+}
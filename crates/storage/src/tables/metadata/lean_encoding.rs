@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use redb::{Database, TableDefinition};
+
+use super::string_field::StringField;
+
+/// Table definition for the Lean Encoding table
+///
+/// Value: String
+pub(crate) const LEAN_ENCODING_FIELD: TableDefinition<&str, &str> =
+    TableDefinition::new("ream_lean_encoding");
+
+const LEAN_ENCODING_KEY: &str = "lean_encoding_key";
+
+/// The encoding of lean blocks and aggregated payloads written by this binary, which depends on
+/// the `devnet5` feature rather than on [crate::migrations::SCHEMA_VERSION].
+#[cfg(feature = "devnet5")]
+pub const LEAN_ENCODING: &str = "devnet5";
+#[cfg(not(feature = "devnet5"))]
+pub const LEAN_ENCODING: &str = "devnet4";
+
+impl StringField {
+    /// The encoding of the lean tables, see [LEAN_ENCODING].
+    pub fn lean_encoding(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            definition: LEAN_ENCODING_FIELD,
+            key: LEAN_ENCODING_KEY,
+        }
+    }
+}
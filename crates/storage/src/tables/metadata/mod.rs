@@ -0,0 +1,5 @@
+pub mod chain;
+pub mod lean_encoding;
+pub mod network;
+pub mod schema_version;
+pub mod string_field;
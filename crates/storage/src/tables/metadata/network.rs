@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use redb::{Database, TableDefinition};
+
+use super::string_field::StringField;
+
+/// Table definition for the Network table
+///
+/// Value: String
+pub(crate) const NETWORK_FIELD: TableDefinition<&str, &str> = TableDefinition::new("ream_network");
+
+const NETWORK_KEY: &str = "network_key";
+
+impl StringField {
+    /// The network the database belongs to.
+    pub fn network(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            definition: NETWORK_FIELD,
+            key: NETWORK_KEY,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use redb::{Database, TableDefinition};
+
+use crate::tables::field::REDBField;
+
+pub struct SchemaVersionField {
+    pub db: Arc<Database>,
+}
+
+/// Table definition for the Schema Version table
+///
+/// Value: u64
+impl REDBField for SchemaVersionField {
+    const FIELD_DEFINITION: TableDefinition<'_, &str, u64> =
+        TableDefinition::new("ream_schema_version");
+
+    const KEY: &str = "schema_version_key";
+
+    type Value = u64;
+
+    type ValueFieldDefinition = u64;
+
+    fn database(&self) -> Arc<Database> {
+        self.db.clone()
+    }
+}
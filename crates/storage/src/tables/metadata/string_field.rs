@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use redb::{Database, ReadableDatabase, TableDefinition};
+
+use crate::{errors::StoreError, tables::field::CustomField, write_batch::WriteBatch};
+
+/// A string describing the database, stored under `key` in a table of its own.
+pub struct StringField {
+    pub db: Arc<Database>,
+    pub definition: TableDefinition<'static, &'static str, &'static str>,
+    pub key: &'static str,
+}
+
+impl CustomField for StringField {
+    type Value = String;
+
+    fn get(&self) -> Result<Self::Value, StoreError> {
+        let read_txn = self.db.begin_read()?;
+
+        let table = read_txn.open_table(self.definition)?;
+        let result = table.get(self.key)?.ok_or(StoreError::FieldNotInitilized)?;
+        Ok(result.value().to_string())
+    }
+
+    fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
+        let mut batch = WriteBatch::new(&self.db)?;
+        {
+            let mut table = batch.open_table(self.definition)?;
+            table.insert(self.key, value.as_str())?;
+        }
+        batch.commit()
+    }
+
+    fn remove(&self) -> Result<Option<Self::Value>, StoreError> {
+        let mut batch = WriteBatch::new(&self.db)?;
+        let value = {
+            let mut table = batch.open_table(self.definition)?;
+            table
+                .remove(self.key)?
+                .map(|value| value.value().to_string())
+        };
+        batch.commit()?;
+        Ok(value)
+    }
+}
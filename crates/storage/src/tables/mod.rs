@@ -1,6 +1,7 @@
 pub mod beacon;
 pub mod field;
 pub mod lean;
+pub mod metadata;
 pub mod multimap_table;
 pub mod ssz_encoder;
 pub mod table;
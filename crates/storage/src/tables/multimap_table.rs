@@ -1,4 +1,4 @@
-use crate::errors::StoreError;
+use crate::{errors::StoreError, write_batch::WriteBatch};
 
 #[allow(clippy::result_large_err)]
 pub trait MultimapTable {
@@ -11,4 +11,21 @@ pub trait MultimapTable {
     fn get(&self, key: Self::Key) -> Result<Option<Self::GetValue>, StoreError>;
 
     fn insert(&self, key: Self::Key, value: Self::InsertValue) -> Result<(), StoreError>;
+
+    /// Stage an insert in `batch`, applied once the batch is committed.
+    fn insert_batch(
+        &self,
+        batch: &mut WriteBatch,
+        key: Self::Key,
+        value: Self::InsertValue,
+    ) -> Result<(), StoreError>;
+
+    /// Stage the removal of `value` from the values of `key` in `batch`. Returns whether it was
+    /// present.
+    fn remove_batch(
+        &self,
+        batch: &mut WriteBatch,
+        key: Self::Key,
+        value: Self::InsertValue,
+    ) -> Result<bool, StoreError>;
 }
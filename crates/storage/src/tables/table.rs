@@ -1,9 +1,9 @@
 use std::{fmt::Debug, sync::Arc};
 
-use redb::{Database, Durability, ReadableDatabase, TableDefinition};
+use redb::{Database, ReadableDatabase, TableDefinition};
 use ssz::{Decode, Encode};
 
-use crate::errors::StoreError;
+use crate::{errors::StoreError, write_batch::WriteBatch};
 
 pub trait REDBTable
 where
@@ -39,26 +39,41 @@ where
         key: <Self::KeyTableDefinition as redb::Value>::SelfType<'a>,
         value: Self::Value,
     ) -> Result<(), StoreError> {
-        let mut write_txn = self.database().begin_write()?;
-        write_txn.set_durability(Durability::Immediate)?;
-        {
-            let mut table = write_txn.open_table(Self::TABLE_DEFINITION)?;
-            table.insert(key, value)?;
-        }
-        write_txn.commit()?;
-        Ok(())
+        let mut batch = WriteBatch::new(&self.database())?;
+        self.insert_batch(&mut batch, key, value)?;
+        batch.commit()
     }
 
     fn remove<'a>(
         &self,
         key: <Self::KeyTableDefinition as redb::Value>::SelfType<'a>,
     ) -> Result<Option<Self::Value>, StoreError> {
-        let write_txn = self.database().begin_write()?;
-        let value = {
-            let mut table = write_txn.open_table(Self::TABLE_DEFINITION)?;
-            table.remove(key)?.map(|value| value.value())
-        };
-        write_txn.commit()?;
+        let mut batch = WriteBatch::new(&self.database())?;
+        let value = self.remove_batch(&mut batch, key)?;
+        batch.commit()?;
+        Ok(value)
+    }
+
+    /// Stage an insert in `batch`, applied once the batch is committed.
+    fn insert_batch<'a>(
+        &self,
+        batch: &mut WriteBatch,
+        key: <Self::KeyTableDefinition as redb::Value>::SelfType<'a>,
+        value: Self::Value,
+    ) -> Result<(), StoreError> {
+        let mut table = batch.open_table(Self::TABLE_DEFINITION)?;
+        table.insert(key, value)?;
+        Ok(())
+    }
+
+    /// Stage a removal in `batch`, applied once the batch is committed.
+    fn remove_batch<'a>(
+        &self,
+        batch: &mut WriteBatch,
+        key: <Self::KeyTableDefinition as redb::Value>::SelfType<'a>,
+    ) -> Result<Option<Self::Value>, StoreError> {
+        let mut table = batch.open_table(Self::TABLE_DEFINITION)?;
+        let value = table.remove(key)?.map(|value| value.value());
         Ok(value)
     }
 }
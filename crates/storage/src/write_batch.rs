@@ -0,0 +1,245 @@
+use redb::{
+    Database, Durability, Key, MultimapTable, MultimapTableDefinition, Table, TableDefinition,
+    TableHandle, Value, WriteTransaction,
+};
+
+use crate::errors::StoreError;
+
+type CommitHook = Box<dyn FnOnce() + Send>;
+
+/// Updates to any set of tables and fields, staged in a single redb write transaction and
+/// committed atomically.
+///
+/// Dropping a batch without calling [WriteBatch::commit] discards all of its updates.
+pub struct WriteBatch {
+    transaction: WriteTransaction,
+    commit_hooks: Vec<CommitHook>,
+}
+
+impl WriteBatch {
+    pub fn new(db: &Database) -> Result<Self, StoreError> {
+        let mut transaction = db.begin_write()?;
+        transaction.set_durability(Durability::Immediate)?;
+        Ok(Self {
+            transaction,
+            commit_hooks: vec![],
+        })
+    }
+
+    pub fn open_table<K: Key + 'static, V: Value + 'static>(
+        &self,
+        definition: TableDefinition<K, V>,
+    ) -> Result<Table<'_, K, V>, StoreError> {
+        Ok(self.transaction.open_table(definition)?)
+    }
+
+    pub fn open_multimap_table<K: Key + 'static, V: Key + 'static>(
+        &self,
+        definition: MultimapTableDefinition<K, V>,
+    ) -> Result<MultimapTable<'_, K, V>, StoreError> {
+        Ok(self.transaction.open_multimap_table(definition)?)
+    }
+
+    /// Delete the table of `definition` with all of its entries. Returns whether it existed.
+    pub fn delete_table(&self, definition: impl TableHandle) -> Result<bool, StoreError> {
+        Ok(self.transaction.delete_table(definition)?)
+    }
+
+    /// Run `hook` once the batch is committed. Used to keep in-memory caches in sync with the
+    /// database, so an aborted batch never leaves them ahead of it.
+    pub fn on_commit(&mut self, hook: impl FnOnce() + Send + 'static) {
+        self.commit_hooks.push(Box::new(hook));
+    }
+
+    pub fn commit(self) -> Result<(), StoreError> {
+        self.transaction.commit()?;
+        for hook in self.commit_hooks {
+            hook();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    use alloy_primitives::B256;
+    use ream_bls::BLSSignature;
+    use ream_consensus_beacon::electra::beacon_block::{BeaconBlock, SignedBeaconBlock};
+    use ream_consensus_misc::checkpoint::Checkpoint;
+    use tempdir::TempDir;
+
+    use crate::{
+        cache::BeaconCacheDB,
+        db::{ReamDB, beacon::BeaconDB},
+        errors::StoreError,
+        tables::{
+            beacon::beacon_block::BeaconBlockTable, field::REDBField,
+            multimap_table::MultimapTable, table::REDBTable,
+        },
+    };
+
+    fn init_db(tmp_dir: &TempDir) -> anyhow::Result<BeaconDB> {
+        Ok(ReamDB::new(tmp_dir.path().to_path_buf())?.init_beacon_db()?)
+    }
+
+    fn block(slot: u64, parent_root: B256) -> SignedBeaconBlock {
+        SignedBeaconBlock {
+            message: BeaconBlock {
+                slot,
+                parent_root,
+                state_root: B256::repeat_byte(slot as u8),
+                ..Default::default()
+            },
+            signature: BLSSignature::default(),
+        }
+    }
+
+    #[test]
+    fn test_dropped_batch_rolls_back_every_table() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_dropped_batch")?;
+        let db = init_db(&tmp_dir)?;
+        let block = block(1, B256::repeat_byte(0xaa));
+        let block_root = block.message.block_root();
+        let checkpoint = Checkpoint {
+            epoch: 1,
+            root: block_root,
+        };
+
+        let mut batch = db.write_batch()?;
+        db.block_provider()
+            .insert_batch(&mut batch, block_root, block.clone())?;
+        db.justified_checkpoint_provider()
+            .insert_batch(&mut batch, checkpoint)?;
+        drop(batch);
+
+        assert!(db.block_provider().get(block_root)?.is_none());
+        assert!(db.slot_index_provider().get(1)?.is_none());
+        assert!(
+            db.state_root_index_provider()
+                .get(block.message.state_root)?
+                .is_none()
+        );
+        assert_eq!(
+            db.parent_root_index_multimap_provider()
+                .get(block.message.parent_root)?,
+            Some(vec![])
+        );
+        assert!(matches!(
+            db.justified_checkpoint_provider().get(),
+            Err(StoreError::FieldNotInitilized)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_hooks_run_only_after_commit() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_commit_hooks")?;
+        let db = init_db(&tmp_dir)?.with_cache(Arc::new(BeaconCacheDB::new()));
+        let cache = db.cache.clone().expect("Cache was attached above");
+        let block = block(1, B256::repeat_byte(0xaa));
+        let block_root = block.message.block_root();
+        let is_cached = || {
+            cache
+                .blocks
+                .lock()
+                .is_ok_and(|blocks| blocks.contains(&block_root))
+        };
+
+        // An aborted batch neither runs its hooks nor fills the cache
+        let ran = Arc::new(AtomicBool::new(false));
+        let mut batch = db.write_batch()?;
+        db.block_provider()
+            .insert_batch(&mut batch, block_root, block.clone())?;
+        let hook_ran = ran.clone();
+        batch.on_commit(move || hook_ran.store(true, Ordering::SeqCst));
+        drop(batch);
+        assert!(!ran.load(Ordering::SeqCst));
+        assert!(!is_cached());
+
+        // A committed batch runs them once the updates are visible
+        let mut batch = db.write_batch()?;
+        db.block_provider()
+            .insert_batch(&mut batch, block_root, block)?;
+        let hook_ran = ran.clone();
+        let hook_db = db.db.clone();
+        batch.on_commit(move || {
+            let block_provider = BeaconBlockTable {
+                db: hook_db,
+                cache: None,
+            };
+            assert!(matches!(block_provider.get(block_root), Ok(Some(_))));
+            hook_ran.store(true, Ordering::SeqCst);
+        });
+        assert!(!is_cached());
+        assert!(!ran.load(Ordering::SeqCst));
+        batch.commit()?;
+        assert!(ran.load(Ordering::SeqCst));
+        assert!(is_cached());
+        Ok(())
+    }
+
+    #[test]
+    fn test_block_indexes_stay_in_sync() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_block_indexes")?;
+        let db = init_db(&tmp_dir)?;
+        let parent = block(1, B256::repeat_byte(0xaa));
+        let parent_root = parent.message.block_root();
+        let child = block(2, parent_root);
+        let child_root = child.message.block_root();
+
+        let mut batch = db.write_batch()?;
+        db.block_provider()
+            .insert_batch(&mut batch, parent_root, parent.clone())?;
+        db.block_provider()
+            .insert_batch(&mut batch, child_root, child.clone())?;
+        batch.commit()?;
+
+        assert_eq!(db.slot_index_provider().get(1)?, Some(parent_root));
+        assert_eq!(db.slot_index_provider().get(2)?, Some(child_root));
+        assert_eq!(
+            db.state_root_index_provider()
+                .get(child.message.state_root)?,
+            Some(child_root)
+        );
+        assert_eq!(
+            db.parent_root_index_multimap_provider().get(parent_root)?,
+            Some(vec![child_root])
+        );
+
+        let mut batch = db.write_batch()?;
+        let removed = db.block_provider().remove_batch(&mut batch, child_root)?;
+        assert_eq!(removed, Some(child.clone()));
+        batch.commit()?;
+
+        assert!(db.block_provider().get(child_root)?.is_none());
+        assert!(db.slot_index_provider().get(2)?.is_none());
+        assert!(
+            db.state_root_index_provider()
+                .get(child.message.state_root)?
+                .is_none()
+        );
+        assert_eq!(
+            db.parent_root_index_multimap_provider().get(parent_root)?,
+            Some(vec![])
+        );
+
+        // Entries of the remaining block are untouched
+        assert_eq!(db.slot_index_provider().get(1)?, Some(parent_root));
+        assert_eq!(
+            db.state_root_index_provider()
+                .get(parent.message.state_root)?,
+            Some(parent_root)
+        );
+        assert_eq!(
+            db.parent_root_index_multimap_provider()
+                .get(parent.message.parent_root)?,
+            Some(vec![parent_root])
+        );
+        Ok(())
+    }
+}
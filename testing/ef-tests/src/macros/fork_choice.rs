@@ -22,7 +22,7 @@ macro_rules! test_fork_choice {
                 use ream_network_spec::networks::initialize_test_network_spec;
                 use ream_storage::{
                     db::{ReamDB, beacon::BeaconDB},
-                    tables::{table::CustomTable, field::REDBField},
+                    tables::{table::{CustomTable, REDBTable}, field::REDBField},
                     dir::setup_data_dir
                 };
                 use rstest::rstest;
@@ -191,6 +191,18 @@ macro_rules! test_fork_choice {
                                     assert_eq!(on_attester_slashing(&mut store, slashing).is_ok(), slashing_step.valid.unwrap_or(true), "Unexpected result on on_attester_slashing");
                                 }
                                 ForkChoiceStep::Checks { checks } => {
+                                    if let Some(head) = checks.head {
+                                        let head_root = store.get_head()?;
+                                        assert_eq!(
+                                            head_root, head.root,
+                                            "checks head root mismatch in case {case_name}"
+                                        );
+                                        let head_block = store.db.block_provider().get(head_root)?.expect("cannot find head block");
+                                        assert_eq!(
+                                            head_block.message.slot, head.slot,
+                                            "checks head slot mismatch in case {case_name}"
+                                        );
+                                    }
                                     if let Some(time) = checks.time {
                                         assert_eq!(
                                             store.db.time_provider().get()?, time,
@@ -70,6 +70,7 @@ macro_rules! test_sanity_blocks {
                             result = state
                                 .state_transition(&signed_block, true, &mock_engine)
                                 .await
+                                .map(|_| ())
                                 .map_err(|err| err.to_string());
                         }
 
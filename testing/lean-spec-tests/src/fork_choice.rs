@@ -14,7 +14,7 @@ use ream_consensus_lean::{
     state::LeanState,
 };
 use ream_consensus_misc::constants::lean::INTERVALS_PER_SLOT;
-use ream_fork_choice_lean::store::Store;
+use ream_fork_choice_lean::{proto_array::ProtoArray, store::Store};
 use ream_network_spec::networks::LeanNetworkSpec;
 use ream_post_quantum_crypto::{
     lean_multisig::type_2::type_2_setup_verifier,
@@ -381,6 +381,18 @@ async fn validate_checks(store: &Store, checks: &StoreChecks) -> anyhow::Result<
         debug!("Head root: {actual_head_root}");
     }
 
+    if checks.head_slot.is_some() || checks.head_root.is_some() {
+        // The head comes from the incremental proto-array, so check that it agrees with one
+        // rebuilt from the database as on startup
+        let justified_root = db.latest_justified_provider().get()?.root;
+        let (head_root, _) = store.proto_array.lock().find_head(justified_root)?;
+        let (rebuilt_head_root, _) = ProtoArray::from_db(&db)?.find_head(justified_root)?;
+        ensure!(
+            head_root == rebuilt_head_root,
+            "Proto-array head mismatch: incremental {head_root}, rebuilt {rebuilt_head_root}"
+        );
+    }
+
     if let Some(expected_time) = checks.time {
         let actual_time = db.time_provider().get()?;
         ensure!(